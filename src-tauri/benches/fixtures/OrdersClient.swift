@@ -0,0 +1,113 @@
+import Foundation
+
+enum OrderFilter: String {
+    case all
+    case open
+    case shipped
+}
+
+struct Order: Codable, Equatable {
+    let id: UUID
+    let title: String
+    let customerName: String
+    let createdAt: Date
+    let dueAt: Date?
+    let isUrgent: Bool
+    let isShipped: Bool
+
+    var isEditable: Bool {
+        return !isShipped
+    }
+
+    var isOverdue: Bool {
+        guard let dueAt = dueAt else {
+            return false
+        }
+        return dueAt < Date() && !isShipped
+    }
+}
+
+enum OrdersClientError: Error {
+    case invalidResponse(statusCode: Int)
+    case emptyBody
+}
+
+final class OrdersClient {
+    private let session: URLSession
+    private let baseURL: URL
+    private let decoder: JSONDecoder
+    private var retryCount = 0
+    private let maxRetryCount = 3
+
+    init(session: URLSession = .shared, baseURL: URL) {
+        self.session = session
+        self.baseURL = baseURL
+        self.decoder = JSONDecoder()
+        self.decoder.dateDecodingStrategy = .iso8601
+    }
+
+    func fetchOrders(filter: OrderFilter, completion: @escaping (Result<[Order], Error>) -> Void) {
+        var components = URLComponents(url: baseURL.appendingPathComponent("orders"), resolvingAgainstBaseURL: false)
+        if filter != .all {
+            components?.queryItems = [URLQueryItem(name: "filter", value: filter.rawValue)]
+        }
+        guard let url = components?.url else {
+            completion(.failure(URLError(.badURL)))
+            return
+        }
+
+        session.dataTask(with: url) { [weak self] data, response, error in
+            guard let self = self else { return }
+            if let error = error {
+                if self.retryCount < self.maxRetryCount {
+                    self.retryCount += 1
+                    self.fetchOrders(filter: filter, completion: completion)
+                } else {
+                    completion(.failure(error))
+                }
+                return
+            }
+            self.retryCount = 0
+
+            guard let response = response as? HTTPURLResponse, (200..<300).contains(response.statusCode) else {
+                let statusCode = (response as? HTTPURLResponse)?.statusCode ?? 0
+                completion(.failure(OrdersClientError.invalidResponse(statusCode: statusCode)))
+                return
+            }
+            guard let data = data, !data.isEmpty else {
+                completion(.failure(OrdersClientError.emptyBody))
+                return
+            }
+
+            do {
+                let orders = try self.decoder.decode([Order].self, from: data)
+                completion(.success(orders.filter { order in
+                    switch filter {
+                    case .all:
+                        return true
+                    case .open:
+                        return !order.isShipped
+                    case .shipped:
+                        return order.isShipped
+                    }
+                }))
+            } catch {
+                completion(.failure(error))
+            }
+        }.resume()
+    }
+
+    func cancel(order: Order, completion: @escaping (Result<Void, Error>) -> Void) {
+        var request = URLRequest(url: baseURL.appendingPathComponent("orders/\(order.id)"))
+        request.httpMethod = "DELETE"
+        session.dataTask(with: request) { _, response, error in
+            if let error = error {
+                completion(.failure(error))
+            } else if let response = response as? HTTPURLResponse, response.statusCode >= 400 {
+                completion(.failure(OrdersClientError.invalidResponse(statusCode: response.statusCode)))
+            } else {
+                completion(.success(()))
+            }
+        }.resume()
+    }
+}
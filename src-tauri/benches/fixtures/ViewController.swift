@@ -0,0 +1,158 @@
+import UIKit
+
+final class OrdersViewController: UIViewController, UITableViewDataSource, UITableViewDelegate {
+    private let tableView = UITableView(frame: .zero, style: .insetGrouped)
+    private let refreshControl = UIRefreshControl()
+    private let emptyLabel = UILabel()
+    private var orders: [Order] = []
+    private var filter: OrderFilter = .all
+    private let client: OrdersClient
+
+    init(client: OrdersClient) {
+        self.client = client
+        super.init(nibName: nil, bundle: nil)
+    }
+
+    required init?(coder: NSCoder) {
+        fatalError("init(coder:) has not been implemented")
+    }
+
+    override func viewDidLoad() {
+        super.viewDidLoad()
+        title = "Orders"
+        view.backgroundColor = .systemBackground
+
+        tableView.dataSource = self
+        tableView.delegate = self
+        tableView.register(OrderCell.self, forCellReuseIdentifier: OrderCell.reuseIdentifier)
+        tableView.refreshControl = refreshControl
+        refreshControl.addTarget(self, action: #selector(reload), for: .valueChanged)
+
+        emptyLabel.text = "No orders yet"
+        emptyLabel.textAlignment = .center
+        emptyLabel.textColor = .secondaryLabel
+
+        view.addSubview(tableView)
+        tableView.translatesAutoresizingMaskIntoConstraints = false
+        NSLayoutConstraint.activate([
+            tableView.topAnchor.constraint(equalTo: view.topAnchor),
+            tableView.bottomAnchor.constraint(equalTo: view.bottomAnchor),
+            tableView.leadingAnchor.constraint(equalTo: view.leadingAnchor),
+            tableView.trailingAnchor.constraint(equalTo: view.trailingAnchor),
+        ])
+
+        reload()
+    }
+
+    @objc private func reload() {
+        client.fetchOrders(filter: filter) { [weak self] result in
+            DispatchQueue.main.async {
+                guard let self = self else { return }
+                self.refreshControl.endRefreshing()
+                switch result {
+                case .success(let orders):
+                    self.orders = orders.sorted { lhs, rhs in
+                        if lhs.isUrgent != rhs.isUrgent {
+                            return lhs.isUrgent
+                        }
+                        return lhs.createdAt > rhs.createdAt
+                    }
+                    self.tableView.backgroundView = orders.isEmpty ? self.emptyLabel : nil
+                    self.tableView.reloadData()
+                case .failure(let error):
+                    self.present(error: error)
+                }
+            }
+        }
+    }
+
+    private func present(error: Error) {
+        let alert = UIAlertController(
+            title: "Could not load orders",
+            message: error.localizedDescription,
+            preferredStyle: .alert
+        )
+        alert.addAction(UIAlertAction(title: "Retry", style: .default) { [weak self] _ in
+            self?.reload()
+        })
+        alert.addAction(UIAlertAction(title: "Cancel", style: .cancel))
+        present(alert, animated: true)
+    }
+
+    func numberOfSections(in tableView: UITableView) -> Int {
+        return 1
+    }
+
+    func tableView(_ tableView: UITableView, numberOfRowsInSection section: Int) -> Int {
+        return orders.count
+    }
+
+    func tableView(_ tableView: UITableView, cellForRowAt indexPath: IndexPath) -> UITableViewCell {
+        guard let cell = tableView.dequeueReusableCell(
+            withIdentifier: OrderCell.reuseIdentifier,
+            for: indexPath
+        ) as? OrderCell else {
+            return UITableViewCell()
+        }
+
+        let order = orders[indexPath.row]
+        cell.titleLabel.text = order.title
+        cell.subtitleLabel.text = order.customerName
+        if order.isUrgent {
+            cell.badgeView.isHidden = false
+            cell.badgeView.backgroundColor = order.isOverdue ? .systemRed : .systemOrange
+        } else if order.isShipped {
+            cell.badgeView.isHidden = false
+            cell.badgeView.backgroundColor = .systemGreen
+        } else {
+            cell.badgeView.isHidden = true
+        }
+        return cell
+    }
+
+    func tableView(_ tableView: UITableView, didSelectRowAt indexPath: IndexPath) {
+        tableView.deselectRow(at: indexPath, animated: true)
+        let order = orders[indexPath.row]
+        guard order.isEditable else {
+            return
+        }
+
+        let detail = OrderDetailViewController(order: order)
+        detail.onSave = { [weak self] updatedOrder in
+            guard let self = self else { return }
+            for (index, existingOrder) in self.orders.enumerated() {
+                if existingOrder.id == updatedOrder.id {
+                    self.orders[index] = updatedOrder
+                    self.tableView.reloadRows(at: [IndexPath(row: index, section: 0)], with: .automatic)
+                    break
+                }
+            }
+        }
+        navigationController?.pushViewController(detail, animated: true)
+    }
+
+    func tableView(
+        _ tableView: UITableView,
+        trailingSwipeActionsConfigurationForRowAt indexPath: IndexPath
+    ) -> UISwipeActionsConfiguration? {
+        let order = orders[indexPath.row]
+        if !order.isEditable || order.isShipped {
+            return nil
+        }
+
+        let cancel = UIContextualAction(style: .destructive, title: "Cancel") { [weak self] _, _, completion in
+            self?.client.cancel(order: order) { result in
+                DispatchQueue.main.async {
+                    if case .success = result {
+                        self?.orders.removeAll { $0.id == order.id }
+                        self?.tableView.deleteRows(at: [indexPath], with: .automatic)
+                        completion(true)
+                    } else {
+                        completion(false)
+                    }
+                }
+            }
+        }
+        return UISwipeActionsConfiguration(actions: [cancel])
+    }
+}
@@ -0,0 +1,165 @@
+// Criterion benchmarks of editing text, parsing and computing node metadata for files of up to
+// 20,000 lines: `cargo bench --bench syntax_tree`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use tauri::async_runtime::block_on;
+use tree_sitter::Range;
+
+use pretzl::core_engine::{
+    syntax_tree::{
+        calculate_cognitive_complexities, detect_input_edits, SwiftSyntaxTree, TreeMetaData,
+    },
+    XcodeText,
+};
+
+// Typical source files of an app, a view controller and a networking client. They are small on
+// their own; the benchmarks repeat them into files of `LINE_COUNTS` lines.
+const FIXTURES: &[&str] = &[
+    include_str!("fixtures/ViewController.swift"),
+    include_str!("fixtures/OrdersClient.swift"),
+];
+
+// The fixtures repeated until the file has at least `min_lines` lines.
+fn large_file(min_lines: usize) -> String {
+    let mut content = String::new();
+    let mut copy = 0;
+    while content.lines().count() < min_lines {
+        for fixture in FIXTURES {
+            // Renamed, so the copies do not look like recursive calls of each other.
+            content += &fixture
+                .replace("class ", &format!("class Copy{}", copy))
+                .replace("func ", &format!("func copy{}_", copy));
+        }
+        copy += 1;
+    }
+    content
+}
+
+// Inserts a statement into the body of the middle function, like typing would.
+fn edited(content: &str) -> String {
+    let middle = content.len() / 2;
+    let body_start = middle + content[middle..].find("{\n").unwrap() + 2;
+    format!(
+        "{}        let x = 1\n{}",
+        &content[..body_start],
+        &content[body_start..]
+    )
+}
+
+fn parse(content: &str, previous_tree: Option<SwiftSyntaxTree>) -> SwiftSyntaxTree {
+    block_on(SwiftSyntaxTree::from_XcodeText(
+        XcodeText::from_str(content),
+        previous_tree,
+    ))
+    .unwrap()
+}
+
+const LINE_COUNTS: [usize; 3] = [1_000, 5_000, 20_000];
+
+fn benchmark_replace_range(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("text");
+    for lines in LINE_COUNTS {
+        let text = XcodeText::from_str(&large_file(lines));
+        let middle = text.len() / 2;
+
+        group.bench_function(
+            BenchmarkId::new("clone and replace range", lines),
+            |bencher| {
+                bencher.iter(|| {
+                    let mut edited_text = text.clone();
+                    edited_text.replace_range(middle..middle + 1, XcodeText::from_str("let x = 1"));
+                    edited_text
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn benchmark_parse(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("parse");
+    for lines in LINE_COUNTS {
+        let content = large_file(lines);
+        let edited_content = edited(&content);
+        let tree = parse(&content, None);
+
+        group.bench_with_input(
+            BenchmarkId::new("full", lines),
+            &edited_content,
+            |bencher, edited_content| bencher.iter(|| parse(edited_content, None)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("incremental after typing", lines),
+            &edited_content,
+            |bencher, edited_content| {
+                bencher.iter_batched(
+                    || tree.clone(),
+                    |previous_tree| parse(edited_content, Some(previous_tree)),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn benchmark_cognitive_complexities(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("cognitive complexities");
+    for lines in LINE_COUNTS {
+        let text = XcodeText::from_str(&large_file(lines));
+        let tree = SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &text, None).unwrap();
+        let mut metadata = TreeMetaData::default();
+        calculate_cognitive_complexities(&tree.root_node(), &text, &mut metadata, None).unwrap();
+
+        group.bench_function(BenchmarkId::new("from scratch", lines), |bencher| {
+            bencher.iter_batched(
+                TreeMetaData::default,
+                |mut metadata| {
+                    calculate_cognitive_complexities(&tree.root_node(), &text, &mut metadata, None)
+                        .unwrap()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        let edited_text = XcodeText::from_str(&edited(&text.as_string()));
+        let edits = detect_input_edits(&text, &edited_text, 20);
+        let mut edited_tree = tree.clone();
+        for edit in &edits {
+            edited_tree.edit(edit);
+        }
+        let new_tree = SwiftSyntaxTree::parse(
+            &mut SwiftSyntaxTree::parser(),
+            &edited_text,
+            Some(&edited_tree),
+        )
+        .unwrap();
+        let changed_ranges: Vec<Range> = edited_tree.changed_ranges(&new_tree).collect();
+
+        group.bench_function(BenchmarkId::new("after typing", lines), |bencher| {
+            bencher.iter_batched(
+                || metadata.clone(),
+                |mut metadata| {
+                    metadata.apply_edits(&edits);
+                    metadata.remove_changed_ranges(&changed_ranges);
+                    calculate_cognitive_complexities(
+                        &new_tree.root_node(),
+                        &edited_text,
+                        &mut metadata,
+                        None,
+                    )
+                    .unwrap()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = benchmark_replace_range, benchmark_parse, benchmark_cognitive_complexities
+}
+criterion_main!(benches);
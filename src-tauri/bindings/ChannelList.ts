@@ -13,4 +13,11 @@ export type ChannelList =
   | "RuleResults"
   | "EventViewport"
   | "NodeExplanationEvent"
-  | "SuggestionEvent";
+  | "SuggestionEvent"
+  | "StickyScopeHeaderEvent"
+  | "DocumentOutlineEvent"
+  | "StructuralSearchEvent"
+  | "SettingsEvent"
+  | "FeatureStateEvent"
+  | "DeadCodeEvent"
+  | "CloneDetectionEvent";
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExplanationProvider } from "./ExplanationProvider";
+import type { FeatureKind } from "../features/code_annotations/FeatureKind";
+import type { FormatterSettings } from "./FormatterSettings";
+import type { TelemetryLevel } from "../telemetry/TelemetryLevel";
+import type { ThresholdSettings } from "./ThresholdSettings";
+
+export interface Settings {
+  version: number;
+  ai_features_active: boolean;
+  features: Record<FeatureKind, boolean>;
+  thresholds: ThresholdSettings;
+  shortcuts: Record<string, string>;
+  telemetry_level: TelemetryLevel | null;
+  explanation_provider: ExplanationProvider;
+  formatter: FormatterSettings;
+}
@@ -1,6 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export interface CoreEngineState {
-  ai_features_active: boolean;
-  swift_format_on_cmd_s: boolean;
+export interface ThresholdSettings {
+  max_function_complexity: number;
+  min_clone_tokens: number;
 }
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperationSummary } from "./OperationSummary";
+
+export interface DiagnosticsReport {
+  generated_at: string;
+  scopes: Record<string, Record<string, OperationSummary>>;
+}
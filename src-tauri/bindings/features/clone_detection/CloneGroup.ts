@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CloneLocation } from "./CloneLocation";
+import type { LocalRefactoringMessage } from "../../user_interaction/LocalRefactoringMessage";
+
+export interface CloneGroup {
+  token_count: number;
+  parameter_count: number;
+  suggestion: string;
+  locations: Array<CloneLocation>;
+  extraction: LocalRefactoringMessage | null;
+}
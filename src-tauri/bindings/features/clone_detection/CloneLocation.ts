@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TextRange } from "../../rules/utils/TextRange";
+
+export interface CloneLocation {
+  window_uid: number | null;
+  file_path: string | null;
+  text_range: TextRange;
+  start_line: number;
+  line_count: number;
+}
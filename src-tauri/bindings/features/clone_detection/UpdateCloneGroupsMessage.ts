@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CloneDetectionScope } from "./CloneDetectionScope";
+import type { CloneGroup } from "./CloneGroup";
+
+export interface UpdateCloneGroupsMessage {
+  scope: CloneDetectionScope;
+  groups: Array<CloneGroup>;
+}
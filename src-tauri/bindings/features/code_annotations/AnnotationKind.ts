@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScopeGuideProps } from "../scope_guides/ScopeGuideProps";
 
 export type AnnotationKind =
   | "OpeningBracket"
@@ -9,4 +10,8 @@ export type AnnotationKind =
   | "CodeblockFirstChar"
   | "CodeblockLastChar"
   | "ExtractionStartChar"
-  | "ExtractionEndChar";
+  | "ExtractionEndChar"
+  | "SearchMatchStartChar"
+  | "SearchMatchEndChar"
+  | { ScopeGuideStart: ScopeGuideProps }
+  | { ScopeGuideEnd: ScopeGuideProps };
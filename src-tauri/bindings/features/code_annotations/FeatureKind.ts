@@ -4,4 +4,11 @@ export type FeatureKind =
   | "BracketHighlight"
   | "ComplexityRefactoring"
   | "DocsGeneration"
-  | "Formatter";
+  | "Formatter"
+  | "ScopeGuides"
+  | "StickyScopeHeader"
+  | "StructuralNavigation"
+  | "DocumentOutline"
+  | "StructuralSearch"
+  | "DeadCode"
+  | "CloneDetection";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RuleResults } from "../../rules/RuleResults";
+
+export interface UpdateDeadCodeResultsMessage {
+  window_uid: number;
+  results: RuleResults;
+}
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DocumentSymbolKind } from "./DocumentSymbolKind";
+import type { TextRange } from "../../rules/utils/TextRange";
+
+export interface DocumentSymbol {
+  kind: DocumentSymbolKind;
+  name: string;
+  signature: string;
+  text_range: TextRange;
+  complexity: number;
+  children: Array<DocumentSymbol>;
+}
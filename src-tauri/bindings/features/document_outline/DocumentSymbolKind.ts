@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DocumentSymbolKind =
+  | "Class"
+  | "Struct"
+  | "Enum"
+  | "Protocol"
+  | "Extension"
+  | "Actor"
+  | "Function"
+  | "Initializer"
+  | "Deinitializer"
+  | "Subscript"
+  | "Property"
+  | "EnumCase";
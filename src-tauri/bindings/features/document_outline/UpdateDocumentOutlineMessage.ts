@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DocumentSymbol } from "./DocumentSymbol";
+
+export interface UpdateDocumentOutlineMessage {
+  window_uid: number;
+  symbols: Array<DocumentSymbol>;
+}
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeatureKind } from "../code_annotations/FeatureKind";
+
+export interface UpdateFeatureStatesMessage {
+  feature_states: Record<FeatureKind, boolean>;
+}
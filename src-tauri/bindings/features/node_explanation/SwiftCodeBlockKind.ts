@@ -9,4 +9,14 @@ export type SwiftCodeBlockKind =
   | "Switch"
   | "While"
   | "Do"
-  | "Guard";
+  | "Guard"
+  | "Struct"
+  | "Enum"
+  | "Protocol"
+  | "Extension"
+  | "Actor"
+  | "Closure"
+  | "Initializer"
+  | "Deinitializer"
+  | "ComputedProperty"
+  | "Subscript";
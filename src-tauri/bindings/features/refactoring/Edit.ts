@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { XcodeText } from "../../XcodeText";
+
+export interface Edit {
+  text: XcodeText;
+  start_index: number;
+  end_index: number;
+}
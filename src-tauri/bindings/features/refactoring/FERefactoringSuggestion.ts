@@ -1,7 +1,9 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SuggestionKind } from "./SuggestionKind";
 import type { SuggestionState } from "./SuggestionState";
 
 export interface FERefactoringSuggestion {
+  kind: SuggestionKind;
   state: SuggestionState;
   new_text_content_string: string | null;
   old_text_content_string: string | null;
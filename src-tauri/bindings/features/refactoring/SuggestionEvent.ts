@@ -1,7 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ReplaceSuggestionsMessage } from "./ReplaceSuggestionsMessage";
+import type { UpdateLocalRefactoringPreviewMessage } from "./UpdateLocalRefactoringPreviewMessage";
+import type { UpdateRenamePreviewMessage } from "./UpdateRenamePreviewMessage";
 
-export type SuggestionEvent = {
-  event: "ReplaceSuggestions";
-  payload: ReplaceSuggestionsMessage;
-};
+export type SuggestionEvent =
+  | { event: "ReplaceSuggestions"; payload: ReplaceSuggestionsMessage }
+  | { event: "UpdateRenamePreview"; payload: UpdateRenamePreviewMessage }
+  | {
+    event: "UpdateLocalRefactoringPreview";
+    payload: UpdateLocalRefactoringPreviewMessage;
+  };
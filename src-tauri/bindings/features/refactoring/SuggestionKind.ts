@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SuggestionKind =
+  | "MethodExtraction"
+  | "GuardConversion"
+  | "ConditionFlattening"
+  | "SwitchConversion"
+  | "BooleanReturn";
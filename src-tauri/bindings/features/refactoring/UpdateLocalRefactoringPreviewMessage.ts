@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LocalRefactoringMessage } from "../../user_interaction/LocalRefactoringMessage";
+import type { ReplacePreview } from "../structural_search/ReplacePreview";
+
+export interface UpdateLocalRefactoringPreviewMessage {
+  request: LocalRefactoringMessage;
+  preview: ReplacePreview | null;
+  error: string | null;
+}
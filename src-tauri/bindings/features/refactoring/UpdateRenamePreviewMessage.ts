@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RenameSymbolMessage } from "../../user_interaction/RenameSymbolMessage";
+import type { ReplacePreview } from "../structural_search/ReplacePreview";
+
+export interface UpdateRenamePreviewMessage {
+  request: RenameSymbolMessage;
+  preview: ReplacePreview | null;
+  error: string | null;
+}
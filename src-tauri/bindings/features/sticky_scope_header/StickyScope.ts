@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SwiftCodeBlockKind } from "../node_explanation/SwiftCodeBlockKind";
+import type { TextPosition } from "../../rules/utils/TextPosition";
+
+export interface StickyScope {
+  kind: SwiftCodeBlockKind;
+  header_text: string;
+  first_char_position: TextPosition;
+  last_char_position: TextPosition;
+}
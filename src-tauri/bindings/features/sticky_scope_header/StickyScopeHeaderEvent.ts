@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UpdateStickyScopeHeaderMessage } from "./UpdateStickyScopeHeaderMessage";
+
+export type StickyScopeHeaderEvent = {
+  event: "UpdateStickyScopeHeader";
+  payload: UpdateStickyScopeHeaderMessage;
+};
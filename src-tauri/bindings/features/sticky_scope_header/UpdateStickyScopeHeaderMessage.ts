@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StickyScope } from "./StickyScope";
+
+export interface UpdateStickyScopeHeaderMessage {
+  window_uid: number;
+  scopes: Array<StickyScope>;
+}
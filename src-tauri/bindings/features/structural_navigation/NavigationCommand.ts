@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type NavigationCommand =
+  | "MatchingBracket"
+  | "EnclosingBlockStart"
+  | "EnclosingBlockEnd"
+  | "NextDeclaration"
+  | "PreviousDeclaration"
+  | "NextComplexityHotspot"
+  | "PreviousComplexityHotspot";
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TextRange } from "../../rules/utils/TextRange";
+
+export interface MetavariableCapture {
+  name: string;
+  text: string;
+  text_range: TextRange;
+}
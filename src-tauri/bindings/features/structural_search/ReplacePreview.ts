@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface ReplacePreview {
+  replacement_count: number;
+  old_text_content_string: string;
+  new_text_content_string: string;
+  unified_diff: string;
+}
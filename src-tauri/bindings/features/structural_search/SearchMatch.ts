@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MetavariableCapture } from "./MetavariableCapture";
+import type { TextRange } from "../../rules/utils/TextRange";
+
+export interface SearchMatch {
+  window_uid: number;
+  file_path: string | null;
+  text_range: TextRange;
+  line: number;
+  column: number;
+  matched_text: string;
+  captures: Array<MetavariableCapture>;
+  score: number;
+}
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UpdateReplacePreviewMessage } from "./UpdateReplacePreviewMessage";
+import type { UpdateSearchResultsMessage } from "./UpdateSearchResultsMessage";
+
+export type StructuralSearchEvent =
+  | { event: "UpdateSearchResults"; payload: UpdateSearchResultsMessage }
+  | { event: "UpdateReplacePreview"; payload: UpdateReplacePreviewMessage };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReplacePreview } from "./ReplacePreview";
+import type { ReplaceQueryMessage } from "../../user_interaction/ReplaceQueryMessage";
+
+export interface UpdateReplacePreviewMessage {
+  query: ReplaceQueryMessage;
+  preview: ReplacePreview | null;
+  error: string | null;
+}
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SearchMatch } from "./SearchMatch";
+import type { SearchQueryMessage } from "../../user_interaction/SearchQueryMessage";
+
+export interface UpdateSearchResultsMessage {
+  query: SearchQueryMessage;
+  matches: Array<SearchMatch>;
+  error: string | null;
+}
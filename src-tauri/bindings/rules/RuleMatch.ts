@@ -2,6 +2,7 @@
 import type { LogicalFrame } from "../geometry/LogicalFrame";
 import type { MatchRange } from "./utils/MatchRange";
 import type { RuleMatchProps } from "./RuleMatchProps";
+import type { RuleMatchQuickFix } from "./RuleMatchQuickFix";
 import type { RuleName } from "./RuleName";
 
 export interface RuleMatch {
@@ -11,4 +12,5 @@ export interface RuleMatch {
   rectangles: Array<LogicalFrame>;
   rule_name: RuleName;
   match_properties: RuleMatchProps;
+  quick_fix: RuleMatchQuickFix | null;
 }
@@ -4,4 +4,5 @@ export type RuleName =
   | "BracketHighlight"
   | "SearchAndReplace"
   | "SwiftLinter"
+  | "DeadCode"
   | "None";
@@ -1,8 +1,15 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AiFeaturesStatusMessage } from "./AiFeaturesStatusMessage";
+import type { ApplyQuickFixMessage } from "./ApplyQuickFixMessage";
+import type { DetectClonesMessage } from "./DetectClonesMessage";
 import type { DismissSuggestionMessage } from "./DismissSuggestionMessage";
+import type { LocalRefactoringMessage } from "./LocalRefactoringMessage";
+import type { NavigationCommandMessage } from "./NavigationCommandMessage";
 import type { NodeAnnotationClickedMessage } from "./NodeAnnotationClickedMessage";
 import type { PerformSuggestionMessage } from "./PerformSuggestionMessage";
+import type { RenameSymbolMessage } from "./RenameSymbolMessage";
+import type { ReplaceQueryMessage } from "./ReplaceQueryMessage";
+import type { SearchQueryMessage } from "./SearchQueryMessage";
 import type { SwiftFormatOnCMDSMessage } from "./SwiftFormatOnCMDSMessage";
 import type { UpdateSelectedSuggestionMessage } from "./UpdateSelectedSuggestionMessage";
 
@@ -16,4 +23,14 @@ export type EventUserInteraction =
     payload: UpdateSelectedSuggestionMessage;
   }
   | { event: "ToggleMainWindow"; payload: boolean }
-  | { event: "NodeAnnotationClicked"; payload: NodeAnnotationClickedMessage };
+  | { event: "NodeAnnotationClicked"; payload: NodeAnnotationClickedMessage }
+  | { event: "Navigate"; payload: NavigationCommandMessage }
+  | { event: "Search"; payload: SearchQueryMessage }
+  | { event: "PreviewReplace"; payload: ReplaceQueryMessage }
+  | { event: "PerformReplace"; payload: ReplaceQueryMessage }
+  | { event: "PreviewRenameSymbol"; payload: RenameSymbolMessage }
+  | { event: "PerformRenameSymbol"; payload: RenameSymbolMessage }
+  | { event: "PreviewLocalRefactoring"; payload: LocalRefactoringMessage }
+  | { event: "PerformLocalRefactoring"; payload: LocalRefactoringMessage }
+  | { event: "ApplyQuickFix"; payload: ApplyQuickFixMessage }
+  | { event: "DetectClones"; payload: DetectClonesMessage };
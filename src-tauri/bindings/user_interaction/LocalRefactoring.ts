@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type LocalRefactoring =
+  | {
+    kind: "ExtractVariable";
+    variable_name: string;
+    replace_all_occurrences: boolean;
+  }
+  | { kind: "InlineVariable" }
+  | { kind: "ExtractFunction" };
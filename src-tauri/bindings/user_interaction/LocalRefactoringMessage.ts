@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LocalRefactoring } from "./LocalRefactoring";
+import type { TextRange } from "../rules/utils/TextRange";
+
+export interface LocalRefactoringMessage {
+  refactoring: LocalRefactoring;
+  text_range: TextRange | null;
+  editor_window_uid: number;
+}
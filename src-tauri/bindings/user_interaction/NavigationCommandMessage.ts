@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NavigationCommand } from "../features/structural_navigation/NavigationCommand";
+
+export interface NavigationCommandMessage {
+  command: NavigationCommand;
+  editor_window_uid: number;
+}
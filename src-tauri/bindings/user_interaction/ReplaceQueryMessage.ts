@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SearchQueryMessage } from "./SearchQueryMessage";
+
+export interface ReplaceQueryMessage {
+  search: SearchQueryMessage;
+  template: string;
+  editor_window_uid: number;
+}
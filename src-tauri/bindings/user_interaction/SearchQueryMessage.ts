@@ -1,5 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SearchQueryKind } from "./SearchQueryKind";
 
 export interface SearchQueryMessage {
   query: string;
+  kind: SearchQueryKind;
+  case_sensitive: boolean;
 }
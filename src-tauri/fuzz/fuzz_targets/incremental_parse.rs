@@ -0,0 +1,81 @@
+#![no_main]
+
+// Edits the text in steps and updates the tree and its metadata the way `SwiftSyntaxTree` does
+// when the editor's text changes. After each step they must equal a fresh parse of the text:
+// `cargo fuzz run incremental_parse`.
+
+use libfuzzer_sys::fuzz_target;
+use pretzl::core_engine::{
+    syntax_tree::{
+        calculate_cognitive_complexities, detect_input_edits, SwiftSyntaxTree, TreeMetaData,
+    },
+    XcodeText,
+};
+use tree_sitter::Range;
+
+// (start, removed char count, inserted text); positions beyond the end of the text wrap around.
+type TextEdit = (usize, u8, String);
+
+fuzz_target!(|input: (String, Vec<TextEdit>)| {
+    let (code, edits) = input;
+    let mut parser = SwiftSyntaxTree::parser();
+
+    let mut chars: Vec<char> = code.chars().collect();
+    let mut text = XcodeText::from_str(&code);
+    let mut tree = SwiftSyntaxTree::parse(&mut parser, &text, None).unwrap();
+    let mut metadata = TreeMetaData::default();
+    _ = calculate_cognitive_complexities(&tree.root_node(), &text, &mut metadata, None);
+
+    for (start, removed_char_count, inserted) in edits {
+        // Edited on char boundaries, so the text stays valid UTF-16.
+        let start = start % (chars.len() + 1);
+        let end = (start + removed_char_count as usize).min(chars.len());
+        chars.splice(start..end, inserted.chars());
+        let new_text = XcodeText::from_str(&chars.iter().collect::<String>());
+
+        let input_edits = detect_input_edits(&text, &new_text, 20);
+        for edit in &input_edits {
+            tree.edit(edit);
+        }
+        metadata.apply_edits(&input_edits);
+        let new_tree = SwiftSyntaxTree::parse(&mut parser, &new_text, Some(&tree)).unwrap();
+        let changed_ranges: Vec<Range> = tree.changed_ranges(&new_tree).collect();
+        metadata.remove_changed_ranges(&changed_ranges);
+        let complexities =
+            calculate_cognitive_complexities(&new_tree.root_node(), &new_text, &mut metadata, None)
+                .ok();
+
+        let fresh_tree = SwiftSyntaxTree::parse(&mut parser, &new_text, None).unwrap();
+        let mut fresh_metadata = TreeMetaData::default();
+        let fresh_complexities = calculate_cognitive_complexities(
+            &fresh_tree.root_node(),
+            &new_text,
+            &mut fresh_metadata,
+            None,
+        )
+        .ok();
+
+        // Error recovery may take another path when parsing incrementally.
+        if !fresh_tree.root_node().has_error() {
+            assert_eq!(
+                new_tree.root_node().to_sexp(),
+                fresh_tree.root_node().to_sexp()
+            );
+            assert_eq!(complexities, fresh_complexities);
+
+            let mut nodes = vec![fresh_tree.root_node()];
+            while let Some(node) = nodes.pop() {
+                assert_eq!(
+                    metadata.get(&node).map(|metadata| &metadata.complexities),
+                    fresh_metadata
+                        .get(&node)
+                        .map(|metadata| &metadata.complexities)
+                );
+                nodes.extend(node.named_children(&mut node.walk()));
+            }
+        }
+
+        text = new_text;
+        tree = new_tree;
+    }
+});
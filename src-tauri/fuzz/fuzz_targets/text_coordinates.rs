@@ -0,0 +1,32 @@
+#![no_main]
+
+// Every UTF-16 index of the text maps to a position and back, and the positions tree-sitter
+// reports for the nodes agree with their byte offsets: `cargo fuzz run text_coordinates`.
+
+use libfuzzer_sys::fuzz_target;
+use pretzl::core_engine::{syntax_tree::SwiftSyntaxTree, TextPosition, XcodeText};
+
+fuzz_target!(|code: String| {
+    let text = XcodeText::from_str(&code);
+
+    for index in 0..=text.len() {
+        let position = TextPosition::from_TextIndex(&text, index).unwrap();
+        assert_eq!(position.as_TextIndex(&text), Some(index));
+        assert_eq!(TextPosition::from_TSPoint(&position.as_TSPoint()), position);
+    }
+    assert_eq!(TextPosition::from_TextIndex(&text, text.len() + 1), None);
+
+    let tree = SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &text, None).unwrap();
+    let mut nodes = vec![tree.root_node()];
+    while let Some(node) = nodes.pop() {
+        let start_position = TextPosition::from_TSPoint(&node.start_position());
+        let end_position = TextPosition::from_TSPoint(&node.end_position());
+        assert_eq!(
+            start_position.as_TextIndex(&text),
+            Some(node.start_byte() / 2)
+        );
+        assert_eq!(end_position.as_TextIndex(&text), Some(node.end_byte() / 2));
+
+        nodes.extend(node.children(&mut node.walk()));
+    }
+});
@@ -0,0 +1,67 @@
+use serde_json::{json, Value};
+
+pub const CURRENT_SETTINGS_VERSION: u32 = 1;
+
+// `MIGRATIONS[n]` turns settings of version `n` into version `n + 1`. Version 0 is the former
+// `CoreEngineState`, which had no version field.
+const MIGRATIONS: [fn(Value) -> Value; 1] = [migrate_v0_to_v1];
+
+// Settings written by a newer version of the app are returned unchanged; fields this version
+// does not know are ignored when deserializing.
+pub fn migrate(mut settings: Value) -> Value {
+    let version = settings.get("version").and_then(Value::as_u64).unwrap_or(0) as usize;
+
+    for migration in MIGRATIONS.iter().skip(version) {
+        settings = migration(settings);
+    }
+
+    settings
+}
+
+fn migrate_v0_to_v1(legacy_state: Value) -> Value {
+    let mut settings = json!({ "version": 1 });
+    if let Some(ai_features_active) = legacy_state.get("ai_features_active") {
+        settings["ai_features_active"] = ai_features_active.clone();
+    }
+    if let Some(swift_format_on_cmd_s) = legacy_state.get("swift_format_on_cmd_s") {
+        settings["formatter"] = json!({ "format_on_cmd_s": swift_format_on_cmd_s });
+    }
+
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    mod migrate {
+        use serde_json::json;
+
+        use crate::app_state::{migrate, CURRENT_SETTINGS_VERSION};
+
+        #[test]
+        fn legacy_core_engine_state() {
+            let settings = migrate(json!({
+                "ai_features_active": false,
+                "swift_format_on_cmd_s": false
+            }));
+
+            assert_eq!(
+                settings,
+                json!({
+                    "version": CURRENT_SETTINGS_VERSION,
+                    "ai_features_active": false,
+                    "formatter": { "format_on_cmd_s": false }
+                })
+            );
+        }
+
+        #[test]
+        fn current_and_newer_versions_are_unchanged() {
+            let current =
+                json!({ "version": CURRENT_SETTINGS_VERSION, "ai_features_active": false });
+            let newer = json!({ "version": CURRENT_SETTINGS_VERSION + 1, "unknown": true });
+
+            assert_eq!(migrate(current.clone()), current);
+            assert_eq!(migrate(newer.clone()), newer);
+        }
+    }
+}
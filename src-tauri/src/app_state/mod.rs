@@ -0,0 +1,14 @@
+pub use migrations::{migrate, CURRENT_SETTINGS_VERSION};
+pub use settings::{ExplanationProvider, FormatterSettings, Settings, ThresholdSettings};
+pub use settings_event::SettingsEvent;
+pub use settings_store::{
+    cmd_get_feature_states, cmd_get_settings, cmd_set_feature_enabled, cmd_update_settings,
+    init_settings, settings, settings_for_file, update_settings, SettingsError, SettingsStore,
+    LEGACY_CORE_ENGINE_STATE_FILENAME, PROJECT_SETTINGS_FILENAME, PROJECT_SETTINGS_TOML_FILENAME,
+    SETTINGS_FILENAME, SETTINGS_TOML_FILENAME,
+};
+
+mod migrations;
+mod settings;
+mod settings_event;
+mod settings_store;
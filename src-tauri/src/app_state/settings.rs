@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use ts_rs::TS;
+
+use crate::{
+    core_engine::{FeatureKind, Keymap},
+    utils::telemetry::TelemetryLevel,
+};
+
+use super::{SettingsError, CURRENT_SETTINGS_VERSION};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export, export_to = "bindings/app_state/")]
+pub struct Settings {
+    pub version: u32,
+    pub ai_features_active: bool,
+    pub features: HashMap<FeatureKind, bool>, // Features which are not listed are enabled
+    pub thresholds: ThresholdSettings,
+    pub shortcuts: HashMap<String, String>, // Command name to key combination, e.g. `"format_document": "cmd+s"`
+    pub telemetry_level: Option<TelemetryLevel>, // Overrides the level configured in `telemetry.json`
+    pub explanation_provider: ExplanationProvider,
+    pub formatter: FormatterSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_SETTINGS_VERSION,
+            ai_features_active: true,
+            features: HashMap::new(),
+            thresholds: ThresholdSettings::default(),
+            shortcuts: HashMap::new(),
+            telemetry_level: None,
+            explanation_provider: ExplanationProvider::PretzlCloud,
+            formatter: FormatterSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn is_feature_enabled(&self, feature_kind: &FeatureKind) -> bool {
+        self.features.get(feature_kind).cloned().unwrap_or(true)
+    }
+
+    pub fn feature_states(&self) -> HashMap<FeatureKind, bool> {
+        FeatureKind::iter()
+            .map(|feature_kind| {
+                let enabled = self.is_feature_enabled(&feature_kind);
+                (feature_kind, enabled)
+            })
+            .collect()
+    }
+
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.thresholds.max_function_complexity < 0 {
+            return Err(SettingsError::Invalid(
+                "max_function_complexity must not be negative".to_string(),
+            ));
+        }
+        if self.thresholds.min_clone_tokens == 0 {
+            return Err(SettingsError::Invalid(
+                "min_clone_tokens must be positive".to_string(),
+            ));
+        }
+        if self.formatter.max_width == 0 || self.formatter.indent == 0 {
+            return Err(SettingsError::Invalid(
+                "formatter max_width and indent must be positive".to_string(),
+            ));
+        }
+        if let ExplanationProvider::Custom { url } = &self.explanation_provider {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(SettingsError::Invalid(format!(
+                    "explanation provider url `{}` is not an http(s) url",
+                    url
+                )));
+            }
+        }
+        if let Some(err) = Keymap::from_shortcuts(&self.shortcuts).1.into_iter().next() {
+            return Err(SettingsError::Invalid(err.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export, export_to = "bindings/app_state/")]
+pub struct ThresholdSettings {
+    pub max_function_complexity: isize, // Functions above are offered a refactoring
+    pub min_clone_tokens: usize,        // Duplicated code below is not reported
+}
+
+impl Default for ThresholdSettings {
+    fn default() -> Self {
+        Self {
+            max_function_complexity: 9,
+            min_clone_tokens: 40,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "kind")]
+#[ts(export, export_to = "bindings/app_state/")]
+pub enum ExplanationProvider {
+    PretzlCloud,
+    Custom { url: String },
+}
+
+// Only applies if the project has no `.swiftformat` file of its own.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export, export_to = "bindings/app_state/")]
+pub struct FormatterSettings {
+    pub format_on_cmd_s: bool,
+    pub max_width: usize,
+    pub indent: usize,
+}
+
+impl Default for FormatterSettings {
+    fn default() -> Self {
+        Self {
+            format_on_cmd_s: true,
+            max_width: 100,
+            indent: 4,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod feature_states {
+        use strum::IntoEnumIterator;
+
+        use crate::{app_state::Settings, core_engine::FeatureKind};
+
+        #[test]
+        fn unlisted_features_are_enabled() {
+            let mut settings = Settings::default();
+            settings.features.insert(FeatureKind::ScopeGuides, false);
+            settings.features.insert(FeatureKind::Formatter, true);
+
+            let feature_states = settings.feature_states();
+
+            assert_eq!(feature_states.len(), FeatureKind::iter().count());
+            assert!(!feature_states[&FeatureKind::ScopeGuides]);
+            assert!(feature_states[&FeatureKind::Formatter]);
+            assert!(feature_states[&FeatureKind::BracketHighlight]);
+        }
+    }
+
+    mod validate {
+        use crate::app_state::{Settings, SettingsError};
+
+        #[test]
+        fn rejects_conflicting_shortcuts() {
+            let mut settings = Settings::default();
+            settings
+                .shortcuts
+                .insert("format_document".to_string(), "cmd+k".to_string());
+            settings
+                .shortcuts
+                .insert("explain_selection".to_string(), "cmd+k cmd+e".to_string());
+
+            assert!(matches!(
+                settings.validate(),
+                Err(SettingsError::Invalid(_))
+            ));
+        }
+
+        #[test]
+        fn accepts_rebound_defaults() {
+            let mut settings = Settings::default();
+            settings
+                .shortcuts
+                .insert("explain_selection".to_string(), "cmd+s".to_string());
+
+            assert!(settings.validate().is_ok());
+        }
+    }
+}
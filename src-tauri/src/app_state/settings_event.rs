@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use ts_rs::TS;
+
+use crate::{app_handle, utils::messaging::ChannelList};
+
+use super::Settings;
+
+#[derive(Clone, Serialize, Deserialize, Debug, TS)]
+#[serde(tag = "event", content = "payload")]
+#[ts(export, export_to = "bindings/app_state/")]
+pub enum SettingsEvent {
+    SettingsChanged(Settings),
+}
+
+impl SettingsEvent {
+    pub fn publish_to_tauri(&self) {
+        let event_name = ChannelList::SettingsEvent.to_string();
+
+        // Emit to rust listeners
+        app_handle().trigger_global(
+            event_name.as_str(),
+            Some(serde_json::to_string(self).unwrap()),
+        );
+
+        // Emit to all windows
+        _ = app_handle().emit_all(
+            event_name.as_str(),
+            Some(serde_json::to_string(self).unwrap()),
+        );
+    }
+}
@@ -0,0 +1,550 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::core_engine::FeatureKind;
+
+use super::{migrate, Settings, SettingsEvent, CURRENT_SETTINGS_VERSION};
+
+pub const SETTINGS_FILENAME: &str = "settings.json";
+pub const SETTINGS_TOML_FILENAME: &str = "settings.toml";
+pub const PROJECT_SETTINGS_FILENAME: &str = ".pretzl.json";
+pub const PROJECT_SETTINGS_TOML_FILENAME: &str = ".pretzl.toml";
+pub const LEGACY_CORE_ENGINE_STATE_FILENAME: &str = ".app-core-engine-state";
+
+#[derive(thiserror::Error, Debug)]
+pub enum SettingsError {
+    #[error("Settings could not be read or written.")]
+    Io(#[from] std::io::Error),
+    #[error("Settings could not be serialized.")]
+    Json(#[from] serde_json::Error),
+    #[error("Settings could not be read as TOML.")]
+    TomlRead(#[from] toml::de::Error),
+    #[error("Settings could not be serialized as TOML.")]
+    TomlWrite(#[from] toml::ser::Error),
+    #[error("Invalid settings: {0}")]
+    Invalid(String),
+}
+
+// The bincode layout of the former `CoreEngineState`; only read to migrate it.
+#[derive(Serialize, Deserialize)]
+struct LegacyCoreEngineState {
+    ai_features_active: bool,
+    swift_format_on_cmd_s: bool,
+}
+
+struct ProjectOverrides {
+    modified: Option<SystemTime>,
+    overrides: Option<Value>, // None if the file is malformed
+}
+
+pub struct SettingsStore {
+    path: Option<PathBuf>, // None keeps the settings in memory only
+    user_settings: Settings,
+    project_overrides: HashMap<PathBuf, ProjectOverrides>,
+}
+
+impl SettingsStore {
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            user_settings: Settings::default(),
+            project_overrides: HashMap::new(),
+        }
+    }
+
+    // Reads `settings.json`, or `settings.toml` if the user keeps their settings in TOML; they
+    // are saved in the same format. Falls back to the former `CoreEngineState` file and then to
+    // the defaults. Migrated settings are written back right away, so the migration only runs once.
+    pub fn load(app_dir: &Path) -> Self {
+        let toml_path = app_dir.join(SETTINGS_TOML_FILENAME);
+        let path = if !app_dir.join(SETTINGS_FILENAME).is_file() && toml_path.is_file() {
+            toml_path
+        } else {
+            app_dir.join(SETTINGS_FILENAME)
+        };
+        let legacy_path = app_dir.join(LEGACY_CORE_ENGINE_STATE_FILENAME);
+
+        let stored_settings = match fs::read_to_string(&path) {
+            Ok(content) => parse_settings_file(&path, &content)
+                .map_err(|err| warn!(?err, "Malformed settings file, using defaults"))
+                .ok(),
+            Err(_) => fs::read(&legacy_path)
+                .ok()
+                .and_then(|content| bincode::deserialize::<LegacyCoreEngineState>(&content).ok())
+                .and_then(|legacy_state| serde_json::to_value(legacy_state).ok()),
+        };
+
+        let stored_version = stored_settings
+            .as_ref()
+            .and_then(|settings| settings.get("version"))
+            .and_then(Value::as_u64);
+        let stored_settings_found = stored_settings.is_some();
+        let user_settings = stored_settings
+            .map(migrate)
+            .and_then(|settings| serde_json::from_value::<Settings>(settings).ok())
+            .unwrap_or_default();
+
+        let store = Self {
+            path: Some(path),
+            user_settings,
+            project_overrides: HashMap::new(),
+        };
+
+        let is_outdated = match (stored_settings_found, stored_version) {
+            (false, _) => false,
+            (true, Some(version)) if version > CURRENT_SETTINGS_VERSION as u64 => {
+                warn!(
+                    version,
+                    "Settings were written by a newer version of the app"
+                );
+                false
+            }
+            (true, version) => version.unwrap_or(0) < CURRENT_SETTINGS_VERSION as u64,
+        };
+        if is_outdated {
+            match store.save() {
+                Ok(_) => {
+                    _ = fs::remove_file(legacy_path);
+                }
+                Err(err) => warn!(?err, "Failed to save migrated settings"),
+            }
+        }
+        debug!(settings = ?store.user_settings, "Loaded settings");
+
+        store
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.user_settings
+    }
+
+    // The user settings, with the `.pretzl.json` or `.pretzl.toml` closest to the file merged
+    // over them.
+    pub fn settings_for_file(&mut self, file_path: &Option<String>) -> Settings {
+        let overrides = match file_path
+            .as_ref()
+            .and_then(|file_path| find_project_settings_file(Path::new(file_path)))
+        {
+            Some(overrides_path) => self.load_project_overrides(overrides_path),
+            None => None,
+        };
+
+        let overrides = match overrides {
+            Some(overrides) => overrides,
+            None => return self.user_settings.clone(),
+        };
+
+        let mut merged = match serde_json::to_value(&self.user_settings) {
+            Ok(merged) => merged,
+            Err(_) => return self.user_settings.clone(),
+        };
+        merge_json(&mut merged, overrides);
+
+        match serde_json::from_value::<Settings>(merged) {
+            Ok(settings) if settings.validate().is_ok() => settings,
+            _ => {
+                warn!("Project settings are invalid, using user settings");
+                self.user_settings.clone()
+            }
+        }
+    }
+
+    // Returns whether the settings changed.
+    pub fn update(&mut self, settings: Settings) -> Result<bool, SettingsError> {
+        settings.validate()?;
+        if settings == self.user_settings {
+            return Ok(false);
+        }
+
+        let previous_settings = std::mem::replace(&mut self.user_settings, settings);
+        if let Err(err) = self.save() {
+            self.user_settings = previous_settings;
+            return Err(err);
+        }
+
+        Ok(true)
+    }
+
+    fn save(&self) -> Result<(), SettingsError> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let content = if is_toml_file(path) {
+            // TOML has no null, so unset options are left out
+            let mut settings = serde_json::to_value(&self.user_settings)?;
+            remove_nulls(&mut settings);
+            toml::to_string_pretty(&toml::Value::try_from(settings)?)?
+        } else {
+            serde_json::to_string_pretty(&self.user_settings)?
+        };
+
+        let mut temp_path = path.clone().into_os_string();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(temp_path, path)?;
+
+        Ok(())
+    }
+
+    // Cached until the file is modified.
+    fn load_project_overrides(&mut self, path: PathBuf) -> Option<Value> {
+        let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+        if let Some(cached) = self.project_overrides.get(&path) {
+            if cached.modified == modified {
+                return cached.overrides.clone();
+            }
+        }
+
+        let overrides = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| parse_settings_file(&path, &content).ok())
+            .map(|mut overrides| {
+                // A project cannot change the schema version of the user settings.
+                if let Some(fields) = overrides.as_object_mut() {
+                    fields.remove("version");
+                }
+                overrides
+            });
+        if overrides.is_none() {
+            warn!(?path, "Malformed project settings file");
+        }
+
+        self.project_overrides.insert(
+            path,
+            ProjectOverrides {
+                modified,
+                overrides: overrides.clone(),
+            },
+        );
+
+        overrides
+    }
+}
+
+fn find_project_settings_file(file_path: &Path) -> Option<PathBuf> {
+    file_path.ancestors().skip(1).find_map(|directory| {
+        [PROJECT_SETTINGS_FILENAME, PROJECT_SETTINGS_TOML_FILENAME]
+            .iter()
+            .map(|filename| directory.join(filename))
+            .find(|path| path.is_file())
+    })
+}
+
+fn is_toml_file(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension == "toml")
+}
+
+// Both formats are read into JSON values, so migrations and merging only deal with one format.
+fn parse_settings_file(path: &Path, content: &str) -> Result<Value, SettingsError> {
+    if is_toml_file(path) {
+        Ok(toml::from_str::<Value>(content)?)
+    } else {
+        Ok(serde_json::from_str::<Value>(content)?)
+    }
+}
+
+fn remove_nulls(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            fields.retain(|_, value| !value.is_null());
+            fields.values_mut().for_each(remove_nulls);
+        }
+        Value::Array(values) => values.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+// Objects are merged key by key; any other value replaces the base value.
+fn merge_json(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base_fields), Value::Object(override_fields)) => {
+            for (key, value) in override_fields {
+                match base_fields.get_mut(&key) {
+                    Some(base_value) => merge_json(base_value, value),
+                    None => {
+                        base_fields.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+lazy_static! {
+    static ref SETTINGS_STORE: Mutex<SettingsStore> = Mutex::new(SettingsStore::in_memory());
+}
+
+// Read before Tauri is set up, since the telemetry level is part of the settings.
+pub fn init_settings(app_dir: Option<&Path>) {
+    if let Some(app_dir) = app_dir {
+        *SETTINGS_STORE.lock() = SettingsStore::load(app_dir);
+    }
+}
+
+pub fn settings() -> Settings {
+    SETTINGS_STORE.lock().settings().clone()
+}
+
+pub fn settings_for_file(file_path: &Option<String>) -> Settings {
+    SETTINGS_STORE.lock().settings_for_file(file_path)
+}
+
+// Persists the updated settings and notifies all listeners if anything changed. The store is
+// locked while `update` runs, so it must not read the settings itself.
+pub fn update_settings<F>(update: F) -> Result<Settings, SettingsError>
+where
+    F: FnOnce(&mut Settings),
+{
+    // Held for the whole read-modify-write, so concurrent updates do not overwrite each other.
+    // Listeners are only notified once it is released.
+    let mut store = SETTINGS_STORE.lock();
+    let mut settings = store.settings().clone();
+    update(&mut settings);
+
+    let changed = store.update(settings.clone())?;
+    drop(store);
+
+    if changed {
+        SettingsEvent::SettingsChanged(settings.clone()).publish_to_tauri();
+    }
+
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn cmd_get_settings(file_path: Option<String>) -> Settings {
+    settings_for_file(&file_path)
+}
+
+#[tauri::command]
+pub fn cmd_update_settings(settings: Settings) -> Result<Settings, String> {
+    update_settings(|current| *current = settings).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn cmd_get_feature_states() -> HashMap<FeatureKind, bool> {
+    settings().feature_states()
+}
+
+// The core engine (de)activates the feature once it is notified of the changed settings.
+#[tauri::command]
+pub fn cmd_set_feature_enabled(
+    feature: FeatureKind,
+    enabled: bool,
+) -> Result<HashMap<FeatureKind, bool>, String> {
+    update_settings(|settings| {
+        settings.features.insert(feature, enabled);
+    })
+    .map(|settings| settings.feature_states())
+    .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    mod load {
+        use std::fs;
+
+        use crate::app_state::{
+            Settings, SettingsStore, CURRENT_SETTINGS_VERSION, LEGACY_CORE_ENGINE_STATE_FILENAME,
+            SETTINGS_FILENAME, SETTINGS_TOML_FILENAME,
+        };
+
+        use super::super::LegacyCoreEngineState;
+
+        #[test]
+        fn missing_file_falls_back_to_default() {
+            let app_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+
+            let store = SettingsStore::load(&app_dir);
+
+            assert_eq!(*store.settings(), Settings::default());
+            _ = fs::remove_dir_all(app_dir);
+        }
+
+        #[test]
+        fn migrates_legacy_core_engine_state() {
+            let app_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            fs::create_dir_all(&app_dir).unwrap();
+            let legacy_state = bincode::serialize(&LegacyCoreEngineState {
+                ai_features_active: false,
+                swift_format_on_cmd_s: false,
+            })
+            .unwrap();
+            fs::write(
+                app_dir.join(LEGACY_CORE_ENGINE_STATE_FILENAME),
+                legacy_state,
+            )
+            .unwrap();
+
+            let store = SettingsStore::load(&app_dir);
+
+            assert!(!store.settings().ai_features_active);
+            assert!(!store.settings().formatter.format_on_cmd_s);
+            assert!(!app_dir.join(LEGACY_CORE_ENGINE_STATE_FILENAME).exists());
+            let saved: Settings =
+                serde_json::from_str(&fs::read_to_string(app_dir.join(SETTINGS_FILENAME)).unwrap())
+                    .unwrap();
+            assert_eq!(saved.version, CURRENT_SETTINGS_VERSION);
+            assert_eq!(saved, *store.settings());
+
+            _ = fs::remove_dir_all(app_dir);
+        }
+
+        #[test]
+        fn partial_file_keeps_defaults() {
+            let app_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            fs::create_dir_all(&app_dir).unwrap();
+            fs::write(
+                app_dir.join(SETTINGS_FILENAME),
+                r#"{ "version": 1, "thresholds": { "max_function_complexity": 12 } }"#,
+            )
+            .unwrap();
+
+            let store = SettingsStore::load(&app_dir);
+
+            assert_eq!(store.settings().thresholds.max_function_complexity, 12);
+            assert!(store.settings().ai_features_active);
+            assert_eq!(store.settings().formatter.max_width, 100);
+
+            _ = fs::remove_dir_all(app_dir);
+        }
+
+        #[test]
+        fn toml_file() {
+            let app_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            fs::create_dir_all(&app_dir).unwrap();
+            fs::write(
+                app_dir.join(SETTINGS_TOML_FILENAME),
+                r#"
+                version = 1
+
+                [thresholds]
+                max_function_complexity = 12
+
+                [shortcuts]
+                "navigate.MatchingBracket" = "ctrl+cmd+b"
+                "#,
+            )
+            .unwrap();
+
+            let mut store = SettingsStore::load(&app_dir);
+
+            assert_eq!(store.settings().thresholds.max_function_complexity, 12);
+            assert_eq!(
+                store.settings().shortcuts.get("navigate.MatchingBracket"),
+                Some(&"ctrl+cmd+b".to_string())
+            );
+
+            // Changes are saved as TOML as well
+            let mut settings = store.settings().clone();
+            settings.ai_features_active = false;
+            store.update(settings.clone()).unwrap();
+            assert!(!app_dir.join(SETTINGS_FILENAME).exists());
+            assert_eq!(*SettingsStore::load(&app_dir).settings(), settings);
+
+            _ = fs::remove_dir_all(app_dir);
+        }
+    }
+
+    mod update {
+        use std::fs;
+
+        use crate::app_state::{ExplanationProvider, Settings, SettingsStore};
+
+        #[test]
+        fn persists_changes_and_rejects_invalid_settings() {
+            let app_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            let mut store = SettingsStore::load(&app_dir);
+
+            let mut settings = store.settings().clone();
+            assert!(!store.update(settings.clone()).unwrap());
+
+            settings.ai_features_active = false;
+            assert!(store.update(settings.clone()).unwrap());
+            assert_eq!(*SettingsStore::load(&app_dir).settings(), settings);
+
+            let invalid = Settings {
+                explanation_provider: ExplanationProvider::Custom {
+                    url: "ftp://example.com".to_string(),
+                },
+                ..settings.clone()
+            };
+            assert!(store.update(invalid).is_err());
+            assert_eq!(*store.settings(), settings);
+
+            _ = fs::remove_dir_all(app_dir);
+        }
+    }
+
+    mod settings_for_file {
+        use std::fs;
+
+        use crate::app_state::{
+            SettingsStore, PROJECT_SETTINGS_FILENAME, PROJECT_SETTINGS_TOML_FILENAME,
+        };
+
+        #[test]
+        fn merges_closest_project_settings() {
+            let project_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            let sources_dir = project_dir.join("Sources");
+            fs::create_dir_all(&sources_dir).unwrap();
+            fs::write(
+                project_dir.join(PROJECT_SETTINGS_FILENAME),
+                r#"{ "version": 99, "formatter": { "indent": 2 }, "features": { "ScopeGuides": false } }"#,
+            )
+            .unwrap();
+            let mut store = SettingsStore::in_memory();
+
+            let settings = store.settings_for_file(&Some(
+                sources_dir.join("App.swift").to_string_lossy().to_string(),
+            ));
+
+            assert_eq!(settings.formatter.indent, 2);
+            assert_eq!(settings.formatter.max_width, 100);
+            assert!(settings.formatter.format_on_cmd_s);
+            assert!(!settings.is_feature_enabled(&crate::core_engine::FeatureKind::ScopeGuides));
+            assert_eq!(settings.version, store.settings().version);
+            assert_eq!(store.settings_for_file(&None), *store.settings());
+
+            _ = fs::remove_dir_all(project_dir);
+        }
+
+        #[test]
+        fn reads_toml_project_settings() {
+            let project_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            fs::create_dir_all(&project_dir).unwrap();
+            fs::write(
+                project_dir.join(PROJECT_SETTINGS_TOML_FILENAME),
+                "[formatter]\nindent = 2\n",
+            )
+            .unwrap();
+            let mut store = SettingsStore::in_memory();
+
+            let settings = store.settings_for_file(&Some(
+                project_dir.join("App.swift").to_string_lossy().to_string(),
+            ));
+
+            assert_eq!(settings.formatter.indent, 2);
+            assert_eq!(settings.formatter.max_width, 100);
+
+            _ = fs::remove_dir_all(project_dir);
+        }
+    }
+}
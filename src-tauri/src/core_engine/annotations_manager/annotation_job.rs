@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::core_engine::{EditorWindowUid, TextRange};
+use crate::core_engine::{features::ScopeGuideProps, EditorWindowUid, TextRange};
 
 use super::{
     annotations_manager::{Annotation, AnnotationError, AnnotationResult},
@@ -20,6 +20,10 @@ pub enum AnnotationKind {
     CodeblockLastChar,
     ExtractionStartChar,
     ExtractionEndChar,
+    SearchMatchStartChar,
+    SearchMatchEndChar,
+    ScopeGuideStart(ScopeGuideProps),
+    ScopeGuideEnd(ScopeGuideProps),
 }
 
 // Wrapped lines are tricky to handle using the macOS AX API. Lines wrapping always yield a rectangle that stretches
@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use crate::core_engine::{
-    events::AnnotationEvent, features::FeatureKind, EditorWindowUid, TextRange,
+    diagnostics::DiagnosticsSpan, events::AnnotationEvent, features::FeatureKind, EditorWindowUid,
+    TextRange,
 };
 
 use super::{AnnotationGroup, AnnotationJob, AnnotationJobTrait, AnnotationResult};
@@ -74,6 +75,7 @@ impl AnnotationJobGroupTrait for AnnotationJobGroup {
     }
 
     fn compute_annotations(&mut self, visible_text_range: &TextRange) {
+        let span = DiagnosticsSpan::enter(self.feature.clone(), "compute_annotations");
         for job in self.jobs.values_mut() {
             if let Ok(result) = job.compute_bounds(visible_text_range, self.editor_window_uid) {
                 self.results.insert(result.id, result);
@@ -81,11 +83,13 @@ impl AnnotationJobGroupTrait for AnnotationJobGroup {
                 debug!(?job, feature = ?self.feature, "Failed to `compute_bounds`");
             }
         }
+        drop(span);
 
         self.publish_annotations();
     }
 
     fn update_annotations(&mut self, visible_text_range: &TextRange) {
+        let span = DiagnosticsSpan::enter(self.feature.clone(), "update_annotations");
         for job in self.jobs.values_mut() {
             if let Ok(result) =
                 job.compute_bounds_if_missing(visible_text_range, self.editor_window_uid)
@@ -95,6 +99,7 @@ impl AnnotationJobGroupTrait for AnnotationJobGroup {
                 debug!(?job, feature = ?self.feature, "Failed to `compute_bounds_if_missing`");
             }
         }
+        drop(span);
 
         self.publish_annotations();
     }
@@ -1,33 +1,40 @@
 use std::{
-    collections::HashMap,
-    hash::{Hash, Hasher},
+    collections::{HashMap, HashSet},
     sync::Arc,
 };
 
 use parking_lot::Mutex;
 use strum::IntoEnumIterator;
-use tauri::Manager;
-use tokio::sync::oneshot::{self, Receiver};
-use tracing::error;
+use tracing::{error, warn};
 
 use crate::{
     app_handle,
-    app_state::CoreEngineStateCache,
+    app_state::{settings, update_settings, Settings},
     platform::macos::{
-        get_selected_text_range, get_textarea_content, get_textarea_file_path, GetVia, XcodeError,
+        get_selected_text_range, get_textarea_content, get_textarea_file_path,
+        models::editor::EditorShortcutPressedMessage, GetVia, XcodeError,
     },
 };
 
 use super::{
     annotations_manager::{AnnotationsManager, AnnotationsManagerTrait},
+    diagnostics::DiagnosticsSpan,
+    events::{models::UpdateFeatureStatesMessage, FeatureStateEvent},
     features::{
-        BracketHighlight, ComplexityRefactoring, CoreEngineTrigger, DocsGenerator, Feature,
-        FeatureBase, FeatureError, FeatureKind, SwiftFormatter,
+        BracketHighlight, CloneDetection, ComplexityRefactoring, CoreEngineTrigger,
+        DeadCodeDetection, DocsGenerator, DocumentOutline, Feature, FeatureBase, FeatureError,
+        FeatureKind, ScopeGuides, StickyScopeHeader, StructuralNavigation, StructuralSearch,
+        SwiftFormatter,
+    },
+    keymap::{KeyChord, KeymapAction, KeymapResolution},
+    listeners::{
+        settings::settings_listener, user_interaction::user_interaction_listener,
+        xcode::xcode_listener,
     },
-    listeners::{user_interaction::user_interaction_listener, xcode::xcode_listener},
     log_list_of_module_names,
+    scheduler::{CancellationToken, CoreEngineProcedure, ProcedureScheduler, ScheduledProcedure},
     syntax_tree::SwiftSyntaxTree,
-    CodeDocument, EditorWindowProps, XcodeText,
+    CodeDocument, EditorWindowProps, Keymap, XcodeText,
 };
 
 pub type EditorWindowUid = usize;
@@ -54,43 +61,6 @@ impl From<XcodeError> for CoreEngineError {
     }
 }
 
-#[derive(Debug, Clone)]
-enum CodeDocUpdate {
-    Finished,
-    Cancelled,
-}
-
-#[derive(Debug, Clone)]
-pub struct CoreEngineProcedure {
-    pub feature: FeatureKind,
-    pub trigger: CoreEngineTrigger,
-    pub window_uid: EditorWindowUid,
-}
-
-impl CoreEngineProcedure {
-    pub fn new(
-        feature: FeatureKind,
-        trigger: CoreEngineTrigger,
-        window_uid: EditorWindowUid,
-    ) -> Self {
-        Self {
-            feature,
-            trigger,
-            window_uid,
-        }
-    }
-
-    fn hash(&self) -> u64 {
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        self.trigger.hash(&mut hasher);
-        self.feature.hash(&mut hasher);
-        self.window_uid.hash(&mut hasher);
-        hasher.finish()
-    }
-}
-
-type CoreEngineProcedureSchedule = HashMap<u64, CoreEngineProcedure>;
-
 pub struct CoreEngine {
     pub app_handle: tauri::AppHandle,
 
@@ -103,19 +73,17 @@ pub struct CoreEngine {
     ai_features_active: bool,
     swift_format_on_cmd_s_active: bool,
 
+    /// Features the user turned off; they are deactivated and never scheduled.
+    disabled_features: HashSet<FeatureKind>,
+
+    /// Maps the shortcuts pressed in the editor to the actions they trigger.
+    keymap: Keymap,
+
     /// Annotations manager handles where to draw annotations on the code editor via the CodeOverlay window
     _annotations_manager: Arc<Mutex<AnnotationsManager>>,
 
-    /// We only allow a the most recent combination of trigger and feature to be scheduled for execution.
-    /// Any newly scheduled feature execution replaces the previous one from the hash map.
-    feature_procedures_schedule: Arc<Mutex<CoreEngineProcedureSchedule>>,
-
-    /// A Sender that is used to cancel an ongoing code document update task
-    cancel_code_doc_update_task_send: Option<oneshot::Sender<&'static ()>>,
-    /// A receiver that gets notified when a code document update task is finished
-    finished_code_doc_update_task_recv: Option<Receiver<&'static CodeDocUpdate>>,
-    /// Is true if there is a procedures processor waiting to run once a code document has finished updating
-    awaiting_code_doc_update_task: Arc<Mutex<bool>>,
+    /// Debounces, prioritizes and cancels feature procedures and code document updates.
+    scheduler: Arc<Mutex<ProcedureScheduler>>,
 }
 
 impl CoreEngine {
@@ -123,19 +91,7 @@ impl CoreEngine {
         let annotations_manager = Arc::new(Mutex::new(AnnotationsManager::new()));
         AnnotationsManager::start_event_listeners(&annotations_manager);
 
-        let ai_features_active =
-            if let Some(cache) = app_handle().try_state::<CoreEngineStateCache>() {
-                cache.0.lock().ai_features_active
-            } else {
-                true
-            };
-
-        let swift_format_on_cmd_s_active =
-            if let Some(cache) = app_handle().try_state::<CoreEngineStateCache>() {
-                cache.0.lock().swift_format_on_cmd_s
-            } else {
-                true
-            };
+        let settings = settings();
 
         let mut features = HashMap::new();
         features.insert(
@@ -158,27 +114,112 @@ impl CoreEngine {
                 ComplexityRefactoring::new(),
             ))),
         );
+        features.insert(
+            FeatureKind::ScopeGuides,
+            Arc::new(Mutex::new(Feature::ScopeGuides(ScopeGuides::new()))),
+        );
+        features.insert(
+            FeatureKind::StickyScopeHeader,
+            Arc::new(Mutex::new(Feature::StickyScopeHeader(
+                StickyScopeHeader::new(),
+            ))),
+        );
+        features.insert(
+            FeatureKind::StructuralNavigation,
+            Arc::new(Mutex::new(Feature::StructuralNavigation(
+                StructuralNavigation::new(),
+            ))),
+        );
+        features.insert(
+            FeatureKind::DocumentOutline,
+            Arc::new(Mutex::new(Feature::DocumentOutline(DocumentOutline::new()))),
+        );
+        features.insert(
+            FeatureKind::StructuralSearch,
+            Arc::new(Mutex::new(Feature::StructuralSearch(
+                StructuralSearch::new(),
+            ))),
+        );
+        features.insert(
+            FeatureKind::DeadCode,
+            Arc::new(Mutex::new(Feature::DeadCode(DeadCodeDetection::new()))),
+        );
+        features.insert(
+            FeatureKind::CloneDetection,
+            Arc::new(Mutex::new(Feature::CloneDetection(CloneDetection::new()))),
+        );
 
-        Self {
+        let mut core_engine = Self {
             app_handle: app_handle(),
             code_documents: Arc::new(Mutex::new(HashMap::new())),
-            ai_features_active,
+            ai_features_active: settings.ai_features_active,
             features: Arc::new(Mutex::new(features)),
             _annotations_manager: annotations_manager,
-            feature_procedures_schedule: Arc::new(Mutex::new(HashMap::new())),
-            cancel_code_doc_update_task_send: None,
-            finished_code_doc_update_task_recv: None,
-            awaiting_code_doc_update_task: Arc::new(Mutex::new(false)),
-            swift_format_on_cmd_s_active,
-        }
+            scheduler: Arc::new(Mutex::new(ProcedureScheduler::new())),
+            swift_format_on_cmd_s_active: settings.formatter.format_on_cmd_s,
+            disabled_features: HashSet::new(),
+            keymap: Keymap::default(),
+        };
+
+        // All features are constructed active; deactivate the ones the user disabled.
+        core_engine.apply_settings(&settings);
+
+        core_engine
     }
 
-    pub fn set_ai_features_active(&mut self, ai_features_active: bool) {
-        self.ai_features_active = ai_features_active;
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.ai_features_active = settings.ai_features_active;
+        self.swift_format_on_cmd_s_active = settings.formatter.format_on_cmd_s;
+
+        let (keymap, keymap_errors) = Keymap::from_shortcuts(&settings.shortcuts);
+        for err in keymap_errors {
+            warn!(?err, "Ignoring shortcut from settings");
+        }
+        self.keymap = keymap;
+
+        let mut feature_states_changed = false;
+        for feature_kind in FeatureKind::iter() {
+            let enabled = settings.is_feature_enabled(&feature_kind);
+            if enabled == self.disabled_features.contains(&feature_kind) {
+                self.set_feature_enabled(feature_kind, enabled);
+                feature_states_changed = true;
+            }
+        }
+
+        if feature_states_changed {
+            FeatureStateEvent::UpdateFeatureStates(UpdateFeatureStatesMessage {
+                feature_states: settings.feature_states(),
+            })
+            .publish_to_tauri(&self.app_handle);
+        }
     }
 
-    pub fn set_swift_format_on_cmd_s_active(&mut self, active: bool) {
-        self.swift_format_on_cmd_s_active = active;
+    fn set_feature_enabled(&mut self, feature_kind: FeatureKind, enabled: bool) {
+        if enabled {
+            self.disabled_features.remove(&feature_kind);
+        } else {
+            self.disabled_features.insert(feature_kind.clone());
+            // Procedures scheduled before the feature was disabled are dropped as well.
+            self.scheduler.lock().cancel_feature(&feature_kind);
+        }
+
+        let feature = self.features.lock().get(&feature_kind).cloned();
+        if let Some(feature) = feature {
+            let result = if enabled {
+                feature.lock().activate()
+            } else {
+                feature.lock().deactivate()
+            };
+
+            if let Err(err) = result {
+                error!(
+                    ?err,
+                    feature = feature_kind.to_string(),
+                    enabled,
+                    "Failed to change feature activation"
+                );
+            }
+        }
     }
 
     pub fn handle_trigger(
@@ -191,8 +232,50 @@ impl CoreEngine {
         if trigger == CoreEngineTrigger::OnTextContentChange
             || trigger == CoreEngineTrigger::OnTextSelectionChange
         {
-            self.finished_code_doc_update_task_recv =
-                Some(self.compute_code_doc_updates(editor_window_uid));
+            self.scheduler
+                .lock()
+                .schedule_code_doc_update(editor_window_uid);
+        }
+
+        self.process_features_schedule();
+
+        Ok(())
+    }
+
+    pub fn handle_shortcut_pressed(
+        &mut self,
+        msg: &EditorShortcutPressedMessage,
+    ) -> Result<(), CoreEngineError> {
+        let action = match self.keymap.resolve(KeyChord::from_shortcut(msg)) {
+            KeymapResolution::Action(action) => action,
+            KeymapResolution::Pending | KeymapResolution::Unbound => return Ok(()),
+        };
+
+        if let KeymapAction::ToggleFeature(feature_kind) = action {
+            // Updating the settings re-enters the core engine through the settings listener.
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = update_settings(|settings| {
+                    let enabled = settings.is_feature_enabled(&feature_kind);
+                    settings.features.insert(feature_kind, !enabled);
+                }) {
+                    error!(?err, "Failed to toggle feature");
+                }
+            });
+            return Ok(());
+        }
+
+        self.handle_trigger(msg.window_uid, CoreEngineTrigger::OnShortcutPressed(action))
+    }
+
+    // For triggers which are not tied to a single editor window, e.g. searching across all open documents.
+    pub fn handle_trigger_for_all_documents(
+        &mut self,
+        trigger: CoreEngineTrigger,
+    ) -> Result<(), CoreEngineError> {
+        let window_uids: Vec<EditorWindowUid> =
+            self.code_documents.lock().keys().cloned().collect();
+        for window_uid in window_uids {
+            self.schedule_feature_procedures(&trigger, window_uid);
         }
 
         self.process_features_schedule();
@@ -205,9 +288,13 @@ impl CoreEngine {
         trigger: &CoreEngineTrigger,
         window_uid: EditorWindowUid,
     ) {
-        let mut feature_procedures_schedule = self.feature_procedures_schedule.lock();
+        let mut scheduler = self.scheduler.lock();
 
         for feature_kind in FeatureKind::iter() {
+            if self.disabled_features.contains(&feature_kind) {
+                continue;
+            }
+
             if feature_kind == FeatureKind::Formatter && !self.swift_format_on_cmd_s_active {
                 continue;
             }
@@ -217,173 +304,136 @@ impl CoreEngine {
             }
 
             if feature_kind.should_compute(trigger) {
-                let procedure =
-                    CoreEngineProcedure::new(feature_kind, trigger.to_owned(), window_uid);
-
-                feature_procedures_schedule.insert(procedure.hash(), procedure);
+                scheduler.schedule(CoreEngineProcedure::new(
+                    feature_kind,
+                    trigger.to_owned(),
+                    window_uid,
+                ));
             }
         }
     }
 
     fn process_features_schedule(&mut self) {
-        tauri::async_runtime::spawn({
-            let feature_procedures_schedule = self.feature_procedures_schedule.clone();
-            let features = self.features.clone();
-            let code_documents = self.code_documents.clone();
-            let awaiting_code_doc_update_task = self.awaiting_code_doc_update_task.clone();
-
-            let finished_code_doc_update_task_recv =
-                if self.finished_code_doc_update_task_recv.is_some() {
-                    *awaiting_code_doc_update_task.lock() = true;
-                    self.finished_code_doc_update_task_recv.take()
-                } else {
-                    None
-                };
-
-            async move {
-                if let Some(task_finished_recv) = finished_code_doc_update_task_recv {
-                    // Waiting for an ongoing code_doc update to finish before proceeding.
-                    if let Ok(code_doc_update) = task_finished_recv.await {
-                        match code_doc_update {
-                            CodeDocUpdate::Finished => {
-                                Self::process_features(
-                                    feature_procedures_schedule.clone(),
-                                    features,
-                                    code_documents,
-                                );
-                                *awaiting_code_doc_update_task.lock() = false;
-                            }
-                            CodeDocUpdate::Cancelled => {
-                                // Syntax tree parsing task restarted.
-                            }
-                        }
-                    }
-                } else {
-                    if *awaiting_code_doc_update_task.lock() == false {
-                        Self::process_features(
-                            feature_procedures_schedule,
-                            features,
-                            code_documents,
-                        );
-                    }
-                }
-            }
-        });
+        Self::process_features(
+            self.scheduler.clone(),
+            self.features.clone(),
+            self.code_documents.clone(),
+        );
     }
 
+    // Starts the code document updates and procedures the scheduler considers ready. It is invoked
+    // again whenever one of them finishes or a debounce window ends, so nothing stays scheduled
+    // without being looked at.
     fn process_features(
-        core_engine_procedures_schedule: Arc<Mutex<CoreEngineProcedureSchedule>>,
+        scheduler: Arc<Mutex<ProcedureScheduler>>,
         features: Arc<Mutex<HashMap<FeatureKind, Arc<Mutex<Feature>>>>>,
         code_documents: Arc<Mutex<HashMap<usize, CodeDocument>>>,
     ) {
-        for (_, core_engine_procedure) in core_engine_procedures_schedule.lock().drain() {
-            if let (Some(feature), Some(code_doc)) = (
-                features.lock().get_mut(&core_engine_procedure.feature),
-                code_documents.lock().get(&core_engine_procedure.window_uid),
-            ) {
-                Self::process_single_feature(
-                    &core_engine_procedure.trigger,
-                    code_doc.to_owned(),
-                    feature,
-                );
+        let ready_code_doc_updates = scheduler.lock().take_ready_code_doc_updates();
+        for (window_uid, cancellation_token) in ready_code_doc_updates {
+            tauri::async_runtime::spawn({
+                let scheduler = scheduler.clone();
+                let features = features.clone();
+                let code_documents = code_documents.clone();
+
+                async move {
+                    if let Err(e) = Self::compute_code_doc_update(
+                        code_documents.clone(),
+                        window_uid,
+                        &cancellation_token,
+                    )
+                    .await
+                    {
+                        error!("Error in compute_code_doc_update: {:?}", e);
+                    }
+
+                    scheduler
+                        .lock()
+                        .complete_code_doc_update(window_uid, &cancellation_token);
+                    Self::process_features(scheduler, features, code_documents);
+                }
+            });
+        }
+
+        let ready_procedures = scheduler.lock().take_ready();
+        for scheduled in ready_procedures {
+            let feature = features.lock().get(&scheduled.procedure.feature).cloned();
+            let code_doc = code_documents
+                .lock()
+                .get(&scheduled.procedure.window_uid)
+                .cloned();
+
+            if let (Some(feature), Some(code_doc)) = (feature, code_doc) {
+                tauri::async_runtime::spawn({
+                    let scheduler = scheduler.clone();
+                    let features = features.clone();
+                    let code_documents = code_documents.clone();
+
+                    async move {
+                        let id = scheduled.id;
+                        Self::process_single_feature(scheduled, code_doc, feature);
+
+                        scheduler.lock().complete(id);
+                        Self::process_features(scheduler, features, code_documents);
+                    }
+                });
             } else {
                 error!(
-                    ?core_engine_procedure,
+                    procedure = ?scheduled.procedure,
                     "Feature or code document not found.",
                 );
+                scheduler.lock().complete(scheduled.id);
             }
         }
+
+        let timer_delay = scheduler.lock().arm_timer();
+        if let Some(delay) = timer_delay {
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(delay).await;
+                Self::process_features(scheduler, features, code_documents);
+            });
+        }
     }
 
     fn process_single_feature(
-        trigger: &CoreEngineTrigger,
+        scheduled: ScheduledProcedure,
         code_doc: CodeDocument,
-        feature: &mut Arc<Mutex<Feature>>,
+        feature: Arc<Mutex<Feature>>,
     ) {
-        tauri::async_runtime::spawn({
-            let trigger = trigger.clone();
-            let feature = feature.clone();
-            async move {
-                if let Err(e) = feature.lock().compute(code_doc, trigger) {
-                    error!(?e, "Error while computing feature.");
-                }
-            }
-        });
-    }
+        let result =
+            DiagnosticsSpan::measure(scheduled.procedure.feature.clone(), "compute", || {
+                feature.lock().compute(
+                    code_doc,
+                    scheduled.procedure.trigger,
+                    scheduled.cancellation_token,
+                )
+            });
 
-    fn cancel_code_doc_update(&mut self) -> oneshot::Receiver<&'static ()> {
-        if let Some(sender) = self.cancel_code_doc_update_task_send.take() {
-            // Cancel previous code doc update task if it exists.
-            _ = sender.send(&());
+        if let Err(e) = result {
+            error!(?e, "Error while computing feature.");
         }
-
-        let (send, recv) = oneshot::channel();
-        self.cancel_code_doc_update_task_send = Some(send);
-        recv
     }
 
-    fn compute_code_doc_updates(
-        &mut self,
+    // Dropped as soon as a newer update of the window is scheduled.
+    async fn compute_code_doc_update(
+        code_documents: Arc<Mutex<HashMap<EditorWindowUid, CodeDocument>>>,
         window_uid: EditorWindowUid,
-    ) -> Receiver<&'static CodeDocUpdate> {
-        let (code_doc_update_send, code_doc_update_recv) = oneshot::channel();
-        let (ast_compute_send, ast_compute_recv) = oneshot::channel();
-
-        let cancel_recv = self.cancel_code_doc_update();
-
-        tauri::async_runtime::spawn({
-            let code_documents_arc = self.code_documents.clone();
-
-            async move {
-                // Spin up task to compute syntax tree
-                tauri::async_runtime::spawn({
-                    let code_documents_arc = code_documents_arc.clone();
-
-                    async move {
-                        if let Err(e) = Self::compute_abstract_syntax_tree(
-                            code_documents_arc,
-                            window_uid,
-                            ast_compute_send,
-                        )
-                        .await
-                        {
-                            error!("Error in compute_abstract_syntax_tree: {:?}", e);
-                        }
-                    }
-                });
-
-                tokio::select! {
-                    recv_res = ast_compute_recv => {
-                        match recv_res {
-                            Ok(tree_option) => {
-                                _ = Self::update_code_document(
-                                    code_documents_arc,
-                                    window_uid,
-                                    tree_option,
-                                );
-                                _ = code_doc_update_send.send(&CodeDocUpdate::Finished);
-                            }
-                            Err(_) => {
-                                // Channel closed
-                            },
-                        }
-                    }
-                    _ = cancel_recv => {
-                        _ = code_doc_update_send.send(&CodeDocUpdate::Cancelled);
-                    }
-                }
-            }
-        });
+        cancellation_token: &CancellationToken,
+    ) -> Result<(), CoreEngineError> {
+        let syntax_tree = tokio::select! {
+            syntax_tree = Self::compute_abstract_syntax_tree(code_documents.clone(), window_uid) => syntax_tree?,
+            _ = cancellation_token.cancelled() => return Ok(()),
+        };
 
-        return code_doc_update_recv;
+        _ = Self::update_code_document(code_documents, window_uid, syntax_tree);
+        Ok(())
     }
 
+    // Returns None if the text did not change.
     pub async fn compute_abstract_syntax_tree(
         code_documents: Arc<Mutex<HashMap<EditorWindowUid, CodeDocument>>>,
         window_uid: EditorWindowUid,
-        mut sender: oneshot::Sender<Option<SwiftSyntaxTree>>,
-    ) -> Result<(), CoreEngineError> {
-        let code_text_u16;
+    ) -> Result<Option<SwiftSyntaxTree>, CoreEngineError> {
         let previous_ast;
         {
             let docs = code_documents.lock();
@@ -397,31 +447,23 @@ impl CoreEngine {
         let code_text = get_textarea_content(&GetVia::Hash(window_uid))
             .map_err(|e| CoreEngineError::GenericError(e.into()))?;
 
-        code_text_u16 = XcodeText::from_str(&code_text);
+        let code_text_u16 = XcodeText::from_str(&code_text);
 
         if let Some(ref previous_ast) = previous_ast {
             if code_text_u16 == *previous_ast.text_content() {
                 // No change in text
-                _ = sender.send(None);
-                return Ok(());
+                return Ok(None);
             }
         }
 
         // Recompute AST because code text has changed
-        Ok(tokio::select! {
-            syntax_tree = SwiftSyntaxTree::from_XcodeText(code_text_u16, previous_ast) => {
-                match syntax_tree {
-                    Ok(tree) => {
-                        _ = sender.send(Some(tree));
-                    },
-                    Err(e) => {
-                        error!("Error while computing AST: {}", e);
-                        _ = sender.send(None);
-                    },
-                }
+        match SwiftSyntaxTree::from_XcodeText(code_text_u16, previous_ast).await {
+            Ok(tree) => Ok(Some(tree)),
+            Err(e) => {
+                error!("Error while computing AST: {}", e);
+                Ok(None)
             }
-            _ = sender.closed() => {}
-        })
+        }
     }
 
     fn update_code_document(
@@ -484,6 +526,10 @@ impl CoreEngine {
         {
             Err(CoreEngineError::CodeDocNotFound(editor_window_uid))
         } else {
+            self.scheduler.lock().remove_window(editor_window_uid);
+            for feature in self.features.lock().values() {
+                feature.lock().remove_window(editor_window_uid);
+            }
             Ok(())
         }
     }
@@ -499,5 +545,6 @@ impl CoreEngine {
     pub fn start_core_engine_listeners(core_engine: &Arc<Mutex<CoreEngine>>) {
         xcode_listener(&core_engine);
         user_interaction_listener(&core_engine);
+        settings_listener(&core_engine);
     }
 }
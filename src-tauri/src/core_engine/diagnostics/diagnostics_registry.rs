@@ -0,0 +1,155 @@
+use std::{collections::HashMap, fmt, time::Duration};
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+
+use crate::core_engine::FeatureKind;
+
+use super::{DiagnosticsReport, LatencyHistogram, OperationSummary};
+
+lazy_static! {
+    pub static ref DIAGNOSTICS: Mutex<DiagnosticsRegistry> = Mutex::new(DiagnosticsRegistry::new());
+}
+
+// Work done by the engine itself, e.g. parsing, is not attributed to any feature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DiagnosticsScope {
+    Engine,
+    Feature(FeatureKind),
+}
+
+impl From<FeatureKind> for DiagnosticsScope {
+    fn from(feature: FeatureKind) -> Self {
+        DiagnosticsScope::Feature(feature)
+    }
+}
+
+impl fmt::Display for DiagnosticsScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiagnosticsScope::Engine => write!(f, "Engine"),
+            DiagnosticsScope::Feature(feature) => write!(f, "{}", feature),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct OperationStats {
+    latencies: LatencyHistogram,
+    failures: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct DiagnosticsRegistry {
+    operations: HashMap<DiagnosticsScope, HashMap<&'static str, OperationStats>>,
+}
+
+impl DiagnosticsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(
+        &mut self,
+        scope: DiagnosticsScope,
+        operation: &'static str,
+        latency: Duration,
+        failed: bool,
+    ) {
+        let stats = self
+            .operations
+            .entry(scope)
+            .or_default()
+            .entry(operation)
+            .or_default();
+
+        stats.latencies.record(latency);
+        if failed {
+            stats.failures += 1;
+        }
+    }
+
+    pub fn report(&self) -> DiagnosticsReport {
+        let scopes = self
+            .operations
+            .iter()
+            .map(|(scope, operations)| {
+                let summaries = operations
+                    .iter()
+                    .map(|(operation, stats)| {
+                        (
+                            operation.to_string(),
+                            OperationSummary {
+                                count: stats.latencies.count(),
+                                failures: stats.failures,
+                                mean_ms: as_millis(stats.latencies.mean()),
+                                p50_ms: as_millis(stats.latencies.percentile(50.0)),
+                                p95_ms: as_millis(stats.latencies.percentile(95.0)),
+                                p99_ms: as_millis(stats.latencies.percentile(99.0)),
+                                max_ms: as_millis(stats.latencies.max()),
+                            },
+                        )
+                    })
+                    .collect();
+
+                (scope.to_string(), summaries)
+            })
+            .collect();
+
+        DiagnosticsReport {
+            generated_at: Utc::now().to_rfc3339(),
+            scopes,
+        }
+    }
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    mod report {
+        use std::time::Duration;
+
+        use crate::core_engine::{
+            diagnostics::{DiagnosticsRegistry, DiagnosticsScope},
+            FeatureKind,
+        };
+
+        #[test]
+        fn groups_operations_by_scope() {
+            let mut registry = DiagnosticsRegistry::new();
+            registry.record(
+                DiagnosticsScope::Engine,
+                "parse_content",
+                Duration::from_millis(2),
+                false,
+            );
+            registry.record(
+                FeatureKind::Formatter.into(),
+                "format_code",
+                Duration::from_millis(30),
+                false,
+            );
+            registry.record(
+                FeatureKind::Formatter.into(),
+                "format_code",
+                Duration::from_millis(10),
+                true,
+            );
+
+            let report = registry.report();
+
+            assert_eq!(report.scopes.len(), 2);
+            assert_eq!(report.scopes["Engine"]["parse_content"].count, 1);
+
+            let format_code = &report.scopes["Formatter"]["format_code"];
+            assert_eq!(format_code.count, 2);
+            assert_eq!(format_code.failures, 1);
+            assert_eq!(format_code.mean_ms, 20.0);
+            assert_eq!(format_code.max_ms, 30.0);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::{collections::HashMap, fs};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use ts_rs::TS;
+
+use crate::app_handle;
+
+use super::DIAGNOSTICS;
+
+const DIAGNOSTICS_DIR_NAME: &str = "diagnostics";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/diagnostics/")]
+pub struct OperationSummary {
+    pub count: usize,
+    pub failures: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/diagnostics/")]
+pub struct DiagnosticsReport {
+    pub generated_at: String,
+    pub scopes: HashMap<String, HashMap<String, OperationSummary>>, // Scope, e.g. a feature, to operation
+}
+
+#[tauri::command]
+pub fn cmd_get_diagnostics() -> DiagnosticsReport {
+    DIAGNOSTICS.lock().report()
+}
+
+// Writes the report next to the other app data and returns the path of the file.
+#[tauri::command]
+pub fn cmd_dump_diagnostics() -> Result<String, String> {
+    let report = DIAGNOSTICS.lock().report();
+
+    let diagnostics_dir = app_handle()
+        .path_resolver()
+        .app_dir()
+        .ok_or_else(|| "App directory not found".to_string())?
+        .join(DIAGNOSTICS_DIR_NAME);
+    fs::create_dir_all(&diagnostics_dir).map_err(|err| err.to_string())?;
+
+    let path = diagnostics_dir.join(format!(
+        "diagnostics-{}.json",
+        Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    let json = serde_json::to_string_pretty(&report).map_err(|err| err.to_string())?;
+    fs::write(&path, json).map_err(|err| err.to_string())?;
+
+    info!(?path, "Diagnostics report written");
+
+    Ok(path.to_string_lossy().to_string())
+}
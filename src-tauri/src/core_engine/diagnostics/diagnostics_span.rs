@@ -0,0 +1,66 @@
+use std::{future::Future, time::Instant};
+
+use super::{DiagnosticsScope, DIAGNOSTICS};
+
+// Measures from `enter` until dropped, so it also covers early returns and awaits in between.
+pub struct DiagnosticsSpan {
+    scope: DiagnosticsScope,
+    operation: &'static str,
+    started_at: Instant,
+    failed: bool,
+}
+
+impl DiagnosticsSpan {
+    pub fn enter(scope: impl Into<DiagnosticsScope>, operation: &'static str) -> Self {
+        Self {
+            scope: scope.into(),
+            operation,
+            started_at: Instant::now(),
+            failed: false,
+        }
+    }
+
+    pub fn fail(&mut self) {
+        self.failed = true;
+    }
+
+    pub fn record_result<T, E>(&mut self, result: &Result<T, E>) {
+        if result.is_err() {
+            self.fail();
+        }
+    }
+
+    // Measures the operation, counting an `Err` as a failure.
+    pub fn measure<T, E>(
+        scope: impl Into<DiagnosticsScope>,
+        operation: &'static str,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut span = Self::enter(scope, operation);
+        let result = f();
+        span.record_result(&result);
+        result
+    }
+
+    pub async fn measure_async<T, E>(
+        scope: impl Into<DiagnosticsScope>,
+        operation: &'static str,
+        future: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let mut span = Self::enter(scope, operation);
+        let result = future.await;
+        span.record_result(&result);
+        result
+    }
+}
+
+impl Drop for DiagnosticsSpan {
+    fn drop(&mut self) {
+        DIAGNOSTICS.lock().record(
+            self.scope.clone(),
+            self.operation,
+            self.started_at.elapsed(),
+            self.failed,
+        );
+    }
+}
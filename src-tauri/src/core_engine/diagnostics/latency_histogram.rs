@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+// Upper bounds of the buckets in microseconds; slower measurements land in an overflow bucket.
+const BUCKET_BOUNDS_MICROS: [u64; 17] = [
+    50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000, 500_000,
+    1_000_000, 2_500_000, 5_000_000, 10_000_000,
+];
+
+// Fixed buckets keep memory constant no matter how often an operation runs, at the cost of
+// percentiles only being as precise as the bucket they fall into.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyHistogram {
+    buckets: [usize; BUCKET_BOUNDS_MICROS.len() + 1],
+    count: usize,
+    sum: Duration,
+    max: Duration,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: [0; BUCKET_BOUNDS_MICROS.len() + 1],
+            count: 0,
+            sum: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        let micros = latency.as_micros();
+        let bucket = BUCKET_BOUNDS_MICROS
+            .iter()
+            .position(|bound| micros <= *bound as u128)
+            .unwrap_or(BUCKET_BOUNDS_MICROS.len());
+
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum += latency;
+        self.max = self.max.max(latency);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        self.sum / self.count as u32
+    }
+
+    // Returns the upper bound of the bucket containing the percentile, capped at the slowest
+    // measurement. `percentile` is between 0 and 100.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+
+        let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * self.count as f64).ceil() as usize;
+        let rank = rank.max(1);
+
+        let mut seen = 0;
+        for (bucket, bucket_count) in self.buckets.iter().enumerate() {
+            seen += bucket_count;
+            if seen >= rank {
+                return match BUCKET_BOUNDS_MICROS.get(bucket) {
+                    Some(bound) => Duration::from_micros(*bound).min(self.max),
+                    None => self.max,
+                };
+            }
+        }
+
+        self.max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod percentile {
+        use std::time::Duration;
+
+        use crate::core_engine::diagnostics::LatencyHistogram;
+
+        #[test]
+        fn empty_histogram() {
+            let histogram = LatencyHistogram::new();
+
+            assert_eq!(histogram.percentile(50.0), Duration::ZERO);
+            assert_eq!(histogram.mean(), Duration::ZERO);
+        }
+
+        #[test]
+        fn returns_bucket_bounds() {
+            let mut histogram = LatencyHistogram::new();
+            for _ in 0..90 {
+                histogram.record(Duration::from_micros(800));
+            }
+            for _ in 0..10 {
+                histogram.record(Duration::from_millis(40));
+            }
+
+            assert_eq!(histogram.count(), 100);
+            assert_eq!(histogram.percentile(50.0), Duration::from_millis(1));
+            assert_eq!(histogram.percentile(90.0), Duration::from_millis(1));
+            assert_eq!(histogram.percentile(95.0), Duration::from_millis(40));
+            assert_eq!(histogram.max(), Duration::from_millis(40));
+        }
+
+        #[test]
+        fn overflow_bucket_uses_max() {
+            let mut histogram = LatencyHistogram::new();
+            histogram.record(Duration::from_secs(12));
+
+            assert_eq!(histogram.percentile(99.0), Duration::from_secs(12));
+        }
+    }
+}
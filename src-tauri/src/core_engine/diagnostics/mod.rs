@@ -0,0 +1,11 @@
+pub use diagnostics_registry::{DiagnosticsRegistry, DiagnosticsScope, DIAGNOSTICS};
+pub use diagnostics_report::{
+    cmd_dump_diagnostics, cmd_get_diagnostics, DiagnosticsReport, OperationSummary,
+};
+pub use diagnostics_span::DiagnosticsSpan;
+pub use latency_histogram::LatencyHistogram;
+
+mod diagnostics_registry;
+mod diagnostics_report;
+mod diagnostics_span;
+mod latency_histogram;
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use ts_rs::TS;
+
+use crate::utils::messaging::ChannelList;
+
+use super::models::UpdateFeatureStatesMessage;
+
+#[derive(Clone, Serialize, Deserialize, Debug, TS)]
+#[serde(tag = "event", content = "payload")]
+#[ts(export, export_to = "bindings/features/feature_state/")]
+pub enum FeatureStateEvent {
+    UpdateFeatureStates(UpdateFeatureStatesMessage),
+}
+
+impl FeatureStateEvent {
+    pub fn publish_to_tauri(&self, app_handle: &tauri::AppHandle) {
+        let event_name = ChannelList::FeatureStateEvent.to_string();
+
+        // Emit to rust listeners
+        app_handle.trigger_global(
+            event_name.as_str(),
+            Some(serde_json::to_string(self).unwrap()),
+        );
+
+        // Emit to all windows
+        _ = app_handle.emit_all(
+            event_name.as_str(),
+            Some(serde_json::to_string(self).unwrap()),
+        );
+    }
+}
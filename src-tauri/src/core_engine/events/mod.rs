@@ -1,13 +1,25 @@
 pub use annotation_event::AnnotationEvent;
 pub use annotation_event::AnnotationManagerEvent;
+pub use clone_detection_event::CloneDetectionEvent;
+pub use dead_code_event::DeadCodeEvent;
+pub use document_outline_event::DocumentOutlineEvent;
+pub use feature_state_event::FeatureStateEvent;
 pub use node_explanation_event::NodeExplanationEvent;
 pub use rule_execution_event::EventRuleExecutionState;
+pub use sticky_scope_header_event::StickyScopeHeaderEvent;
+pub use structural_search_event::StructuralSearchEvent;
 pub use suggestion_event::*;
 pub use user_interaction::EventUserInteraction;
 pub mod models;
 
 mod annotation_event;
+mod clone_detection_event;
+mod dead_code_event;
+mod document_outline_event;
+mod feature_state_event;
 mod node_explanation_event;
 mod rule_execution_event;
+mod sticky_scope_header_event;
+mod structural_search_event;
 mod suggestion_event;
 mod user_interaction;
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::EditorWindowUid;
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[ts(export, export_to = "bindings/user_interaction/")]
+pub struct DetectClonesMessage {
+    pub directory: Option<String>, // Defaults to the project containing the editor's file
+    pub editor_window_uid: EditorWindowUid,
+}
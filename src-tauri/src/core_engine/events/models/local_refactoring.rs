@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::{EditorWindowUid, TextRange};
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[serde(tag = "kind")]
+#[ts(export, export_to = "bindings/user_interaction/")]
+pub enum LocalRefactoring {
+    ExtractVariable {
+        variable_name: String,
+        replace_all_occurrences: bool, // Also replaces identical expressions later in the block
+    },
+    InlineVariable,
+    ExtractFunction, // Through SourceKit, which names the function and finds its parameters
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[ts(export, export_to = "bindings/user_interaction/")]
+pub struct LocalRefactoringMessage {
+    pub refactoring: LocalRefactoring,
+    pub text_range: Option<TextRange>, // The selected text range if not given
+    pub editor_window_uid: EditorWindowUid,
+}
@@ -1,17 +1,47 @@
 pub use code_annotation::NodeAnnotationClickedMessage;
 
 pub use ai_features_activation_status::AiFeaturesStatusMessage;
+pub use apply_quick_fix::ApplyQuickFixMessage;
+pub use detect_clones::DetectClonesMessage;
+pub use local_refactoring::{LocalRefactoring, LocalRefactoringMessage};
+pub use navigation_command::NavigationCommandMessage;
 pub use node_explanation_fetched::NodeExplanationFetchedMessage;
+pub use rename_symbol::RenameSymbolMessage;
+pub use replace_query::ReplaceQueryMessage;
 pub use replace_suggestions_message::ReplaceSuggestionsMessage;
-pub use search_query::SearchQueryMessage;
+pub use search_query::{SearchQueryKind, SearchQueryMessage};
 pub use suggestion_interaction_events::*;
 pub use swift_format_cmd_s_activation_status::SwiftFormatOnCMDSMessage;
+pub use update_clone_groups::{CloneDetectionScope, UpdateCloneGroupsMessage};
+pub use update_dead_code_results::UpdateDeadCodeResultsMessage;
+pub use update_document_outline::UpdateDocumentOutlineMessage;
+pub use update_feature_states::UpdateFeatureStatesMessage;
+pub use update_local_refactoring_preview::UpdateLocalRefactoringPreviewMessage;
 pub use update_node_explanation::UpdateNodeExplanationMessage;
+pub use update_rename_preview::UpdateRenamePreviewMessage;
+pub use update_replace_preview::UpdateReplacePreviewMessage;
+pub use update_search_results::UpdateSearchResultsMessage;
+pub use update_sticky_scope_header::UpdateStickyScopeHeaderMessage;
 mod ai_features_activation_status;
+mod apply_quick_fix;
 mod code_annotation;
+mod detect_clones;
+mod local_refactoring;
+mod navigation_command;
 mod node_explanation_fetched;
+mod rename_symbol;
+mod replace_query;
 mod replace_suggestions_message;
 mod search_query;
 mod suggestion_interaction_events;
 mod swift_format_cmd_s_activation_status;
+mod update_clone_groups;
+mod update_dead_code_results;
+mod update_document_outline;
+mod update_feature_states;
+mod update_local_refactoring_preview;
 mod update_node_explanation;
+mod update_rename_preview;
+mod update_replace_preview;
+mod update_search_results;
+mod update_sticky_scope_header;
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::{features::NavigationCommand, EditorWindowUid};
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Hash)]
+#[ts(export, export_to = "bindings/user_interaction/")]
+pub struct NavigationCommandMessage {
+    pub command: NavigationCommand,
+    pub editor_window_uid: EditorWindowUid,
+}
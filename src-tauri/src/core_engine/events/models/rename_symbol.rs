@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::EditorWindowUid;
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[ts(export, export_to = "bindings/user_interaction/")]
+pub struct RenameSymbolMessage {
+    pub text_index: Option<usize>, // Position of the symbol; the cursor position if not given
+    pub new_name: String,
+    pub editor_window_uid: EditorWindowUid,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::EditorWindowUid;
+
+use super::SearchQueryMessage;
+
+// Replacements only apply to the given editor window, so they can be reviewed before applying.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[ts(export, export_to = "bindings/user_interaction/")]
+pub struct ReplaceQueryMessage {
+    pub search: SearchQueryMessage,
+    pub template: String, // Metavariables of the search pattern are substituted, e.g. `$y.map { $x in $$$ }`
+    pub editor_window_uid: EditorWindowUid,
+}
@@ -1,8 +1,27 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[ts(export, export_to = "bindings/user_interaction/")]
+pub enum SearchQueryKind {
+    Text,
+    Regex,
+    Structural, // Swift code with metavariables, e.g. `if let $x = $y { $$$ }`
+}
+
+impl Default for SearchQueryKind {
+    fn default() -> Self {
+        SearchQueryKind::Text
+    }
+}
+
+// An empty query clears the search results.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
 #[ts(export, export_to = "bindings/user_interaction/")]
 pub struct SearchQueryMessage {
     pub query: String,
+    #[serde(default)]
+    pub kind: SearchQueryKind,
+    #[serde(default)]
+    pub case_sensitive: bool, // Only applies to text and regex queries
 }
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::features::CloneGroup;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "kind")]
+#[ts(export, export_to = "bindings/features/clone_detection/")]
+pub enum CloneDetectionScope {
+    OpenDocuments,
+    Directory { path: String },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/clone_detection/")]
+pub struct UpdateCloneGroupsMessage {
+    pub scope: CloneDetectionScope,
+    pub groups: Vec<CloneGroup>, // Largest clones first
+}
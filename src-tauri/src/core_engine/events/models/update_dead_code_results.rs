@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::{rules::rule_base::RuleResults, EditorWindowUid};
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/dead_code/")]
+pub struct UpdateDeadCodeResultsMessage {
+    pub window_uid: EditorWindowUid,
+    pub results: RuleResults, // Ordered by position in the file
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::{syntax_tree::DocumentSymbol, EditorWindowUid};
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/document_outline/")]
+pub struct UpdateDocumentOutlineMessage {
+    pub window_uid: EditorWindowUid,
+    pub symbols: Vec<DocumentSymbol>, // Sorted by complexity on every level, most complex first
+}
@@ -0,0 +1,12 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::features::FeatureKind;
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/feature_state/")]
+pub struct UpdateFeatureStatesMessage {
+    pub feature_states: HashMap<FeatureKind, bool>, // Every feature, true if enabled
+}
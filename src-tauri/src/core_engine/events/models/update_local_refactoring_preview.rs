@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::features::ReplacePreview;
+
+use super::LocalRefactoringMessage;
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/refactoring/")]
+pub struct UpdateLocalRefactoringPreviewMessage {
+    pub request: LocalRefactoringMessage,
+    pub preview: Option<ReplacePreview>,
+    pub error: Option<String>, // Why the refactoring cannot be applied at the given range
+}
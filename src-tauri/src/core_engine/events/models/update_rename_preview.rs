@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::features::ReplacePreview;
+
+use super::RenameSymbolMessage;
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/refactoring/")]
+pub struct UpdateRenamePreviewMessage {
+    pub request: RenameSymbolMessage,
+    pub preview: Option<ReplacePreview>,
+    pub error: Option<String>, // E.g. why the new name was rejected
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::features::ReplacePreview;
+
+use super::ReplaceQueryMessage;
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/structural_search/")]
+pub struct UpdateReplacePreviewMessage {
+    pub query: ReplaceQueryMessage,
+    pub preview: Option<ReplacePreview>,
+    pub error: Option<String>,
+}
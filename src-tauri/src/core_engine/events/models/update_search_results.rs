@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::features::SearchMatch;
+
+use super::SearchQueryMessage;
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/structural_search/")]
+pub struct UpdateSearchResultsMessage {
+    pub query: SearchQueryMessage,
+    pub matches: Vec<SearchMatch>, // Across all open documents, most relevant first
+    pub error: Option<String>,     // Set if the query could not be compiled
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::{features::StickyScope, EditorWindowUid};
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/sticky_scope_header/")]
+pub struct UpdateStickyScopeHeaderMessage {
+    pub window_uid: EditorWindowUid,
+    pub scopes: Vec<StickyScope>, // Ordered from the outermost to the innermost scope
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use ts_rs::TS;
+
+use crate::{utils::messaging::ChannelList, window_controls::config::AppWindow};
+
+use super::models::{UpdateReplacePreviewMessage, UpdateSearchResultsMessage};
+
+#[derive(Clone, Serialize, Deserialize, Debug, TS)]
+#[serde(tag = "event", content = "payload")]
+#[ts(export, export_to = "bindings/features/structural_search/")]
+pub enum StructuralSearchEvent {
+    UpdateSearchResults(UpdateSearchResultsMessage),
+    UpdateReplacePreview(UpdateReplacePreviewMessage),
+}
+
+impl StructuralSearchEvent {
+    pub fn publish_to_tauri(&self, app_handle: &tauri::AppHandle) {
+        let event_name = ChannelList::StructuralSearchEvent.to_string();
+
+        // Emit to rust listeners
+        app_handle.trigger_global(
+            event_name.as_str(),
+            Some(serde_json::to_string(self).unwrap()),
+        );
+
+        // Emit to Main FE window
+        _ = app_handle.emit_to(
+            &AppWindow::Main.to_string(),
+            event_name.as_str(),
+            Some(serde_json::to_string(self).unwrap()),
+        );
+    }
+}
@@ -4,13 +4,17 @@ use ts_rs::TS;
 
 use crate::{utils::messaging::ChannelList, window_controls::config::AppWindow};
 
-use super::models::ReplaceSuggestionsMessage;
+use super::models::{
+    ReplaceSuggestionsMessage, UpdateLocalRefactoringPreviewMessage, UpdateRenamePreviewMessage,
+};
 
 #[derive(Clone, Serialize, Deserialize, Debug, TS)]
 #[serde(tag = "event", content = "payload")]
 #[ts(export, export_to = "bindings/features/refactoring/")]
 pub enum SuggestionEvent {
     ReplaceSuggestions(ReplaceSuggestionsMessage),
+    UpdateRenamePreview(UpdateRenamePreviewMessage),
+    UpdateLocalRefactoringPreview(UpdateLocalRefactoringPreviewMessage),
 }
 
 impl SuggestionEvent {
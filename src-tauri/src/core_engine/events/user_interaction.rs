@@ -5,8 +5,10 @@ use ts_rs::TS;
 use crate::{app_handle, utils::messaging::ChannelList};
 
 use super::models::{
-    AiFeaturesStatusMessage, DismissSuggestionMessage, NodeAnnotationClickedMessage,
-    PerformSuggestionMessage, SwiftFormatOnCMDSMessage, UpdateSelectedSuggestionMessage,
+    AiFeaturesStatusMessage, ApplyQuickFixMessage, DetectClonesMessage, DismissSuggestionMessage,
+    LocalRefactoringMessage, NavigationCommandMessage, NodeAnnotationClickedMessage,
+    PerformSuggestionMessage, RenameSymbolMessage, ReplaceQueryMessage, SearchQueryMessage,
+    SwiftFormatOnCMDSMessage, UpdateSelectedSuggestionMessage,
 };
 
 #[derive(Clone, Serialize, Deserialize, Debug, TS)]
@@ -20,6 +22,16 @@ pub enum EventUserInteraction {
     UpdateSelectedSuggestion(UpdateSelectedSuggestionMessage),
     ToggleMainWindow(bool),
     NodeAnnotationClicked(NodeAnnotationClickedMessage),
+    Navigate(NavigationCommandMessage),
+    Search(SearchQueryMessage),
+    PreviewReplace(ReplaceQueryMessage),
+    PerformReplace(ReplaceQueryMessage),
+    PreviewRenameSymbol(RenameSymbolMessage),
+    PerformRenameSymbol(RenameSymbolMessage),
+    PreviewLocalRefactoring(LocalRefactoringMessage),
+    PerformLocalRefactoring(LocalRefactoringMessage),
+    ApplyQuickFix(ApplyQuickFixMessage),
+    DetectClones(DetectClonesMessage),
 }
 
 impl EventUserInteraction {
@@ -10,6 +10,7 @@ use crate::{
             FeatureKind,
         },
         rules::get_index_of_next_row,
+        scheduler::CancellationToken,
         syntax_tree::SwiftSyntaxTreeError,
         utils::XcodeText,
         CodeDocument, EditorWindowUid, TextPosition, TextRange,
@@ -22,7 +23,7 @@ use tracing::debug;
 use tree_sitter::Node;
 
 use super::utils::{
-    get_char_rectangle_from_text_index, get_code_block_parent,
+    get_char_rectangle_from_text_index, get_code_block_parent, get_index_displayed_below,
     get_indexes_of_first_and_last_char_in_node, get_text_pos_and_index_of_left_most_char_in_range,
     length_to_code_block_body_start, only_whitespace_on_line_until_position,
 };
@@ -65,6 +66,7 @@ impl FeatureBase for BracketHighlight {
         &mut self,
         code_document: CodeDocument,
         _trigger: CoreEngineTrigger,
+        _cancellation_token: CancellationToken,
     ) -> Result<(), FeatureError> {
         if !self.is_activated {
             return Ok(());
@@ -280,9 +282,12 @@ impl BracketHighlight {
             Some(left_most_char)
         } else {
             // Check if maybe opening or closing bracket are further left than the elbow point.
-            if line_opening_character.position.column < left_most_char.position.column {
-                left_most_char.index -=
-                    left_most_char.position.column - line_opening_character.position.column;
+            if let Some(elbow_index) = get_index_displayed_below(
+                &text_content,
+                &line_opening_character.position,
+                left_most_char.index,
+            ) {
+                left_most_char.index = elbow_index;
             }
             Some(left_most_char)
         };
@@ -1,4 +1,5 @@
 pub use bracket_highlight::{BracketHighlight, BracketHighlightError};
+pub use utils::{get_code_block_parent, get_indexes_of_first_and_last_char_in_node};
 
 mod bracket_highlight;
 mod utils;
@@ -2,7 +2,10 @@ use tracing::error;
 use tree_sitter::Node;
 
 use crate::{
-    core_engine::{utils::XcodeText, TextPosition, TextRange},
+    core_engine::{
+        utils::{XcodeText, DEFAULT_TAB_WIDTH},
+        TextPosition, TextRange,
+    },
     platform::macos::{get_bounds_for_TextRange, GetVia, XcodeError},
     utils::geometry::LogicalFrame,
 };
@@ -43,9 +46,9 @@ pub fn length_to_code_block_body_start(
             get_node_start_index(&node, &text),
             get_node_end_index(&node, &text),
         ) {
-            let text_from_index = &text[first_index..last_index];
+            let text_from_index = text.slice(first_index..last_index);
             let mut additional_index: usize = 0;
-            for c in text_from_index {
+            for c in &text_from_index {
                 if *c == '{' as u16 {
                     if selected_text_index < first_index + additional_index
                         && selected_text_index >= first_index
@@ -139,12 +142,10 @@ pub fn only_whitespace_on_line_until_position(
     position: TextPosition,
     text: &XcodeText,
 ) -> Result<bool, BracketHighlightError> {
-    let rows = &text.rows;
-    if position.row + 1 > rows.len() {
-        return Err(BracketHighlightError::PositionOutOfBounds);
-    }
-
-    let row = &rows[position.row];
+    let row = match text.row(position.row) {
+        Some(row) => row,
+        None => return Err(BracketHighlightError::PositionOutOfBounds),
+    };
     if row.len() == 0 {
         // e.g. last line in document
         return Ok(true);
@@ -154,7 +155,7 @@ pub fn only_whitespace_on_line_until_position(
         return Err(BracketHighlightError::PositionOutOfBounds);
     }
 
-    for c_u16 in row[0..position.column].into_iter() {
+    for c_u16 in row.slice(0..position.column).iter() {
         if !XcodeText::char_is_whitespace(c_u16) {
             return Ok(false);
         }
@@ -183,22 +184,32 @@ pub fn get_text_pos_and_index_of_left_most_char_in_range(
     if text.len() < range.index + range.length {
         return None;
     }
-    let text = XcodeText::from_array(&text[range.index..range.index + range.length]);
-    let mut index = range.index;
+    let text = text.slice(range.index..range.index + range.length);
     let mut rows_data = vec![];
 
     for (row_i, row) in text.rows_iter().enumerate() {
         if let Some(non_whitespace_column_i) =
             row.iter().position(|c| !XcodeText::char_is_whitespace(c))
         {
-            rows_data.push((row_i, index, non_whitespace_column_i));
+            // Compared by where they are displayed, as indentation can mix tabs and spaces
+            let visual_column = text
+                .visual_column(
+                    &TextPosition::new(row_i, non_whitespace_column_i),
+                    DEFAULT_TAB_WIDTH,
+                )
+                .unwrap_or(non_whitespace_column_i);
+            rows_data.push((
+                row_i,
+                range.index + text.line_start(row_i)?,
+                non_whitespace_column_i,
+                visual_column,
+            ));
         }
-        index += row.len() + 1;
     }
-    rows_data.sort_by(|a, b| a.2.cmp(&b.2));
+    rows_data.sort_by(|a, b| a.3.cmp(&b.3));
 
     if rows_data.len() > 0 {
-        let (row, index, non_whitespace_column_i) = rows_data[0];
+        let (row, index, non_whitespace_column_i, _) = rows_data[0];
 
         return Some((
             TextPosition {
@@ -211,6 +222,28 @@ pub fn get_text_pos_and_index_of_left_most_char_in_range(
     None
 }
 
+// The index on the row of `index` which is displayed below `position`, if `position` is displayed
+// further left. Rows can be indented with tabs and spaces, so columns are compared by where they
+// are displayed. If a tab spans the column, the index of the tab is returned.
+pub fn get_index_displayed_below(
+    text: &XcodeText,
+    position: &TextPosition,
+    index: usize,
+) -> Option<usize> {
+    let visual_column = text.visual_column(position, DEFAULT_TAB_WIDTH)?;
+    let row_position = TextPosition::from_TextIndex(text, index)?;
+    if visual_column >= text.visual_column(&row_position, DEFAULT_TAB_WIDTH)? {
+        return None;
+    }
+
+    (0..=visual_column)
+        .rev()
+        .find_map(|column| {
+            text.position_of_visual_column(row_position.row, column, DEFAULT_TAB_WIDTH)
+        })?
+        .as_TextIndex(text)
+}
+
 fn get_node_start_index(node: &Node, text: &XcodeText) -> Option<usize> {
     TextPosition::from_TSPoint(&node.start_position()).as_TextIndex(&text)
 }
@@ -395,6 +428,26 @@ mod tests {
             );
         }
 
+        #[test]
+        fn tab_indentation() {
+            test_fn(
+                "if (test) {\n\tprint(x)\n   }",
+                12,
+                14,
+                Some((TextPosition { row: 1, column: 3 }, 25)),
+            );
+        }
+
+        #[test]
+        fn crlf_line_breaks() {
+            test_fn(
+                "if (test) {\r\n    print(x)\r\n  }",
+                13,
+                17,
+                Some((TextPosition { row: 1, column: 2 }, 29)),
+            );
+        }
+
         #[test]
         fn out_of_range() {
             test_fn(
@@ -408,4 +461,40 @@ mod tests {
             );
         }
     }
+
+    #[cfg(test)]
+    mod get_index_displayed_below {
+        use crate::core_engine::{
+            features::bracket_highlight::utils::get_index_displayed_below, utils::XcodeText,
+            TextPosition,
+        };
+
+        fn test_fn(text: &str, position: TextPosition, index: usize) -> Option<usize> {
+            get_index_displayed_below(&XcodeText::from_str(text), &position, index)
+        }
+
+        #[test]
+        fn tab_indentation() {
+            assert_eq!(
+                test_fn("\tif x {\n\t\tf()\n\t}", TextPosition::new(0, 1), 10),
+                Some(9)
+            );
+        }
+
+        #[test]
+        fn within_tab() {
+            assert_eq!(
+                test_fn("  if x {\n\t\tf()\n  }", TextPosition::new(0, 2), 11),
+                Some(9)
+            );
+        }
+
+        #[test]
+        fn position_further_right() {
+            assert_eq!(
+                test_fn("    if x {\n  f()\n}", TextPosition::new(0, 4), 13),
+                None
+            );
+        }
+    }
 }
@@ -0,0 +1,273 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use tracing::{error, warn};
+
+use crate::{
+    app_handle,
+    app_state::settings_for_file,
+    core_engine::{
+        events::{
+            models::{CloneDetectionScope, DetectClonesMessage, UpdateCloneGroupsMessage},
+            CloneDetectionEvent,
+        },
+        features::{
+            feature_base::{CoreEngineTrigger, FeatureBase, FeatureError, UserCommand},
+            FeatureKind,
+        },
+        scheduler::CancellationToken,
+        syntax_tree::SwiftSyntaxTree,
+        CodeDocument, EditorWindowUid, XcodeText,
+    },
+    CORE_ENGINE_ACTIVE_AT_STARTUP,
+};
+
+use super::{
+    find_clone_groups,
+    project_files::{find_project_root, swift_files_in},
+    CloneGroup, CloneSource, FileFingerprints,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum CloneDetectionError {
+    #[error("Insufficient context for clone detection")]
+    InsufficientContext,
+    #[error("Something went wrong when executing the CloneDetection feature.")]
+    GenericError(#[source] anyhow::Error),
+}
+
+enum CloneDetectionProcedure {
+    DetectInOpenDocuments,
+    DetectInDirectory(DetectClonesMessage),
+}
+
+pub struct CloneDetection {
+    is_activated: bool,
+
+    fingerprints: HashMap<EditorWindowUid, FileFingerprints>,
+    groups: Option<Vec<CloneGroup>>, // Last groups published for the open documents
+}
+
+impl FeatureBase for CloneDetection {
+    fn compute(
+        &mut self,
+        code_document: CodeDocument,
+        trigger: CoreEngineTrigger,
+        cancellation_token: CancellationToken,
+    ) -> Result<(), FeatureError> {
+        if !self.is_activated {
+            return Ok(());
+        }
+
+        match Self::determine_procedure(&trigger) {
+            Some(CloneDetectionProcedure::DetectInOpenDocuments) => {
+                self.procedure_detect_in_open_documents(&code_document)?
+            }
+            Some(CloneDetectionProcedure::DetectInDirectory(msg)) => {
+                self.procedure_detect_in_directory(&code_document, msg, cancellation_token)?
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    fn activate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = true;
+
+        Ok(())
+    }
+
+    fn deactivate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = false;
+        self.clear_results();
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), FeatureError> {
+        self.clear_results();
+
+        Ok(())
+    }
+
+    // Closed documents must stop showing up as clone locations, so the open documents are
+    // compared again on the next text change.
+    fn remove_window(&mut self, window_uid: EditorWindowUid) {
+        if self.fingerprints.remove(&window_uid).is_some() {
+            self.groups = None;
+        }
+    }
+
+    fn kind(&self) -> FeatureKind {
+        FeatureKind::CloneDetection
+    }
+
+    fn should_compute(_kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool {
+        Self::determine_procedure(trigger).is_some()
+    }
+
+    fn requires_ai(_kind: &FeatureKind, _trigger: &CoreEngineTrigger) -> bool {
+        false
+    }
+}
+
+impl CloneDetection {
+    pub fn new() -> Self {
+        Self {
+            is_activated: CORE_ENGINE_ACTIVE_AT_STARTUP,
+            fingerprints: HashMap::new(),
+            groups: None,
+        }
+    }
+
+    fn determine_procedure(trigger: &CoreEngineTrigger) -> Option<CloneDetectionProcedure> {
+        match trigger {
+            CoreEngineTrigger::OnTextContentChange => {
+                Some(CloneDetectionProcedure::DetectInOpenDocuments)
+            }
+            CoreEngineTrigger::OnUserCommand(UserCommand::DetectClones(msg)) => {
+                Some(CloneDetectionProcedure::DetectInDirectory(msg.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    fn procedure_detect_in_open_documents(
+        &mut self,
+        code_document: &CodeDocument,
+    ) -> Result<(), CloneDetectionError> {
+        let window_uid = code_document.editor_window_props().window_uid;
+        let syntax_tree = code_document
+            .syntax_tree()
+            .ok_or(CloneDetectionError::InsufficientContext)?;
+
+        self.fingerprints.insert(
+            window_uid,
+            FileFingerprints::new(
+                CloneSource {
+                    window_uid: Some(window_uid),
+                    file_path: code_document.file_path().clone(),
+                },
+                syntax_tree.tree(),
+                syntax_tree.text_content(),
+            ),
+        );
+
+        let min_clone_tokens = settings_for_file(code_document.file_path())
+            .thresholds
+            .min_clone_tokens;
+        // Sorted so the groups of unchanged documents come out in the same order.
+        let mut files: Vec<FileFingerprints> = self.fingerprints.values().cloned().collect();
+        files.sort_by_key(|fingerprints| fingerprints.source.window_uid);
+        let groups = find_clone_groups(&files, min_clone_tokens);
+        if self.groups.as_ref() == Some(&groups) {
+            return Ok(());
+        }
+
+        Self::publish_groups(CloneDetectionScope::OpenDocuments, groups.clone());
+        self.groups = Some(groups);
+
+        Ok(())
+    }
+
+    // Scanning a project can take a while, so it runs on the thread pool and publishes its
+    // groups when done. Files open in the editor are taken from their unsaved text. A cancelled
+    // scan stops before the next file and publishes nothing.
+    fn procedure_detect_in_directory(
+        &mut self,
+        code_document: &CodeDocument,
+        msg: DetectClonesMessage,
+        cancellation_token: CancellationToken,
+    ) -> Result<(), CloneDetectionError> {
+        let directory = match msg.directory {
+            Some(directory) => PathBuf::from(directory),
+            None => code_document
+                .file_path()
+                .as_ref()
+                .and_then(|file_path| find_project_root(&PathBuf::from(file_path)))
+                .ok_or(CloneDetectionError::InsufficientContext)?,
+        };
+        if !directory.is_dir() {
+            return Err(CloneDetectionError::GenericError(anyhow::anyhow!(
+                "{} is not a directory",
+                directory.display()
+            )));
+        }
+
+        let min_clone_tokens = settings_for_file(code_document.file_path())
+            .thresholds
+            .min_clone_tokens;
+        let open_documents: HashMap<String, FileFingerprints> = self
+            .fingerprints
+            .values()
+            .filter_map(|fingerprints| {
+                Some((fingerprints.source.file_path.clone()?, fingerprints.clone()))
+            })
+            .collect();
+
+        rayon::spawn(move || {
+            let mut files: Vec<FileFingerprints> = vec![];
+            for path in swift_files_in(&directory) {
+                if cancellation_token.is_cancelled() {
+                    return;
+                }
+
+                let file_path = path.to_string_lossy().to_string();
+                if let Some(fingerprints) = open_documents.get(&file_path) {
+                    files.push(fingerprints.clone());
+                } else if let Some(fingerprints) = fingerprint_file(file_path) {
+                    files.push(fingerprints);
+                }
+            }
+
+            Self::publish_groups(
+                CloneDetectionScope::Directory {
+                    path: directory.to_string_lossy().to_string(),
+                },
+                find_clone_groups(&files, min_clone_tokens),
+            );
+        });
+
+        Ok(())
+    }
+
+    fn publish_groups(scope: CloneDetectionScope, groups: Vec<CloneGroup>) {
+        CloneDetectionEvent::UpdateCloneGroups(UpdateCloneGroupsMessage { scope, groups })
+            .publish_to_tauri(&app_handle());
+    }
+
+    fn clear_results(&mut self) {
+        if self.groups.take().is_some() {
+            Self::publish_groups(CloneDetectionScope::OpenDocuments, vec![]);
+        }
+        self.fingerprints.clear();
+    }
+}
+
+fn fingerprint_file(file_path: String) -> Option<FileFingerprints> {
+    let content = match std::fs::read_to_string(&file_path) {
+        Ok(content) => content,
+        Err(err) => {
+            warn!(?err, %file_path, "Could not read file for clone detection");
+            return None;
+        }
+    };
+
+    let text = XcodeText::from_str(&content);
+    let tree = match SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &text, None) {
+        Some(tree) => tree,
+        None => {
+            error!(%file_path, "Could not parse file for clone detection");
+            return None;
+        }
+    };
+
+    Some(FileFingerprints::new(
+        CloneSource {
+            window_uid: None,
+            file_path: Some(file_path),
+        },
+        &tree,
+        &text,
+    ))
+}
@@ -0,0 +1,125 @@
+use tree_sitter::{Node, Tree};
+
+use crate::{
+    core_engine::{syntax_tree::get_node_text, EditorWindowUid, TextRange, XcodeText},
+    utils::calculate_hash,
+};
+
+pub type CloneHash = u64;
+
+// Leaves which are compared by their kind only, so renamed copies still match.
+const ABSTRACTED_KINDS: &[&str] = &[
+    "simple_identifier",
+    "type_identifier",
+    "integer_literal",
+    "real_literal",
+    "hex_literal",
+    "oct_literal",
+    "bin_literal",
+    "boolean_literal",
+    "line_string_literal",
+    "multi_line_string_literal",
+    "raw_string_literal",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CloneSource {
+    pub window_uid: Option<EditorWindowUid>, // None for files which are not open in the editor
+    pub file_path: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct StatementFingerprint {
+    pub hash: CloneHash,
+    pub token_count: usize,
+    pub range: TextRange,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub leaves: Vec<XcodeText>, // Text of the abstracted leaves, in order
+}
+
+// Statements of a file hashed by the shape of their subtree, like the s-expressions keying the
+// method extraction cache, but keeping keywords and operators.
+#[derive(Clone, Debug)]
+pub struct FileFingerprints {
+    pub source: CloneSource,
+    pub statements: Vec<StatementFingerprint>,
+    pub blocks: Vec<(usize, usize)>, // Start and end of each block's consecutive statements
+}
+
+impl FileFingerprints {
+    pub fn new(source: CloneSource, tree: &Tree, text: &XcodeText) -> Self {
+        let line_of = |index: usize| text.row_of_index(index).unwrap_or(0);
+
+        let mut statements = vec![];
+        let mut blocks = vec![];
+        let mut nodes = vec![tree.root_node()];
+        while let Some(node) = nodes.pop() {
+            if node.kind() == "statements" {
+                let block_start = statements.len();
+                for statement in node.named_children(&mut node.walk()) {
+                    if is_comment(&statement) || statement.has_error() {
+                        continue;
+                    }
+
+                    let mut leaves = vec![];
+                    let (hash, token_count) = fingerprint_node(&statement, text, &mut leaves);
+                    let range = TextRange::from_StartEndIndex(
+                        statement.start_byte() / 2,
+                        statement.end_byte() / 2,
+                    );
+                    statements.push(StatementFingerprint {
+                        hash,
+                        token_count,
+                        range,
+                        start_line: line_of(range.index),
+                        end_line: line_of(range.index + range.length),
+                        leaves,
+                    });
+                }
+                blocks.push((block_start, statements.len()));
+            }
+            nodes.extend(node.children(&mut node.walk()));
+        }
+
+        Self {
+            source,
+            statements,
+            blocks,
+        }
+    }
+}
+
+fn fingerprint_node(
+    node: &Node,
+    text: &XcodeText,
+    leaves: &mut Vec<XcodeText>,
+) -> (CloneHash, usize) {
+    if ABSTRACTED_KINDS.contains(&node.kind()) {
+        if let Ok(leaf) = get_node_text(node, text) {
+            leaves.push(leaf);
+        }
+        return (calculate_hash(&node.kind()), 1);
+    }
+    if node.child_count() == 0 {
+        // Anonymous leaves are keywords and operators; their kind is their text.
+        return (calculate_hash(&node.kind()), 1);
+    }
+
+    let mut child_hashes = vec![];
+    let mut token_count = 0;
+    for child in node.children(&mut node.walk()) {
+        if is_comment(&child) {
+            continue;
+        }
+        let (child_hash, child_token_count) = fingerprint_node(&child, text, leaves);
+        child_hashes.push(child_hash);
+        token_count += child_token_count;
+    }
+
+    (calculate_hash(&(node.kind(), child_hashes)), token_count)
+}
+
+fn is_comment(node: &Node) -> bool {
+    node.kind() == "comment" || node.kind() == "multiline_comment"
+}
@@ -0,0 +1,266 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    core_engine::{
+        events::models::{LocalRefactoring, LocalRefactoringMessage},
+        EditorWindowUid, TextRange, XcodeText,
+    },
+    utils::calculate_hash,
+};
+
+use super::{CloneHash, FileFingerprints};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/clone_detection/")]
+pub struct CloneLocation {
+    pub window_uid: Option<EditorWindowUid>, // None for files which are not open in the editor
+    pub file_path: Option<String>,
+    pub text_range: TextRange,
+    pub start_line: usize,
+    pub line_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/clone_detection/")]
+pub struct CloneGroup {
+    pub token_count: usize,
+    pub parameter_count: usize,
+    pub suggestion: String,
+    pub locations: Vec<CloneLocation>,
+    // Extracts the first copy open in the editor; None if no copy is open
+    pub extraction: Option<LocalRefactoringMessage>,
+}
+
+#[derive(Clone, Copy)]
+struct Occurrence {
+    file: usize,
+    first_statement: usize,
+    last_statement: usize,
+    range: TextRange,
+}
+
+// Groups runs of consecutive statements which have the same shape. Larger clones are reported
+// first; runs within an already reported clone are not reported again.
+pub fn find_clone_groups(files: &[FileFingerprints], min_token_count: usize) -> Vec<CloneGroup> {
+    let mut occurrences_by_hash: HashMap<(CloneHash, usize), Vec<Occurrence>> = HashMap::new();
+    for (file_index, file) in files.iter().enumerate() {
+        for (block_start, block_end) in &file.blocks {
+            for first in *block_start..*block_end {
+                let mut hash = 0;
+                let mut token_count = 0;
+                for last in first..*block_end {
+                    let statement = &file.statements[last];
+                    hash = calculate_hash(&(hash, statement.hash));
+                    token_count += statement.token_count;
+                    if token_count < min_token_count {
+                        continue;
+                    }
+
+                    let start_index = file.statements[first].range.index;
+                    occurrences_by_hash
+                        .entry((hash, token_count))
+                        .or_default()
+                        .push(Occurrence {
+                            file: file_index,
+                            first_statement: first,
+                            last_statement: last,
+                            range: TextRange::from_StartEndIndex(
+                                start_index,
+                                statement.range.index + statement.range.length,
+                            ),
+                        });
+                }
+            }
+        }
+    }
+
+    let mut candidates: Vec<(usize, Vec<Occurrence>)> = occurrences_by_hash
+        .into_iter()
+        .filter(|(_, occurrences)| occurrences.len() > 1)
+        .map(|((_, token_count), occurrences)| (token_count, occurrences))
+        .collect();
+    candidates.sort_by_key(|(token_count, occurrences)| {
+        (
+            std::cmp::Reverse(*token_count),
+            occurrences[0].file,
+            occurrences[0].range.index,
+        )
+    });
+
+    let mut reported_ranges: Vec<(usize, TextRange)> = vec![];
+    let mut groups = vec![];
+    for (token_count, occurrences) in candidates {
+        let mut kept: Vec<Occurrence> = vec![];
+        for occurrence in occurrences {
+            let is_covered = reported_ranges
+                .iter()
+                .copied()
+                .chain(kept.iter().map(|kept| (kept.file, kept.range)))
+                .any(|(file, range)| {
+                    file == occurrence.file && overlaps(&range, &occurrence.range)
+                });
+            if !is_covered {
+                kept.push(occurrence);
+            }
+        }
+        if kept.len() < 2 {
+            continue;
+        }
+
+        reported_ranges.extend(
+            kept.iter()
+                .map(|occurrence| (occurrence.file, occurrence.range)),
+        );
+        groups.push(clone_group(files, token_count, &kept));
+    }
+
+    groups
+}
+
+fn clone_group(
+    files: &[FileFingerprints],
+    token_count: usize,
+    occurrences: &[Occurrence],
+) -> CloneGroup {
+    let locations: Vec<CloneLocation> = occurrences
+        .iter()
+        .map(|occurrence| {
+            let file = &files[occurrence.file];
+            let start_line = file.statements[occurrence.first_statement].start_line;
+            let end_line = file.statements[occurrence.last_statement].end_line;
+            CloneLocation {
+                window_uid: file.source.window_uid,
+                file_path: file.source.file_path.clone(),
+                text_range: occurrence.range,
+                start_line,
+                line_count: end_line - start_line + 1,
+            }
+        })
+        .collect();
+
+    let parameter_count = count_parameters(files, occurrences);
+    let extraction = locations.iter().find_map(|location| {
+        Some(LocalRefactoringMessage {
+            refactoring: LocalRefactoring::ExtractFunction,
+            text_range: Some(location.text_range),
+            editor_window_uid: location.window_uid?,
+        })
+    });
+    CloneGroup {
+        token_count,
+        parameter_count,
+        suggestion: format!(
+            "Extract one copy into a function, then call it from the other {} with the {} differing part{} as arguments",
+            locations.len() - 1,
+            parameter_count,
+            if parameter_count == 1 { "" } else { "s" }
+        ),
+        locations,
+        extraction,
+    }
+}
+
+// Identifiers and literals which differ between the copies become parameters of the extracted
+// function. Locals declared in the copies are counted too if they were renamed.
+fn count_parameters(files: &[FileFingerprints], occurrences: &[Occurrence]) -> usize {
+    let leaves_per_occurrence: Vec<Vec<&XcodeText>> = occurrences
+        .iter()
+        .map(|occurrence| {
+            files[occurrence.file].statements
+                [occurrence.first_statement..=occurrence.last_statement]
+                .iter()
+                .flat_map(|statement| statement.leaves.iter())
+                .collect()
+        })
+        .collect();
+
+    let leaf_count = leaves_per_occurrence[0].len();
+    let mut differing_leaves = HashSet::new();
+    for position in 0..leaf_count {
+        let leaves: Vec<&XcodeText> = leaves_per_occurrence
+            .iter()
+            .filter_map(|leaves| leaves.get(position).copied())
+            .collect();
+        if leaves.iter().any(|leaf| *leaf != leaves[0]) {
+            differing_leaves.insert(leaves);
+        }
+    }
+    differing_leaves.len()
+}
+
+fn overlaps(a: &TextRange, b: &TextRange) -> bool {
+    a.index < b.index + b.length && b.index < a.index + a.length
+}
+
+#[cfg(test)]
+mod tests {
+    mod find_clone_groups {
+        use crate::core_engine::{
+            features::clone_detection::{find_clone_groups, CloneSource, FileFingerprints},
+            syntax_tree::SwiftSyntaxTree,
+            XcodeText,
+        };
+
+        fn fingerprints(code: &str, window_uid: usize) -> FileFingerprints {
+            let text = XcodeText::from_str(code);
+            let tree = SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &text, None).unwrap();
+            FileFingerprints::new(
+                CloneSource {
+                    window_uid: Some(window_uid),
+                    file_path: None,
+                },
+                &tree,
+                &text,
+            )
+        }
+
+        #[test]
+        fn finds_renamed_copies_across_files() {
+            let first = "func a() {\n    let view = UIView()\n    view.backgroundColor = .red\n    view.layer.cornerRadius = 4\n}\n";
+            let second = "func b() {\n    print(0)\n    let label = UILabel()\n    label.backgroundColor = .red\n    label.layer.cornerRadius = 8\n}\n";
+
+            let groups = find_clone_groups(&[fingerprints(first, 1), fingerprints(second, 2)], 10);
+
+            assert_eq!(groups.len(), 1);
+            let locations = &groups[0].locations;
+            assert_eq!(locations.len(), 2);
+            assert_eq!(locations[0].start_line, 1);
+            assert_eq!(locations[0].line_count, 3);
+            assert_eq!(locations[1].window_uid, Some(2));
+            assert_eq!(locations[1].start_line, 2);
+            // `view`/`label`, `UIView`/`UILabel` and `4`/`8`
+            assert_eq!(groups[0].parameter_count, 3);
+            let extraction = groups[0].extraction.as_ref().unwrap();
+            assert_eq!(extraction.editor_window_uid, 1);
+            assert_eq!(extraction.text_range, Some(locations[0].text_range));
+        }
+
+        #[test]
+        fn ignores_copies_below_the_minimum_size() {
+            let code = "func a() {\n    print(1)\n}\nfunc b() {\n    print(2)\n}\n";
+
+            assert_eq!(find_clone_groups(&[fingerprints(code, 1)], 10), vec![]);
+            assert_eq!(find_clone_groups(&[fingerprints(code, 1)], 1).len(), 1);
+        }
+
+        #[test]
+        fn only_reports_the_largest_clone() {
+            let code = "func a() {\n    x = 1\n    y = 2\n    z = 3\n}\nfunc b() {\n    x = 1\n    y = 2\n    z = 3\n}\n";
+
+            let groups = find_clone_groups(&[fingerprints(code, 1)], 3);
+
+            assert_eq!(groups.len(), 1);
+            assert_eq!(groups[0].locations[0].line_count, 3);
+        }
+
+        #[test]
+        fn does_not_match_different_operators() {
+            let code = "func a() {\n    total = total + price * count\n}\nfunc b() {\n    total = total - price * count\n}\n";
+
+            assert_eq!(find_clone_groups(&[fingerprints(code, 1)], 1), vec![]);
+        }
+    }
+}
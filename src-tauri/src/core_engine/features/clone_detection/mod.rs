@@ -0,0 +1,8 @@
+pub use clone_detection::{CloneDetection, CloneDetectionError};
+pub use clone_fingerprints::{CloneHash, CloneSource, FileFingerprints, StatementFingerprint};
+pub use clone_groups::{find_clone_groups, CloneGroup, CloneLocation};
+
+mod clone_detection;
+mod clone_fingerprints;
+mod clone_groups;
+mod project_files;
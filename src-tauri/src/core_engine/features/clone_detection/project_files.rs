@@ -0,0 +1,60 @@
+use std::path::{Path, PathBuf};
+
+// Directories which only contain dependencies or build products.
+const IGNORED_DIRECTORIES: &[&str] = &["Pods", "Carthage", "DerivedData", "build"];
+
+const MAX_FILE_COUNT: usize = 2000;
+
+// The nearest ancestor which looks like the root of an Xcode project or Swift package.
+pub fn find_project_root(file_path: &Path) -> Option<PathBuf> {
+    let directory = file_path.parent()?;
+    for ancestor in directory.ancestors() {
+        let is_root = std::fs::read_dir(ancestor)
+            .map(|entries| {
+                entries.filter_map(|entry| entry.ok()).any(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    name == ".git"
+                        || name == "Package.swift"
+                        || name.ends_with(".xcodeproj")
+                        || name.ends_with(".xcworkspace")
+                })
+            })
+            .unwrap_or(false);
+        if is_root {
+            return Some(ancestor.to_path_buf());
+        }
+    }
+
+    Some(directory.to_path_buf())
+}
+
+pub fn swift_files_in(directory: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        let entries = match std::fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() {
+                if !name.starts_with('.') && !IGNORED_DIRECTORIES.contains(&name.as_str()) {
+                    directories.push(path);
+                }
+            } else if path
+                .extension()
+                .map_or(false, |extension| extension == "swift")
+            {
+                files.push(path);
+                if files.len() >= MAX_FILE_COUNT {
+                    return files;
+                }
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
@@ -6,20 +6,29 @@ use super::{
 use crate::{
     app_handle,
     core_engine::{
-        events::{models::ReplaceSuggestionsMessage, SuggestionEvent},
+        events::{
+            models::{
+                LocalRefactoringMessage, RenameSymbolMessage, ReplaceSuggestionsMessage,
+                UpdateSelectedSuggestionMessage,
+            },
+            EventUserInteraction, SuggestionEvent,
+        },
         features::{
             complexity_refactoring::{remove_annotations_for_suggestions, SuggestionsMap},
             feature_base::{CoreEngineTrigger, FeatureBase, FeatureError},
             FeatureKind, FeatureSignal, UserCommand,
         },
-        get_index_of_first_difference, CodeDocument, EditorWindowUid, TextRange,
+        get_index_of_first_difference,
+        keymap::KeymapAction,
+        scheduler::CancellationToken,
+        CodeDocument, EditorWindowUid, TextRange,
     },
     CORE_ENGINE_ACTIVE_AT_STARTUP,
 };
 use lazy_static::lazy_static;
 
 use parking_lot::Mutex;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, future::Future, sync::Arc};
 use tauri::api::process::CommandChild;
 
 use tokio::sync::mpsc;
@@ -35,16 +44,22 @@ lazy_static! {
 enum ComplexityRefactoringProcedure {
     PerformSuggestion(SuggestionId),
     DismissSuggestion(SuggestionId),
-    SelectSuggestion(SuggestionId),
+    SelectSuggestion(Option<SuggestionId>),
+    SelectNextSuggestion(UpdateSelectedSuggestionMessage),
     ComputeSuggestions,
+    PreviewRenameSymbol(RenameSymbolMessage),
+    PerformRenameSymbol(RenameSymbolMessage),
+    PreviewLocalRefactoring(LocalRefactoringMessage),
+    PerformLocalRefactoring(LocalRefactoringMessage),
 }
 
 pub struct ComplexityRefactoring {
     is_activated: bool,
     suggestions_arc: SuggestionsArcMutex,
     dismissed_suggestions_arc: Arc<Mutex<Vec<SuggestionHash>>>,
+    selected_suggestion_ids: HashMap<EditorWindowUid, SuggestionId>,
 
-    cancel_long_running_task_send: Option<mpsc::Sender<&'static ()>>,
+    cancellation_token: Option<CancellationToken>,
 }
 
 impl FeatureBase for ComplexityRefactoring {
@@ -52,19 +67,25 @@ impl FeatureBase for ComplexityRefactoring {
         &mut self,
         code_document: CodeDocument,
         trigger: CoreEngineTrigger,
+        cancellation_token: CancellationToken,
     ) -> Result<(), FeatureError> {
         if !self.is_activated {
             return Ok(());
         }
 
-        let cancelation_event_recv = self.cancel_complexity_refactoring_task();
+        let procedure = match self.resolve_procedure(&trigger, &code_document) {
+            Some(procedure) => procedure,
+            None => return Ok(()),
+        };
+
+        self.cancel_complexity_refactoring_task(&cancellation_token);
 
         tauri::async_runtime::spawn({
             let dismissed_suggestions_arc = self.dismissed_suggestions_arc.clone();
             let suggestions_arc = self.suggestions_arc.clone();
 
             async move {
-                if let Some(procedure) = Self::determine_procedure(&trigger) {
+                {
                     if let Err(e) = match procedure {
                         ComplexityRefactoringProcedure::PerformSuggestion(id) => {
                             perform_suggestion(code_document, id, suggestions_arc).await
@@ -77,17 +98,21 @@ impl FeatureBase for ComplexityRefactoring {
                                 dismissed_suggestions_arc,
                             )
                         }
-                        ComplexityRefactoringProcedure::SelectSuggestion(id) => {
+                        ComplexityRefactoringProcedure::SelectSuggestion(Some(id)) => {
                             procedures::select_suggestion(id)
                         }
+                        ComplexityRefactoringProcedure::SelectSuggestion(None) => Ok(()),
+                        ComplexityRefactoringProcedure::SelectNextSuggestion(msg) => {
+                            // Selected like from the frontend, so the suggestion also expands there.
+                            EventUserInteraction::UpdateSelectedSuggestion(msg).publish_to_tauri();
+                            Ok(())
+                        }
                         ComplexityRefactoringProcedure::ComputeSuggestions => {
                             // See Tokio Select Cancellation pattern -> https://tokio.rs/tokio/tutorial/select, chapter Canceling
                             let (feature_signals_send, feature_signals_recv) =
                                 tokio::sync::mpsc::channel(1);
 
                             tauri::async_runtime::spawn(async move {
-                                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-
                                 if feature_signals_send.is_closed() {
                                     return;
                                 }
@@ -106,11 +131,36 @@ impl FeatureBase for ComplexityRefactoring {
                                 }
                             });
 
-                            Self::handle_signals(feature_signals_recv, cancelation_event_recv)
-                                .await;
+                            Self::handle_signals(feature_signals_recv, cancellation_token).await;
 
                             Ok(())
                         }
+                        ComplexityRefactoringProcedure::PreviewRenameSymbol(msg) => {
+                            procedures::preview_rename_symbol(code_document, msg)
+                        }
+                        ComplexityRefactoringProcedure::PerformRenameSymbol(msg) => {
+                            procedures::perform_rename_symbol(code_document, msg).await
+                        }
+                        ComplexityRefactoringProcedure::PreviewLocalRefactoring(msg) => {
+                            Self::run_with_signals(cancellation_token, |signals_sender| {
+                                procedures::preview_local_refactoring(
+                                    code_document,
+                                    msg,
+                                    signals_sender,
+                                )
+                            })
+                            .await
+                        }
+                        ComplexityRefactoringProcedure::PerformLocalRefactoring(msg) => {
+                            Self::run_with_signals(cancellation_token, |signals_sender| {
+                                procedures::perform_local_refactoring(
+                                    code_document,
+                                    msg,
+                                    signals_sender,
+                                )
+                            })
+                            .await
+                        }
                     } {
                         error!(?e, "Error while performing procedure");
                     }
@@ -129,6 +179,7 @@ impl FeatureBase for ComplexityRefactoring {
 
     fn deactivate(&mut self) -> Result<(), FeatureError> {
         self.is_activated = false;
+        self.clear_suggestions();
 
         Ok(())
     }
@@ -143,6 +194,14 @@ impl FeatureBase for ComplexityRefactoring {
 
     fn should_compute(_kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool {
         Self::determine_procedure(trigger).is_some()
+            || matches!(
+                trigger,
+                CoreEngineTrigger::OnShortcutPressed(
+                    KeymapAction::PerformSelectedSuggestion
+                        | KeymapAction::DismissSelectedSuggestion
+                        | KeymapAction::NextSuggestion
+                )
+            )
     }
 
     fn requires_ai(_kind: &FeatureKind, _trigger: &CoreEngineTrigger) -> bool {
@@ -158,7 +217,8 @@ impl ComplexityRefactoring {
             dismissed_suggestions_arc: Arc::new(Mutex::new(
                 procedures::read_dismissed_suggestions(),
             )),
-            cancel_long_running_task_send: None,
+            selected_suggestion_ids: HashMap::new(),
+            cancellation_token: None,
         }
     }
 
@@ -170,9 +230,21 @@ impl ComplexityRefactoring {
             CoreEngineTrigger::OnUserCommand(UserCommand::DismissSuggestion(msg)) => {
                 Some(ComplexityRefactoringProcedure::DismissSuggestion(msg.id))
             }
-            CoreEngineTrigger::OnUserCommand(UserCommand::SelectSuggestion(msg)) => msg
-                .id
-                .map(|id| ComplexityRefactoringProcedure::SelectSuggestion(id)),
+            CoreEngineTrigger::OnUserCommand(UserCommand::SelectSuggestion(msg)) => {
+                Some(ComplexityRefactoringProcedure::SelectSuggestion(msg.id))
+            }
+            CoreEngineTrigger::OnUserCommand(UserCommand::PreviewRenameSymbol(msg)) => Some(
+                ComplexityRefactoringProcedure::PreviewRenameSymbol(msg.clone()),
+            ),
+            CoreEngineTrigger::OnUserCommand(UserCommand::PerformRenameSymbol(msg)) => Some(
+                ComplexityRefactoringProcedure::PerformRenameSymbol(msg.clone()),
+            ),
+            CoreEngineTrigger::OnUserCommand(UserCommand::PreviewLocalRefactoring(msg)) => Some(
+                ComplexityRefactoringProcedure::PreviewLocalRefactoring(msg.clone()),
+            ),
+            CoreEngineTrigger::OnUserCommand(UserCommand::PerformLocalRefactoring(msg)) => Some(
+                ComplexityRefactoringProcedure::PerformLocalRefactoring(msg.clone()),
+            ),
             CoreEngineTrigger::OnTextContentChange => {
                 Some(ComplexityRefactoringProcedure::ComputeSuggestions)
             }
@@ -180,15 +252,105 @@ impl ComplexityRefactoring {
         }
     }
 
-    fn cancel_complexity_refactoring_task(&mut self) -> mpsc::Receiver<&'static ()> {
-        if let Some(sender) = self.cancel_long_running_task_send.take() {
-            // Cancel previous task if it exists.
-            _ = sender.send(&());
+    // Shortcuts act on the suggestion selected in the window; user commands keep track of it.
+    fn resolve_procedure(
+        &mut self,
+        trigger: &CoreEngineTrigger,
+        code_document: &CodeDocument,
+    ) -> Option<ComplexityRefactoringProcedure> {
+        let window_uid = code_document.editor_window_props().window_uid;
+
+        if let CoreEngineTrigger::OnShortcutPressed(action) = trigger {
+            let selected_id = self.selected_suggestion_id(window_uid);
+            return match action {
+                KeymapAction::PerformSelectedSuggestion => {
+                    selected_id.map(ComplexityRefactoringProcedure::PerformSuggestion)
+                }
+                KeymapAction::DismissSelectedSuggestion => {
+                    selected_id.map(ComplexityRefactoringProcedure::DismissSuggestion)
+                }
+                KeymapAction::NextSuggestion => self.next_suggestion_id(window_uid).map(|id| {
+                    ComplexityRefactoringProcedure::SelectNextSuggestion(
+                        UpdateSelectedSuggestionMessage {
+                            id: Some(id),
+                            editor_window_uid: window_uid,
+                        },
+                    )
+                }),
+                _ => None,
+            };
+        }
+
+        let procedure = Self::determine_procedure(trigger)?;
+        match &procedure {
+            ComplexityRefactoringProcedure::SelectSuggestion(Some(id)) => {
+                self.selected_suggestion_ids.insert(window_uid, *id);
+            }
+            ComplexityRefactoringProcedure::SelectSuggestion(None) => {
+                self.selected_suggestion_ids.remove(&window_uid);
+            }
+            ComplexityRefactoringProcedure::PerformSuggestion(id)
+            | ComplexityRefactoringProcedure::DismissSuggestion(id) => {
+                if self.selected_suggestion_ids.get(&window_uid) == Some(id) {
+                    self.selected_suggestion_ids.remove(&window_uid);
+                }
+            }
+            _ => {}
+        }
+
+        Some(procedure)
+    }
+
+    fn selected_suggestion_id(&self, window_uid: EditorWindowUid) -> Option<SuggestionId> {
+        let selected_id = self.selected_suggestion_ids.get(&window_uid)?;
+        self.suggestions_arc
+            .lock()
+            .get(&window_uid)
+            .filter(|suggestions| suggestions.contains_key(selected_id))
+            .map(|_| *selected_id)
+    }
+
+    // In document order, wrapping around after the last one.
+    fn next_suggestion_id(&self, window_uid: EditorWindowUid) -> Option<SuggestionId> {
+        let mut suggestions: Vec<(Option<usize>, SuggestionId)> = self
+            .suggestions_arc
+            .lock()
+            .get(&window_uid)?
+            .iter()
+            .map(|(id, suggestion)| (suggestion.start_index, *id))
+            .collect();
+        suggestions.sort();
+
+        let next_position = self
+            .selected_suggestion_id(window_uid)
+            .and_then(|selected_id| suggestions.iter().position(|(_, id)| *id == selected_id))
+            .map_or(0, |position| (position + 1) % suggestions.len());
+
+        suggestions.get(next_position).map(|(_, id)| *id)
+    }
+
+    // Only one task runs at a time, even across windows, so the previous one is cancelled too.
+    fn cancel_complexity_refactoring_task(&mut self, cancellation_token: &CancellationToken) {
+        if let Some(previous_token) = self.cancellation_token.replace(cancellation_token.clone()) {
+            previous_token.cancel();
+        }
+    }
+
+    fn clear_suggestions(&mut self) {
+        if let Some(cancellation_token) = self.cancellation_token.take() {
+            cancellation_token.cancel();
         }
 
-        let (send, recv) = mpsc::channel(1);
-        self.cancel_long_running_task_send = Some(send);
-        recv
+        let suggestion_ids: Vec<Uuid> = self
+            .suggestions_arc
+            .lock()
+            .drain()
+            .flat_map(|(_, suggestions)| suggestions.into_keys())
+            .collect();
+        remove_annotations_for_suggestions(suggestion_ids);
+        self.selected_suggestion_ids.clear();
+
+        Self::publish_to_frontend(HashMap::new());
     }
 
     pub fn verify_task_not_cancelled(
@@ -201,9 +363,24 @@ impl ComplexityRefactoring {
         }
     }
 
+    // For procedures which may run SourceKit, which is killed if the procedure is cancelled.
+    async fn run_with_signals<F: Future<Output = Result<(), ComplexityRefactoringError>>>(
+        cancellation_token: CancellationToken,
+        procedure: impl FnOnce(mpsc::Sender<FeatureSignal>) -> F,
+    ) -> Result<(), ComplexityRefactoringError> {
+        let (feature_signals_send, feature_signals_recv) = mpsc::channel(1);
+
+        tokio::select! {
+            result = procedure(feature_signals_send) => result,
+            _ = Self::handle_signals(feature_signals_recv, cancellation_token) => {
+                Err(ComplexityRefactoringError::ExecutionCancelled)
+            }
+        }
+    }
+
     async fn handle_signals(
         mut feature_signals_recv: mpsc::Receiver<FeatureSignal>,
-        mut cancelation_event_recv: mpsc::Receiver<&()>,
+        cancellation_token: CancellationToken,
     ) {
         let mut swift_lsp_commands: Vec<CommandChild> = vec![];
         loop {
@@ -221,7 +398,7 @@ impl ComplexityRefactoring {
                     }
 
                 }
-                _ = cancelation_event_recv.recv() => {
+                _ = cancellation_token.cancelled() => {
                     for command in swift_lsp_commands {
                         _ = command.kill();
                     }
@@ -316,6 +493,7 @@ fn map_refactoring_suggestion_to_fe_refactoring_suggestion(
     suggestion: RefactoringSuggestion,
 ) -> FERefactoringSuggestion {
     FERefactoringSuggestion {
+        kind: suggestion.kind,
         state: suggestion.state,
         new_text_content_string: suggestion.new_text_content_string,
         old_text_content_string: suggestion.old_text_content_string,
@@ -346,6 +524,12 @@ pub enum ComplexityRefactoringError {
     ReadWriteDismissedSuggestionsFailed,
     #[error("Could not derive final cursor position to scroll to after performing suggestion")]
     CouldNotGetCursorPositionAfterPerforming,
+    #[error("Refactoring not possible: {0}")]
+    RefactoringRejected(String),
     #[error("Something went wrong when executing this ComplexityRefactoring feature.")]
     GenericError(#[source] anyhow::Error),
 }
+
+pub(super) fn rejected(reason: &str) -> ComplexityRefactoringError {
+    ComplexityRefactoringError::RefactoringRejected(reason.to_string())
+}
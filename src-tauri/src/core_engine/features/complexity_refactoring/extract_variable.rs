@@ -0,0 +1,374 @@
+use tree_sitter::Node;
+
+use crate::core_engine::{
+    syntax_tree::{
+        get_node_text, identifiers_in, indentation_at, is_expression, is_l_expression,
+        is_mutated_at, SwiftSyntaxTree, SymbolId,
+    },
+    TextRange, XcodeText,
+};
+
+use super::{rejected, rename_symbol::validate_identifier, ComplexityRefactoringError, Edit};
+
+const LITERAL_KINDS: &[&str] = &[
+    "integer_literal",
+    "real_literal",
+    "boolean_literal",
+    "line_string_literal",
+    "multi_line_string_literal",
+    "array_literal",
+    "dictionary_literal",
+];
+
+// Extracts the expression at the range into a `let` declared right before the statement
+// containing it, in the innermost enclosing `statements`. Other occurrences of the same
+// expression in that block, after the declaration, are optionally replaced as well.
+pub fn compute_extract_variable_edits(
+    syntax_tree: &SwiftSyntaxTree,
+    text_range: &TextRange,
+    variable_name: &str,
+    replace_all_occurrences: bool,
+) -> Result<Vec<Edit>, ComplexityRefactoringError> {
+    validate_identifier(variable_name)?;
+    let text = syntax_tree.text_content();
+
+    let expression = expression_at(syntax_tree, text_range)
+        .ok_or_else(|| rejected("No expression found at the selection"))?;
+    let (statements, statement) = enclosing_statement(&expression)
+        .ok_or_else(|| rejected("The expression is not inside a code block"))?;
+    if statement.id() == expression.id() {
+        return Err(rejected("The expression is a statement on its own"));
+    }
+    if is_conditionally_evaluated(&expression, &statement) {
+        return Err(rejected(
+            "The expression is not always evaluated, or evaluated repeatedly",
+        ));
+    }
+
+    let insertion_index = statement.start_byte() / 2;
+    check_dependencies_available(syntax_tree, &expression, insertion_index)?;
+    check_name_unused(&statements, variable_name, text)?;
+
+    let expression_text = get_node_text(&expression, text)
+        .map_err(|err| ComplexityRefactoringError::GenericError(err.into()))?;
+    let occurrences = if replace_all_occurrences {
+        find_occurrences(syntax_tree, &statements, &expression, insertion_index)
+    } else {
+        vec![expression]
+    };
+
+    let declaration = XcodeText::from_str(&format!("let {} = ", variable_name))
+        + expression_text
+        + "\n"
+        + indentation_at(text, insertion_index);
+    let mut edits = vec![Edit {
+        text: declaration,
+        start_index: insertion_index,
+        end_index: insertion_index,
+    }];
+    for occurrence in occurrences {
+        edits.push(Edit {
+            text: XcodeText::from_str(variable_name),
+            start_index: occurrence.start_byte() / 2,
+            end_index: occurrence.end_byte() / 2,
+        });
+    }
+
+    Ok(edits)
+}
+
+fn expression_at<'a>(syntax_tree: &'a SwiftSyntaxTree, text_range: &TextRange) -> Option<Node<'a>> {
+    let text = syntax_tree.text_content();
+
+    // Whitespace around the selection is ignored.
+    let mut end = (text_range.index + text_range.length).min(text.len());
+    let mut start = text_range.index.min(end);
+    while start < end && XcodeText::char_is_whitespace(&text[start]) {
+        start += 1;
+    }
+    while end > start && XcodeText::char_is_whitespace(&text[end - 1]) {
+        end -= 1;
+    }
+
+    let mut node = syntax_tree
+        .tree()
+        .root_node()
+        .descendant_for_byte_range(start * 2, end * 2)?;
+    loop {
+        if is_extractable(&node) {
+            return Some(node);
+        }
+        node = node.parent()?;
+    }
+}
+
+fn is_extractable(node: &Node) -> bool {
+    if !(is_expression(node.kind()) || LITERAL_KINDS.contains(&node.kind()))
+        || is_l_expression(node.kind())
+    {
+        return false;
+    }
+
+    // The callee of a call, e.g. `list.append` in `list.append(1)`, is extracted with the call.
+    if let Some(parent) = node.parent() {
+        if parent.kind() == "call_expression" && parent.child(0).map(|n| n.id()) == Some(node.id())
+        {
+            return false;
+        }
+    }
+
+    let mut ancestor = node.parent();
+    while let Some(node) = ancestor {
+        if is_l_expression(node.kind()) {
+            return false;
+        }
+        if node.kind() == "statements" {
+            break;
+        }
+        ancestor = node.parent();
+    }
+    true
+}
+
+// The innermost `statements` around the expression and its child containing the expression.
+fn enclosing_statement<'a>(expression: &Node<'a>) -> Option<(Node<'a>, Node<'a>)> {
+    let mut child = *expression;
+    while let Some(parent) = child.parent() {
+        match parent.kind() {
+            "statements" => return Some((parent, child)),
+            "function_declaration"
+            | "class_body"
+            | "enum_class_body"
+            | "protocol_body"
+            | "source_file" => return None,
+            _ => child = parent,
+        }
+    }
+    None
+}
+
+// A `let` before the statement evaluates the expression exactly once, which is only the same if
+// the statement would do so too.
+fn is_conditionally_evaluated(expression: &Node, statement: &Node) -> bool {
+    let mut child = *expression;
+    while let Some(parent) = child.parent() {
+        let is_first_operand = parent.named_child(0).map(|n| n.id()) == Some(child.id());
+        match parent.kind() {
+            "conjunction_expression"
+            | "disjunction_expression"
+            | "nil_coalescing_expression"
+            | "ternary_expression"
+                if !is_first_operand =>
+            {
+                return true
+            }
+            "while_statement" | "repeat_while_statement" => return true,
+            "if_statement" if parent.id() != statement.id() => return true, // `else if`
+            _ => {}
+        }
+        if parent.id() == statement.id() {
+            break;
+        }
+        child = parent;
+    }
+    false
+}
+
+fn check_dependencies_available(
+    syntax_tree: &SwiftSyntaxTree,
+    expression: &Node,
+    insertion_index: usize,
+) -> Result<(), ComplexityRefactoringError> {
+    let symbol_table = syntax_tree.symbol_table();
+    for identifier in identifiers_in(expression) {
+        if let Some(symbol) = symbol_table.definition_of(identifier.start_byte() / 2) {
+            if !symbol.visibility.includes_index(insertion_index) {
+                return Err(rejected(&format!(
+                    "`{}` is not available before the statement",
+                    symbol.name.as_string()
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Any use of the name in the block, even an unresolved one, could be shadowed by the new variable.
+fn check_name_unused(
+    statements: &Node,
+    variable_name: &str,
+    text: &XcodeText,
+) -> Result<(), ComplexityRefactoringError> {
+    let is_used = identifiers_in(statements).iter().any(|identifier| {
+        get_node_text(identifier, text).ok() == Some(XcodeText::from_str(variable_name))
+    });
+
+    if is_used {
+        return Err(rejected(&format!(
+            "`{}` is already used in this block",
+            variable_name
+        )));
+    }
+    Ok(())
+}
+
+// Same text and same resolved symbols, so e.g. `a + 1` is not matched where `a` is shadowed.
+// Past a write to one of those symbols the expression has a different value, so occurrences are
+// only collected up to the first one.
+fn find_occurrences<'a>(
+    syntax_tree: &'a SwiftSyntaxTree,
+    statements: &Node<'a>,
+    expression: &Node<'a>,
+    insertion_index: usize,
+) -> Vec<Node<'a>> {
+    let text = syntax_tree.text_content();
+    let expression_text = get_node_text(expression, text).ok();
+    let expression_symbols = referenced_symbols(syntax_tree, expression);
+    let first_write = first_write_to(syntax_tree, &expression_symbols, insertion_index);
+
+    let mut occurrences = vec![];
+    let mut candidates = vec![*statements];
+    while let Some(node) = candidates.pop() {
+        let is_occurrence = node.kind() == expression.kind()
+            && node.start_byte() / 2 >= insertion_index
+            && first_write.map_or(true, |write_index| node.start_byte() / 2 < write_index)
+            && is_extractable(&node)
+            && get_node_text(&node, text).ok() == expression_text
+            && referenced_symbols(syntax_tree, &node) == expression_symbols;
+
+        if is_occurrence {
+            occurrences.push(node);
+        } else {
+            candidates.extend(node.children(&mut node.walk()));
+        }
+    }
+
+    occurrences.sort_by_key(|node| node.start_byte());
+    occurrences
+}
+
+fn first_write_to(
+    syntax_tree: &SwiftSyntaxTree,
+    symbol_ids: &[Option<SymbolId>],
+    from_index: usize,
+) -> Option<usize> {
+    let symbol_table = syntax_tree.symbol_table();
+    symbol_ids
+        .iter()
+        .flatten()
+        .filter_map(|symbol_id| symbol_table.symbol(*symbol_id))
+        .flat_map(|symbol| symbol.references.iter())
+        .filter(|reference| {
+            reference.index >= from_index
+                && is_mutated_at(syntax_tree, reference.index, reference.length)
+        })
+        .map(|reference| reference.index)
+        .min()
+}
+
+fn referenced_symbols(syntax_tree: &SwiftSyntaxTree, node: &Node) -> Vec<Option<SymbolId>> {
+    identifiers_in(node)
+        .iter()
+        .map(|identifier| {
+            syntax_tree
+                .symbol_table()
+                .definition_of(identifier.start_byte() / 2)
+                .map(|symbol| symbol.id)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    mod compute_extract_variable_edits {
+        use crate::core_engine::{
+            features::{
+                complexity_refactoring::compute_extract_variable_edits, test_utils::refactored,
+            },
+            TextRange,
+        };
+
+        fn test_fn(code: &str, selection: &str, replace_all_occurrences: bool) -> Option<String> {
+            let text_range = TextRange::new(code.find(selection).unwrap(), selection.len());
+
+            refactored(code, |syntax_tree| {
+                compute_extract_variable_edits(
+                    syntax_tree,
+                    &text_range,
+                    "extracted",
+                    replace_all_occurrences,
+                )
+            })
+            .ok()
+        }
+
+        #[test]
+        fn declares_before_the_statement() {
+            assert_eq!(
+                test_fn(
+                    "func f(a: Int) {\n    if a > 0 {\n        print(a * 2 + 1)\n    }\n}\n",
+                    "a * 2",
+                    false
+                ),
+                Some(
+                    "func f(a: Int) {\n    if a > 0 {\n        let extracted = a * 2\n        print(extracted + 1)\n    }\n}\n"
+                        .to_string()
+                )
+            );
+        }
+
+        #[test]
+        fn replaces_all_occurrences() {
+            assert_eq!(
+                test_fn(
+                    "func f(a: Int) {\n    print(a + 1)\n    print(a + 1)\n}\n",
+                    "a + 1",
+                    true
+                ),
+                Some(
+                    "func f(a: Int) {\n    let extracted = a + 1\n    print(extracted)\n    print(extracted)\n}\n"
+                        .to_string()
+                )
+            );
+        }
+
+        #[test]
+        fn stops_replacing_at_a_write_to_a_dependency() {
+            assert_eq!(
+                test_fn(
+                    "func f() {\n    var a = 1\n    print(a + 1)\n    a += 1\n    print(a + 1)\n}\n",
+                    "a + 1",
+                    true
+                ),
+                Some(
+                    "func f() {\n    var a = 1\n    let extracted = a + 1\n    print(extracted)\n    a += 1\n    print(a + 1)\n}\n"
+                        .to_string()
+                )
+            );
+        }
+
+        #[test]
+        fn rejects_conditionally_evaluated_expressions() {
+            assert_eq!(
+                test_fn(
+                    "func f(a: [Int]?) {\n    print(a != nil && a!.count > 0)\n}\n",
+                    "a!.count > 0",
+                    false
+                ),
+                None
+            );
+        }
+
+        #[test]
+        fn rejects_bindings_of_the_statement() {
+            assert_eq!(
+                test_fn(
+                    "func f(a: Int?) {\n    if let b = a {\n        print(b)\n    }\n    guard let c = a, c * 2 > 4 else { return }\n}\n",
+                    "c * 2",
+                    false
+                ),
+                None
+            );
+        }
+    }
+}
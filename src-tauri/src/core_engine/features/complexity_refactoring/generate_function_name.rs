@@ -10,7 +10,7 @@ pub async fn _generate_function_name(code: XcodeText) -> Result<String, reqwest:
         version: "v1".to_string(),
         method: "generate-name".to_string(),
         apiKey: get_cloud_function_apikey(),
-        code: String::from_utf16_lossy(&code),
+        code: code.as_string(),
     };
 
     let response = reqwest::Client::new()
@@ -0,0 +1,241 @@
+use tree_sitter::Node;
+
+use crate::core_engine::{
+    syntax_tree::{
+        contains_kind, get_node_text, identifiers_in, is_expression, is_mutated_at,
+        SwiftSyntaxTree, Symbol, SymbolKind,
+    },
+    XcodeText,
+};
+
+use super::{rejected, ComplexityRefactoringError, Edit};
+
+// Values of these kinds bind looser than the expression they are inlined into might.
+const COMPOUND_EXPRESSION_KINDS: &[&str] = &[
+    "multiplicative_expression",
+    "additive_expression",
+    "nil_coalescing_expression",
+    "comparison_expression",
+    "conjunction_expression",
+    "disjunction_expression",
+    "bitwise_operation",
+    "ternary_expression",
+    "equality_expression",
+    "range_expression",
+    "open_start_range_expression",
+    "open_end_range_expression",
+    "check_expression",
+    "try_expression",
+    "await_expression",
+    "prefix_expression",
+];
+
+// Replaces all references of a local `let` with its value and removes the declaration.
+pub fn compute_inline_variable_edits(
+    syntax_tree: &SwiftSyntaxTree,
+    index: usize,
+) -> Result<Vec<Edit>, ComplexityRefactoringError> {
+    let text = syntax_tree.text_content();
+    let symbol = syntax_tree
+        .symbol_table()
+        .symbol_at(index)
+        .ok_or_else(|| rejected("No variable found at the cursor"))?;
+    if symbol.kind != SymbolKind::Variable {
+        return Err(rejected("Only local variables can be inlined"));
+    }
+
+    let declaration = property_declaration_of(syntax_tree, symbol)
+        .ok_or_else(|| rejected("Declaration of the variable not found"))?;
+    // A `var` could be reassigned, so its references do not necessarily see the initial value.
+    if !is_let(&declaration, text) {
+        return Err(rejected(
+            "Only constants declared with `let` can be inlined",
+        ));
+    }
+    if declaration
+        .children_by_field_name("name", &mut declaration.walk())
+        .count()
+        != 1
+    {
+        return Err(rejected("The declaration binds more than one name"));
+    }
+    if declaration
+        .children(&mut declaration.walk())
+        .any(|child| child.kind() == "type_annotation")
+    {
+        return Err(rejected("The explicit type of the variable would be lost"));
+    }
+    let value = declaration
+        .child_by_field_name("value")
+        .ok_or_else(|| rejected("The variable has no initial value"))?;
+
+    check_value_unchanged(syntax_tree, symbol, &value)?;
+
+    let value_text = get_node_text(&value, text)
+        .map_err(|err| ComplexityRefactoringError::GenericError(err.into()))?;
+    let parenthesized_value_text = XcodeText::from_str("(") + value_text.clone() + ")";
+
+    let mut edits = vec![Edit::removal_of_range(
+        text,
+        declaration.start_byte() / 2,
+        declaration.end_byte() / 2,
+    )];
+    for reference in &symbol.references {
+        let reference_node = syntax_tree.tree().root_node().descendant_for_byte_range(
+            reference.index * 2,
+            (reference.index + reference.length) * 2,
+        );
+        let needs_parentheses = COMPOUND_EXPRESSION_KINDS.contains(&value.kind())
+            && reference_node
+                .and_then(|node| node.parent())
+                .map_or(false, |parent| is_expression(parent.kind()));
+
+        edits.push(Edit {
+            text: if needs_parentheses {
+                parenthesized_value_text.clone()
+            } else {
+                value_text.clone()
+            },
+            start_index: reference.index,
+            end_index: reference.index + reference.length,
+        });
+    }
+
+    Ok(edits)
+}
+
+// Inlining moves the evaluation of the value to every reference, so the value must neither have
+// side effects evaluated more than once nor depend on symbols which change in between.
+fn check_value_unchanged(
+    syntax_tree: &SwiftSyntaxTree,
+    symbol: &Symbol,
+    value: &Node,
+) -> Result<(), ComplexityRefactoringError> {
+    let symbol_table = syntax_tree.symbol_table();
+
+    if contains_kind(value, "call_expression") && symbol.references.len() > 1 {
+        return Err(rejected(
+            "The value contains a call which would be evaluated more than once",
+        ));
+    }
+
+    let last_use = symbol
+        .references
+        .iter()
+        .map(|reference| reference.index)
+        .max()
+        .unwrap_or(symbol.declaration.index);
+
+    for identifier in identifiers_in(value) {
+        let dependency = match symbol_table.definition_of(identifier.start_byte() / 2) {
+            Some(dependency) => dependency,
+            None => continue,
+        };
+
+        for reference in &symbol.references {
+            let resolved = symbol_table.lookup(&dependency.name, reference.index);
+            if resolved.map(|resolved| resolved.id) != Some(dependency.id) {
+                return Err(rejected(&format!(
+                    "`{}` refers to something else where the variable is used",
+                    dependency.name.as_string()
+                )));
+            }
+        }
+
+        let is_modified_before_last_use = dependency.references.iter().any(|reference| {
+            reference.index > symbol.declaration.index
+                && reference.index < last_use
+                && is_mutated_at(syntax_tree, reference.index, reference.length)
+        });
+        if is_modified_before_last_use {
+            return Err(rejected(&format!(
+                "`{}` changes before the variable is used",
+                dependency.name.as_string()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn property_declaration_of<'a>(
+    syntax_tree: &'a SwiftSyntaxTree,
+    symbol: &Symbol,
+) -> Option<Node<'a>> {
+    let mut node = syntax_tree.tree().root_node().descendant_for_byte_range(
+        symbol.declaration.index * 2,
+        (symbol.declaration.index + symbol.declaration.length) * 2,
+    )?;
+    while node.kind() != "property_declaration" {
+        node = node.parent()?;
+    }
+    Some(node)
+}
+
+fn is_let(declaration: &Node, text: &XcodeText) -> bool {
+    declaration
+        .children(&mut declaration.walk())
+        .filter(|child| child.kind() == "value_binding_pattern" || child.kind() == "let")
+        .filter_map(|child| get_node_text(&child, text).ok())
+        .any(|binding| binding.as_string().trim() == "let")
+}
+
+#[cfg(test)]
+mod tests {
+    mod compute_inline_variable_edits {
+        use crate::core_engine::features::{
+            complexity_refactoring::compute_inline_variable_edits, test_utils::refactored,
+        };
+
+        fn test_fn(code: &str, name: &str) -> Option<String> {
+            refactored(code, |syntax_tree| {
+                compute_inline_variable_edits(syntax_tree, code.find(name).unwrap())
+            })
+            .ok()
+        }
+
+        #[test]
+        fn inlines_and_parenthesizes() {
+            assert_eq!(
+                test_fn(
+                    "func f(a: Int) {\n    let sum = a + 1\n    print(sum * 2, sum)\n}\n",
+                    "sum"
+                ),
+                Some("func f(a: Int) {\n    print((a + 1) * 2, a + 1)\n}\n".to_string())
+            );
+        }
+
+        #[test]
+        fn rejects_var() {
+            assert_eq!(
+                test_fn(
+                    "func f(a: Int) {\n    var sum = a + 1\n    print(sum)\n}\n",
+                    "sum"
+                ),
+                None
+            );
+        }
+
+        #[test]
+        fn rejects_dependencies_changing_before_use() {
+            assert_eq!(
+                test_fn(
+                    "func f() {\n    var count = 1\n    let old = count\n    count += 1\n    print(old)\n}\n",
+                    "old"
+                ),
+                None
+            );
+        }
+
+        #[test]
+        fn rejects_calls_used_more_than_once() {
+            assert_eq!(
+                test_fn(
+                    "func f() {\n    let value = compute()\n    print(value, value)\n}\n",
+                    "value"
+                ),
+                None
+            );
+        }
+    }
+}
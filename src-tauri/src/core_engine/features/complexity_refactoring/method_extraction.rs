@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use cached::proc_macro::cached;
 
 use tokio::sync::mpsc;
@@ -11,14 +9,15 @@ use super::{
     ComplexityRefactoringError, Edit, NodeAddress, NodeSubSlice, SliceInputsAndOutputs,
 };
 use crate::core_engine::{
+    diagnostics::DiagnosticsSpan,
     features::{
         complexity_refactoring::{NodeSlice, ParsingMetadata, SerializedNodeSlice},
-        FeatureSignal,
+        FeatureKind, FeatureSignal,
     },
     rules::TemporaryFileOnDisk,
     syntax_tree::{
         calculate_cognitive_complexities, is_expression, is_l_expression, Complexities,
-        SwiftFunction, SwiftSyntaxTree,
+        SwiftFunction, SwiftSyntaxTree, TreeMetaData,
     },
     SwiftLspError, TextPosition, XcodeText,
 };
@@ -42,7 +41,7 @@ pub fn check_for_method_extraction(
     // Build up a list of possible nodes to extract, each with relevant metrics used for comparison
 
     let node_address = vec![node.id()];
-    let mut parsing_metadata = ParsingMetadata::new(node_address.clone());
+    let mut parsing_metadata = ParsingMetadata::default();
 
     let possible_extractions: Vec<NodeSlice> = walk_node(
         node,
@@ -94,10 +93,14 @@ pub async fn get_edits_for_method_extraction(
 
     ComplexityRefactoring::verify_task_not_cancelled(&signals_sender)?;
 
-    let suggestion = refactor_function(
-        method_extraction_task,
-        &temp_file.path.to_string_lossy().to_string(),
-        signals_sender,
+    let suggestion = DiagnosticsSpan::measure_async(
+        FeatureKind::ComplexityRefactoring,
+        "refactor_function",
+        refactor_function(
+            method_extraction_task,
+            &temp_file.path.to_string_lossy().to_string(),
+            signals_sender,
+        ),
     )
     .await;
 
@@ -236,7 +239,13 @@ fn walk_node<'a>(
 
     let mut cursor = node.walk();
 
-    update_parsing_metadata_for_node(parsing_metadata, &node, &node_address, &text_content)?;
+    update_parsing_metadata_for_node(
+        parsing_metadata,
+        &node,
+        &node_address,
+        syntax_tree.symbol_table(),
+        &text_content,
+    )?;
 
     for child in node.named_children(&mut cursor) {
         possible_extractions.append(&mut walk_node(
@@ -416,9 +425,13 @@ fn get_resulting_complexities(
 
     for node in method_extraction.nodes {
         // Start depth at 1, since we assume wrapping nodes in a function_declaration
-        new_function_complexity +=
-            calculate_cognitive_complexities(&node, &text_content, &mut HashMap::new(), Some(1))
-                .map_err(|e| ComplexityRefactoringError::GenericError(e.into()))?
+        new_function_complexity += calculate_cognitive_complexities(
+            &node,
+            &text_content,
+            &mut TreeMetaData::default(),
+            Some(1),
+        )
+        .map_err(|e| ComplexityRefactoringError::GenericError(e.into()))?
     }
 
     return Ok(ComplexitiesPrediction {
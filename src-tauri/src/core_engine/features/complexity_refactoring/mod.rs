@@ -1,20 +1,28 @@
 pub use annotations::*;
 pub use complexity_refactoring::*;
+pub use extract_variable::compute_extract_variable_edits;
 pub use generate_function_name::*;
+pub use inline_variable::compute_inline_variable_edits;
 pub use method_extraction::check_for_method_extraction;
 pub use node_address::*;
 pub use node_slice::*;
+pub use rename_symbol::compute_rename_edits;
 pub use slice_inputs_and_outputs::*;
+pub use structural_rewrites::check_for_structural_rewrites;
 pub use swift_lsp_refactoring::*;
 pub use types::*;
 
 mod annotations;
 mod complexity_refactoring;
+mod extract_variable;
 mod generate_function_name;
+mod inline_variable;
 mod method_extraction;
 mod node_address;
 mod node_slice;
 mod procedures;
+mod rename_symbol;
 mod slice_inputs_and_outputs;
+mod structural_rewrites;
 mod swift_lsp_refactoring;
 mod types;
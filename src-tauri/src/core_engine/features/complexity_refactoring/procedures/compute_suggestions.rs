@@ -1,20 +1,23 @@
 use crate::{
+    app_state::settings_for_file,
     core_engine::{
+        diagnostics::DiagnosticsSpan,
         features::{
             complexity_refactoring::{
-                check_for_method_extraction, create_annotation_group_for_extraction_and_context,
+                check_for_method_extraction, check_for_structural_rewrites,
+                create_annotation_group_for_extraction_and_context,
                 method_extraction::{get_edits_for_method_extraction, MethodExtractionTask},
                 remove_annotations_for_suggestions, ComplexityRefactoring,
-                ComplexityRefactoringError, Edit, NodeSlice, RefactoringSuggestion, SuggestionHash,
-                SuggestionState, SuggestionsArcMutex, SuggestionsMap,
+                ComplexityRefactoringError, Edit, NodeSlice, RefactoringSuggestion,
+                SerializedNodeSlice, SuggestionHash, SuggestionKind, SuggestionState,
+                SuggestionsArcMutex, SuggestionsMap,
             },
-            FeatureSignal,
+            FeatureKind, FeatureSignal,
         },
         format_code,
         syntax_tree::{SwiftCodeBlockBase, SwiftFunction, SwiftSyntaxTree},
         CodeDocument, EditorWindowUid, TextPosition, TextRange, XcodeText,
     },
-    utils::calculate_hash,
 };
 use anyhow::anyhow;
 use parking_lot::Mutex;
@@ -25,8 +28,6 @@ use tokio::sync::mpsc;
 use tracing::{error, warn};
 use uuid::Uuid;
 
-const MAX_ALLOWED_COMPLEXITY: isize = 9;
-
 pub async fn compute_suggestions(
     suggestions_arc: SuggestionsArcMutex,
     dismissed_suggestions: Arc<Mutex<Vec<SuggestionHash>>>,
@@ -54,6 +55,9 @@ pub async fn compute_suggestions(
     .map_err(|err| ComplexityRefactoringError::GenericError(err.into()))?;
 
     let file_path = code_document.file_path().clone();
+    let max_function_complexity = settings_for_file(&file_path)
+        .thresholds
+        .max_function_complexity;
     let mut s_exps = vec![];
     let mut suggestions: SuggestionsMap = HashMap::new();
 
@@ -66,6 +70,7 @@ pub async fn compute_suggestions(
             function,
             &text_content,
             &file_path,
+            max_function_complexity,
             code_document
                 .syntax_tree()
                 .ok_or(ComplexityRefactoringError::InsufficientContext.into())?,
@@ -98,6 +103,7 @@ fn generate_suggestions_for_function(
     function: SwiftFunction,
     text_content: &XcodeText,
     file_path: &Option<String>,
+    max_function_complexity: isize,
     syntax_tree: &SwiftSyntaxTree,
     suggestions_arc: SuggestionsArcMutex,
     dismissed_suggestions_arc: Arc<Mutex<Vec<SuggestionHash>>>,
@@ -107,6 +113,7 @@ fn generate_suggestions_for_function(
     // This is heavy, should be done in parallel -> rayon, but since it takes TSNodes which is not sent this is not trivial.
     let mut suggestions = compute_suggestions_for_function(
         &function,
+        max_function_complexity,
         suggestions_arc.clone(),
         &text_content,
         &syntax_tree,
@@ -153,7 +160,7 @@ fn generate_suggestions_for_function(
         suggestions_and_meta_infos.iter()
     {
         //
-        // Spin up a task for each suggestion to run against SourceKit
+        // Spin up a task for each suggestion to run against SourceKit, unless its edits are known
         //
         tauri::async_runtime::spawn({
             let method_extraction_task = MethodExtractionTask {
@@ -184,8 +191,13 @@ fn generate_suggestions_for_function(
                     return;
                 };
 
-                let edits =
-                    get_edits_for_method_extraction(method_extraction_task, &signals_sender).await;
+                let edits = match suggestion.edits.clone() {
+                    Some(edits) => Ok(edits),
+                    None => {
+                        get_edits_for_method_extraction(method_extraction_task, &signals_sender)
+                            .await
+                    }
+                };
 
                 match edits {
                     Ok(edits) => {
@@ -286,7 +298,14 @@ async fn format_and_apply_edits_to_text_content(
         edited_content.replace_range(edit.start_index..edit.end_index, edit.text);
     }
 
-    let formatted_new_content = match format_code(&edited_content, &file_path).await {
+    let formatted_new_content = DiagnosticsSpan::measure_async(
+        FeatureKind::ComplexityRefactoring,
+        "format_code",
+        format_code(&edited_content, &file_path),
+    )
+    .await;
+
+    let formatted_new_content = match formatted_new_content {
         Ok(content) => content,
         Err(e) => {
             error!(?e, "Failed to format during refactoring: new content");
@@ -294,7 +313,14 @@ async fn format_and_apply_edits_to_text_content(
         }
     };
 
-    let formatted_old_content = match format_code(&text_content, &file_path).await {
+    let formatted_old_content = DiagnosticsSpan::measure_async(
+        FeatureKind::ComplexityRefactoring,
+        "format_code",
+        format_code(&text_content, &file_path),
+    )
+    .await;
+
+    let formatted_old_content = match formatted_old_content {
         Ok(content) => content,
         Err(e) => {
             error!(?e, "Failed to format during refactoring: old content");
@@ -307,6 +333,7 @@ async fn format_and_apply_edits_to_text_content(
 
 fn compute_suggestions_for_function(
     function: &SwiftFunction,
+    max_function_complexity: isize,
     suggestions_arc: SuggestionsArcMutex,
     text_content: &XcodeText,
     syntax_tree: &SwiftSyntaxTree,
@@ -314,61 +341,98 @@ fn compute_suggestions_for_function(
     window_uid: EditorWindowUid,
 ) -> Result<SuggestionsMap, ComplexityRefactoringError> {
     let prev_complexity = function.get_complexity();
-    if prev_complexity <= MAX_ALLOWED_COMPLEXITY {
+    if prev_complexity <= max_function_complexity {
         return Ok(HashMap::new());
     }
-    let (serialized_node_slice, new_complexity) =
-        match check_for_method_extraction(&function, &text_content, &syntax_tree)? {
-            Some(result) => result,
-            None => return Ok(HashMap::new()),
-        };
+    let method_extraction = DiagnosticsSpan::measure(
+        FeatureKind::ComplexityRefactoring,
+        "check_for_method_extraction",
+        || check_for_method_extraction(&function, &text_content, &syntax_tree),
+    );
 
-    if dismissed_suggestions_arc
-        .lock()
-        .contains(&calculate_hash(&serialized_node_slice))
-    {
-        return Ok(HashMap::new());
+    let mut candidates: Vec<(
+        SuggestionKind,
+        SerializedNodeSlice,
+        isize,
+        Option<Vec<Edit>>,
+    )> = vec![];
+    if let Some((serialized_node_slice, new_complexity)) = method_extraction? {
+        candidates.push((
+            SuggestionKind::MethodExtraction,
+            serialized_node_slice,
+            new_complexity,
+            None,
+        ));
+    }
+
+    let structural_rewrites = DiagnosticsSpan::measure(
+        FeatureKind::ComplexityRefactoring,
+        "check_for_structural_rewrites",
+        || check_for_structural_rewrites(&function, &text_content, &syntax_tree),
+    );
+
+    for rewrite in structural_rewrites? {
+        candidates.push((
+            rewrite.kind,
+            rewrite.serialized_slice,
+            rewrite.new_complexity,
+            Some(rewrite.edits),
+        ));
     }
 
     let mut new_suggestions = HashMap::new();
 
     let old_suggestions =
         ComplexityRefactoring::get_suggestions_for_window(suggestions_arc, window_uid);
-    let old_suggestions_with_same_serialization: Vec<(&Uuid, &RefactoringSuggestion)> =
-        old_suggestions
-            .iter()
-            .filter(|&(_, suggestion)| suggestion.serialized_slice == serialized_node_slice)
-            .collect::<Vec<_>>();
-
-    let id;
-    let state;
-    if old_suggestions_with_same_serialization.len() == 1 {
-        // Re-identify ID with previous value to avoid unnecessary removal and addition
-        id = *old_suggestions_with_same_serialization[0].0;
-        state = match (*old_suggestions_with_same_serialization[0].1).state {
-            SuggestionState::New => SuggestionState::New,
-            SuggestionState::Ready | SuggestionState::Recalculating => {
-                SuggestionState::Recalculating
-            }
+
+    for (kind, serialized_node_slice, new_complexity, edits) in candidates {
+        if dismissed_suggestions_arc
+            .lock()
+            .contains(&kind.dismissal_hash(&serialized_node_slice))
+        {
+            continue;
+        }
+
+        let old_suggestions_with_same_serialization: Vec<(&Uuid, &RefactoringSuggestion)> =
+            old_suggestions
+                .iter()
+                .filter(|&(_, suggestion)| {
+                    suggestion.kind == kind && suggestion.serialized_slice == serialized_node_slice
+                })
+                .collect::<Vec<_>>();
+
+        let id;
+        let state;
+        if old_suggestions_with_same_serialization.len() == 1 {
+            // Re-identify ID with previous value to avoid unnecessary removal and addition
+            id = *old_suggestions_with_same_serialization[0].0;
+            state = match (*old_suggestions_with_same_serialization[0].1).state {
+                SuggestionState::New => SuggestionState::New,
+                SuggestionState::Ready | SuggestionState::Recalculating => {
+                    SuggestionState::Recalculating
+                }
+            };
+        } else {
+            id = uuid::Uuid::new_v4();
+            state = SuggestionState::New;
         };
-    } else {
-        id = uuid::Uuid::new_v4();
-        state = SuggestionState::New;
-    };
 
-    new_suggestions.insert(
-        id,
-        RefactoringSuggestion {
-            state,
-            serialized_slice: serialized_node_slice,
-            main_function_name: function.get_name(),
-            new_complexity,
-            prev_complexity,
-            old_text_content_string: None,
-            new_text_content_string: None,
-            start_index: None,
-        },
-    );
+        new_suggestions.insert(
+            id,
+            RefactoringSuggestion {
+                kind,
+                state,
+                serialized_slice: serialized_node_slice,
+                main_function_name: function.get_name(),
+                new_complexity,
+                prev_complexity,
+                old_text_content_string: None,
+                new_text_content_string: None,
+                start_index: None,
+                edits,
+            },
+        );
+    }
 
     Ok(new_suggestions)
 }
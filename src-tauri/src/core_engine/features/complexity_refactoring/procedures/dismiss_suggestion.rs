@@ -8,14 +8,12 @@ use crate::{
     core_engine::{
         features::{
             complexity_refactoring::{
-                ComplexityRefactoringError, SerializedNodeSlice, SuggestionHash, SuggestionId,
-                SuggestionsArcMutex,
+                ComplexityRefactoringError, SuggestionHash, SuggestionId, SuggestionsArcMutex,
             },
             ComplexityRefactoring,
         },
         CodeDocument,
     },
-    utils::calculate_hash,
 };
 
 const DISMISSED_SUGGESTIONS_FILE_NAME: &str = "dismissed_suggestions.json";
@@ -36,7 +34,11 @@ pub fn dismiss_suggestion(
             ComplexityRefactoringError::SuggestionNotFound(suggestion_id.to_string()),
         )?;
 
-        let hash = write_dismissed_suggestion(&suggestion_to_dismiss.serialized_slice)?;
+        let hash = write_dismissed_suggestion(
+            suggestion_to_dismiss
+                .kind
+                .dismissal_hash(&suggestion_to_dismiss.serialized_slice),
+        )?;
         dismissed_suggestions.lock().push(hash);
     }
 
@@ -48,9 +50,8 @@ pub fn dismiss_suggestion(
 }
 
 fn write_dismissed_suggestion(
-    suggestion: &SerializedNodeSlice,
+    hash: SuggestionHash,
 ) -> Result<SuggestionHash, ComplexityRefactoringError> {
-    let hash = calculate_hash::<SerializedNodeSlice>(&suggestion);
     let app_dir = app_handle()
         .path_resolver()
         .app_dir()
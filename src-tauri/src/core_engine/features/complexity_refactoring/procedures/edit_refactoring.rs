@@ -0,0 +1,54 @@
+use crate::{
+    app_handle,
+    core_engine::{
+        events::SuggestionEvent,
+        features::{
+            complexity_refactoring::{ComplexityRefactoringError, Edit},
+            structural_search::{apply_edits, compute_replace_preview},
+            ReplacePreview,
+        },
+        CodeDocument, XcodeText,
+    },
+};
+
+use super::apply_refactored_text_content;
+
+// Refactorings which are computed as edits of the current text, e.g. a rename. `event` builds the
+// message updating the frontend's preview from the preview or the reason it was rejected.
+pub fn preview_edit_refactoring(
+    edits: Result<(XcodeText, Vec<Edit>), ComplexityRefactoringError>,
+    event: impl FnOnce(Option<ReplacePreview>, Option<String>) -> SuggestionEvent,
+) -> Result<(), ComplexityRefactoringError> {
+    let preview = edits.and_then(|(text, edits)| {
+        compute_replace_preview(&text, &edits)
+            .map_err(|err| ComplexityRefactoringError::GenericError(err.into()))
+    });
+
+    let (preview, error) = match preview {
+        Ok(preview) => (Some(preview), None),
+        Err(ComplexityRefactoringError::RefactoringRejected(reason)) => (None, Some(reason)),
+        Err(err) => (None, Some(err.to_string())),
+    };
+
+    event(preview, error).publish_to_tauri(&app_handle());
+
+    Ok(())
+}
+
+// The edits are expected to be recomputed against the current text, so a preview never applies
+// to outdated content. Clears the preview once applied.
+pub async fn perform_edit_refactoring(
+    code_document: &CodeDocument,
+    edits: Result<(XcodeText, Vec<Edit>), ComplexityRefactoringError>,
+    event: impl FnOnce(Option<ReplacePreview>, Option<String>) -> SuggestionEvent,
+) -> Result<(), ComplexityRefactoringError> {
+    let (text, edits) = edits?;
+    let new_text = apply_edits(&text, &edits)
+        .map_err(|err| ComplexityRefactoringError::GenericError(err.into()))?;
+
+    apply_refactored_text_content(&text, &new_text, code_document.selected_text_range()).await?;
+
+    event(None, None).publish_to_tauri(&app_handle());
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use tokio::sync::mpsc;
+
+use crate::core_engine::{
+    events::{
+        models::{LocalRefactoring, LocalRefactoringMessage, UpdateLocalRefactoringPreviewMessage},
+        SuggestionEvent,
+    },
+    features::{
+        complexity_refactoring::{
+            compute_extract_variable_edits, compute_inline_variable_edits,
+            method_extraction::{get_edits_for_method_extraction, MethodExtractionTask},
+            ComplexityRefactoringError, Edit,
+        },
+        FeatureSignal,
+    },
+    CodeDocument, TextPosition, TextRange, XcodeText,
+};
+
+use super::edit_refactoring::{perform_edit_refactoring, preview_edit_refactoring};
+
+pub async fn preview_local_refactoring(
+    code_document: CodeDocument,
+    msg: LocalRefactoringMessage,
+    signals_sender: mpsc::Sender<FeatureSignal>,
+) -> Result<(), ComplexityRefactoringError> {
+    preview_edit_refactoring(
+        compute_local_refactoring(&code_document, &msg, &signals_sender).await,
+        |preview, error| {
+            SuggestionEvent::UpdateLocalRefactoringPreview(UpdateLocalRefactoringPreviewMessage {
+                request: msg,
+                preview,
+                error,
+            })
+        },
+    )
+}
+
+pub async fn perform_local_refactoring(
+    code_document: CodeDocument,
+    msg: LocalRefactoringMessage,
+    signals_sender: mpsc::Sender<FeatureSignal>,
+) -> Result<(), ComplexityRefactoringError> {
+    let edits = compute_local_refactoring(&code_document, &msg, &signals_sender).await;
+    perform_edit_refactoring(&code_document, edits, |preview, error| {
+        SuggestionEvent::UpdateLocalRefactoringPreview(UpdateLocalRefactoringPreviewMessage {
+            request: msg,
+            preview,
+            error,
+        })
+    })
+    .await
+}
+
+async fn compute_local_refactoring(
+    code_document: &CodeDocument,
+    msg: &LocalRefactoringMessage,
+    signals_sender: &mpsc::Sender<FeatureSignal>,
+) -> Result<(XcodeText, Vec<Edit>), ComplexityRefactoringError> {
+    let text_range = msg
+        .text_range
+        .or(*code_document.selected_text_range())
+        .ok_or(ComplexityRefactoringError::InsufficientContext)?;
+    let syntax_tree = code_document
+        .syntax_tree()
+        .ok_or(ComplexityRefactoringError::InsufficientContext)?;
+
+    let edits = match &msg.refactoring {
+        LocalRefactoring::ExtractVariable {
+            variable_name,
+            replace_all_occurrences,
+        } => compute_extract_variable_edits(
+            syntax_tree,
+            &text_range,
+            variable_name,
+            *replace_all_occurrences,
+        )?,
+        LocalRefactoring::InlineVariable => {
+            compute_inline_variable_edits(syntax_tree, text_range.index)?
+        }
+        LocalRefactoring::ExtractFunction => {
+            return compute_extract_function_edits(code_document, &text_range, signals_sender)
+                .await;
+        }
+    };
+
+    Ok((syntax_tree.text_content().clone(), edits))
+}
+
+async fn compute_extract_function_edits(
+    code_document: &CodeDocument,
+    text_range: &TextRange,
+    signals_sender: &mpsc::Sender<FeatureSignal>,
+) -> Result<(XcodeText, Vec<Edit>), ComplexityRefactoringError> {
+    let text_content = code_document
+        .text_content()
+        .ok_or(ComplexityRefactoringError::InsufficientContext)?
+        .clone();
+    let start_position = TextPosition::from_TextIndex(&text_content, text_range.index)
+        .ok_or(ComplexityRefactoringError::InsufficientContext)?;
+
+    let edits = get_edits_for_method_extraction(
+        MethodExtractionTask {
+            text_content: text_content.clone(),
+            start_position,
+            range_length: text_range.length,
+            file_path: code_document.file_path().clone(),
+        },
+        signals_sender,
+    )
+    .await?;
+
+    Ok((text_content, edits))
+}
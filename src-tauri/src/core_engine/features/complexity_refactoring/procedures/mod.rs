@@ -1,9 +1,14 @@
 pub use compute_suggestions::compute_suggestions;
 pub use dismiss_suggestion::*;
-pub use perform_suggestion::perform_suggestion;
+pub use local_refactoring::{perform_local_refactoring, preview_local_refactoring};
+pub use perform_suggestion::{apply_refactored_text_content, perform_suggestion};
+pub use rename_symbol::{perform_rename_symbol, preview_rename_symbol};
 pub use select_suggestion::select_suggestion;
 
 mod compute_suggestions;
 mod dismiss_suggestion;
+mod edit_refactoring;
+mod local_refactoring;
 mod perform_suggestion;
+mod rename_symbol;
 mod select_suggestion;
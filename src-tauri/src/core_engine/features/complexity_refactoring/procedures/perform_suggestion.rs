@@ -6,7 +6,7 @@ use crate::{
             complexity_refactoring::{ComplexityRefactoringError, SuggestionsArcMutex},
             ComplexityRefactoring, FeatureKind, SuggestionId,
         },
-        CodeDocument, XcodeText,
+        CodeDocument, TextRange, XcodeText,
     },
     platform::macos::{replace_text_content, set_selected_text_range, GetVia},
 };
@@ -46,19 +46,12 @@ pub async fn perform_suggestion(
         .text_content()
         .ok_or(ComplexityRefactoringError::InsufficientContext)?;
 
-    match replace_text_content(
+    apply_refactored_text_content(
         &text_content,
         &XcodeText::from_str(&new_content),
         code_document.selected_text_range(),
     )
-    .await
-    {
-        Ok(_) => {}
-        Err(err) => {
-            error!(?err, "Error replacing text content");
-            return Err(ComplexityRefactoringError::GenericError(err.into()));
-        }
-    }
+    .await?;
 
     ComplexityRefactoring::remove_suggestion_and_publish(
         &window_uid,
@@ -81,3 +74,18 @@ pub async fn perform_suggestion(
 
     Ok(())
 }
+
+// Shared by all refactorings, so they restore the cursor and scroll position the same way.
+pub async fn apply_refactored_text_content(
+    text_content: &XcodeText,
+    new_text_content: &XcodeText,
+    selected_text_range: &Option<TextRange>,
+) -> Result<(), ComplexityRefactoringError> {
+    match replace_text_content(text_content, new_text_content, selected_text_range).await {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            error!(?err, "Error replacing text content");
+            Err(ComplexityRefactoringError::GenericError(err.into()))
+        }
+    }
+}
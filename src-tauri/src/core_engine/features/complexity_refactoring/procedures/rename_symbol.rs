@@ -0,0 +1,60 @@
+use crate::core_engine::{
+    events::{
+        models::{RenameSymbolMessage, UpdateRenamePreviewMessage},
+        SuggestionEvent,
+    },
+    features::complexity_refactoring::{compute_rename_edits, ComplexityRefactoringError, Edit},
+    CodeDocument, XcodeText,
+};
+
+use super::edit_refactoring::{perform_edit_refactoring, preview_edit_refactoring};
+
+pub fn preview_rename_symbol(
+    code_document: CodeDocument,
+    msg: RenameSymbolMessage,
+) -> Result<(), ComplexityRefactoringError> {
+    preview_edit_refactoring(compute_rename(&code_document, &msg), |preview, error| {
+        SuggestionEvent::UpdateRenamePreview(UpdateRenamePreviewMessage {
+            request: msg,
+            preview,
+            error,
+        })
+    })
+}
+
+pub async fn perform_rename_symbol(
+    code_document: CodeDocument,
+    msg: RenameSymbolMessage,
+) -> Result<(), ComplexityRefactoringError> {
+    let edits = compute_rename(&code_document, &msg);
+    perform_edit_refactoring(&code_document, edits, |preview, error| {
+        SuggestionEvent::UpdateRenamePreview(UpdateRenamePreviewMessage {
+            request: msg,
+            preview,
+            error,
+        })
+    })
+    .await
+}
+
+fn compute_rename(
+    code_document: &CodeDocument,
+    msg: &RenameSymbolMessage,
+) -> Result<(XcodeText, Vec<Edit>), ComplexityRefactoringError> {
+    let syntax_tree = code_document
+        .syntax_tree()
+        .ok_or(ComplexityRefactoringError::InsufficientContext)?;
+    let index = msg
+        .text_index
+        .or_else(|| {
+            code_document
+                .selected_text_range()
+                .as_ref()
+                .map(|range| range.index)
+        })
+        .ok_or(ComplexityRefactoringError::InsufficientContext)?;
+
+    let edits = compute_rename_edits(syntax_tree, index, &msg.new_name)?;
+
+    Ok((syntax_tree.text_content().clone(), edits))
+}
@@ -0,0 +1,339 @@
+use tree_sitter::Node;
+
+use crate::core_engine::{
+    syntax_tree::{
+        get_node_text, ScopeId, ScopeKind, SwiftSyntaxTree, Symbol, SymbolKind, SymbolTable,
+    },
+    XcodeText,
+};
+
+use super::{rejected, ComplexityRefactoringError, Edit};
+
+const SWIFT_KEYWORDS: &[&str] = &[
+    "associatedtype",
+    "class",
+    "deinit",
+    "enum",
+    "extension",
+    "fileprivate",
+    "func",
+    "import",
+    "init",
+    "inout",
+    "internal",
+    "let",
+    "open",
+    "operator",
+    "private",
+    "precedencegroup",
+    "protocol",
+    "public",
+    "rethrows",
+    "static",
+    "struct",
+    "subscript",
+    "typealias",
+    "var",
+    "break",
+    "case",
+    "catch",
+    "continue",
+    "default",
+    "defer",
+    "do",
+    "else",
+    "fallthrough",
+    "for",
+    "guard",
+    "if",
+    "in",
+    "repeat",
+    "return",
+    "throw",
+    "switch",
+    "where",
+    "while",
+    "Any",
+    "as",
+    "await",
+    "false",
+    "is",
+    "nil",
+    "self",
+    "Self",
+    "super",
+    "throws",
+    "true",
+    "try",
+    "_",
+];
+
+// Edits renaming the symbol declared or referenced at the index, including all its references.
+// Only symbols which cannot be referenced from other files are renamed.
+pub fn compute_rename_edits(
+    syntax_tree: &SwiftSyntaxTree,
+    index: usize,
+    new_name: &str,
+) -> Result<Vec<Edit>, ComplexityRefactoringError> {
+    validate_identifier(new_name)?;
+
+    let symbol_table = syntax_tree.symbol_table();
+    let symbol = symbol_table
+        .symbol_at(index)
+        .ok_or_else(|| rejected("No symbol found at the cursor"))?;
+
+    let new_name_text = XcodeText::from_str(new_name);
+    if symbol.name == new_name_text {
+        return Err(rejected("The symbol already has this name"));
+    }
+
+    let declaration_node = declaration_node(syntax_tree, symbol)
+        .ok_or_else(|| rejected("Declaration of the symbol not found"))?;
+    if !is_file_local(
+        symbol,
+        symbol_table,
+        &declaration_node,
+        syntax_tree.text_content(),
+    ) {
+        return Err(rejected(
+            "Only local symbols and private members can be renamed",
+        ));
+    }
+    check_for_conflicts(symbol, symbol_table, &new_name_text)?;
+
+    let mut edits = vec![Edit {
+        text: new_name_text.clone(),
+        start_index: symbol.declaration.index,
+        end_index: symbol.declaration.index + symbol.declaration.length,
+    }];
+    if symbol.kind == SymbolKind::Parameter && !has_argument_label(&declaration_node) {
+        // The name doubles as argument label; keep it so call sites stay valid.
+        edits[0].text = symbol.name.clone() + " " + new_name;
+    }
+    for reference in &symbol.references {
+        edits.push(Edit {
+            text: new_name_text.clone(),
+            start_index: reference.index,
+            end_index: reference.index + reference.length,
+        });
+    }
+
+    Ok(edits)
+}
+
+pub fn validate_identifier(name: &str) -> Result<(), ComplexityRefactoringError> {
+    let mut chars = name.chars();
+    let is_identifier = match chars.next() {
+        Some(first) => {
+            (first.is_alphabetic() || first == '_')
+                && chars.all(|c| c.is_alphanumeric() || c == '_')
+        }
+        None => false,
+    };
+
+    if !is_identifier {
+        return Err(rejected(&format!("`{}` is not a valid identifier", name)));
+    }
+    if SWIFT_KEYWORDS.contains(&name) {
+        return Err(rejected(&format!("`{}` is a reserved keyword", name)));
+    }
+    Ok(())
+}
+
+// Renaming must neither clash with a declaration in the same scope, nor capture references of
+// another symbol with the new name, nor let references of this symbol resolve to another one.
+fn check_for_conflicts(
+    symbol: &Symbol,
+    symbol_table: &SymbolTable,
+    new_name: &XcodeText,
+) -> Result<(), ComplexityRefactoringError> {
+    for other in symbol_table.symbols() {
+        if other.id == symbol.id || other.name != *new_name {
+            continue;
+        }
+
+        if other.scope == symbol.scope {
+            return Err(rejected(&format!(
+                "`{}` is already declared in this scope",
+                new_name.as_string()
+            )));
+        }
+
+        if other
+            .references
+            .iter()
+            .any(|reference| symbol.visibility.includes_index(reference.index))
+        {
+            return Err(rejected(&format!(
+                "Renaming would shadow `{}` where it is used",
+                new_name.as_string()
+            )));
+        }
+    }
+
+    for reference in &symbol.references {
+        if let Some(other) = symbol_table.lookup(new_name, reference.index) {
+            if is_nested_in(symbol_table, other.scope, symbol.scope) {
+                return Err(rejected(&format!(
+                    "A reference would resolve to another `{}`",
+                    new_name.as_string()
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn is_nested_in(symbol_table: &SymbolTable, scope: ScopeId, outer_scope: ScopeId) -> bool {
+    let mut scope_id = Some(scope);
+    while let Some(id) = scope_id {
+        if id == outer_scope {
+            return true;
+        }
+        scope_id = symbol_table.scopes().get(id).and_then(|scope| scope.parent);
+    }
+    false
+}
+
+// Members and file level declarations are visible to other files, unless they, or a
+// declaration around them, are private or fileprivate.
+fn is_file_local(
+    symbol: &Symbol,
+    symbol_table: &SymbolTable,
+    declaration_node: &Node,
+    text: &XcodeText,
+) -> bool {
+    let scope_kind = symbol_table
+        .scopes()
+        .get(symbol.scope)
+        .map(|scope| scope.kind);
+    if scope_kind != Some(ScopeKind::File) && scope_kind != Some(ScopeKind::Type) {
+        return true;
+    }
+
+    let mut node = declaration_node.parent();
+    while let Some(ancestor) = node {
+        if has_private_modifier(&ancestor, text) {
+            return true;
+        }
+        node = ancestor.parent();
+    }
+    false
+}
+
+fn has_private_modifier(node: &Node, text: &XcodeText) -> bool {
+    node.children(&mut node.walk())
+        .filter(|child| child.kind() == "modifiers")
+        .filter_map(|modifiers| get_node_text(&modifiers, text).ok())
+        .any(|modifiers| {
+            modifiers
+                .as_string()
+                .split_whitespace()
+                .any(|modifier| modifier == "private" || modifier == "fileprivate")
+        })
+}
+
+fn has_argument_label(parameter_name: &Node) -> bool {
+    match parameter_name.parent() {
+        Some(parameter) if parameter.kind() == "parameter" => {
+            parameter
+                .children(&mut parameter.walk())
+                .filter(|child| child.kind() == "simple_identifier" || child.kind() == "_")
+                .count()
+                > 1
+        }
+        _ => true,
+    }
+}
+
+fn declaration_node<'a>(syntax_tree: &'a SwiftSyntaxTree, symbol: &Symbol) -> Option<Node<'a>> {
+    let start_byte = symbol.declaration.index * 2;
+    let end_byte = (symbol.declaration.index + symbol.declaration.length) * 2;
+    syntax_tree
+        .tree()
+        .root_node()
+        .descendant_for_byte_range(start_byte, end_byte)
+}
+
+#[cfg(test)]
+mod tests {
+    mod compute_rename_edits {
+        use crate::core_engine::features::{
+            complexity_refactoring::compute_rename_edits, test_utils::refactored,
+        };
+
+        fn test_fn(code: &str, name: &str, new_name: &str) -> Result<String, String> {
+            refactored(code, |syntax_tree| {
+                compute_rename_edits(syntax_tree, code.find(name).unwrap(), new_name)
+            })
+        }
+
+        #[test]
+        fn renames_local_and_references() {
+            assert_eq!(
+                test_fn(
+                    "func f() {\n    let total = 1\n    print(total + total)\n}\n",
+                    "total",
+                    "sum"
+                ),
+                Ok("func f() {\n    let sum = 1\n    print(sum + sum)\n}\n".to_string())
+            );
+        }
+
+        #[test]
+        fn keeps_argument_label_of_parameter() {
+            assert_eq!(
+                test_fn(
+                    "func f(count: Int) -> Int {\n    return count * 2\n}\n",
+                    "count",
+                    "n"
+                ),
+                Ok("func f(count n: Int) -> Int {\n    return n * 2\n}\n".to_string())
+            );
+        }
+
+        #[test]
+        fn renames_closure_parameter() {
+            assert_eq!(
+                test_fn("let doubled = [1].map { x in x * 2 }\n", "x", "value"),
+                Ok("let doubled = [1].map { value in value * 2 }\n".to_string())
+            );
+        }
+
+        #[test]
+        fn rejects_invalid_names() {
+            let code = "func f() {\n    let total = 1\n    print(total)\n}\n";
+
+            assert!(test_fn(code, "total", "return").is_err());
+            assert!(test_fn(code, "total", "1total").is_err());
+            assert!(test_fn(code, "total", "").is_err());
+        }
+
+        #[test]
+        fn rejects_collisions_and_shadowing() {
+            let code = "func f(limit: Int) {\n    let total = 1\n    let other = 2\n    print(total + limit + other)\n}\n";
+
+            // Declared in the same scope.
+            assert!(test_fn(code, "total", "other").is_err());
+            // Would capture the reference to the parameter.
+            assert!(test_fn(code, "total", "limit").is_err());
+        }
+
+        #[test]
+        fn rejects_non_private_members() {
+            assert!(test_fn("class A {\n    var count = 0\n}\n", "count", "total").is_err());
+            assert_eq!(
+                test_fn(
+                    "class A {\n    private var count = 0\n    func f() { count += 1 }\n}\n",
+                    "count",
+                    "total"
+                ),
+                Ok(
+                    "class A {\n    private var total = 0\n    func f() { total += 1 }\n}\n"
+                        .to_string()
+                )
+            );
+        }
+    }
+}
@@ -2,80 +2,53 @@ use std::collections::HashMap;
 
 use tree_sitter::Node;
 
-use crate::core_engine::{syntax_tree::get_node_text, XcodeText};
+use crate::core_engine::{
+    syntax_tree::{get_node_text, SymbolId, SymbolTable},
+    XcodeText,
+};
 
 use super::{
     get_node_address, is_child_of, ComplexityRefactoringError, NodeAddress, NodeSlice, NodeSubSlice,
 };
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Scope {
-    pub declarations: HashMap<XcodeText, Declaration>,
-}
-
 #[derive(Clone, Debug)]
 pub struct ContinueOrBreak {
     pub node_address: NodeAddress,
     pub target_node_address: NodeAddress,
 }
 
+// Declarations are resolved by the file's symbol table; only those inside the walked function
+// are collected, so references to anything declared outside of it are not counted as inputs.
+#[derive(Default)]
 pub struct ParsingMetadata {
-    pub scopes: HashMap<NodeAddress, Scope>,
+    pub declarations: HashMap<SymbolId, Declaration>,
     pub continues_and_breaks: Vec<ContinueOrBreak>,
 }
 
-impl ParsingMetadata {
-    pub fn new(function_node_address: NodeAddress) -> Self {
-        Self {
-            scopes: HashMap::from([(
-                function_node_address,
-                Scope {
-                    declarations: HashMap::new(),
-                },
-            )]),
-            continues_and_breaks: Vec::new(),
-        }
-    }
-}
 pub fn update_parsing_metadata_for_node(
     parsing_metadata: &mut ParsingMetadata,
     node: &Node,
     node_address: &NodeAddress,
+    symbol_table: &SymbolTable,
     text_content: &XcodeText,
 ) -> Result<(), ComplexityRefactoringError> {
-    if node_has_own_scope(&node) {
-        parsing_metadata.scopes.insert(
-            node_address.clone(),
-            Scope {
-                declarations: HashMap::new(),
-            },
-        );
-    }
-
-    if let Some(declaration) = try_get_declaration_node(&node) {
-        let name = get_node_text(&declaration, &text_content)
-            .map_err(|e| ComplexityRefactoringError::GenericError(e.into()))?;
-
-        get_scope(&node_address, &mut parsing_metadata.scopes)
-            .declarations
-            .insert(
-                name.clone(),
+    if node.kind() == "simple_identifier" {
+        // Treesitter parses UTF-16, so every character takes up two bytes.
+        let index = node.start_byte() / 2;
+        if let Some(symbol) = symbol_table.declared_at(index) {
+            // The declaring identifier counts as the first reference.
+            parsing_metadata.declarations.insert(
+                symbol.id,
                 Declaration {
+                    name: symbol.name.clone(),
                     declared_in_node: node_address.clone(),
-                    referenced_in_nodes: Vec::new(),
+                    referenced_in_nodes: vec![node_address.clone()],
                 },
             );
-    }
-    if let Some(name) = get_variable_name_if_reference(&node, &text_content) {
-        let mut curr_address: NodeAddress = node_address.clone();
-        while curr_address.len() > 0 {
-            if let Some(scope) = parsing_metadata.scopes.get_mut(&curr_address) {
-                if let Some(declaration) = scope.declarations.get_mut(&name) {
-                    declaration.referenced_in_nodes.push(node_address.clone());
-                    break;
-                }
+        } else if let Some(symbol) = symbol_table.definition_of(index) {
+            if let Some(declaration) = parsing_metadata.declarations.get_mut(&symbol.id) {
+                declaration.referenced_in_nodes.push(node_address.clone());
             }
-            curr_address.pop();
         }
     }
     if node.kind() == "control_transfer_statement" {
@@ -153,7 +126,7 @@ pub fn get_slice_inputs_and_outputs(
     get_slice_inputs_and_outputs_internal(
         &slice.nodes.iter().map(|n| n.id()).collect(),
         &slice.parent_address,
-        &parsing_metadata.scopes,
+        &parsing_metadata.declarations,
     )
 }
 
@@ -164,112 +137,39 @@ pub fn get_sub_slice_inputs_and_outputs(
     get_slice_inputs_and_outputs_internal(
         &slice.nodes.iter().map(|n| n.id()).collect(),
         &slice.parent_address,
-        &parsing_metadata.scopes,
+        &parsing_metadata.declarations,
     )
 }
 fn get_slice_inputs_and_outputs_internal(
     node_ids: &Vec<usize>,
     parent_address: &NodeAddress,
-    scopes: &HashMap<NodeAddress, Scope>,
+    declarations: &HashMap<SymbolId, Declaration>,
 ) -> SliceInputsAndOutputs {
     let mut result = SliceInputsAndOutputs {
         input_names: Vec::new(),
         output_names: Vec::new(),
     };
-    let mut curr_address = parent_address.clone();
-    while curr_address.len() > 0 {
-        if let Some(scope) = scopes.get(&curr_address) {
-            for (name, declaration) in &scope.declarations {
-                let (referenced_in_slice, referenced_in_and_after_slice) =
-                    check_if_declaration_referenced_in_nodes_or_in_and_after_nodes(
-                        &declaration,
-                        &node_ids,
-                        &parent_address,
-                    );
-                // TODO: Can just use one check. Doesn't matter if declaration or reference.
-                let declared_in_slice = check_if_declaration_declared_in_slice(
-                    &node_ids,
-                    &parent_address,
-                    &declaration,
-                );
-                let name = name.clone();
-                if declared_in_slice && referenced_in_and_after_slice {
-                    result.output_names.push(name);
-                } else if referenced_in_slice && !declared_in_slice {
-                    result.input_names.push(name);
-                }
-            }
-        }
-        curr_address.pop();
-    }
-    return result;
-}
-
-fn node_has_own_scope(node: &Node) -> bool {
-    node.kind() == "statements" // TODO: Fix this. Is it true??
-}
-
-fn get_scope<'a>(
-    node_address: &NodeAddress,
-    scopes: &'a mut HashMap<NodeAddress, Scope>,
-) -> &'a mut Scope {
-    let mut curr_address: NodeAddress = node_address.clone();
-    while curr_address.len() > 0 {
-        if scopes.get(&curr_address).is_some() {
-            return scopes.get_mut(&curr_address).unwrap();
-        }
-        curr_address.pop();
-    }
-    panic!("No parent scope for node!");
-}
-
-fn get_variable_name_if_reference(node: &Node, text_content: &XcodeText) -> Option<XcodeText> {
-    if node.kind() == "simple_identifier" {
-        get_node_text(node, text_content).ok()
-    } else {
-        None
-    }
-}
-
-fn try_get_declaration_node<'a>(node: &Node<'a>) -> Option<Node<'a>> {
-    match node.kind() {
-        "property_declaration" => {
-            return Some(
-                node.child_by_field_name("name")?
-                    .child_by_field_name("bound_identifier")?,
+    // Sorted to keep the order of the extracted function's parameters stable.
+    let mut declarations: Vec<(&SymbolId, &Declaration)> = declarations.iter().collect();
+    declarations.sort_by_key(|(symbol_id, _)| **symbol_id);
+    for (_, declaration) in declarations {
+        let (referenced_in_slice, referenced_in_and_after_slice) =
+            check_if_declaration_referenced_in_nodes_or_in_and_after_nodes(
+                &declaration,
+                &node_ids,
+                &parent_address,
             );
-        }
-        "function_declaration" => {
-            // Note: This will also pick up the main function name, treating it as an 'input', even though it is globally available. TODO: Fix this at some point - although it's not too bad to penalise recursion triangles...
-            for child in node.children_by_field_name("name", &mut node.walk()) {
-                if child.kind() == "simple_identifier" {
-                    return Some(child);
-                }
-            }
-        }
-        "parameter" => {
-            // Second "simple_identifier" is internal identifier, which matters; first will be overwritten
-            let mut result = None;
-            for child in node.children_by_field_name("name", &mut node.walk()) {
-                if child.kind() == "simple_identifier" {
-                    result = Some(child);
-                }
-            }
-            return result;
-        }
-        "for_statement" => {
-            return Some(
-                node.child_by_field_name("item")?
-                    .child_by_field_name("bound_identifier")?,
-            );
-        }
-        _ => {
-            // Not sure if there are any other cases?
-            return None;
+        // TODO: Can just use one check. Doesn't matter if declaration or reference.
+        let declared_in_slice =
+            check_if_declaration_declared_in_slice(&node_ids, &parent_address, &declaration);
+        let name = declaration.name.clone();
+        if declared_in_slice && referenced_in_and_after_slice {
+            result.output_names.push(name);
+        } else if referenced_in_slice && !declared_in_slice {
+            result.input_names.push(name);
         }
     }
-
-    return None;
+    return result;
 }
 
 // Checks if declaration is referenced in node range. If it is, checks if it is also referenced after it.
@@ -313,6 +213,7 @@ fn check_if_declaration_declared_in_slice(
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Declaration {
+    pub name: XcodeText,
     pub declared_in_node: NodeAddress,
     pub referenced_in_nodes: Vec<NodeAddress>,
 }
@@ -338,8 +239,14 @@ mod tests {
             node_address: NodeAddress,
             parsing_metadata: &mut ParsingMetadata,
         ) -> () {
-            update_parsing_metadata_for_node(parsing_metadata, &node, &node_address, &text_content)
-                .unwrap();
+            update_parsing_metadata_for_node(
+                parsing_metadata,
+                &node,
+                &node_address,
+                syntax_tree.symbol_table(),
+                &text_content,
+            )
+            .unwrap();
 
             for child in node.named_children(&mut node.walk()) {
                 walk_node_test(
@@ -379,7 +286,7 @@ mod tests {
             let function_decl_node = functions[0].props.node;
             let function_node_address = vec![function_decl_node.clone().id()];
 
-            let mut parsing_metadata = ParsingMetadata::new(function_node_address.clone());
+            let mut parsing_metadata = ParsingMetadata::default();
             walk_node_test(
                 function_decl_node,
                 &swift_syntax_tree.text_content(),
@@ -421,7 +328,7 @@ mod tests {
             let function_decl_node = functions[0].props.node;
             let function_node_address = vec![function_decl_node.clone().id()];
 
-            let mut parsing_metadata = ParsingMetadata::new(function_node_address.clone());
+            let mut parsing_metadata = ParsingMetadata::default();
             walk_node_test(
                 function_decl_node,
                 &swift_syntax_tree.text_content(),
@@ -464,7 +371,7 @@ mod tests {
             let function_decl_node = functions[0].props.node;
             let function_node_address = vec![function_decl_node.clone().id()];
 
-            let mut parsing_metadata = ParsingMetadata::new(function_node_address.clone());
+            let mut parsing_metadata = ParsingMetadata::default();
             walk_node_test(
                 function_decl_node,
                 &swift_syntax_tree.text_content(),
@@ -480,7 +387,7 @@ mod tests {
         }
 
         #[test]
-        fn builds_up_correct_scope_tree() {
+        fn collects_declarations_and_references() {
             let text_content = XcodeText::from_str(
                 r#"
                 func function(arg1: Int) {
@@ -503,7 +410,7 @@ mod tests {
             let function_decl_node = functions[0].props.node;
             let function_node_address = vec![function_decl_node.clone().id()];
 
-            let mut parsing_metadata = ParsingMetadata::new(function_node_address.clone());
+            let mut parsing_metadata = ParsingMetadata::default();
             walk_node_test(
                 function_decl_node,
                 &swift_syntax_tree.text_content(),
@@ -513,52 +420,31 @@ mod tests {
             );
 
             assert_eq!(parsing_metadata.continues_and_breaks.len(), 0);
-            assert_eq!(parsing_metadata.scopes.len(), 2); // Starting scope, statements scope
-            let function_scope_decls = parsing_metadata
-                .scopes
-                .get(&vec![function_decl_node.id()])
-                .unwrap()
-                .declarations
-                .clone();
-            assert_eq!(function_scope_decls.len(), 2); // TODO: Should really be 1, but it's not too bad to also include the main function name here as a false input (penalising triangular recursion implicitly)
-            let arg1_decl = function_scope_decls
-                .get(&XcodeText::from_str("arg1"))
-                .unwrap();
-            assert_eq!(arg1_decl.declared_in_node.len(), 2); // function_decl, parameter
-            assert_eq!(arg1_decl.referenced_in_nodes.len(), 2); // Declared once, used in one other place
-            assert_eq!(arg1_decl.referenced_in_nodes[0].len(), 3); // function_decl, parameter, simple_expression
-            assert_eq!(arg1_decl.referenced_in_nodes[1].len(), 6); // function_decl, function_body, statements, property_declaration, additive_expression, simple_identifier
-
-            parsing_metadata
-                .scopes
-                .remove(&vec![function_decl_node.id()]);
-            assert_eq!(parsing_metadata.scopes.keys().len(), 1);
-            assert_eq!(parsing_metadata.scopes.keys().next().unwrap().len(), 3); // function_decl, function_body, statements
-            assert_eq!(
+            let declaration_of = |name: &str| {
                 parsing_metadata
-                    .scopes
+                    .declarations
                     .values()
-                    .next()
+                    .find(|declaration| declaration.name == XcodeText::from_str(name))
                     .unwrap()
-                    .declarations
-                    .len(),
-                1
-            );
-
-            let statements_scope_decls = parsing_metadata
-                .scopes
-                .values()
-                .next()
-                .unwrap()
+                    .clone()
+            };
+            // TODO: Should really be 2, but it's not too bad to also include the main function name here as a false input (penalising triangular recursion implicitly)
+            assert_eq!(parsing_metadata.declarations.len(), 3);
+            assert!(!parsing_metadata
                 .declarations
-                .clone();
-            let newNum_decl = statements_scope_decls
-                .get(&XcodeText::from_str("newNum"))
-                .unwrap();
+                .values()
+                .any(|declaration| declaration.name == XcodeText::from_str("externalArg")));
 
-            assert_eq!(newNum_decl.declared_in_node.len(), 4); // function_decl, function_body_statements, property_declaration
+            let arg1_decl = declaration_of("arg1");
+            assert_eq!(arg1_decl.declared_in_node.len(), 3); // function_decl, parameter, simple_identifier
+            assert_eq!(arg1_decl.referenced_in_nodes.len(), 2); // Declared once, used in one other place
+            assert_eq!(arg1_decl.referenced_in_nodes[0], arg1_decl.declared_in_node);
+            assert_eq!(arg1_decl.referenced_in_nodes[1].len(), 6); // function_decl, function_body, statements, property_declaration, additive_expression, simple_identifier
+
+            let newNum_decl = declaration_of("newNum");
+            assert_eq!(newNum_decl.declared_in_node.len(), 6); // function_decl, function_body, statements, property_declaration, pattern, simple_identifier
             assert_eq!(newNum_decl.referenced_in_nodes.len(), 2); // Declared once, referenced once
-            assert_eq!(newNum_decl.referenced_in_nodes[0].len(), 6); // function_decl, function_body, statements, property_declaration, additive_expression, simple_identifier
+            assert_eq!(newNum_decl.referenced_in_nodes[1].len(), 5); // function_decl, function_body, statements, control_transfer_statement, simple_identifier
         }
     }
 
@@ -568,30 +454,31 @@ mod tests {
         use crate::core_engine::{
             features::complexity_refactoring::{
                 slice_inputs_and_outputs::get_slice_inputs_and_outputs_internal, Declaration,
-                NodeAddress, Scope, SliceInputsAndOutputs,
+                SliceInputsAndOutputs,
             },
+            syntax_tree::SymbolId,
             XcodeText,
         };
 
         #[test]
         fn no_inputs_and_outputs() {
             let var_x = XcodeText::from_str("x");
-            let scopes: HashMap<NodeAddress, Scope> = HashMap::from([(
-                vec![1],
-                Scope {
-                    declarations: HashMap::from([(
-                        var_x,
-                        Declaration {
-                            declared_in_node: vec![1, 15],
-                            referenced_in_nodes: vec![vec![1, 15], vec![1, 16]],
-                        },
-                    )]),
+            let declarations: HashMap<SymbolId, Declaration> = HashMap::from([(
+                0,
+                Declaration {
+                    name: var_x.clone(),
+                    declared_in_node: vec![1, 15],
+                    referenced_in_nodes: vec![vec![1, 15], vec![1, 16]],
                 },
             )]);
             let slice_node_ids = vec![15, 16, 17];
             let parent_address = vec![1];
             assert_eq!(
-                get_slice_inputs_and_outputs_internal(&slice_node_ids, &parent_address, &scopes),
+                get_slice_inputs_and_outputs_internal(
+                    &slice_node_ids,
+                    &parent_address,
+                    &declarations
+                ),
                 SliceInputsAndOutputs {
                     input_names: vec![],
                     output_names: vec![]
@@ -602,22 +489,22 @@ mod tests {
         #[test]
         fn used_in_block_after_declaration_in_slice() {
             let var_x = XcodeText::from_str("x");
-            let scopes: HashMap<NodeAddress, Scope> = HashMap::from([(
-                vec![1],
-                Scope {
-                    declarations: HashMap::from([(
-                        var_x.clone(),
-                        Declaration {
-                            declared_in_node: vec![1, 15],
-                            referenced_in_nodes: vec![vec![1, 15], vec![1, 16], vec![1, 17]],
-                        },
-                    )]),
+            let declarations: HashMap<SymbolId, Declaration> = HashMap::from([(
+                0,
+                Declaration {
+                    name: var_x.clone(),
+                    declared_in_node: vec![1, 15],
+                    referenced_in_nodes: vec![vec![1, 15], vec![1, 16], vec![1, 17]],
                 },
             )]);
             let slice_node_ids = vec![15, 16];
             let parent_address = vec![1];
             assert_eq!(
-                get_slice_inputs_and_outputs_internal(&slice_node_ids, &parent_address, &scopes),
+                get_slice_inputs_and_outputs_internal(
+                    &slice_node_ids,
+                    &parent_address,
+                    &declarations
+                ),
                 SliceInputsAndOutputs {
                     input_names: vec![],
                     output_names: vec![var_x]
@@ -628,22 +515,22 @@ mod tests {
         #[test]
         fn used_only_before_and_after_slice() {
             let var_x = XcodeText::from_str("x");
-            let scopes: HashMap<NodeAddress, Scope> = HashMap::from([(
-                vec![1],
-                Scope {
-                    declarations: HashMap::from([(
-                        var_x.clone(),
-                        Declaration {
-                            declared_in_node: vec![1, 15],
-                            referenced_in_nodes: vec![vec![1, 15], vec![1, 17]],
-                        },
-                    )]),
+            let declarations: HashMap<SymbolId, Declaration> = HashMap::from([(
+                0,
+                Declaration {
+                    name: var_x.clone(),
+                    declared_in_node: vec![1, 15],
+                    referenced_in_nodes: vec![vec![1, 15], vec![1, 17]],
                 },
             )]);
             let slice_node_ids = vec![16];
             let parent_address = vec![1];
             assert_eq!(
-                get_slice_inputs_and_outputs_internal(&slice_node_ids, &parent_address, &scopes),
+                get_slice_inputs_and_outputs_internal(
+                    &slice_node_ids,
+                    &parent_address,
+                    &declarations
+                ),
                 SliceInputsAndOutputs {
                     input_names: vec![],
                     output_names: vec![]
@@ -654,22 +541,22 @@ mod tests {
         #[test]
         fn used_before_and_in_slice() {
             let var_x = XcodeText::from_str("x");
-            let scopes: HashMap<NodeAddress, Scope> = HashMap::from([(
-                vec![1],
-                Scope {
-                    declarations: HashMap::from([(
-                        var_x.clone(),
-                        Declaration {
-                            declared_in_node: vec![1, 14],
-                            referenced_in_nodes: vec![vec![1, 15], vec![1, 16]],
-                        },
-                    )]),
+            let declarations: HashMap<SymbolId, Declaration> = HashMap::from([(
+                0,
+                Declaration {
+                    name: var_x.clone(),
+                    declared_in_node: vec![1, 14],
+                    referenced_in_nodes: vec![vec![1, 15], vec![1, 16]],
                 },
             )]);
             let slice_node_ids = vec![15, 16, 17];
             let parent_address = vec![1];
             assert_eq!(
-                get_slice_inputs_and_outputs_internal(&slice_node_ids, &parent_address, &scopes),
+                get_slice_inputs_and_outputs_internal(
+                    &slice_node_ids,
+                    &parent_address,
+                    &declarations
+                ),
                 SliceInputsAndOutputs {
                     input_names: vec![var_x],
                     output_names: vec![]
@@ -681,23 +568,23 @@ mod tests {
         fn declaration_in_outer_scope_used_in_slice() {
             // A declaration in the outer scope of a function also needs to be extracted if used in the slice
             let var_x = XcodeText::from_str("x");
-            let scopes: HashMap<NodeAddress, Scope> = HashMap::from([(
-                vec![1],
-                Scope {
-                    declarations: HashMap::from([(
-                        var_x.clone(),
-                        Declaration {
-                            declared_in_node: vec![1, 14],
-                            referenced_in_nodes: vec![vec![1, 15, 151], vec![1, 15, 152]],
-                        },
-                    )]),
+            let declarations: HashMap<SymbolId, Declaration> = HashMap::from([(
+                0,
+                Declaration {
+                    name: var_x.clone(),
+                    declared_in_node: vec![1, 14],
+                    referenced_in_nodes: vec![vec![1, 15, 151], vec![1, 15, 152]],
                 },
             )]);
             let slice_node_ids = vec![151, 152];
             let parent_address = vec![1, 15];
 
             assert_eq!(
-                get_slice_inputs_and_outputs_internal(&slice_node_ids, &parent_address, &scopes),
+                get_slice_inputs_and_outputs_internal(
+                    &slice_node_ids,
+                    &parent_address,
+                    &declarations
+                ),
                 SliceInputsAndOutputs {
                     input_names: vec![var_x],
                     output_names: vec![]
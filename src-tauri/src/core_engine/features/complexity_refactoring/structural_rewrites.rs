@@ -0,0 +1,733 @@
+use tree_sitter::Node;
+
+use super::{ComplexityRefactoringError, Edit, NodeSlice, SerializedNodeSlice, SuggestionKind};
+use crate::core_engine::{
+    syntax_tree::{
+        calculate_cognitive_complexities, contains_kind, indentation_at, ScopeKind, SwiftFunction,
+        SwiftSyntaxTree, SymbolKind, TreeMetaData,
+    },
+    TextRange, XcodeText,
+};
+
+const DEFAULT_INDENTATION_UNIT: &str = "    ";
+
+// Shorter `if`/`else if` chains, counting a final `else`, are not worth a `switch`.
+const MIN_SWITCH_BRANCHES: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct StructuralRewrite {
+    pub kind: SuggestionKind,
+    pub serialized_slice: SerializedNodeSlice,
+    pub edits: Vec<Edit>,
+    pub new_complexity: isize,
+}
+
+// Local rewrites of `if` statements in the function, each only suggested if it lowers the
+// function's cognitive complexity.
+pub fn check_for_structural_rewrites(
+    function: &SwiftFunction,
+    text_content: &XcodeText,
+    syntax_tree: &SwiftSyntaxTree,
+) -> Result<Vec<StructuralRewrite>, ComplexityRefactoringError> {
+    let function_node = function.props.node;
+    let prev_complexity = function.get_complexity();
+
+    let mut if_statements = vec![];
+    collect_if_statements(function_node, &mut if_statements);
+
+    let mut rewrites = vec![];
+    for if_statement in if_statements {
+        let candidates = [
+            (
+                SuggestionKind::GuardConversion,
+                rewrite_as_guard(&if_statement, &function_node, text_content, syntax_tree),
+            ),
+            (
+                SuggestionKind::ConditionFlattening,
+                rewrite_as_flattened_condition(&if_statement, text_content),
+            ),
+            (
+                SuggestionKind::SwitchConversion,
+                rewrite_as_switch(&if_statement, text_content),
+            ),
+            (
+                SuggestionKind::BooleanReturn,
+                rewrite_as_boolean_return(&if_statement, text_content),
+            ),
+        ];
+
+        for (kind, new_text) in candidates {
+            let edit = match new_text {
+                Some(new_text) => Edit {
+                    text: XcodeText::from_str(&new_text),
+                    start_index: if_statement.start_byte() / 2,
+                    end_index: if_statement.end_byte() / 2,
+                },
+                None => continue,
+            };
+
+            let new_complexity = match complexity_after_edit(&function_node, text_content, &edit)? {
+                Some(complexity) if complexity < prev_complexity => complexity,
+                _ => continue,
+            };
+
+            rewrites.push(StructuralRewrite {
+                kind,
+                serialized_slice: single_node_slice(&if_statement, &function_node)
+                    .serialize(function_node),
+                edits: vec![edit],
+                new_complexity,
+            });
+        }
+    }
+
+    Ok(rewrites)
+}
+
+// `if <conditions> { <rest of the function> }` as last statement of a function without return
+// value becomes `guard <conditions> else { return }`, which removes one level of nesting.
+fn rewrite_as_guard(
+    if_statement: &Node,
+    function_node: &Node,
+    text: &XcodeText,
+    syntax_tree: &SwiftSyntaxTree,
+) -> Option<String> {
+    let statements = if_statement.parent()?;
+    let function_body = statements.parent()?;
+    if statements.kind() != "statements"
+        || function_body.kind() != "function_body"
+        || function_body.parent()?.id() != function_node.id()
+        || last_named_child(&statements)?.id() != if_statement.id()
+        || !returns_void(function_node, text)
+    {
+        return None;
+    }
+
+    let parts = IfParts::new(if_statement, text)?;
+    if parts.else_branch.is_some() || would_redeclare(if_statement, &parts, syntax_tree) {
+        return None;
+    }
+    let body = parts.body?;
+
+    let indentation = indentation_at(text, if_statement.start_byte() / 2).as_string();
+    let unit = indentation_unit(&indentation, &body, text);
+    let rewritten_body = reindented_block(&body, text, &indentation);
+
+    Some(format!(
+        "guard {} else {{\n{}{}return\n{}}}\n{}",
+        parts.conditions,
+        indentation,
+        unit,
+        indentation,
+        rewritten_body.trim_end()
+    ))
+}
+
+// `if a { if b { ... } }` becomes `if a, b { ... }`.
+fn rewrite_as_flattened_condition(if_statement: &Node, text: &XcodeText) -> Option<String> {
+    let outer = IfParts::new(if_statement, text)?;
+    let outer_body = outer.body?;
+    if outer.else_branch.is_some() || outer_body.named_child_count() != 1 {
+        return None;
+    }
+
+    let nested_if_statement = outer_body.named_child(0)?;
+    if nested_if_statement.kind() != "if_statement" {
+        return None;
+    }
+    let inner = IfParts::new(&nested_if_statement, text)?;
+    let inner_body = inner.body?;
+    if inner.else_branch.is_some() {
+        return None;
+    }
+
+    // Redeclaring a binding within the same condition list does not compile.
+    let outer_bindings = bound_names(if_statement, text);
+    if bound_names(&nested_if_statement, text)
+        .iter()
+        .any(|name| outer_bindings.contains(name))
+    {
+        return None;
+    }
+
+    let indentation = indentation_at(text, if_statement.start_byte() / 2).as_string();
+    let unit = indentation_unit(&indentation, &outer_body, text);
+
+    Some(format!(
+        "if {}, {} {{\n{}{}}}",
+        outer.conditions,
+        inner.conditions,
+        reindented_block(&inner_body, text, &(indentation.clone() + &unit)),
+        indentation
+    ))
+}
+
+// An `if`/`else if` chain whose conditions all compare one subject with `==` becomes a `switch`.
+fn rewrite_as_switch(if_statement: &Node, text: &XcodeText) -> Option<String> {
+    if if_statement.parent()?.kind() == "if_statement" {
+        return None; // Only whole chains are converted
+    }
+
+    let mut subject: Option<String> = None;
+    let mut cases: Vec<(Vec<String>, Option<Node>)> = vec![];
+    let mut default_body: Option<Option<Node>> = None;
+
+    let mut current = *if_statement;
+    loop {
+        let parts = IfParts::new(&current, text)?;
+        if parts.has_bindings || parts.condition_nodes.len() != 1 {
+            return None;
+        }
+
+        let mut values = vec![];
+        collect_compared_values(&parts.condition_nodes[0], text, &mut subject, &mut values)?;
+        cases.push((values, parts.body));
+
+        match parts.else_branch {
+            Some(ElseBranch::If(else_if_statement)) => current = else_if_statement,
+            Some(ElseBranch::Block(body)) => {
+                default_body = Some(body);
+                break;
+            }
+            None => break,
+        }
+    }
+
+    let has_control_transfer = cases
+        .iter()
+        .filter_map(|(_, body)| *body)
+        .chain(default_body.flatten())
+        .any(|body| has_unlabeled_control_transfer(&body, false, false));
+    if has_control_transfer {
+        return None;
+    }
+
+    let subject = subject?;
+    let branch_count = cases.len() + default_body.iter().count();
+    if branch_count < MIN_SWITCH_BRANCHES {
+        return None;
+    }
+
+    let indentation = indentation_at(text, if_statement.start_byte() / 2).as_string();
+    let unit = cases
+        .iter()
+        .find_map(|(_, body)| body.map(|body| indentation_unit(&indentation, &body, text)))
+        .unwrap_or_else(|| DEFAULT_INDENTATION_UNIT.to_string());
+    let case_body = |body: Option<Node>| match body {
+        Some(body) => reindented_block(&body, text, &(indentation.clone() + &unit)),
+        None => format!("{}{}break\n", indentation, unit),
+    };
+
+    let mut switch = format!("switch {} {{\n", subject);
+    for (values, body) in cases {
+        switch += &format!("{}case {}:\n", indentation, values.join(", "));
+        switch += &case_body(body);
+    }
+    switch += &format!("{}default:\n", indentation);
+    switch += &case_body(default_body.flatten());
+    switch += &format!("{}}}", indentation);
+
+    Some(switch)
+}
+
+// An unlabeled `break` in a branch would leave the new `switch` instead of the surrounding loop
+// or `switch`. Branches with `continue` or `fallthrough` are not converted either, to keep their
+// target obvious.
+fn has_unlabeled_control_transfer(
+    node: &Node,
+    break_is_captured: bool,
+    continue_is_captured: bool,
+) -> bool {
+    let (break_is_captured, continue_is_captured) = match node.kind() {
+        "lambda_literal" | "function_declaration" => return false,
+        "for_statement" | "while_statement" | "repeat_while_statement" => (true, true),
+        "switch_statement" => (true, continue_is_captured),
+        "fallthrough" => return !break_is_captured,
+        "control_transfer_statement" if node.child_by_field_name("result").is_none() => {
+            match node.child(0).map(|keyword| keyword.kind()) {
+                Some("break") if !break_is_captured => return true,
+                Some("continue") if !continue_is_captured => return true,
+                _ => (break_is_captured, continue_is_captured),
+            }
+        }
+        _ => (break_is_captured, continue_is_captured),
+    };
+
+    node.children(&mut node.walk()).any(|child| {
+        has_unlabeled_control_transfer(&child, break_is_captured, continue_is_captured)
+    })
+}
+
+// `if c { return true } else { return false }` becomes `return c`.
+fn rewrite_as_boolean_return(if_statement: &Node, text: &XcodeText) -> Option<String> {
+    if if_statement.parent()?.kind() == "if_statement" {
+        return None;
+    }
+
+    let parts = IfParts::new(if_statement, text)?;
+    if parts.has_bindings || parts.condition_nodes.len() != 1 {
+        return None;
+    }
+    let else_body = match parts.else_branch {
+        Some(ElseBranch::Block(Some(else_body))) => else_body,
+        _ => return None,
+    };
+
+    let condition = &parts.condition_nodes[0];
+    match (
+        returned_boolean(&parts.body?, text)?,
+        returned_boolean(&else_body, text)?,
+    ) {
+        (true, false) => Some(format!("return {}", parts.conditions)),
+        (false, true) if is_atomic_expression(condition.kind()) => {
+            Some(format!("return !{}", parts.conditions))
+        }
+        (false, true) => Some(format!("return !({})", parts.conditions)),
+        _ => None,
+    }
+}
+
+enum ElseBranch<'a> {
+    Block(Option<Node<'a>>),
+    If(Node<'a>),
+}
+
+// The parts of an `if_statement`, whose body and `else` block are not wrapped in a node of
+// their own: `if <conditions> { <statements> } else { <statements> }`.
+struct IfParts<'a> {
+    conditions: String,
+    condition_nodes: Vec<Node<'a>>,
+    has_bindings: bool,
+    body: Option<Node<'a>>,
+    else_branch: Option<ElseBranch<'a>>,
+}
+
+impl<'a> IfParts<'a> {
+    fn new(if_statement: &Node<'a>, text: &XcodeText) -> Option<Self> {
+        let children: Vec<Node<'a>> = if_statement.children(&mut if_statement.walk()).collect();
+
+        let mut has_bindings = false;
+        let mut cursor = if_statement.walk();
+        if cursor.goto_first_child() {
+            loop {
+                if cursor.field_name() == Some("bound_identifier")
+                    || ["value_binding_pattern", "availability_condition", "case"]
+                        .contains(&cursor.node().kind())
+                {
+                    has_bindings = true;
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+
+        let body_start = children.iter().position(|child| child.kind() == "{")?;
+        let body_end = body_start
+            + children[body_start..]
+                .iter()
+                .position(|child| child.kind() == "}")?;
+
+        let conditions_start = children.first()?.end_byte() / 2;
+        let conditions_end = children[body_start].start_byte() / 2;
+        let conditions = text
+            .slice(conditions_start..conditions_end)
+            .as_string()
+            .trim()
+            .to_string();
+
+        let condition_children = &children[1..body_start];
+        let condition_nodes = if condition_children.iter().any(|child| child.kind() == ",") {
+            vec![] // A condition list is never a single comparison
+        } else {
+            condition_children
+                .iter()
+                .filter(|child| child.is_named())
+                .copied()
+                .collect()
+        };
+
+        let body = find_statements(&children[body_start..body_end]);
+
+        let else_branch = match children[body_end + 1..].split_first() {
+            None => None,
+            Some((else_keyword, rest)) if else_keyword.kind() == "else" => match rest.first() {
+                Some(else_if_statement) if else_if_statement.kind() == "if_statement" => {
+                    Some(ElseBranch::If(*else_if_statement))
+                }
+                Some(_) => Some(ElseBranch::Block(find_statements(rest))),
+                None => return None,
+            },
+            Some(_) => return None,
+        };
+
+        Some(Self {
+            conditions,
+            condition_nodes,
+            has_bindings,
+            body,
+            else_branch,
+        })
+    }
+}
+
+fn find_statements<'a>(nodes: &[Node<'a>]) -> Option<Node<'a>> {
+    nodes
+        .iter()
+        .find(|node| node.kind() == "statements")
+        .copied()
+}
+
+// Collects the values compared with the subject in `subject == a || subject == b`; the subject
+// is the left hand side of the first comparison.
+fn collect_compared_values(
+    condition: &Node,
+    text: &XcodeText,
+    subject: &mut Option<String>,
+    values: &mut Vec<String>,
+) -> Option<()> {
+    match condition.kind() {
+        "disjunction_expression" => {
+            collect_compared_values(&condition.named_child(0)?, text, subject, values)?;
+            collect_compared_values(&condition.named_child(1)?, text, subject, values)
+        }
+        "equality_expression" => {
+            let is_equals = condition
+                .children(&mut condition.walk())
+                .any(|child| child.kind() == "==");
+            if !is_equals || condition.named_child_count() != 2 {
+                return None;
+            }
+
+            let lhs = condition.named_child(0)?;
+            let rhs = condition.named_child(1)?;
+            let lhs_text = node_string(&lhs, text);
+            let rhs_text = node_string(&rhs, text);
+
+            match subject {
+                None => {
+                    // The subject is evaluated once by the `switch`, instead of once per branch.
+                    if contains_kind(&lhs, "call_expression") {
+                        return None;
+                    }
+                    *subject = Some(lhs_text);
+                    values.push(rhs_text);
+                }
+                Some(subject) if *subject == lhs_text => values.push(rhs_text),
+                Some(subject) if *subject == rhs_text => values.push(lhs_text),
+                Some(_) => return None,
+            }
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+fn returned_boolean(statements: &Node, text: &XcodeText) -> Option<bool> {
+    if statements.named_child_count() != 1 {
+        return None;
+    }
+    let statement = statements.named_child(0)?;
+    if statement.kind() != "control_transfer_statement" {
+        return None;
+    }
+
+    match node_string(&statement, text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["return", "true"] => Some(true),
+        ["return", "false"] => Some(false),
+        _ => None,
+    }
+}
+
+// Moving the body of the `if` into the function's scope must not clash with local declarations
+// before it, e.g. `let x = 1` followed by `if let x = ...`.
+fn would_redeclare(if_statement: &Node, parts: &IfParts, syntax_tree: &SwiftSyntaxTree) -> bool {
+    let symbol_table = syntax_tree.symbol_table();
+    let moved_scope_ranges = [
+        Some(node_range(if_statement)),
+        parts.body.map(|body| node_range(&body)),
+    ];
+    let if_start = if_statement.start_byte() / 2;
+
+    symbol_table
+        .symbols()
+        .iter()
+        .filter(|symbol| {
+            symbol_table
+                .scopes()
+                .get(symbol.scope)
+                .map_or(false, |scope| {
+                    scope.kind == ScopeKind::Block
+                        && moved_scope_ranges.contains(&Some(scope.range))
+                })
+        })
+        .any(|symbol| {
+            symbol_table
+                .lookup(&symbol.name, if_start)
+                .map_or(false, |outer| {
+                    outer.kind == SymbolKind::Variable || outer.kind == SymbolKind::Binding
+                })
+        })
+}
+
+fn returns_void(function_node: &Node, text: &XcodeText) -> bool {
+    match function_node.child_by_field_name("return_type") {
+        Some(return_type) => {
+            let return_type = node_string(&return_type, text);
+            return_type == "Void" || return_type == "()"
+        }
+        None => true,
+    }
+}
+
+fn bound_names(if_statement: &Node, text: &XcodeText) -> Vec<String> {
+    if_statement
+        .children_by_field_name("bound_identifier", &mut if_statement.walk())
+        .map(|identifier| node_string(&identifier, text))
+        .collect()
+}
+
+fn is_atomic_expression(kind: &str) -> bool {
+    [
+        "simple_identifier",
+        "call_expression",
+        "navigation_expression",
+        "boolean_literal",
+        "tuple_expression",
+    ]
+    .contains(&kind)
+}
+
+// Lines of the block, re-indented so its first level starts at the given indentation.
+fn reindented_block(statements: &Node, text: &XcodeText, indentation: &str) -> String {
+    let start = statements.start_byte() / 2;
+    let block_indentation = own_line_indentation(text, start).unwrap_or_default();
+
+    node_string(statements, text)
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = if i == 0 {
+                line
+            } else {
+                line.strip_prefix(block_indentation.as_str())
+                    .unwrap_or(line)
+            };
+            if line.trim().is_empty() {
+                "\n".to_string()
+            } else {
+                format!("{}{}\n", indentation, line)
+            }
+        })
+        .collect()
+}
+
+fn indentation_unit(indentation: &str, body: &Node, text: &XcodeText) -> String {
+    own_line_indentation(text, body.start_byte() / 2)
+        .and_then(|body_indentation| {
+            body_indentation
+                .strip_prefix(indentation)
+                .map(|unit| unit.to_string())
+        })
+        .filter(|unit| !unit.is_empty())
+        .unwrap_or_else(|| DEFAULT_INDENTATION_UNIT.to_string())
+}
+
+// The whitespace before the index, if nothing else precedes it on its line.
+fn own_line_indentation(text: &XcodeText, index: usize) -> Option<String> {
+    let indentation = indentation_at(text, index);
+    let line_start = index
+        - text
+            .slice(..index)
+            .iter()
+            .rev()
+            .take_while(|c| **c != '\n' as u16)
+            .count();
+
+    if line_start + indentation.len() == index {
+        Some(indentation.as_string())
+    } else {
+        None
+    }
+}
+
+// The function's complexity after the edit, measured on the rewritten function on its own.
+fn complexity_after_edit(
+    function_node: &Node,
+    text: &XcodeText,
+    edit: &Edit,
+) -> Result<Option<isize>, ComplexityRefactoringError> {
+    let function_start = function_node.start_byte() / 2;
+    let function_end = function_node.end_byte() / 2;
+
+    let new_function_text = text.slice(function_start..edit.start_index)
+        + edit.text.clone()
+        + text.slice(edit.end_index..function_end);
+
+    let tree =
+        match SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &new_function_text, None) {
+            Some(tree) if !tree.root_node().has_error() => tree,
+            _ => return Ok(None), // The rewrite would not compile
+        };
+
+    let complexities = calculate_cognitive_complexities(
+        &tree.root_node(),
+        &new_function_text,
+        &mut TreeMetaData::default(),
+        None,
+    )
+    .map_err(|err| ComplexityRefactoringError::GenericError(err.into()))?;
+
+    Ok(Some(complexities.get_total_complexity()))
+}
+
+fn single_node_slice<'a>(node: &Node<'a>, function_node: &Node<'a>) -> NodeSlice<'a> {
+    let mut parent_address = vec![];
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        parent_address.push(parent.id());
+        if parent.id() == function_node.id() {
+            break;
+        }
+        ancestor = parent.parent();
+    }
+    parent_address.reverse();
+
+    NodeSlice {
+        nodes: vec![*node],
+        parent_address,
+    }
+}
+
+fn collect_if_statements<'a>(node: Node<'a>, if_statements: &mut Vec<Node<'a>>) {
+    if node.kind() == "if_statement" {
+        if_statements.push(node);
+    }
+    for child in node.named_children(&mut node.walk()) {
+        collect_if_statements(child, if_statements);
+    }
+}
+
+fn last_named_child<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    node.named_child(node.named_child_count().checked_sub(1)?)
+}
+
+fn node_string(node: &Node, text: &XcodeText) -> String {
+    text.slice(node.start_byte() / 2..node.end_byte() / 2)
+        .as_string()
+}
+
+fn node_range(node: &Node) -> TextRange {
+    TextRange::from_StartEndIndex(node.start_byte() / 2, node.end_byte() / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    mod check_for_structural_rewrites {
+        use crate::core_engine::{
+            features::{
+                complexity_refactoring::{check_for_structural_rewrites, SuggestionKind},
+                test_utils::{applied, parse},
+            },
+            syntax_tree::SwiftFunction,
+        };
+
+        fn test_fn(code: &str, kind: SuggestionKind) -> Vec<(String, isize)> {
+            let syntax_tree = parse(code);
+            let functions =
+                SwiftFunction::get_top_level_functions(&syntax_tree, syntax_tree.text_content())
+                    .unwrap();
+            assert_eq!(functions.len(), 1);
+
+            check_for_structural_rewrites(&functions[0], syntax_tree.text_content(), &syntax_tree)
+                .unwrap()
+                .into_iter()
+                .filter(|rewrite| rewrite.kind == kind)
+                .map(|rewrite| (applied(code, &rewrite.edits), rewrite.new_complexity))
+                .collect()
+        }
+
+        #[test]
+        fn converts_if_around_function_body_to_guard() {
+            let code = "func f(value: Int?) {\n    if let value = value {\n        if value > 0 {\n            print(value)\n        }\n    }\n}\n";
+
+            assert_eq!(
+                test_fn(code, SuggestionKind::GuardConversion),
+                vec![(
+                    "func f(value: Int?) {\n    guard let value = value else {\n        return\n    }\n    if value > 0 {\n        print(value)\n    }\n}\n".to_string(),
+                    2
+                )]
+            );
+        }
+
+        #[test]
+        fn does_not_convert_to_guard_when_returning_a_value() {
+            let code = "func f(value: Int?) -> Int {\n    if let value = value {\n        if value > 0 {\n            return value\n        }\n    }\n    return 0\n}\n";
+
+            assert!(test_fn(code, SuggestionKind::GuardConversion).is_empty());
+        }
+
+        #[test]
+        fn flattens_nested_conditions() {
+            let code = "func f(a: Bool, b: Bool) {\n    if a {\n        if b {\n            print(1)\n        }\n    }\n}\n";
+
+            assert_eq!(
+                test_fn(code, SuggestionKind::ConditionFlattening),
+                vec![(
+                    "func f(a: Bool, b: Bool) {\n    if a, b {\n        print(1)\n    }\n}\n"
+                        .to_string(),
+                    2
+                )]
+            );
+        }
+
+        #[test]
+        fn converts_comparisons_of_one_subject_to_switch() {
+            let code = "func f(value: Int) {\n    if value == 1 {\n        print(\"one\")\n    } else if value == 2 || value == 3 {\n        print(\"few\")\n    } else {\n        print(\"many\")\n    }\n}\n";
+
+            assert_eq!(
+                test_fn(code, SuggestionKind::SwitchConversion),
+                vec![(
+                    "func f(value: Int) {\n    switch value {\n    case 1:\n        print(\"one\")\n    case 2, 3:\n        print(\"few\")\n    default:\n        print(\"many\")\n    }\n}\n".to_string(),
+                    1
+                )]
+            );
+        }
+
+        #[test]
+        fn does_not_convert_to_switch_when_breaking_out_of_a_loop() {
+            let code = "func f(values: [Int]) {\n    for value in values {\n        if value == 1 {\n            print(\"one\")\n        } else if value == 2 {\n            print(\"two\")\n        } else {\n            print(\"many\")\n        }\n    }\n}\n";
+            assert_eq!(test_fn(code, SuggestionKind::SwitchConversion).len(), 1);
+
+            let breaking_code = code.replace("print(\"two\")", "break");
+            assert!(test_fn(&breaking_code, SuggestionKind::SwitchConversion).is_empty());
+
+            let continuing_code = code.replace("print(\"many\")", "continue");
+            assert!(test_fn(&continuing_code, SuggestionKind::SwitchConversion).is_empty());
+        }
+
+        #[test]
+        fn does_not_convert_comparisons_of_different_subjects() {
+            let code = "func f(a: Int, b: Int) {\n    if a == 1 {\n        print(1)\n    } else if b == 2 {\n        print(2)\n    } else {\n        print(3)\n    }\n}\n";
+
+            assert!(test_fn(code, SuggestionKind::SwitchConversion).is_empty());
+        }
+
+        #[test]
+        fn returns_condition_directly() {
+            let code = "func f(a: Int) -> Bool {\n    if a > 1 {\n        return false\n    } else {\n        return true\n    }\n}\n";
+
+            assert_eq!(
+                test_fn(code, SuggestionKind::BooleanReturn),
+                vec![(
+                    "func f(a: Int) -> Bool {\n    return !(a > 1)\n}\n".to_string(),
+                    0
+                )]
+            );
+        }
+    }
+}
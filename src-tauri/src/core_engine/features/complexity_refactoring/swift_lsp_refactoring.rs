@@ -1,6 +1,6 @@
 use super::{method_extraction::MethodExtractionTask, ComplexityRefactoring, Edit};
 use crate::core_engine::{
-    features::FeatureSignal, Lsp, SwiftLsp, SwiftLspError, TextPosition, XcodeText,
+    features::FeatureSignal, Lsp, SwiftLsp, SwiftLspError, TextRange, XcodeText,
 };
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
@@ -32,30 +32,31 @@ struct RefactoringResponse {
     categorized_edits: Vec<CategorizedEditDto>,
 }
 
+// SourceKit's lines and columns are one-based, and columns count UTF-8 bytes.
+fn sourcekit_position_to_index(
+    line: usize,
+    column: usize,
+    text_content: &XcodeText,
+) -> Result<usize, SwiftLspError> {
+    text_content
+        .position_of_utf8_column(line - 1, column - 1)
+        .and_then(|position| position.as_TextIndex(text_content))
+        .ok_or(SwiftLspError::GenericError(anyhow!(
+            "Could not get text index for position"
+        )))
+}
+
 fn map_edit_dto_to_edit(
     edit_dto: EditDto,
     text_content: &XcodeText,
 ) -> Result<Edit, SwiftLspError> {
     Ok(Edit {
-        start_index: TextPosition {
-            row: edit_dto.line - 1,
-            column: edit_dto.column - 1,
-        }
-        .as_TextIndex(text_content)
-        .ok_or(SwiftLspError::GenericError(anyhow!(
-            "Could not get text index for position"
-        )))?,
-        end_index: TextPosition {
-            row: edit_dto.endline - 1,
-            column: edit_dto.endcolumn - 1,
-        }
-        .as_TextIndex(text_content)
-        .ok_or(SwiftLspError::GenericError(anyhow!(
-            "Could not get text index for position"
-        )))?,
+        start_index: sourcekit_position_to_index(edit_dto.line, edit_dto.column, text_content)?,
+        end_index: sourcekit_position_to_index(edit_dto.endline, edit_dto.endcolumn, text_content)?,
         text: XcodeText::from_str(&edit_dto.text),
     })
 }
+
 fn format_array_as_yaml(compiler_args: Vec<String>) -> String {
     compiler_args
         .into_iter()
@@ -73,6 +74,24 @@ pub async fn refactor_function(
 
     let compiler_args =
         SwiftLsp::get_compiler_args(&method_extraction_task.file_path, tmp_file_path).await?;
+
+    let text_content = &method_extraction_task.text_content;
+    let start_position = &method_extraction_task.start_position;
+    let (utf8_column, utf8_length) = start_position
+        .as_TextIndex(text_content)
+        .and_then(|start_index| {
+            let (start_offset, end_offset) =
+                TextRange::new(start_index, method_extraction_task.range_length)
+                    .as_StartEndUtf8Offset(text_content)?;
+            Some((
+                text_content.utf8_column(start_position)?,
+                end_offset - start_offset,
+            ))
+        })
+        .ok_or(SwiftLspError::GenericError(anyhow!(
+            "Could not get UTF-8 range of method extraction"
+        )))?;
+
     let payload = format!(
         r#"key.request: source.request.semantic.refactoring
 key.actionuid: source.refactoring.kind.extract.function
@@ -82,9 +101,9 @@ key.column: {}
 key.length: {}
 key.compilerargs:{}"#,
         tmp_file_path,
-        method_extraction_task.start_position.row + 1,
-        method_extraction_task.start_position.column + 1,
-        method_extraction_task.range_length,
+        start_position.row + 1,
+        utf8_column + 1,
+        utf8_length,
         format_array_as_yaml(compiler_args)
     )
     .to_string();
@@ -110,9 +129,53 @@ key.compilerargs:{}"#,
         .map(|categorized_edit| categorized_edit.edits)
         .flatten()
         .map(|edit_dto| -> Result<Edit, SwiftLspError> {
-            map_edit_dto_to_edit(edit_dto, &method_extraction_task.text_content)
+            map_edit_dto_to_edit(edit_dto, text_content)
         })
         .collect::<Result<Vec<Edit>, SwiftLspError>>()?;
 
     return Ok(edits);
 }
+
+#[cfg(test)]
+mod tests {
+    mod map_edit_dto_to_edit {
+        use crate::core_engine::{
+            features::complexity_refactoring::swift_lsp_refactoring::{
+                map_edit_dto_to_edit, EditDto,
+            },
+            XcodeText,
+        };
+
+        #[test]
+        fn converts_utf8_columns_of_multi_byte_characters() {
+            let text_content = XcodeText::from_str("let a = \"😊\"\nlet b = \"é\"; print(b)\n");
+            // From the opening quote on the first line to after the closing quote on the second
+            let edit_dto = EditDto {
+                column: 9,
+                endcolumn: 13,
+                line: 1,
+                endline: 2,
+                text: "0".to_string(),
+            };
+
+            let edit = map_edit_dto_to_edit(edit_dto, &text_content).unwrap();
+
+            assert_eq!(edit.start_index, 8);
+            assert_eq!(edit.end_index, 24);
+        }
+
+        #[test]
+        fn fails_inside_a_character() {
+            let text_content = XcodeText::from_str("let a = \"😊\"");
+            let edit_dto = EditDto {
+                column: 11,
+                endcolumn: 11,
+                line: 1,
+                endline: 1,
+                text: "".to_string(),
+            };
+
+            assert!(map_edit_dto_to_edit(edit_dto, &text_content).is_err());
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use super::SerializedNodeSlice;
-use crate::core_engine::{EditorWindowUid, XcodeText};
+use crate::{
+    core_engine::{EditorWindowUid, XcodeText},
+    utils::calculate_hash,
+};
 
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
@@ -12,16 +15,54 @@ pub type SuggestionsMap = HashMap<SuggestionId, RefactoringSuggestion>;
 pub type SuggestionsPerWindow = HashMap<EditorWindowUid, SuggestionsMap>;
 pub type SuggestionsArcMutex = Arc<Mutex<SuggestionsPerWindow>>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/refactoring/")]
 pub struct Edit {
     pub text: XcodeText,
     pub start_index: usize,
     pub end_index: usize,
 }
 
+impl Edit {
+    // Removes the whole lines if the range is the only thing on them.
+    pub fn removal_of_range(text: &XcodeText, start_index: usize, end_index: usize) -> Self {
+        let line_start = text
+            .slice(..start_index)
+            .iter()
+            .rposition(|c| *c == '\n' as u16)
+            .map_or(0, |newline| newline + 1);
+        let line_end = text
+            .slice(end_index..)
+            .iter()
+            .position(|c| *c == '\n' as u16)
+            .map_or(text.len(), |newline| end_index + newline + 1);
+
+        let is_alone_on_lines = text
+            .slice(line_start..start_index)
+            .iter()
+            .chain(text.slice(end_index..line_end).iter())
+            .all(|c| XcodeText::char_is_whitespace(c));
+
+        if is_alone_on_lines {
+            Self {
+                text: XcodeText::new_empty(),
+                start_index: line_start,
+                end_index: line_end,
+            }
+        } else {
+            Self {
+                text: XcodeText::new_empty(),
+                start_index,
+                end_index,
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "bindings/features/refactoring/")]
 pub struct FERefactoringSuggestion {
+    pub kind: SuggestionKind,
     pub state: SuggestionState,
     pub new_text_content_string: Option<String>,
     pub old_text_content_string: Option<String>,
@@ -38,9 +79,31 @@ pub enum SuggestionState {
     Recalculating,
     Ready,
 }
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[ts(export, export_to = "bindings/features/refactoring/")]
+pub enum SuggestionKind {
+    MethodExtraction,
+    GuardConversion, // `if` around the rest of a function into `guard ... else { return }`
+    ConditionFlattening, // Nested `if`s without `else` into one `if` with combined conditions
+    SwitchConversion, // `if`/`else if` chain comparing one subject into a `switch`
+    BooleanReturn,   // `if c { return true } else { return false }` into `return c`
+}
+
+impl SuggestionKind {
+    pub fn dismissal_hash(&self, serialized_slice: &SerializedNodeSlice) -> SuggestionHash {
+        match self {
+            // Hashed without the kind, so method extractions dismissed before there were other
+            // kinds stay dismissed.
+            SuggestionKind::MethodExtraction => calculate_hash(serialized_slice),
+            kind => calculate_hash(&(kind, serialized_slice)),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RefactoringSuggestion {
-    pub new_text_content_string: Option<String>, // TODO: test if it works with utf 16 emojis etc
+    pub kind: SuggestionKind,
+    pub new_text_content_string: Option<String>,
     pub old_text_content_string: Option<String>,
     pub state: SuggestionState,
     pub new_complexity: isize,
@@ -48,4 +111,5 @@ pub struct RefactoringSuggestion {
     pub main_function_name: Option<String>,
     pub serialized_slice: SerializedNodeSlice,
     pub start_index: Option<usize>,
+    pub edits: Option<Vec<Edit>>, // Known upfront for structural rewrites, from SourceKit otherwise
 }
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use tracing::error;
+
+use crate::{
+    app_handle,
+    core_engine::{
+        events::{
+            models::{ApplyQuickFixMessage, UpdateDeadCodeResultsMessage},
+            DeadCodeEvent,
+        },
+        features::{
+            feature_base::{CoreEngineTrigger, FeatureBase, FeatureError, UserCommand},
+            structural_search::apply_edits,
+            FeatureKind,
+        },
+        rules::{
+            rule_base::{RuleName, RuleResults},
+            RuleMatch,
+        },
+        scheduler::CancellationToken,
+        CodeDocument, EditorWindowUid,
+    },
+    platform::macos::replace_text_content,
+    CORE_ENGINE_ACTIVE_AT_STARTUP,
+};
+
+use super::{detect_dead_code, DeadCodeCache, DeadCodeFinding};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DeadCodeError {
+    #[error("Insufficient context for dead code detection")]
+    InsufficientContext,
+    #[error("The quick fix does not apply to the current text anymore")]
+    OutdatedQuickFix,
+    #[error("Something went wrong when executing the DeadCode feature.")]
+    GenericError(#[source] anyhow::Error),
+}
+
+enum DeadCodeProcedure {
+    Detect,
+    ApplyQuickFix(ApplyQuickFixMessage),
+}
+
+pub struct DeadCodeDetection {
+    is_activated: bool,
+
+    caches: HashMap<EditorWindowUid, DeadCodeCache>,
+    findings: HashMap<EditorWindowUid, Vec<DeadCodeFinding>>,
+    results: HashMap<EditorWindowUid, Vec<RuleMatch>>,
+}
+
+impl FeatureBase for DeadCodeDetection {
+    fn compute(
+        &mut self,
+        code_document: CodeDocument,
+        trigger: CoreEngineTrigger,
+        _cancellation_token: CancellationToken,
+    ) -> Result<(), FeatureError> {
+        if !self.is_activated {
+            return Ok(());
+        }
+
+        match Self::determine_procedure(&trigger) {
+            Some(DeadCodeProcedure::Detect) => self.procedure_detect(&code_document)?,
+            Some(DeadCodeProcedure::ApplyQuickFix(msg)) => {
+                self.procedure_apply_quick_fix(&code_document, msg)?
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    fn activate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = true;
+
+        Ok(())
+    }
+
+    fn deactivate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = false;
+        self.clear_results();
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), FeatureError> {
+        self.clear_results();
+
+        Ok(())
+    }
+
+    fn remove_window(&mut self, window_uid: EditorWindowUid) {
+        self.caches.remove(&window_uid);
+        self.findings.remove(&window_uid);
+        self.results.remove(&window_uid);
+    }
+
+    fn kind(&self) -> FeatureKind {
+        FeatureKind::DeadCode
+    }
+
+    fn should_compute(_kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool {
+        Self::determine_procedure(trigger).is_some()
+    }
+
+    fn requires_ai(_kind: &FeatureKind, _trigger: &CoreEngineTrigger) -> bool {
+        false
+    }
+}
+
+impl DeadCodeDetection {
+    pub fn new() -> Self {
+        Self {
+            is_activated: CORE_ENGINE_ACTIVE_AT_STARTUP,
+            caches: HashMap::new(),
+            findings: HashMap::new(),
+            results: HashMap::new(),
+        }
+    }
+
+    fn determine_procedure(trigger: &CoreEngineTrigger) -> Option<DeadCodeProcedure> {
+        match trigger {
+            CoreEngineTrigger::OnTextContentChange => Some(DeadCodeProcedure::Detect),
+            CoreEngineTrigger::OnUserCommand(UserCommand::ApplyQuickFix(msg)) => {
+                Some(DeadCodeProcedure::ApplyQuickFix(msg.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    fn procedure_detect(&mut self, code_document: &CodeDocument) -> Result<(), DeadCodeError> {
+        let window_uid = code_document.editor_window_props().window_uid;
+        let syntax_tree = code_document
+            .syntax_tree()
+            .ok_or(DeadCodeError::InsufficientContext)?;
+
+        let findings = detect_dead_code(syntax_tree, self.caches.entry(window_uid).or_default());
+        // Only publishing changes keeps the ids of the matches stable, e.g. while typing below them.
+        if self.findings.get(&window_uid) == Some(&findings) {
+            return Ok(());
+        }
+
+        let rule_matches: Vec<RuleMatch> = findings
+            .iter()
+            .filter_map(|finding| finding.to_rule_match(syntax_tree.text_content()))
+            .collect();
+        Self::publish_results(window_uid, rule_matches.clone());
+
+        self.findings.insert(window_uid, findings);
+        self.results.insert(window_uid, rule_matches);
+
+        Ok(())
+    }
+
+    // The fix is looked up again on the current text, in case it changed since the match was
+    // published.
+    fn procedure_apply_quick_fix(
+        &mut self,
+        code_document: &CodeDocument,
+        msg: ApplyQuickFixMessage,
+    ) -> Result<(), DeadCodeError> {
+        let window_uid = code_document.editor_window_props().window_uid;
+        let rule_match = self
+            .results
+            .get(&window_uid)
+            .and_then(|rule_matches| {
+                rule_matches
+                    .iter()
+                    .find(|rule_match| rule_match.id() == msg.rule_match_id)
+            })
+            .cloned()
+            .ok_or(DeadCodeError::OutdatedQuickFix)?;
+        let syntax_tree = code_document
+            .syntax_tree()
+            .ok_or(DeadCodeError::InsufficientContext)?;
+
+        let edits = detect_dead_code(syntax_tree, self.caches.entry(window_uid).or_default())
+            .into_iter()
+            .find(|finding| finding.matches(&rule_match))
+            .and_then(|finding| finding.fix)
+            .ok_or(DeadCodeError::OutdatedQuickFix)?;
+
+        let text = syntax_tree.text_content().clone();
+        let new_text =
+            apply_edits(&text, &edits).map_err(|err| DeadCodeError::GenericError(err.into()))?;
+
+        tauri::async_runtime::spawn({
+            let selected_text_range = code_document.selected_text_range().clone();
+            async move {
+                if let Err(err) = replace_text_content(&text, &new_text, &selected_text_range).await
+                {
+                    error!(?err, "Error replacing text content");
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    fn publish_results(window_uid: EditorWindowUid, rule_matches: Vec<RuleMatch>) {
+        DeadCodeEvent::UpdateDeadCodeResults(UpdateDeadCodeResultsMessage {
+            window_uid,
+            results: RuleResults {
+                rule: RuleName::DeadCode,
+                results: rule_matches,
+            },
+        })
+        .publish_to_tauri(&app_handle());
+    }
+
+    fn clear_results(&mut self) {
+        for (window_uid, _) in self.results.drain() {
+            Self::publish_results(window_uid, vec![]);
+        }
+        self.caches.clear();
+        self.findings.clear();
+    }
+}
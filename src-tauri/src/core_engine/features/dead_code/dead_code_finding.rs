@@ -0,0 +1,90 @@
+use strum::Display;
+
+use crate::{
+    core_engine::{
+        features::Edit,
+        rules::{
+            rule_base::{RuleMatchCategory, RuleName},
+            rule_match::{RuleMatchProps, RuleMatchQuickFix},
+            RuleMatch,
+        },
+        TextRange, XcodeText,
+    },
+    utils::rule_types::MatchRange,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display)]
+pub enum DeadCodeKind {
+    UnusedVariable,
+    UnusedParameter,
+    UnusedPrivateDeclaration,
+    UnreachableCode,
+    EmptyBlock,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeadCodeFinding {
+    pub kind: DeadCodeKind,
+    pub range: TextRange,
+    pub description: String,
+    pub fix: Option<Vec<Edit>>, // None if removing the code could break the file
+}
+
+impl DeadCodeFinding {
+    pub fn to_rule_match(&self, text: &XcodeText) -> Option<RuleMatch> {
+        let rule_match = RuleMatch::new(
+            RuleName::DeadCode,
+            MatchRange::from_text_and_range(text, &self.range)?,
+            RuleMatchProps {
+                identifier: self.kind.to_string(),
+                description: self.description.clone(),
+                category: RuleMatchCategory::Warning,
+            },
+        );
+
+        Some(match &self.fix {
+            Some(edits) => rule_match.with_quick_fix(RuleMatchQuickFix {
+                description: self.fix_description().to_string(),
+                edits: edits.clone(),
+            }),
+            None => rule_match,
+        })
+    }
+
+    // Whether the rule match was computed from this finding, possibly on an earlier run.
+    pub fn matches(&self, rule_match: &RuleMatch) -> bool {
+        rule_match.match_properties().identifier == self.kind.to_string()
+            && rule_match.match_range().range == self.range
+    }
+
+    // The same finding for the code moved from `old_start` to `new_start`.
+    pub fn relocated(&self, old_start: usize, new_start: usize) -> Self {
+        let move_index = |index: usize| index - old_start + new_start;
+
+        Self {
+            kind: self.kind,
+            range: TextRange::new(move_index(self.range.index), self.range.length),
+            description: self.description.clone(),
+            fix: self.fix.as_ref().map(|edits| {
+                edits
+                    .iter()
+                    .map(|edit| Edit {
+                        text: edit.text.clone(),
+                        start_index: move_index(edit.start_index),
+                        end_index: move_index(edit.end_index),
+                    })
+                    .collect()
+            }),
+        }
+    }
+
+    fn fix_description(&self) -> &'static str {
+        match self.kind {
+            DeadCodeKind::UnusedVariable => "Remove the variable",
+            DeadCodeKind::UnusedParameter => "Remove the parameter and its arguments",
+            DeadCodeKind::UnusedPrivateDeclaration => "Remove the declaration",
+            DeadCodeKind::UnreachableCode => "Remove the unreachable code",
+            DeadCodeKind::EmptyBlock => "Remove the empty block",
+        }
+    }
+}
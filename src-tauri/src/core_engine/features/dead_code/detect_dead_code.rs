@@ -0,0 +1,685 @@
+use std::collections::HashMap;
+
+use tree_sitter::Node;
+
+use crate::core_engine::{
+    features::Edit,
+    syntax_tree::{contains_kind, get_node_text, SwiftSyntaxTree, Symbol, SymbolKind},
+    TextRange, XcodeText,
+};
+
+use super::{DeadCodeFinding, DeadCodeKind};
+
+const FUNCTION_KINDS: &[&str] = &[
+    "function_declaration",
+    "init_declaration",
+    "deinit_declaration",
+    "subscript_declaration",
+];
+
+// Calls to these functions never return.
+const NEVER_RETURNING_FUNCTIONS: &[&str] = &["fatalError", "preconditionFailure"];
+
+// Findings inside a function only depend on the function's own text, so they are reused for
+// every function which did not change since the previous update.
+#[derive(Default)]
+pub struct DeadCodeCache {
+    findings_by_function: HashMap<XcodeText, Vec<DeadCodeFinding>>, // Relative to the function start
+}
+
+pub fn detect_dead_code(
+    syntax_tree: &SwiftSyntaxTree,
+    cache: &mut DeadCodeCache,
+) -> Vec<DeadCodeFinding> {
+    let text = syntax_tree.text_content();
+    let mut findings = vec![];
+
+    let mut findings_by_function = HashMap::new();
+    for function in outermost_functions(syntax_tree.tree().root_node()) {
+        let function_text = match get_node_text(&function, text) {
+            Ok(function_text) => function_text,
+            Err(_) => continue,
+        };
+        let function_start = function.start_byte() / 2;
+
+        let relative_findings = cache
+            .findings_by_function
+            .remove(&function_text)
+            .or_else(|| findings_by_function.get(&function_text).cloned())
+            .unwrap_or_else(|| {
+                check_function_body(syntax_tree, &function)
+                    .iter()
+                    .map(|finding| finding.relocated(function_start, 0))
+                    .collect()
+            });
+
+        findings.extend(
+            relative_findings
+                .iter()
+                .map(|finding| finding.relocated(0, function_start)),
+        );
+        findings_by_function.insert(function_text, relative_findings);
+    }
+    cache.findings_by_function = findings_by_function;
+
+    // Whether a declaration is used depends on the whole file.
+    findings.extend(check_private_declarations(syntax_tree));
+    findings.extend(check_parameters(syntax_tree));
+
+    findings.sort_by_key(|finding| finding.range.index);
+    findings
+}
+
+fn check_function_body(syntax_tree: &SwiftSyntaxTree, function: &Node) -> Vec<DeadCodeFinding> {
+    let text = syntax_tree.text_content();
+    let function_range = node_range(function);
+    let mut findings = vec![];
+
+    for symbol in syntax_tree.symbol_table().symbols() {
+        if symbol.kind == SymbolKind::Variable
+            && symbol.references.is_empty()
+            && !symbol.name.as_string().starts_with('_')
+            && function_range.includes_index(symbol.declaration.index)
+        {
+            findings.push(DeadCodeFinding {
+                kind: DeadCodeKind::UnusedVariable,
+                range: symbol.declaration,
+                description: format!("`{}` is never used", symbol.name.as_string()),
+                fix: unused_variable_fix(syntax_tree, symbol),
+            });
+        }
+    }
+
+    let mut nodes = vec![*function];
+    while let Some(node) = nodes.pop() {
+        match node.kind() {
+            "statements" => findings.extend(check_unreachable_statements(&node, text)),
+            "catch_block" => findings.extend(check_empty_catch_block(&node, text)),
+            "if_statement" => findings.extend(check_empty_else_block(&node)),
+            _ => {}
+        }
+        nodes.extend(node.children(&mut node.walk()));
+    }
+
+    findings
+}
+
+// Unused values without side effects are removed with their declaration, others are kept by
+// assigning them to `_`.
+fn unused_variable_fix(syntax_tree: &SwiftSyntaxTree, symbol: &Symbol) -> Option<Vec<Edit>> {
+    let text = syntax_tree.text_content();
+    let declaration = ancestor_of_kind(
+        declaration_node(syntax_tree, symbol)?,
+        "property_declaration",
+    )?;
+    if declaration
+        .children_by_field_name("name", &mut declaration.walk())
+        .count()
+        != 1
+    {
+        return None;
+    }
+
+    match declaration.child_by_field_name("value") {
+        Some(value) if contains_kind(&value, "call_expression") => Some(vec![Edit {
+            text: XcodeText::from_str("_ = "),
+            start_index: declaration.start_byte() / 2,
+            end_index: value.start_byte() / 2,
+        }]),
+        _ => Some(vec![Edit::removal_of_range(
+            text,
+            declaration.start_byte() / 2,
+            declaration.end_byte() / 2,
+        )]),
+    }
+}
+
+fn check_unreachable_statements(statements: &Node, text: &XcodeText) -> Option<DeadCodeFinding> {
+    let statement_nodes: Vec<Node> = statements
+        .named_children(&mut statements.walk())
+        .filter(|node| !is_comment(node))
+        .collect();
+
+    let exit_position = statement_nodes
+        .iter()
+        .position(|statement| exits_scope(statement, text))?;
+    let first_unreachable = statement_nodes.get(exit_position + 1)?;
+    let last_unreachable = statement_nodes.last()?;
+
+    let start_index = first_unreachable.start_byte() / 2;
+    let end_index = last_unreachable.end_byte() / 2;
+    Some(DeadCodeFinding {
+        kind: DeadCodeKind::UnreachableCode,
+        range: TextRange::from_StartEndIndex(start_index, end_index),
+        description: "This code is never executed".to_string(),
+        fix: Some(vec![Edit::removal_of_range(text, start_index, end_index)]),
+    })
+}
+
+fn exits_scope(statement: &Node, text: &XcodeText) -> bool {
+    match statement.kind() {
+        "control_transfer_statement" => true,
+        "call_expression" => statement
+            .child(0)
+            .and_then(|callee| get_node_text(&callee, text).ok())
+            .map_or(false, |callee| {
+                NEVER_RETURNING_FUNCTIONS.contains(&callee.as_string().as_str())
+            }),
+        _ => false,
+    }
+}
+
+// Without the `catch`, its errors would reach the next `catch` instead of being ignored, so only
+// the last one is offered to be removed, and only if the errors can then leave the function.
+fn check_empty_catch_block(catch_block: &Node, text: &XcodeText) -> Option<DeadCodeFinding> {
+    if has_block_content(catch_block) {
+        return None;
+    }
+
+    let has_later_catch_block = std::iter::successors(catch_block.next_named_sibling(), |node| {
+        node.next_named_sibling()
+    })
+    .any(|node| node.kind() == "catch_block");
+    let is_removable = !has_later_catch_block && errors_leave_function(catch_block, text);
+
+    Some(DeadCodeFinding {
+        kind: DeadCodeKind::EmptyBlock,
+        range: node_range(catch_block),
+        description: "The error is silently ignored".to_string(),
+        fix: is_removable.then(|| {
+            vec![Edit {
+                text: XcodeText::new_empty(),
+                // Removes the whitespace after the preceding block as well.
+                start_index: catch_block
+                    .prev_sibling()
+                    .map_or(catch_block.start_byte(), |previous| previous.end_byte())
+                    / 2,
+                end_index: catch_block.end_byte() / 2,
+            }]
+        }),
+    })
+}
+
+// True if the `do` of the catch block is directly in a function declared to throw; in a closure
+// or another `do` the errors would be handled elsewhere or not compile.
+fn errors_leave_function(catch_block: &Node, text: &XcodeText) -> bool {
+    let mut ancestor = catch_block
+        .parent()
+        .and_then(|do_statement| do_statement.parent());
+    while let Some(node) = ancestor {
+        match node.kind() {
+            "lambda_literal" | "do_statement" => return false,
+            kind if FUNCTION_KINDS.contains(&kind) => {
+                return node.children(&mut node.walk()).any(|child| {
+                    child.kind() == "throws"
+                        && get_node_text(&child, text).ok() == Some(XcodeText::from_str("throws"))
+                });
+            }
+            _ => ancestor = node.parent(),
+        }
+    }
+    false
+}
+
+fn check_empty_else_block(if_statement: &Node) -> Option<DeadCodeFinding> {
+    let children: Vec<Node> = if_statement.children(&mut if_statement.walk()).collect();
+    let else_position = children.iter().position(|child| child.kind() == "else")?;
+
+    match &children[else_position..] {
+        [else_keyword, open_brace, close_brace]
+            if open_brace.kind() == "{" && close_brace.kind() == "}" =>
+        {
+            // Removes the whitespace between the closing brace of the `if` and the `else` as well.
+            let removal_start = else_position
+                .checked_sub(1)
+                .map_or(else_keyword.start_byte(), |position| {
+                    children[position].end_byte()
+                })
+                / 2;
+
+            Some(DeadCodeFinding {
+                kind: DeadCodeKind::EmptyBlock,
+                range: TextRange::from_StartEndIndex(
+                    else_keyword.start_byte() / 2,
+                    close_brace.end_byte() / 2,
+                ),
+                description: "The `else` branch is empty".to_string(),
+                fix: Some(vec![Edit {
+                    text: XcodeText::new_empty(),
+                    start_index: removal_start,
+                    end_index: close_brace.end_byte() / 2,
+                }]),
+            })
+        }
+        _ => None,
+    }
+}
+
+// Private functions and properties can only be used in this file. Their name is also looked for
+// where no symbol could be resolved, e.g. in extensions or after other receivers than `self`.
+fn check_private_declarations(syntax_tree: &SwiftSyntaxTree) -> Vec<DeadCodeFinding> {
+    let text = syntax_tree.text_content();
+    let identifier_counts = count_identifiers(syntax_tree.tree().root_node(), text);
+
+    let mut findings = vec![];
+    for symbol in syntax_tree.symbol_table().symbols() {
+        let declaration_kind = match symbol.kind {
+            SymbolKind::Function | SymbolKind::Method => "function_declaration",
+            SymbolKind::Property => "property_declaration",
+            _ => continue,
+        };
+        if !symbol.references.is_empty() || identifier_counts.get(&symbol.name) != Some(&1) {
+            continue;
+        }
+
+        let declaration = match declaration_node(syntax_tree, symbol)
+            .and_then(|node| ancestor_of_kind(node, declaration_kind))
+        {
+            Some(declaration) => declaration,
+            None => continue,
+        };
+        if !is_removable_private_declaration(&declaration, text) {
+            continue;
+        }
+
+        let is_only_name = declaration
+            .children_by_field_name("name", &mut declaration.walk())
+            .count()
+            == 1;
+        findings.push(DeadCodeFinding {
+            kind: DeadCodeKind::UnusedPrivateDeclaration,
+            range: symbol.declaration,
+            description: format!("`{}` is never used", symbol.name.as_string()),
+            fix: (declaration_kind == "function_declaration" || is_only_name).then(|| {
+                vec![Edit::removal_of_range(
+                    text,
+                    declaration.start_byte() / 2,
+                    declaration.end_byte() / 2,
+                )]
+            }),
+        });
+    }
+    findings
+}
+
+// Parameters of private functions can be removed together with the arguments of all calls.
+fn check_parameters(syntax_tree: &SwiftSyntaxTree) -> Vec<DeadCodeFinding> {
+    let text = syntax_tree.text_content();
+
+    let mut findings = vec![];
+    for symbol in syntax_tree.symbol_table().symbols() {
+        if symbol.kind != SymbolKind::Parameter
+            || !symbol.references.is_empty()
+            || symbol.name.as_string().starts_with('_')
+        {
+            continue;
+        }
+
+        let parameter = match declaration_node(syntax_tree, symbol).and_then(|node| node.parent()) {
+            Some(parameter) if parameter.kind() == "parameter" => parameter,
+            _ => continue,
+        };
+        let function = match parameter.parent() {
+            Some(function) if function.kind() == "function_declaration" => function,
+            _ => continue,
+        };
+        if !is_removable_private_declaration(&function, text)
+            || function.child_by_field_name("body").is_none()
+        {
+            continue;
+        }
+
+        findings.push(DeadCodeFinding {
+            kind: DeadCodeKind::UnusedParameter,
+            range: symbol.declaration,
+            description: format!("`{}` is never used", symbol.name.as_string()),
+            fix: parameter_removal_edits(syntax_tree, &function, &parameter),
+        });
+    }
+    findings
+}
+
+fn parameter_removal_edits(
+    syntax_tree: &SwiftSyntaxTree,
+    function: &Node,
+    parameter: &Node,
+) -> Option<Vec<Edit>> {
+    let text = syntax_tree.text_content();
+
+    // Calls could leave out arguments with default values, so positions would not match.
+    if function
+        .children_by_field_name("default_value", &mut function.walk())
+        .next()
+        .is_some()
+    {
+        return None;
+    }
+    let parameters: Vec<Node> = function
+        .named_children(&mut function.walk())
+        .filter(|node| node.kind() == "parameter")
+        .collect();
+    let position = parameters
+        .iter()
+        .position(|node| node.id() == parameter.id())?;
+
+    let name = function.child_by_field_name("name")?;
+    let function_symbol = syntax_tree
+        .symbol_table()
+        .symbol_at(name.start_byte() / 2)?;
+    // Uses which did not resolve, e.g. `other.f(1)`, can not be updated.
+    let identifier_count = count_identifiers(syntax_tree.tree().root_node(), text)
+        .get(&function_symbol.name)
+        .copied()
+        .unwrap_or(0);
+    if identifier_count != function_symbol.references.len() + 1 {
+        return None;
+    }
+
+    let mut edits = vec![removal_from_list(&parameters, position)?];
+    for reference in &function_symbol.references {
+        let arguments = call_arguments(syntax_tree, reference)?;
+        if arguments.len() != parameters.len() {
+            return None;
+        }
+        edits.push(removal_from_list(&arguments, position)?);
+    }
+
+    // Nested calls, like `f(f(1, 2), 3)`, could remove overlapping text.
+    edits.sort_by_key(|edit| edit.start_index);
+    if edits
+        .windows(2)
+        .any(|pair| pair[0].end_index > pair[1].start_index)
+    {
+        return None;
+    }
+
+    Some(edits)
+}
+
+// The arguments of the call if the reference is its callee, e.g. `f` in `f(1)` or `self.f(1)`.
+fn call_arguments<'a>(
+    syntax_tree: &'a SwiftSyntaxTree,
+    reference: &TextRange,
+) -> Option<Vec<Node<'a>>> {
+    let identifier = syntax_tree.tree().root_node().descendant_for_byte_range(
+        reference.index * 2,
+        (reference.index + reference.length) * 2,
+    )?;
+
+    let mut callee = identifier;
+    if let Some(navigation_suffix) = identifier.parent() {
+        if navigation_suffix.kind() == "navigation_suffix" {
+            callee = navigation_suffix.parent()?;
+        }
+    }
+    let call = callee.parent()?;
+    if call.kind() != "call_expression" || call.child(0)?.id() != callee.id() {
+        return None;
+    }
+
+    let call_suffix = call
+        .named_children(&mut call.walk())
+        .find(|node| node.kind() == "call_suffix")?;
+    // Trailing closures are arguments outside of the parentheses.
+    if call_suffix
+        .named_children(&mut call_suffix.walk())
+        .any(|node| node.kind() == "lambda_literal")
+    {
+        return None;
+    }
+    let value_arguments = call_suffix
+        .named_children(&mut call_suffix.walk())
+        .find(|node| node.kind() == "value_arguments")?;
+
+    Some(
+        value_arguments
+            .named_children(&mut value_arguments.walk())
+            .filter(|node| node.kind() == "value_argument")
+            .collect(),
+    )
+}
+
+// Removes an item of a comma separated list together with one of its separators.
+fn removal_from_list(items: &[Node], position: usize) -> Option<Edit> {
+    let item = items.get(position)?;
+    let (start_byte, end_byte) = if let Some(next) = items.get(position + 1) {
+        (item.start_byte(), next.start_byte())
+    } else if let Some(previous) = position.checked_sub(1).and_then(|p| items.get(p)) {
+        (previous.end_byte(), item.end_byte())
+    } else {
+        (item.start_byte(), item.end_byte())
+    };
+
+    Some(Edit {
+        text: XcodeText::new_empty(),
+        start_index: start_byte / 2,
+        end_index: end_byte / 2,
+    })
+}
+
+// Overriding and annotated declarations, e.g. `@objc` or `@IBAction`, can be used without
+// referencing them in this file.
+fn is_removable_private_declaration(declaration: &Node, text: &XcodeText) -> bool {
+    let modifiers: Vec<String> = declaration
+        .children(&mut declaration.walk())
+        .filter(|child| child.kind() == "modifiers")
+        .filter_map(|modifiers| get_node_text(&modifiers, text).ok())
+        .flat_map(|modifiers| {
+            modifiers
+                .as_string()
+                .split_whitespace()
+                .map(|modifier| modifier.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    modifiers
+        .iter()
+        .any(|modifier| modifier == "private" || modifier == "fileprivate")
+        && !modifiers
+            .iter()
+            .any(|modifier| modifier == "override" || modifier.starts_with('@'))
+}
+
+fn has_block_content(block: &Node) -> bool {
+    block
+        .named_children(&mut block.walk())
+        .any(|child| child.kind() == "statements" || is_comment(&child))
+}
+
+fn outermost_functions(root: Node) -> Vec<Node> {
+    let mut functions = vec![];
+    let mut nodes = vec![root];
+    while let Some(node) = nodes.pop() {
+        if FUNCTION_KINDS.contains(&node.kind()) {
+            functions.push(node);
+        } else {
+            nodes.extend(node.children(&mut node.walk()));
+        }
+    }
+    functions
+}
+
+fn count_identifiers(root: Node, text: &XcodeText) -> HashMap<XcodeText, usize> {
+    let mut counts = HashMap::new();
+    let mut nodes = vec![root];
+    while let Some(node) = nodes.pop() {
+        if node.kind() == "simple_identifier" {
+            if let Ok(name) = get_node_text(&node, text) {
+                *counts.entry(name).or_insert(0) += 1;
+            }
+        }
+        nodes.extend(node.children(&mut node.walk()));
+    }
+    counts
+}
+
+fn declaration_node<'a>(syntax_tree: &'a SwiftSyntaxTree, symbol: &Symbol) -> Option<Node<'a>> {
+    syntax_tree.tree().root_node().descendant_for_byte_range(
+        symbol.declaration.index * 2,
+        (symbol.declaration.index + symbol.declaration.length) * 2,
+    )
+}
+
+fn ancestor_of_kind<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+    let mut node = node;
+    while node.kind() != kind {
+        node = node.parent()?;
+    }
+    Some(node)
+}
+
+fn is_comment(node: &Node) -> bool {
+    node.kind() == "comment" || node.kind() == "multiline_comment"
+}
+
+fn node_range(node: &Node) -> TextRange {
+    TextRange::from_StartEndIndex(node.start_byte() / 2, node.end_byte() / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    mod detect_dead_code {
+        use crate::core_engine::features::{
+            dead_code::{detect_dead_code, DeadCodeCache, DeadCodeFinding, DeadCodeKind},
+            test_utils::{applied, parse},
+        };
+
+        fn test_fn(code: &str) -> Vec<DeadCodeFinding> {
+            detect_dead_code(&parse(code), &mut DeadCodeCache::default())
+        }
+
+        fn fixed(code: &str, finding: &DeadCodeFinding) -> Option<String> {
+            finding.fix.as_ref().map(|edits| applied(code, edits))
+        }
+
+        #[test]
+        fn removes_unused_variables() {
+            let code =
+                "func f() {\n    let unused = 1\n    let kept = compute()\n    print(0)\n}\n";
+            let findings = test_fn(code);
+
+            assert_eq!(findings.len(), 2);
+            assert!(findings
+                .iter()
+                .all(|finding| finding.kind == DeadCodeKind::UnusedVariable));
+            assert_eq!(
+                fixed(code, &findings[0]),
+                Some("func f() {\n    let kept = compute()\n    print(0)\n}\n".to_string())
+            );
+            assert_eq!(
+                fixed(code, &findings[1]),
+                Some(
+                    "func f() {\n    let unused = 1\n    _ = compute()\n    print(0)\n}\n"
+                        .to_string()
+                )
+            );
+        }
+
+        #[test]
+        fn removes_unreachable_statements() {
+            let code = "func f() -> Int {\n    return 0\n    print(1)\n    print(2)\n}\n";
+            let findings = test_fn(code);
+
+            assert_eq!(findings.len(), 1);
+            assert_eq!(findings[0].kind, DeadCodeKind::UnreachableCode);
+            assert_eq!(
+                fixed(code, &findings[0]),
+                Some("func f() -> Int {\n    return 0\n}\n".to_string())
+            );
+        }
+
+        #[test]
+        fn removes_unused_parameters_and_arguments() {
+            let code = "private func f(a: Int, b: Int) {\n    print(a)\n}\nfunc g() {\n    f(a: 1, b: 2)\n}\n";
+            let findings = test_fn(code);
+
+            assert_eq!(findings.len(), 1);
+            assert_eq!(findings[0].kind, DeadCodeKind::UnusedParameter);
+            assert_eq!(
+                fixed(code, &findings[0]),
+                Some(
+                    "private func f(a: Int) {\n    print(a)\n}\nfunc g() {\n    f(a: 1)\n}\n"
+                        .to_string()
+                )
+            );
+        }
+
+        #[test]
+        fn ignores_parameters_of_visible_functions() {
+            assert_eq!(test_fn("func f(a: Int) {\n    print(0)\n}\n"), vec![]);
+        }
+
+        #[test]
+        fn removes_unused_private_declarations() {
+            let code = "class A {\n    private var count = 0\n    private func helper() {}\n    func run() {\n        print(count)\n    }\n}\n";
+            let findings = test_fn(code);
+
+            assert_eq!(findings.len(), 1);
+            assert_eq!(findings[0].kind, DeadCodeKind::UnusedPrivateDeclaration);
+            assert_eq!(
+                fixed(code, &findings[0]),
+                Some("class A {\n    private var count = 0\n    func run() {\n        print(count)\n    }\n}\n".to_string())
+            );
+        }
+
+        #[test]
+        fn removes_empty_else_blocks() {
+            let code = "func f(a: Bool) {\n    if a {\n        print(a)\n    } else {\n    }\n}\n";
+            let findings = test_fn(code);
+
+            assert_eq!(findings.len(), 1);
+            assert_eq!(findings[0].kind, DeadCodeKind::EmptyBlock);
+            assert_eq!(
+                fixed(code, &findings[0]),
+                Some("func f(a: Bool) {\n    if a {\n        print(a)\n    }\n}\n".to_string())
+            );
+        }
+
+        #[test]
+        fn keeps_the_only_empty_catch_block() {
+            let findings =
+                test_fn("func f() {\n    do {\n        try g()\n    } catch {\n    }\n}\n");
+
+            assert_eq!(findings.len(), 1);
+            assert_eq!(findings[0].kind, DeadCodeKind::EmptyBlock);
+            assert_eq!(findings[0].fix, None);
+        }
+
+        #[test]
+        fn removes_the_last_empty_catch_block_of_a_throwing_function() {
+            let code = "func f() throws {\n    do {\n        try g()\n    } catch {\n    }\n}\n";
+            let findings = test_fn(code);
+
+            assert_eq!(findings.len(), 1);
+            assert_eq!(
+                fixed(code, &findings[0]),
+                Some("func f() throws {\n    do {\n        try g()\n    }\n}\n".to_string())
+            );
+        }
+
+        #[test]
+        fn keeps_empty_catch_blocks_before_others() {
+            let findings = test_fn(
+                "func f() throws {\n    do {\n        try g()\n    } catch is CancellationError {\n    } catch {\n        print(error)\n    }\n}\n",
+            );
+
+            assert_eq!(findings.len(), 1);
+            assert_eq!(findings[0].kind, DeadCodeKind::EmptyBlock);
+            assert_eq!(findings[0].fix, None);
+        }
+
+        #[test]
+        fn reuses_findings_of_unchanged_functions() {
+            let mut cache = DeadCodeCache::default();
+            let function = "func f() {\n    let unused = 1\n}\n";
+            let before = detect_dead_code(&parse(function), &mut cache);
+
+            let prefix = "let a = 1\n";
+            let after = detect_dead_code(&parse(&format!("{}{}", prefix, function)), &mut cache);
+
+            assert_eq!(after.len(), 1);
+            assert_eq!(after[0], before[0].relocated(0, prefix.len()));
+        }
+    }
+}
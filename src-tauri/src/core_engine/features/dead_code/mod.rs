@@ -0,0 +1,7 @@
+pub use dead_code::{DeadCodeDetection, DeadCodeError};
+pub use dead_code_finding::{DeadCodeFinding, DeadCodeKind};
+pub use detect_dead_code::{detect_dead_code, DeadCodeCache};
+
+mod dead_code;
+mod dead_code_finding;
+mod detect_dead_code;
@@ -5,6 +5,8 @@ use crate::{
         core_engine::EditorWindowUid,
         events::models::NodeAnnotationClickedMessage,
         features::{CoreEngineTrigger, FeatureBase, FeatureError, FeatureKind, UserCommand},
+        keymap::KeymapAction,
+        scheduler::CancellationToken,
         syntax_tree::{SwiftCodeBlock, SwiftCodeBlockBase, SwiftSyntaxTree},
         utils::XcodeText,
         CodeDocument, TextPosition, TextRange, XcodeChar,
@@ -29,6 +31,7 @@ pub enum DocsGenerationError {
 enum DocsGenComputeProcedure {
     CreateNewNodeAnnotation,
     FetchNodeExplanation(NodeAnnotationClickedMessage),
+    ExplainSelection,
 }
 
 pub struct DocsGenerator {
@@ -42,6 +45,7 @@ impl FeatureBase for DocsGenerator {
         &mut self,
         code_document: CodeDocument,
         trigger: CoreEngineTrigger,
+        _cancellation_token: CancellationToken,
     ) -> Result<(), FeatureError> {
         if !self.is_activated {
             return Ok(());
@@ -55,6 +59,17 @@ impl FeatureBase for DocsGenerator {
                 Some(DocsGenComputeProcedure::FetchNodeExplanation(msg)) => {
                     self.procedure_fetch_node_explanation(&code_document, msg)?;
                 }
+                Some(DocsGenComputeProcedure::ExplainSelection) => {
+                    // Same as clicking the annotation of the code block around the cursor.
+                    let editor_window_uid = code_document.editor_window_props().window_uid;
+                    if let Some(annotation) = self.node_annotations.get(&editor_window_uid) {
+                        let msg = NodeAnnotationClickedMessage {
+                            annotation_id: annotation.id(),
+                            editor_window_uid,
+                        };
+                        self.procedure_fetch_node_explanation(&code_document, msg)?;
+                    }
+                }
                 Some(DocsGenComputeProcedure::CreateNewNodeAnnotation) => {
                     if self
                         .procedure_create_new_annotation(&code_document)
@@ -133,6 +148,9 @@ impl DocsGenerator {
                 }
                 _ => None,
             },
+            CoreEngineTrigger::OnShortcutPressed(KeymapAction::ExplainSelection) => {
+                Some(DocsGenComputeProcedure::ExplainSelection)
+            }
             _ => None,
         }
     }
@@ -234,6 +252,7 @@ impl DocsGenerator {
         let name = match codeblock {
             SwiftCodeBlock::Function(ref block) => block.get_name(),
             SwiftCodeBlock::Class(ref block) => block.get_name(),
+            SwiftCodeBlock::ComputedProperty(ref block) => block.get_name(),
             _ => None,
         };
 
@@ -243,11 +262,20 @@ impl DocsGenerator {
                     .get_parameters()
                     .map_err(|err| DocsGenerationError::GenericError(err.into()))?,
             ),
+            SwiftCodeBlock::Closure(ref closure) => Some(closure.get_parameters()),
+            _ => None,
+        };
+
+        let generic_constraints = match codeblock {
+            SwiftCodeBlock::Function(ref function) => Some(function.get_generic_constraints()),
+            SwiftCodeBlock::Class(ref class) => Some(class.get_generic_constraints()),
             _ => None,
         };
 
         let func_complexity = match codeblock {
             SwiftCodeBlock::Function(ref function) => Some(function.get_complexity()),
+            SwiftCodeBlock::Closure(ref closure) => Some(closure.get_complexity()),
+            SwiftCodeBlock::ComputedProperty(ref property) => Some(property.get_complexity()),
             _ => None,
         };
 
@@ -255,6 +283,7 @@ impl DocsGenerator {
             func_complexity_todo: func_complexity,
             name,
             func_parameters_todo: parameters,
+            generic_constraints,
             kind: codeblock.get_kind(),
             first_char_pos,
             last_char_pos,
@@ -54,6 +54,7 @@ pub struct AnnotationCodeBlock {
     pub name: Option<String>,
     pub func_parameters_todo: Option<Vec<FunctionParameter>>, // TODO: COD-320 Majorly refactor CodeBlock. Not ok to allow incompatible kind and parameters etc.
     pub func_complexity_todo: Option<isize>, // TODO: COD-320 Majorly refactor CodeBlock. Not ok to allow incompatible kind and parameters etc.
+    pub generic_constraints: Option<Vec<String>>,
     pub first_char_pos: TextPosition,
     pub last_char_pos: TextPosition,
     pub kind: SwiftCodeBlockKind,
@@ -58,13 +58,15 @@ pub struct NodeExplanationRequest {
     method: String,
     parameter_names: Option<Vec<String>>,
     code_block_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generic_constraints: Option<Vec<String>>,
 }
 
 pub async fn fetch_node_explanation(
     code_block: AnnotationCodeBlock,
 ) -> Result<NodeExplanation, reqwest::Error> {
     let context = if let Some(context) = code_block.context {
-        Some(String::from_utf16_lossy(&context))
+        Some(context.as_string())
     } else {
         None
     };
@@ -75,6 +77,7 @@ pub async fn fetch_node_explanation(
         code_block.func_parameters_todo,
         code_block.name,
         context,
+        code_block.generic_constraints,
     )
     .await;
     if let Ok(node_explanation) = result.as_ref() {
@@ -91,10 +94,11 @@ async fn cached_fetch_node_explanation(
     func_parameters: Option<Vec<FunctionParameter>>,
     name: Option<String>,
     context: Option<String>,
+    generic_constraints: Option<Vec<String>>,
 ) -> Result<NodeExplanation, reqwest::Error> {
     let url = get_cloud_function_url();
 
-    let codeblock_text_string = String::from_utf16_lossy(&text);
+    let codeblock_text_string = text.as_string();
 
     let req_body = NodeExplanationRequest {
         code_block_name: name,
@@ -107,6 +111,7 @@ async fn cached_fetch_node_explanation(
         parameter_names: func_parameters
             .as_ref()
             .map(map_function_parameters_to_names),
+        generic_constraints,
     };
 
     let response = reqwest::Client::new()
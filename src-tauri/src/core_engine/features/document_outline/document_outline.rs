@@ -0,0 +1,138 @@
+use crate::{
+    app_handle,
+    core_engine::{
+        events::{models::UpdateDocumentOutlineMessage, DocumentOutlineEvent},
+        features::{
+            feature_base::{CoreEngineTrigger, FeatureBase, FeatureError},
+            FeatureKind,
+        },
+        scheduler::CancellationToken,
+        syntax_tree::{sort_document_symbols_by_complexity, DocumentSymbol},
+        CodeDocument, EditorWindowUid,
+    },
+    CORE_ENGINE_ACTIVE_AT_STARTUP,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DocumentOutlineError {
+    #[error("Insufficient context for document outline")]
+    InsufficientContext,
+    #[error("Something went wrong when executing the DocumentOutline feature.")]
+    GenericError(#[source] anyhow::Error),
+}
+
+pub struct DocumentOutline {
+    is_activated: bool,
+
+    published_outline: Option<(EditorWindowUid, Vec<DocumentSymbol>)>,
+}
+
+impl FeatureBase for DocumentOutline {
+    fn compute(
+        &mut self,
+        code_document: CodeDocument,
+        _trigger: CoreEngineTrigger,
+        _cancellation_token: CancellationToken,
+    ) -> Result<(), FeatureError> {
+        if !self.is_activated {
+            return Ok(());
+        }
+
+        if let Err(err) = self.procedure_compute_outline(&code_document) {
+            self.clear_outline();
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    fn activate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = true;
+
+        Ok(())
+    }
+
+    fn deactivate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = false;
+        self.clear_outline();
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), FeatureError> {
+        self.clear_outline();
+
+        Ok(())
+    }
+
+    fn kind(&self) -> FeatureKind {
+        FeatureKind::DocumentOutline
+    }
+
+    fn should_compute(_kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool {
+        Self::determine_procedure(trigger)
+    }
+
+    fn requires_ai(_kind: &FeatureKind, _trigger: &CoreEngineTrigger) -> bool {
+        false
+    }
+}
+
+impl DocumentOutline {
+    pub fn new() -> Self {
+        Self {
+            is_activated: CORE_ENGINE_ACTIVE_AT_STARTUP,
+            published_outline: None,
+        }
+    }
+
+    fn determine_procedure(trigger: &CoreEngineTrigger) -> bool {
+        match trigger {
+            CoreEngineTrigger::OnTextContentChange => true,
+            CoreEngineTrigger::OnTextSelectionChange => true, // Also covers switching between editor windows
+            _ => false,
+        }
+    }
+
+    fn procedure_compute_outline(
+        &mut self,
+        code_document: &CodeDocument,
+    ) -> Result<(), DocumentOutlineError> {
+        let window_uid = code_document.editor_window_props().window_uid;
+
+        let syntax_tree = code_document
+            .syntax_tree()
+            .ok_or(DocumentOutlineError::InsufficientContext)?;
+
+        let mut symbols = syntax_tree.get_document_symbols();
+        sort_document_symbols_by_complexity(&mut symbols);
+
+        self.publish_outline(window_uid, symbols);
+
+        Ok(())
+    }
+
+    fn publish_outline(&mut self, window_uid: EditorWindowUid, symbols: Vec<DocumentSymbol>) {
+        // Moving the cursor does not change the outline; only publish if something changed.
+        if let Some((previous_window_uid, previous_symbols)) = &self.published_outline {
+            if *previous_window_uid == window_uid && *previous_symbols == symbols {
+                return;
+            }
+        }
+
+        DocumentOutlineEvent::UpdateDocumentOutline(UpdateDocumentOutlineMessage {
+            window_uid,
+            symbols: symbols.clone(),
+        })
+        .publish_to_tauri(&app_handle());
+
+        self.published_outline = Some((window_uid, symbols));
+    }
+
+    fn clear_outline(&mut self) {
+        if let Some((window_uid, _)) = self.published_outline {
+            self.publish_outline(window_uid, vec![]);
+        }
+        self.published_outline = None;
+    }
+}
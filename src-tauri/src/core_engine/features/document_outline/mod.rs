@@ -0,0 +1,3 @@
+pub use document_outline::{DocumentOutline, DocumentOutlineError};
+
+mod document_outline;
@@ -1,12 +1,13 @@
-use crate::{
-    core_engine::{
-        events::models::{
-            DismissSuggestionMessage, NodeAnnotationClickedMessage, PerformSuggestionMessage,
-            UpdateSelectedSuggestionMessage,
-        },
-        CodeDocument, SwiftFormatError,
+use crate::core_engine::{
+    events::models::{
+        ApplyQuickFixMessage, DetectClonesMessage, DismissSuggestionMessage,
+        LocalRefactoringMessage, NavigationCommandMessage, NodeAnnotationClickedMessage,
+        PerformSuggestionMessage, RenameSymbolMessage, ReplaceQueryMessage, SearchQueryMessage,
+        UpdateSelectedSuggestionMessage,
     },
-    platform::macos::models::editor::EditorShortcutPressedMessage,
+    keymap::KeymapAction,
+    scheduler::CancellationToken,
+    CodeDocument, EditorWindowUid, SwiftFormatError,
 };
 use serde::{Deserialize, Serialize};
 use std::{fmt, hash::Hash};
@@ -16,8 +17,11 @@ use ts_rs::TS;
 
 use super::{
     complexity_refactoring::ComplexityRefactoringError, docs_generation::DocsGenerationError,
-    formatter::SwiftFormatter, BracketHighlight, BracketHighlightError, ComplexityRefactoring,
-    DocsGenerator,
+    formatter::SwiftFormatter, BracketHighlight, BracketHighlightError, CloneDetection,
+    CloneDetectionError, ComplexityRefactoring, DeadCodeDetection, DeadCodeError, DocsGenerator,
+    DocumentOutline, DocumentOutlineError, ScopeGuides, ScopeGuidesError, StickyScopeHeader,
+    StickyScopeHeaderError, StructuralNavigation, StructuralNavigationError, StructuralSearch,
+    StructuralSearchError,
 };
 
 #[derive(Debug, Clone, PartialEq, Hash)]
@@ -26,11 +30,21 @@ pub enum UserCommand {
     DismissSuggestion(DismissSuggestionMessage),
     SelectSuggestion(UpdateSelectedSuggestionMessage),
     NodeAnnotationClicked(NodeAnnotationClickedMessage),
+    Navigate(NavigationCommandMessage),
+    Search(SearchQueryMessage),
+    PreviewReplace(ReplaceQueryMessage),
+    PerformReplace(ReplaceQueryMessage),
+    PreviewRenameSymbol(RenameSymbolMessage),
+    PerformRenameSymbol(RenameSymbolMessage),
+    PreviewLocalRefactoring(LocalRefactoringMessage),
+    PerformLocalRefactoring(LocalRefactoringMessage),
+    ApplyQuickFix(ApplyQuickFixMessage),
+    DetectClones(DetectClonesMessage),
 }
 
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum CoreEngineTrigger {
-    OnShortcutPressed(EditorShortcutPressedMessage),
+    OnShortcutPressed(KeymapAction), // Resolved through the keymap before reaching features
     OnTextContentChange,
     OnTextSelectionChange,
     OnViewportMove,
@@ -46,6 +60,13 @@ pub enum FeatureKind {
     ComplexityRefactoring,
     DocsGeneration,
     Formatter,
+    ScopeGuides,
+    StickyScopeHeader,
+    StructuralNavigation,
+    DocumentOutline,
+    StructuralSearch,
+    DeadCode,
+    CloneDetection,
 }
 
 impl FeatureKind {
@@ -55,6 +76,13 @@ impl FeatureKind {
             FeatureKind::DocsGeneration => DocsGenerator::requires_ai(self, trigger),
             FeatureKind::Formatter => SwiftFormatter::requires_ai(self, trigger),
             FeatureKind::ComplexityRefactoring => ComplexityRefactoring::requires_ai(self, trigger),
+            FeatureKind::ScopeGuides => ScopeGuides::requires_ai(self, trigger),
+            FeatureKind::StickyScopeHeader => StickyScopeHeader::requires_ai(self, trigger),
+            FeatureKind::StructuralNavigation => StructuralNavigation::requires_ai(self, trigger),
+            FeatureKind::DocumentOutline => DocumentOutline::requires_ai(self, trigger),
+            FeatureKind::StructuralSearch => StructuralSearch::requires_ai(self, trigger),
+            FeatureKind::DeadCode => DeadCodeDetection::requires_ai(self, trigger),
+            FeatureKind::CloneDetection => CloneDetection::requires_ai(self, trigger),
         }
     }
     pub fn should_compute(&self, trigger: &CoreEngineTrigger) -> bool {
@@ -65,6 +93,15 @@ impl FeatureKind {
             FeatureKind::ComplexityRefactoring => {
                 ComplexityRefactoring::should_compute(self, trigger)
             }
+            FeatureKind::ScopeGuides => ScopeGuides::should_compute(self, trigger),
+            FeatureKind::StickyScopeHeader => StickyScopeHeader::should_compute(self, trigger),
+            FeatureKind::StructuralNavigation => {
+                StructuralNavigation::should_compute(self, trigger)
+            }
+            FeatureKind::DocumentOutline => DocumentOutline::should_compute(self, trigger),
+            FeatureKind::StructuralSearch => StructuralSearch::should_compute(self, trigger),
+            FeatureKind::DeadCode => DeadCodeDetection::should_compute(self, trigger),
+            FeatureKind::CloneDetection => CloneDetection::should_compute(self, trigger),
         }
     }
 }
@@ -80,6 +117,13 @@ pub enum Feature {
     DocsGeneration(DocsGenerator),
     Formatter(SwiftFormatter),
     ComplexityRefactoring(ComplexityRefactoring),
+    ScopeGuides(ScopeGuides),
+    StickyScopeHeader(StickyScopeHeader),
+    StructuralNavigation(StructuralNavigation),
+    DocumentOutline(DocumentOutline),
+    StructuralSearch(StructuralSearch),
+    DeadCode(DeadCodeDetection),
+    CloneDetection(CloneDetection),
 }
 
 impl fmt::Debug for Feature {
@@ -89,6 +133,13 @@ impl fmt::Debug for Feature {
             Feature::DocsGeneration(_) => "DocsGeneration",
             Feature::Formatter(_) => "Formatter",
             Feature::ComplexityRefactoring(_) => "ComplexityRefactoring",
+            Feature::ScopeGuides(_) => "ScopeGuides",
+            Feature::StickyScopeHeader(_) => "StickyScopeHeader",
+            Feature::StructuralNavigation(_) => "StructuralNavigation",
+            Feature::DocumentOutline(_) => "DocumentOutline",
+            Feature::StructuralSearch(_) => "StructuralSearch",
+            Feature::DeadCode(_) => "DeadCode",
+            Feature::CloneDetection(_) => "CloneDetection",
         };
         write!(f, "{}", name)
     }
@@ -126,16 +177,61 @@ impl From<SwiftFormatError> for FeatureError {
     }
 }
 
+impl From<ScopeGuidesError> for FeatureError {
+    fn from(cause: ScopeGuidesError) -> Self {
+        FeatureError::GenericError(cause.into())
+    }
+}
+
+impl From<StickyScopeHeaderError> for FeatureError {
+    fn from(cause: StickyScopeHeaderError) -> Self {
+        FeatureError::GenericError(cause.into())
+    }
+}
+
+impl From<StructuralNavigationError> for FeatureError {
+    fn from(cause: StructuralNavigationError) -> Self {
+        FeatureError::GenericError(cause.into())
+    }
+}
+
+impl From<DocumentOutlineError> for FeatureError {
+    fn from(cause: DocumentOutlineError) -> Self {
+        FeatureError::GenericError(cause.into())
+    }
+}
+
+impl From<StructuralSearchError> for FeatureError {
+    fn from(cause: StructuralSearchError) -> Self {
+        FeatureError::GenericError(cause.into())
+    }
+}
+
+impl From<DeadCodeError> for FeatureError {
+    fn from(cause: DeadCodeError) -> Self {
+        FeatureError::GenericError(cause.into())
+    }
+}
+
+impl From<CloneDetectionError> for FeatureError {
+    fn from(cause: CloneDetectionError) -> Self {
+        FeatureError::GenericError(cause.into())
+    }
+}
+
 pub trait FeatureBase {
     fn kind(&self) -> FeatureKind;
     fn compute(
         &mut self,
         code_document: CodeDocument,
         trigger: CoreEngineTrigger,
+        cancellation_token: CancellationToken,
     ) -> Result<(), FeatureError>;
     fn activate(&mut self) -> Result<(), FeatureError>;
     fn deactivate(&mut self) -> Result<(), FeatureError>;
     fn reset(&mut self) -> Result<(), FeatureError>;
+    // Drops whatever the feature keeps for a window once its document is gone.
+    fn remove_window(&mut self, _window_uid: EditorWindowUid) {}
     fn should_compute(kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool;
     fn requires_ai(kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool;
 }
@@ -145,12 +241,42 @@ impl FeatureBase for Feature {
         &mut self,
         code_document: CodeDocument,
         trigger: CoreEngineTrigger,
+        cancellation_token: CancellationToken,
     ) -> Result<(), FeatureError> {
         match self {
-            Feature::BracketHighlighting(feature) => feature.compute(code_document, trigger),
-            Feature::DocsGeneration(feature) => feature.compute(code_document, trigger),
-            Feature::Formatter(feature) => feature.compute(code_document, trigger),
-            Feature::ComplexityRefactoring(feature) => feature.compute(code_document, trigger),
+            Feature::BracketHighlighting(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::DocsGeneration(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::Formatter(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::ComplexityRefactoring(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::ScopeGuides(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::StickyScopeHeader(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::StructuralNavigation(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::DocumentOutline(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::StructuralSearch(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::DeadCode(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
+            Feature::CloneDetection(feature) => {
+                feature.compute(code_document, trigger, cancellation_token)
+            }
         }
     }
 
@@ -160,6 +286,13 @@ impl FeatureBase for Feature {
             Feature::DocsGeneration(feature) => feature.activate(),
             Feature::Formatter(feature) => feature.activate(),
             Feature::ComplexityRefactoring(feature) => feature.activate(),
+            Feature::ScopeGuides(feature) => feature.activate(),
+            Feature::StickyScopeHeader(feature) => feature.activate(),
+            Feature::StructuralNavigation(feature) => feature.activate(),
+            Feature::DocumentOutline(feature) => feature.activate(),
+            Feature::StructuralSearch(feature) => feature.activate(),
+            Feature::DeadCode(feature) => feature.activate(),
+            Feature::CloneDetection(feature) => feature.activate(),
         }
     }
 
@@ -169,6 +302,13 @@ impl FeatureBase for Feature {
             Feature::DocsGeneration(feature) => feature.deactivate(),
             Feature::Formatter(feature) => feature.deactivate(),
             Feature::ComplexityRefactoring(feature) => feature.deactivate(),
+            Feature::ScopeGuides(feature) => feature.deactivate(),
+            Feature::StickyScopeHeader(feature) => feature.deactivate(),
+            Feature::StructuralNavigation(feature) => feature.deactivate(),
+            Feature::DocumentOutline(feature) => feature.deactivate(),
+            Feature::StructuralSearch(feature) => feature.deactivate(),
+            Feature::DeadCode(feature) => feature.deactivate(),
+            Feature::CloneDetection(feature) => feature.deactivate(),
         }
     }
 
@@ -178,6 +318,29 @@ impl FeatureBase for Feature {
             Feature::DocsGeneration(feature) => feature.reset(),
             Feature::Formatter(feature) => feature.reset(),
             Feature::ComplexityRefactoring(feature) => feature.reset(),
+            Feature::ScopeGuides(feature) => feature.reset(),
+            Feature::StickyScopeHeader(feature) => feature.reset(),
+            Feature::StructuralNavigation(feature) => feature.reset(),
+            Feature::DocumentOutline(feature) => feature.reset(),
+            Feature::StructuralSearch(feature) => feature.reset(),
+            Feature::DeadCode(feature) => feature.reset(),
+            Feature::CloneDetection(feature) => feature.reset(),
+        }
+    }
+
+    fn remove_window(&mut self, window_uid: EditorWindowUid) {
+        match self {
+            Feature::BracketHighlighting(feature) => feature.remove_window(window_uid),
+            Feature::DocsGeneration(feature) => feature.remove_window(window_uid),
+            Feature::Formatter(feature) => feature.remove_window(window_uid),
+            Feature::ComplexityRefactoring(feature) => feature.remove_window(window_uid),
+            Feature::ScopeGuides(feature) => feature.remove_window(window_uid),
+            Feature::StickyScopeHeader(feature) => feature.remove_window(window_uid),
+            Feature::StructuralNavigation(feature) => feature.remove_window(window_uid),
+            Feature::DocumentOutline(feature) => feature.remove_window(window_uid),
+            Feature::StructuralSearch(feature) => feature.remove_window(window_uid),
+            Feature::DeadCode(feature) => feature.remove_window(window_uid),
+            Feature::CloneDetection(feature) => feature.remove_window(window_uid),
         }
     }
 
@@ -187,6 +350,13 @@ impl FeatureBase for Feature {
             Feature::DocsGeneration(feature) => feature.kind(),
             Feature::Formatter(feature) => feature.kind(),
             Feature::ComplexityRefactoring(feature) => feature.kind(),
+            Feature::ScopeGuides(feature) => feature.kind(),
+            Feature::StickyScopeHeader(feature) => feature.kind(),
+            Feature::StructuralNavigation(feature) => feature.kind(),
+            Feature::DocumentOutline(feature) => feature.kind(),
+            Feature::StructuralSearch(feature) => feature.kind(),
+            Feature::DeadCode(feature) => feature.kind(),
+            Feature::CloneDetection(feature) => feature.kind(),
         }
     }
 
@@ -198,6 +368,15 @@ impl FeatureBase for Feature {
             FeatureKind::ComplexityRefactoring => {
                 ComplexityRefactoring::should_compute(kind, trigger)
             }
+            FeatureKind::ScopeGuides => ScopeGuides::should_compute(kind, trigger),
+            FeatureKind::StickyScopeHeader => StickyScopeHeader::should_compute(kind, trigger),
+            FeatureKind::StructuralNavigation => {
+                StructuralNavigation::should_compute(kind, trigger)
+            }
+            FeatureKind::DocumentOutline => DocumentOutline::should_compute(kind, trigger),
+            FeatureKind::StructuralSearch => StructuralSearch::should_compute(kind, trigger),
+            FeatureKind::DeadCode => DeadCodeDetection::should_compute(kind, trigger),
+            FeatureKind::CloneDetection => CloneDetection::should_compute(kind, trigger),
         }
     }
 
@@ -207,6 +386,13 @@ impl FeatureBase for Feature {
             FeatureKind::DocsGeneration => DocsGenerator::requires_ai(kind, trigger),
             FeatureKind::Formatter => SwiftFormatter::requires_ai(kind, trigger),
             FeatureKind::ComplexityRefactoring => ComplexityRefactoring::requires_ai(kind, trigger),
+            FeatureKind::ScopeGuides => ScopeGuides::requires_ai(kind, trigger),
+            FeatureKind::StickyScopeHeader => StickyScopeHeader::requires_ai(kind, trigger),
+            FeatureKind::StructuralNavigation => StructuralNavigation::requires_ai(kind, trigger),
+            FeatureKind::DocumentOutline => DocumentOutline::requires_ai(kind, trigger),
+            FeatureKind::StructuralSearch => StructuralSearch::requires_ai(kind, trigger),
+            FeatureKind::DeadCode => DeadCodeDetection::requires_ai(kind, trigger),
+            FeatureKind::CloneDetection => CloneDetection::requires_ai(kind, trigger),
         }
     }
 }
@@ -3,13 +3,15 @@ use tracing::{debug, info};
 
 use crate::core_engine::features::FeatureKind;
 use crate::core_engine::{format_code, SwiftFormatError};
-use crate::platform::macos::models::editor::ModifierKey;
 use crate::platform::macos::replace_text_content;
 use crate::{
     app_handle,
     core_engine::{
+        diagnostics::DiagnosticsSpan,
         events::EventRuleExecutionState,
         features::{CoreEngineTrigger, FeatureBase, FeatureError},
+        keymap::KeymapAction,
+        scheduler::CancellationToken,
         utils::XcodeText,
         CodeDocument,
     },
@@ -29,6 +31,7 @@ impl FeatureBase for SwiftFormatter {
         &mut self,
         code_document: CodeDocument,
         trigger: CoreEngineTrigger,
+        _cancellation_token: CancellationToken,
     ) -> Result<(), FeatureError> {
         if !self.is_activated {
             return Ok(());
@@ -77,12 +80,10 @@ impl SwiftFormatter {
     }
 
     fn determine_procedure(trigger: &CoreEngineTrigger) -> bool {
-        match trigger {
-            CoreEngineTrigger::OnShortcutPressed(msg) => {
-                msg.modifier == ModifierKey::Cmd && msg.key == "S"
-            }
-            _ => false,
-        }
+        matches!(
+            trigger,
+            CoreEngineTrigger::OnShortcutPressed(KeymapAction::FormatDocument)
+        )
     }
 
     pub fn format(&self, code_document: &CodeDocument) -> Result<(), SwiftFormatError> {
@@ -109,7 +110,14 @@ impl SwiftFormatter {
                 }
 
                 // 1. Format the text content file
-                let formatted_content = match format_code(&text_content, &file_path).await {
+                let formatted_content = DiagnosticsSpan::measure_async(
+                    FeatureKind::Formatter,
+                    "format_code",
+                    format_code(&text_content, &file_path),
+                )
+                .await;
+
+                let formatted_content = match formatted_content {
                     Ok(content) => content,
                     Err(err) => {
                         EventRuleExecutionState::SwiftFormatFailed()
@@ -1,15 +1,35 @@
 pub use bracket_highlight::{BracketHighlight, BracketHighlightError};
+pub use clone_detection::{CloneDetection, CloneDetectionError, CloneGroup, CloneLocation};
 pub use complexity_refactoring::ComplexityRefactoring;
+pub use complexity_refactoring::Edit;
 pub use complexity_refactoring::FERefactoringSuggestion;
 pub use complexity_refactoring::SuggestionId;
+pub use dead_code::{DeadCodeDetection, DeadCodeError};
 pub use docs_generation::cmd_paste_docs;
 pub use docs_generation::DocsGenerator;
 pub use docs_generation::NodeExplanation;
+pub use document_outline::{DocumentOutline, DocumentOutlineError};
 pub use feature_base::*;
 pub use formatter::SwiftFormatter;
+pub use scope_guides::{ScopeGuideProps, ScopeGuides, ScopeGuidesError};
+pub use sticky_scope_header::{StickyScope, StickyScopeHeader, StickyScopeHeaderError};
+pub use structural_navigation::{
+    NavigationCommand, StructuralNavigation, StructuralNavigationError,
+};
+pub use structural_search::{ReplacePreview, SearchMatch, StructuralSearch, StructuralSearchError};
 
 mod bracket_highlight;
+mod clone_detection;
 mod complexity_refactoring;
+mod dead_code;
 mod docs_generation;
+mod document_outline;
 mod feature_base;
 mod formatter;
+mod scope_guides;
+mod sticky_scope_header;
+mod structural_navigation;
+mod structural_search;
+
+#[cfg(test)]
+pub mod test_utils;
@@ -0,0 +1,4 @@
+pub use scope_guides::{ScopeGuideProps, ScopeGuides, ScopeGuidesError};
+
+mod scope_guides;
+mod utils;
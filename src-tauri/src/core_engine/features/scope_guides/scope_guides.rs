@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    core_engine::{
+        annotations_manager::{
+            AnnotationJob, AnnotationJobInstructions, AnnotationJobSingleChar, AnnotationJobTrait,
+            AnnotationKind, InstructionBoundsPropertyOfInterest,
+        },
+        events::AnnotationManagerEvent,
+        features::{
+            feature_base::{CoreEngineTrigger, FeatureBase, FeatureError},
+            FeatureKind,
+        },
+        scheduler::CancellationToken,
+        CodeDocument, EditorWindowUid, TextRange,
+    },
+    platform::macos::{get_visible_text_range, GetVia},
+    CORE_ENGINE_ACTIVE_AT_STARTUP,
+};
+
+use super::utils::{get_scopes_in_text_range, Scope};
+
+// Guides become noise quickly with deep nesting; code nested this deep should be refactored anyways.
+const MAX_SCOPE_DEPTH: usize = 8;
+
+const COMPLEXITY_TINT_MEDIUM_THRESHOLD: isize = 5;
+const COMPLEXITY_TINT_HIGH_THRESHOLD: isize = 10;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ScopeGuidesError {
+    #[error("Insufficient context for scope guides")]
+    InsufficientContext,
+    #[error("Something went wrong when executing the ScopeGuides feature.")]
+    GenericError(#[source] anyhow::Error),
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, TS)]
+#[ts(export, export_to = "bindings/features/scope_guides/")]
+pub enum ScopeComplexityTint {
+    Low,
+    Medium,
+    High,
+}
+
+impl ScopeComplexityTint {
+    pub fn from_complexity(complexity: isize) -> Self {
+        if complexity >= COMPLEXITY_TINT_HIGH_THRESHOLD {
+            ScopeComplexityTint::High
+        } else if complexity >= COMPLEXITY_TINT_MEDIUM_THRESHOLD {
+            ScopeComplexityTint::Medium
+        } else {
+            ScopeComplexityTint::Low
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, TS)]
+#[ts(export, export_to = "bindings/features/scope_guides/")]
+pub struct ScopeGuideProps {
+    pub depth: usize,
+    pub complexity_tint: ScopeComplexityTint,
+}
+
+pub struct ScopeGuides {
+    is_activated: bool,
+
+    registered_jobs: Vec<AnnotationJob>,
+    group_id: Option<uuid::Uuid>,
+    window_uid: Option<EditorWindowUid>,
+}
+
+impl FeatureBase for ScopeGuides {
+    fn compute(
+        &mut self,
+        code_document: CodeDocument,
+        _trigger: CoreEngineTrigger,
+        _cancellation_token: CancellationToken,
+    ) -> Result<(), FeatureError> {
+        if !self.is_activated {
+            return Ok(());
+        }
+
+        if let Err(err) = self.procedure_compute_scope_guides(&code_document) {
+            self.remove_annotations();
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    fn activate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = true;
+
+        Ok(())
+    }
+
+    fn deactivate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = false;
+        self.remove_annotations();
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), FeatureError> {
+        self.remove_annotations();
+
+        Ok(())
+    }
+
+    fn kind(&self) -> FeatureKind {
+        FeatureKind::ScopeGuides
+    }
+
+    fn should_compute(_kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool {
+        Self::determine_procedure(trigger)
+    }
+
+    fn requires_ai(_kind: &FeatureKind, _trigger: &CoreEngineTrigger) -> bool {
+        false
+    }
+}
+
+impl ScopeGuides {
+    pub fn new() -> Self {
+        Self {
+            is_activated: CORE_ENGINE_ACTIVE_AT_STARTUP,
+            registered_jobs: vec![],
+            group_id: None,
+            window_uid: None,
+        }
+    }
+
+    fn determine_procedure(trigger: &CoreEngineTrigger) -> bool {
+        match trigger {
+            CoreEngineTrigger::OnVisibleTextRangeChange => true,
+            CoreEngineTrigger::OnTextSelectionChange => true, // The TextSelectionChange is also triggered on text content change
+            _ => false,
+        }
+    }
+
+    fn procedure_compute_scope_guides(
+        &mut self,
+        code_document: &CodeDocument,
+    ) -> Result<(), ScopeGuidesError> {
+        let window_uid = code_document.editor_window_props().window_uid;
+
+        let syntax_tree = code_document
+            .syntax_tree()
+            .ok_or(ScopeGuidesError::InsufficientContext)?;
+
+        let visible_text_range = get_visible_text_range(GetVia::Hash(window_uid))
+            .map_err(|err| ScopeGuidesError::GenericError(err.into()))?;
+
+        let scopes = get_scopes_in_text_range(syntax_tree, &visible_text_range, MAX_SCOPE_DEPTH);
+
+        self.register_annotation_jobs(&scopes, window_uid);
+
+        Ok(())
+    }
+
+    fn register_annotation_jobs(&mut self, scopes: &Vec<Scope>, window_uid: EditorWindowUid) {
+        let instructions_default = AnnotationJobInstructions::default();
+
+        let mut jobs = vec![];
+        for scope in scopes {
+            let props = ScopeGuideProps {
+                depth: scope.depth,
+                complexity_tint: ScopeComplexityTint::from_complexity(scope.complexity),
+            };
+
+            jobs.push(AnnotationJob::SingleChar(AnnotationJobSingleChar::new(
+                uuid::Uuid::new_v4(),
+                &TextRange {
+                    index: scope.line_start_index,
+                    length: 1,
+                },
+                AnnotationKind::ScopeGuideStart(props.clone()),
+                AnnotationJobInstructions {
+                    bounds_property_of_interest: InstructionBoundsPropertyOfInterest::PosBotLeft,
+                    ..instructions_default.clone()
+                },
+            )));
+
+            jobs.push(AnnotationJob::SingleChar(AnnotationJobSingleChar::new(
+                uuid::Uuid::new_v4(),
+                &TextRange {
+                    index: scope.closing_char_index,
+                    length: 1,
+                },
+                AnnotationKind::ScopeGuideEnd(props),
+                AnnotationJobInstructions {
+                    bounds_property_of_interest: InstructionBoundsPropertyOfInterest::PosTopLeft,
+                    ..instructions_default.clone()
+                },
+            )));
+        }
+
+        if jobs.is_empty() {
+            self.remove_annotations();
+            return;
+        }
+
+        if self.registered_jobs == jobs && self.group_id.is_some() {
+            return;
+        }
+
+        // Scrolling changes the guides constantly; updating the existing group in place avoids flickering.
+        match (self.group_id, self.window_uid) {
+            (Some(group_id), Some(previous_window_uid)) if previous_window_uid == window_uid => {
+                AnnotationManagerEvent::Upsert((
+                    group_id,
+                    FeatureKind::ScopeGuides,
+                    jobs.clone(),
+                    window_uid,
+                ))
+                .publish_to_tauri();
+            }
+            _ => {
+                self.remove_annotations();
+
+                let new_group_id = uuid::Uuid::new_v4();
+                AnnotationManagerEvent::Add((
+                    new_group_id,
+                    FeatureKind::ScopeGuides,
+                    jobs.clone(),
+                    window_uid,
+                ))
+                .publish_to_tauri();
+
+                self.group_id = Some(new_group_id);
+                self.window_uid = Some(window_uid);
+            }
+        }
+
+        self.registered_jobs = jobs;
+    }
+
+    fn remove_annotations(&mut self) {
+        if let Some(group_id) = self.group_id {
+            AnnotationManagerEvent::Remove(group_id).publish_to_tauri();
+        }
+        self.group_id = None;
+        self.window_uid = None;
+        self.registered_jobs = vec![];
+    }
+}
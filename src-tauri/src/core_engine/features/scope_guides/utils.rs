@@ -0,0 +1,201 @@
+use tree_sitter::Node;
+
+use crate::core_engine::{
+    features::bracket_highlight::{
+        get_code_block_parent, get_indexes_of_first_and_last_char_in_node,
+    },
+    syntax_tree::SwiftSyntaxTree,
+    utils::XcodeText,
+    TextPosition, TextRange,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    pub depth: usize,
+    pub opening_char_index: usize,
+    pub closing_char_index: usize,
+    // Index of the first non-whitespace character on the row of the opening bracket;
+    // the guide is drawn downwards from here.
+    pub line_start_index: usize,
+    pub complexity: isize,
+}
+
+// Collects all multi-line code blocks which intersect the given text range, including the ones
+// that enclose it entirely. Scopes nested deeper than `max_depth` are ignored.
+pub fn get_scopes_in_text_range(
+    syntax_tree: &SwiftSyntaxTree,
+    text_range: &TextRange,
+    max_depth: usize,
+) -> Vec<Scope> {
+    let mut scopes = vec![];
+    collect_scopes(
+        syntax_tree.tree().root_node(),
+        syntax_tree,
+        text_range,
+        0,
+        max_depth,
+        &mut scopes,
+    );
+    scopes
+}
+
+fn collect_scopes(
+    node: Node,
+    syntax_tree: &SwiftSyntaxTree,
+    text_range: &TextRange,
+    depth: usize,
+    max_depth: usize,
+    scopes: &mut Vec<Scope>,
+) {
+    if depth >= max_depth || !node_intersects_text_range(&node, text_range) {
+        return;
+    }
+
+    let mut child_depth = depth;
+    if is_scope_node(&node) {
+        if let Some(scope) = get_scope_of_node(&node, syntax_tree, text_range, depth) {
+            scopes.push(scope);
+            child_depth += 1;
+        }
+    }
+
+    for child in node.named_children(&mut node.walk()) {
+        collect_scopes(
+            child,
+            syntax_tree,
+            text_range,
+            child_depth,
+            max_depth,
+            scopes,
+        );
+    }
+}
+
+fn is_scope_node(node: &Node) -> bool {
+    // The function_body is the scope of a function; treating the declaration as a scope as well
+    // would draw two guides for the same block.
+    if node.kind() == "function_declaration" {
+        return false;
+    }
+
+    get_code_block_parent(*node, false).map(|parent| parent.id()) == Some(node.id())
+}
+
+fn get_scope_of_node(
+    node: &Node,
+    syntax_tree: &SwiftSyntaxTree,
+    text_range: &TextRange,
+    depth: usize,
+) -> Option<Scope> {
+    if node.start_position().row == node.end_position().row {
+        return None;
+    }
+
+    let text_content = syntax_tree.text_content();
+    let (opening_char_index, closing_char_index) =
+        get_indexes_of_first_and_last_char_in_node(node, text_content, text_range.index).ok()?;
+
+    Some(Scope {
+        depth,
+        opening_char_index,
+        closing_char_index,
+        line_start_index: get_index_of_first_non_whitespace_char_on_row(
+            opening_char_index,
+            text_content,
+        )?,
+        complexity: syntax_tree
+            .get_metadata_of_node(node)
+            .map(|metadata| metadata.complexities.get_total_complexity())
+            .unwrap_or(0),
+    })
+}
+
+fn get_index_of_first_non_whitespace_char_on_row(
+    index: usize,
+    text_content: &XcodeText,
+) -> Option<usize> {
+    let position = TextPosition::from_TextIndex(text_content, index)?;
+    let row = text_content.row(position.row)?;
+    let column = row
+        .iter()
+        .position(|c| !XcodeText::char_is_whitespace(c))
+        .unwrap_or(position.column)
+        .min(position.column);
+
+    Some(index - position.column + column)
+}
+
+fn node_intersects_text_range(node: &Node, text_range: &TextRange) -> bool {
+    // Treesitter parses UTF-16, so every character takes up two bytes.
+    let node_start_index = node.start_byte() / 2;
+    let node_end_index = node.end_byte() / 2;
+
+    node_start_index <= text_range.index + text_range.length && node_end_index >= text_range.index
+}
+
+#[cfg(test)]
+mod tests {
+    mod get_scopes_in_text_range {
+        use crate::core_engine::{
+            features::scope_guides::utils::{get_scopes_in_text_range, Scope},
+            syntax_tree::SwiftSyntaxTree,
+            TextRange, XcodeText,
+        };
+
+        fn test_fn(text: &str, text_range: TextRange, max_depth: usize) -> Vec<Scope> {
+            let syntax_tree = tauri::async_runtime::block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(text),
+                None,
+            ))
+            .unwrap();
+
+            get_scopes_in_text_range(&syntax_tree, &text_range, max_depth)
+        }
+
+        #[test]
+        fn nested_scopes() {
+            let text = "func a() {\n    if b {\n        c()\n    }\n}";
+            let scopes = test_fn(text, TextRange::new(0, text.len()), 10);
+
+            assert_eq!(scopes.len(), 2);
+            assert_eq!(scopes[0].depth, 0);
+            assert_eq!(scopes[0].opening_char_index, 9);
+            assert_eq!(scopes[0].closing_char_index, 40);
+            assert_eq!(scopes[0].line_start_index, 0);
+            assert_eq!(scopes[1].depth, 1);
+            assert_eq!(scopes[1].opening_char_index, 20);
+            assert_eq!(scopes[1].closing_char_index, 38);
+            assert_eq!(scopes[1].line_start_index, 15);
+            assert_eq!(scopes[1].complexity, 1);
+        }
+
+        #[test]
+        fn single_line_scopes_are_ignored() {
+            let text = "func a() {\n    if b { c() }\n}";
+            let scopes = test_fn(text, TextRange::new(0, text.len()), 10);
+
+            assert_eq!(scopes.len(), 1);
+            assert_eq!(scopes[0].depth, 0);
+        }
+
+        #[test]
+        fn respects_max_depth() {
+            let text = "func a() {\n    if b {\n        c()\n    }\n}";
+            let scopes = test_fn(text, TextRange::new(0, text.len()), 1);
+
+            assert_eq!(scopes.len(), 1);
+            assert_eq!(scopes[0].opening_char_index, 9);
+        }
+
+        #[test]
+        fn includes_enclosing_scopes_outside_of_range() {
+            let text = "func a() {\n    if b {\n        c()\n    }\n}\n\nfunc d() {\n    e()\n}";
+            // Only the line containing `c()`
+            let scopes = test_fn(text, TextRange::new(22, 11), 10);
+
+            assert_eq!(scopes.len(), 2);
+            assert_eq!(scopes[0].opening_char_index, 9);
+            assert_eq!(scopes[1].opening_char_index, 20);
+        }
+    }
+}
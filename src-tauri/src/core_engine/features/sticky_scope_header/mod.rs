@@ -0,0 +1,4 @@
+pub use sticky_scope_header::{StickyScope, StickyScopeHeader, StickyScopeHeaderError};
+
+mod sticky_scope_header;
+mod utils;
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    app_handle,
+    core_engine::{
+        events::{models::UpdateStickyScopeHeaderMessage, StickyScopeHeaderEvent},
+        features::{
+            feature_base::{CoreEngineTrigger, FeatureBase, FeatureError},
+            FeatureKind,
+        },
+        scheduler::CancellationToken,
+        syntax_tree::SwiftCodeBlockKind,
+        CodeDocument, EditorWindowUid, TextPosition,
+    },
+    platform::macos::{get_visible_text_range, GetVia},
+    CORE_ENGINE_ACTIVE_AT_STARTUP,
+};
+
+use super::utils::get_sticky_scopes_for_top_row;
+
+#[derive(thiserror::Error, Debug)]
+pub enum StickyScopeHeaderError {
+    #[error("Insufficient context for sticky scope header")]
+    InsufficientContext,
+    #[error("Something went wrong when executing the StickyScopeHeader feature.")]
+    GenericError(#[source] anyhow::Error),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/sticky_scope_header/")]
+pub struct StickyScope {
+    pub kind: SwiftCodeBlockKind,
+    pub header_text: String,
+    pub first_char_position: TextPosition,
+    pub last_char_position: TextPosition,
+}
+
+pub struct StickyScopeHeader {
+    is_activated: bool,
+
+    published_scopes: Option<(EditorWindowUid, Vec<StickyScope>)>,
+}
+
+impl FeatureBase for StickyScopeHeader {
+    fn compute(
+        &mut self,
+        code_document: CodeDocument,
+        _trigger: CoreEngineTrigger,
+        _cancellation_token: CancellationToken,
+    ) -> Result<(), FeatureError> {
+        if !self.is_activated {
+            return Ok(());
+        }
+
+        if let Err(err) = self.procedure_compute_sticky_scopes(&code_document) {
+            self.clear_sticky_scopes();
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    fn activate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = true;
+
+        Ok(())
+    }
+
+    fn deactivate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = false;
+        self.clear_sticky_scopes();
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), FeatureError> {
+        self.clear_sticky_scopes();
+
+        Ok(())
+    }
+
+    fn kind(&self) -> FeatureKind {
+        FeatureKind::StickyScopeHeader
+    }
+
+    fn should_compute(_kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool {
+        Self::determine_procedure(trigger)
+    }
+
+    fn requires_ai(_kind: &FeatureKind, _trigger: &CoreEngineTrigger) -> bool {
+        false
+    }
+}
+
+impl StickyScopeHeader {
+    pub fn new() -> Self {
+        Self {
+            is_activated: CORE_ENGINE_ACTIVE_AT_STARTUP,
+            published_scopes: None,
+        }
+    }
+
+    fn determine_procedure(trigger: &CoreEngineTrigger) -> bool {
+        match trigger {
+            CoreEngineTrigger::OnVisibleTextRangeChange => true,
+            CoreEngineTrigger::OnTextSelectionChange => true, // The TextSelectionChange is also triggered on text content change
+            _ => false,
+        }
+    }
+
+    fn procedure_compute_sticky_scopes(
+        &mut self,
+        code_document: &CodeDocument,
+    ) -> Result<(), StickyScopeHeaderError> {
+        let window_uid = code_document.editor_window_props().window_uid;
+
+        let syntax_tree = code_document
+            .syntax_tree()
+            .ok_or(StickyScopeHeaderError::InsufficientContext)?;
+
+        let visible_text_range = get_visible_text_range(GetVia::Hash(window_uid))
+            .map_err(|err| StickyScopeHeaderError::GenericError(err.into()))?;
+
+        let scopes = get_sticky_scopes_for_top_row(syntax_tree, visible_text_range.index);
+
+        self.publish_sticky_scopes(window_uid, scopes);
+
+        Ok(())
+    }
+
+    fn publish_sticky_scopes(&mut self, window_uid: EditorWindowUid, scopes: Vec<StickyScope>) {
+        if let Some((previous_window_uid, previous_scopes)) = &self.published_scopes {
+            if *previous_window_uid == window_uid && *previous_scopes == scopes {
+                return;
+            }
+        }
+
+        StickyScopeHeaderEvent::UpdateStickyScopeHeader(UpdateStickyScopeHeaderMessage {
+            window_uid,
+            scopes: scopes.clone(),
+        })
+        .publish_to_tauri(&app_handle());
+
+        self.published_scopes = Some((window_uid, scopes));
+    }
+
+    fn clear_sticky_scopes(&mut self) {
+        if let Some((window_uid, _)) = self.published_scopes {
+            self.publish_sticky_scopes(window_uid, vec![]);
+        }
+        self.published_scopes = None;
+    }
+}
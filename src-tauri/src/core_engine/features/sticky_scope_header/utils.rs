@@ -0,0 +1,141 @@
+use crate::core_engine::{
+    syntax_tree::{SwiftCodeBlock, SwiftCodeBlockBase, SwiftSyntaxTree},
+    TextPosition, TextRange, XcodeText,
+};
+
+use super::StickyScope;
+
+// Computes the chain of code blocks which enclose the row of the given index, but whose header
+// was already scrolled out of view. The chain is ordered from the outermost to the innermost block.
+pub fn get_sticky_scopes_for_top_row(
+    syntax_tree: &SwiftSyntaxTree,
+    top_index: usize,
+) -> Vec<StickyScope> {
+    let text_content = syntax_tree.text_content();
+
+    let top_position = match TextPosition::from_TextIndex(text_content, top_index) {
+        Some(position) => position,
+        None => return vec![],
+    };
+    let top_row_range = TextRange {
+        index: top_index - top_position.column,
+        length: 0,
+    };
+
+    let code_block =
+        match SwiftCodeBlock::from_text_range(syntax_tree, &top_row_range, text_content) {
+            Ok(code_block) => code_block,
+            Err(_) => return vec![],
+        };
+
+    let mut scopes = vec![];
+    collect_enclosing_scopes(&code_block, text_content, top_position.row, &mut scopes);
+    scopes.reverse();
+
+    scopes
+}
+
+fn collect_enclosing_scopes(
+    code_block: &SwiftCodeBlock,
+    text_content: &XcodeText,
+    top_row: usize,
+    scopes: &mut Vec<StickyScope>,
+) {
+    let first_char_position = code_block.get_first_char_position();
+    let last_char_position = code_block.get_last_char_position();
+
+    if first_char_position.row < top_row && last_char_position.row >= top_row {
+        if let Some(header_text) = get_header_text(&first_char_position, text_content) {
+            scopes.push(StickyScope {
+                kind: code_block.get_kind(),
+                header_text,
+                first_char_position,
+                last_char_position,
+            });
+        }
+    }
+
+    if let Ok(parent_code_block) = code_block.get_parent_code_block() {
+        collect_enclosing_scopes(&parent_code_block, text_content, top_row, scopes);
+    }
+}
+
+// The header is the first row of the code block, without the opening bracket.
+fn get_header_text(first_char_position: &TextPosition, text_content: &XcodeText) -> Option<String> {
+    let row = text_content.row(first_char_position.row)?;
+    if first_char_position.column > row.len() {
+        return None;
+    }
+    let header = row.slice(first_char_position.column..).as_string();
+
+    let header = header.trim_end();
+    let header = header.strip_suffix('{').unwrap_or(header).trim_end();
+
+    Some(header.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    mod get_sticky_scopes_for_top_row {
+        use crate::core_engine::{
+            features::sticky_scope_header::utils::get_sticky_scopes_for_top_row,
+            syntax_tree::{SwiftCodeBlockKind, SwiftSyntaxTree},
+            TextPosition, XcodeText,
+        };
+
+        fn test_fn(text: &str, top_row: usize) -> Vec<(SwiftCodeBlockKind, String)> {
+            let text_content = XcodeText::from_str(text);
+            let top_index = TextPosition {
+                row: top_row,
+                column: 0,
+            }
+            .as_TextIndex(&text_content)
+            .unwrap();
+
+            let syntax_tree =
+                tauri::async_runtime::block_on(SwiftSyntaxTree::from_XcodeText(text_content, None))
+                    .unwrap();
+
+            get_sticky_scopes_for_top_row(&syntax_tree, top_index)
+                .into_iter()
+                .map(|scope| (scope.kind, scope.header_text))
+                .collect()
+        }
+
+        const TEXT: &str = "class A {
+    func b(c: Int) {
+        if c > 0 {
+            print(c)
+        }
+    }
+}";
+
+        #[test]
+        fn nested_scopes() {
+            assert_eq!(
+                test_fn(TEXT, 3),
+                vec![
+                    (SwiftCodeBlockKind::Class, "class A".to_string()),
+                    (SwiftCodeBlockKind::Function, "func b(c: Int)".to_string()),
+                    (SwiftCodeBlockKind::If, "if c > 0".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn header_of_scope_on_top_row_is_not_sticky() {
+            assert_eq!(
+                test_fn(TEXT, 2),
+                vec![
+                    (SwiftCodeBlockKind::Class, "class A".to_string()),
+                    (SwiftCodeBlockKind::Function, "func b(c: Int)".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn top_of_document() {
+            assert_eq!(test_fn(TEXT, 0), vec![]);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub use navigation_commands::{resolve_navigation_command, NavigationCommand};
+pub use structural_navigation::{StructuralNavigation, StructuralNavigationError};
+
+mod navigation_commands;
+mod structural_navigation;
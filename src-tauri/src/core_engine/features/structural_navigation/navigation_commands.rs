@@ -0,0 +1,378 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+use tree_sitter::Node;
+use ts_rs::TS;
+
+use crate::core_engine::{
+    features::bracket_highlight::{
+        get_code_block_parent, get_indexes_of_first_and_last_char_in_node,
+    },
+    syntax_tree::SwiftSyntaxTree,
+    TextRange,
+};
+
+use super::StructuralNavigationError;
+
+const DECLARATION_KINDS: [&str; 6] = [
+    "class_declaration", // Also covers structs, enums, extensions and actors
+    "protocol_declaration",
+    "function_declaration",
+    "init_declaration",
+    "deinit_declaration",
+    "subscript_declaration",
+];
+
+const FUNCTION_KINDS: [&str; 3] = [
+    "function_declaration",
+    "init_declaration",
+    "deinit_declaration",
+];
+
+const BRACKET_PAIRS: [(&str, &str); 3] = [("(", ")"), ("{", "}"), ("[", "]")];
+
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, TS, EnumIter, Display,
+)]
+#[ts(export, export_to = "bindings/features/structural_navigation/")]
+pub enum NavigationCommand {
+    MatchingBracket,
+    EnclosingBlockStart,
+    EnclosingBlockEnd,
+    NextDeclaration,
+    PreviousDeclaration,
+    NextComplexityHotspot,
+    PreviousComplexityHotspot,
+}
+
+// Resolves the command into the text range the cursor should be moved to. Only depends on the
+// syntax tree, so it can be evaluated without an editor. Functions above the max function
+// complexity, the threshold of the ComplexityRefactoring feature, are considered hotspots.
+pub fn resolve_navigation_command(
+    command: &NavigationCommand,
+    syntax_tree: &SwiftSyntaxTree,
+    selected_text_range: &TextRange,
+    max_function_complexity: isize,
+) -> Result<TextRange, StructuralNavigationError> {
+    let cursor_index = selected_text_range.index;
+
+    let target_index = match command {
+        NavigationCommand::MatchingBracket => get_matching_bracket_index(syntax_tree, cursor_index),
+        NavigationCommand::EnclosingBlockStart => {
+            get_enclosing_block_index(syntax_tree, selected_text_range, false)
+        }
+        NavigationCommand::EnclosingBlockEnd => {
+            get_enclosing_block_index(syntax_tree, selected_text_range, true)
+        }
+        NavigationCommand::NextDeclaration => {
+            get_adjacent_node_index(syntax_tree, cursor_index, true, &is_declaration)
+        }
+        NavigationCommand::PreviousDeclaration => {
+            get_adjacent_node_index(syntax_tree, cursor_index, false, &is_declaration)
+        }
+        NavigationCommand::NextComplexityHotspot => {
+            get_adjacent_node_index(syntax_tree, cursor_index, true, &|node| {
+                is_complexity_hotspot(node, syntax_tree, max_function_complexity)
+            })
+        }
+        NavigationCommand::PreviousComplexityHotspot => {
+            get_adjacent_node_index(syntax_tree, cursor_index, false, &|node| {
+                is_complexity_hotspot(node, syntax_tree, max_function_complexity)
+            })
+        }
+    }
+    .ok_or(StructuralNavigationError::NoTargetFound(*command))?;
+
+    Ok(TextRange {
+        index: target_index,
+        length: 0,
+    })
+}
+
+fn get_matching_bracket_index(syntax_tree: &SwiftSyntaxTree, cursor_index: usize) -> Option<usize> {
+    // The cursor can either be in front of or right behind the bracket.
+    let candidate_indexes = [Some(cursor_index), cursor_index.checked_sub(1)];
+
+    for index in candidate_indexes.into_iter().flatten() {
+        if let Some(bracket_node) = syntax_tree
+            .tree()
+            .root_node()
+            .descendant_for_byte_range(index * 2, index * 2 + 2)
+        {
+            if let Some(matching_bracket_node) = get_matching_bracket_node(&bracket_node) {
+                return Some(get_node_start_index(&matching_bracket_node));
+            }
+        }
+    }
+
+    None
+}
+
+fn get_matching_bracket_node<'a>(bracket_node: &Node<'a>) -> Option<Node<'a>> {
+    for (opening, closing) in BRACKET_PAIRS {
+        if bracket_node.kind() == opening {
+            let mut sibling = bracket_node.next_sibling();
+            while let Some(node) = sibling {
+                if node.kind() == closing {
+                    return Some(node);
+                }
+                sibling = node.next_sibling();
+            }
+        } else if bracket_node.kind() == closing {
+            let mut sibling = bracket_node.prev_sibling();
+            while let Some(node) = sibling {
+                if node.kind() == opening {
+                    return Some(node);
+                }
+                sibling = node.prev_sibling();
+            }
+        }
+    }
+
+    None
+}
+
+fn get_enclosing_block_index(
+    syntax_tree: &SwiftSyntaxTree,
+    selected_text_range: &TextRange,
+    to_end: bool,
+) -> Option<usize> {
+    let text_content = syntax_tree.text_content();
+    let node = syntax_tree
+        .get_code_node_by_text_range(selected_text_range)
+        .ok()?;
+
+    let mut code_block_node = get_code_block_parent(node, false)?;
+    loop {
+        let (first_index, last_index) = get_indexes_of_first_and_last_char_in_node(
+            &code_block_node,
+            text_content,
+            selected_text_range.index,
+        )
+        .ok()?;
+
+        let target_index = if to_end { last_index } else { first_index };
+
+        // Repeatedly invoking the command walks outwards through the enclosing blocks.
+        if target_index != selected_text_range.index {
+            return Some(target_index);
+        }
+
+        code_block_node = get_code_block_parent(code_block_node.parent()?, false)?;
+    }
+}
+
+fn get_adjacent_node_index(
+    syntax_tree: &SwiftSyntaxTree,
+    cursor_index: usize,
+    forward: bool,
+    predicate: &dyn Fn(&Node) -> bool,
+) -> Option<usize> {
+    let mut nodes = vec![];
+    collect_nodes(syntax_tree.tree().root_node(), predicate, &mut nodes);
+
+    let start_indexes = nodes.iter().map(get_node_start_index);
+    if forward {
+        start_indexes.filter(|index| *index > cursor_index).min()
+    } else {
+        start_indexes.filter(|index| *index < cursor_index).max()
+    }
+}
+
+fn collect_nodes<'a>(node: Node<'a>, predicate: &dyn Fn(&Node) -> bool, nodes: &mut Vec<Node<'a>>) {
+    if predicate(&node) {
+        nodes.push(node);
+    }
+
+    for child in node.named_children(&mut node.walk()) {
+        collect_nodes(child, predicate, nodes);
+    }
+}
+
+fn is_declaration(node: &Node) -> bool {
+    DECLARATION_KINDS.contains(&node.kind())
+}
+
+fn is_complexity_hotspot(
+    node: &Node,
+    syntax_tree: &SwiftSyntaxTree,
+    max_function_complexity: isize,
+) -> bool {
+    if !FUNCTION_KINDS.contains(&node.kind()) {
+        return false;
+    }
+
+    syntax_tree
+        .get_metadata_of_node(node)
+        .map(|metadata| metadata.complexities.get_total_complexity() > max_function_complexity)
+        .unwrap_or(false)
+}
+
+fn get_node_start_index(node: &Node) -> usize {
+    // Treesitter parses UTF-16, so every character takes up two bytes.
+    node.start_byte() / 2
+}
+
+#[cfg(test)]
+mod tests {
+    mod resolve_navigation_command {
+        use crate::core_engine::{
+            features::structural_navigation::{
+                resolve_navigation_command, NavigationCommand, StructuralNavigationError,
+            },
+            syntax_tree::SwiftSyntaxTree,
+            TextRange, XcodeText,
+        };
+
+        fn test_fn(
+            text: &str,
+            command: NavigationCommand,
+            cursor_index: usize,
+        ) -> Result<TextRange, StructuralNavigationError> {
+            test_fn_with_threshold(text, command, cursor_index, 9)
+        }
+
+        fn test_fn_with_threshold(
+            text: &str,
+            command: NavigationCommand,
+            cursor_index: usize,
+            max_function_complexity: isize,
+        ) -> Result<TextRange, StructuralNavigationError> {
+            let syntax_tree = tauri::async_runtime::block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(text),
+                None,
+            ))
+            .unwrap();
+
+            resolve_navigation_command(
+                &command,
+                &syntax_tree,
+                &TextRange::new(cursor_index, 0),
+                max_function_complexity,
+            )
+        }
+
+        fn target_index(text: &str, command: NavigationCommand, cursor_index: usize) -> usize {
+            test_fn(text, command, cursor_index).unwrap().index
+        }
+
+        const TEXT: &str = "func a() {
+    if b {
+        c(d)
+    }
+}
+
+class E {
+    func f() {}
+}";
+
+        #[test]
+        fn matching_bracket_forward() {
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::MatchingBracket, 9),
+                41
+            );
+        }
+
+        #[test]
+        fn matching_bracket_backward() {
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::MatchingBracket, 41),
+                9
+            );
+        }
+
+        #[test]
+        fn matching_bracket_cursor_behind_bracket() {
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::MatchingBracket, 34),
+                31
+            );
+        }
+
+        #[test]
+        fn matching_bracket_no_bracket() {
+            assert!(test_fn(TEXT, NavigationCommand::MatchingBracket, 2).is_err());
+        }
+
+        #[test]
+        fn enclosing_block_start() {
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::EnclosingBlockStart, 30),
+                20
+            );
+        }
+
+        #[test]
+        fn enclosing_block_start_walks_outwards() {
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::EnclosingBlockStart, 20),
+                9
+            );
+        }
+
+        #[test]
+        fn enclosing_block_end() {
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::EnclosingBlockEnd, 30),
+                39
+            );
+        }
+
+        #[test]
+        fn next_declaration() {
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::NextDeclaration, 30),
+                44
+            );
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::NextDeclaration, 44),
+                58
+            );
+            assert!(test_fn(TEXT, NavigationCommand::NextDeclaration, 58).is_err());
+        }
+
+        #[test]
+        fn previous_declaration() {
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::PreviousDeclaration, 58),
+                44
+            );
+            assert_eq!(
+                target_index(TEXT, NavigationCommand::PreviousDeclaration, 44),
+                0
+            );
+        }
+
+        #[test]
+        fn complexity_hotspots() {
+            let text = "func simple() {
+    a()
+}
+
+func complex() {
+    if a { if b { if c { if d { e() } } } }
+}";
+            assert_eq!(
+                target_index(text, NavigationCommand::NextComplexityHotspot, 0),
+                27
+            );
+            assert!(test_fn(text, NavigationCommand::PreviousComplexityHotspot, 27).is_err());
+        }
+
+        #[test]
+        fn complexity_hotspots_follow_the_threshold() {
+            let text = "let a = 1
+
+func simple() {
+    if a { b() }
+}";
+            assert!(test_fn(text, NavigationCommand::NextComplexityHotspot, 0).is_err());
+            assert_eq!(
+                test_fn_with_threshold(text, NavigationCommand::NextComplexityHotspot, 0, 0)
+                    .unwrap()
+                    .index,
+                11
+            );
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use tracing::info;
+
+use crate::{
+    app_state::settings_for_file,
+    core_engine::{
+        features::{
+            feature_base::{CoreEngineTrigger, FeatureBase, FeatureError, UserCommand},
+            FeatureKind,
+        },
+        keymap::KeymapAction,
+        scheduler::CancellationToken,
+        CodeDocument,
+    },
+    platform::macos::{set_selected_text_range, GetVia},
+    CORE_ENGINE_ACTIVE_AT_STARTUP,
+};
+
+use super::{resolve_navigation_command, NavigationCommand};
+
+#[derive(thiserror::Error, Debug)]
+pub enum StructuralNavigationError {
+    #[error("Insufficient context for structural navigation")]
+    InsufficientContext,
+    #[error("No navigation target found for command {0:?}")]
+    NoTargetFound(NavigationCommand),
+    #[error("Something went wrong when executing the StructuralNavigation feature.")]
+    GenericError(#[source] anyhow::Error),
+}
+
+pub struct StructuralNavigation {
+    is_activated: bool,
+}
+
+impl FeatureBase for StructuralNavigation {
+    fn compute(
+        &mut self,
+        code_document: CodeDocument,
+        trigger: CoreEngineTrigger,
+        _cancellation_token: CancellationToken,
+    ) -> Result<(), FeatureError> {
+        if !self.is_activated {
+            return Ok(());
+        }
+
+        if let Some(command) = Self::determine_procedure(&trigger) {
+            info!(
+                ?command,
+                feature = FeatureKind::StructuralNavigation.to_string(),
+                "User request: Navigate"
+            );
+            self.navigate(&code_document, &command)?;
+        }
+
+        Ok(())
+    }
+
+    fn activate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = true;
+
+        Ok(())
+    }
+
+    fn deactivate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = false;
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), FeatureError> {
+        // Do nothing
+        Ok(())
+    }
+
+    fn kind(&self) -> FeatureKind {
+        FeatureKind::StructuralNavigation
+    }
+
+    fn should_compute(_kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool {
+        Self::determine_procedure(trigger).is_some()
+    }
+
+    fn requires_ai(_kind: &FeatureKind, _trigger: &CoreEngineTrigger) -> bool {
+        false
+    }
+}
+
+impl StructuralNavigation {
+    pub fn new() -> Self {
+        Self {
+            is_activated: CORE_ENGINE_ACTIVE_AT_STARTUP,
+        }
+    }
+
+    fn determine_procedure(trigger: &CoreEngineTrigger) -> Option<NavigationCommand> {
+        match trigger {
+            CoreEngineTrigger::OnShortcutPressed(KeymapAction::Navigate(command)) => Some(*command),
+            CoreEngineTrigger::OnUserCommand(UserCommand::Navigate(msg)) => Some(msg.command),
+            _ => None,
+        }
+    }
+
+    fn navigate(
+        &self,
+        code_document: &CodeDocument,
+        command: &NavigationCommand,
+    ) -> Result<(), StructuralNavigationError> {
+        let syntax_tree = code_document
+            .syntax_tree()
+            .ok_or(StructuralNavigationError::InsufficientContext)?;
+
+        let selected_text_range = code_document
+            .selected_text_range()
+            .ok_or(StructuralNavigationError::InsufficientContext)?;
+
+        let max_function_complexity = settings_for_file(code_document.file_path())
+            .thresholds
+            .max_function_complexity;
+
+        let target_text_range = resolve_navigation_command(
+            command,
+            syntax_tree,
+            &selected_text_range,
+            max_function_complexity,
+        )?;
+
+        set_selected_text_range(
+            &target_text_range,
+            &GetVia::Hash(code_document.editor_window_props().window_uid),
+        )
+        .map_err(|err| StructuralNavigationError::GenericError(err.into()))
+    }
+}
@@ -0,0 +1,61 @@
+use uuid::Uuid;
+
+use crate::core_engine::{
+    annotations_manager::{
+        AnnotationJob, AnnotationJobInstructions, AnnotationJobSingleChar, AnnotationJobTrait,
+        AnnotationKind, InstructionBounds, InstructionBoundsPropertyOfInterest,
+        InstructionWrappedLines,
+    },
+    events::AnnotationManagerEvent,
+    features::FeatureKind,
+    EditorWindowUid, TextRange,
+};
+
+use super::SearchMatch;
+
+pub fn remove_annotation_group(group_id: Uuid) {
+    AnnotationManagerEvent::Remove(group_id).publish_to_tauri();
+}
+
+// One group per editor window, marking the first and last character of every match.
+pub fn upsert_annotation_group_for_search_matches(
+    group_id: Uuid,
+    search_matches: &[SearchMatch],
+    window_uid: EditorWindowUid,
+) {
+    let mut jobs = vec![];
+    for search_match in search_matches {
+        let text_range = &search_match.text_range;
+
+        jobs.push(AnnotationJob::SingleChar(AnnotationJobSingleChar::new(
+            Uuid::new_v4(),
+            &TextRange {
+                index: text_range.index,
+                length: 1,
+            },
+            AnnotationKind::SearchMatchStartChar,
+            AnnotationJobInstructions {
+                bounds: InstructionBounds::SingleRect,
+                bounds_property_of_interest: InstructionBoundsPropertyOfInterest::PosTopLeft,
+                wrapped_lines: InstructionWrappedLines::None,
+            },
+        )));
+
+        jobs.push(AnnotationJob::SingleChar(AnnotationJobSingleChar::new(
+            Uuid::new_v4(),
+            &TextRange {
+                index: (text_range.index + text_range.length).saturating_sub(1),
+                length: 1,
+            },
+            AnnotationKind::SearchMatchEndChar,
+            AnnotationJobInstructions {
+                bounds: InstructionBounds::SingleRect,
+                bounds_property_of_interest: InstructionBoundsPropertyOfInterest::PosBotRight,
+                wrapped_lines: InstructionWrappedLines::None,
+            },
+        )));
+    }
+
+    AnnotationManagerEvent::Upsert((group_id, FeatureKind::StructuralSearch, jobs, window_uid))
+        .publish_to_tauri();
+}
@@ -0,0 +1,13 @@
+pub use replace::{
+    apply_edits, compute_replace_preview, compute_replacement_edits, ReplacePreview,
+    RewriteTemplate,
+};
+pub use search_query::{rank_search_matches, CompiledSearchQuery, SearchMatch};
+pub use structural_pattern::{MetavariableCapture, StructuralMatch, StructuralPattern};
+pub use structural_search::{StructuralSearch, StructuralSearchError};
+
+mod annotations;
+mod replace;
+mod search_query;
+mod structural_pattern;
+mod structural_search;
@@ -0,0 +1,312 @@
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use ts_rs::TS;
+
+use crate::core_engine::{features::complexity_refactoring::Edit, XcodeText};
+
+use super::{MetavariableCapture, SearchMatch, StructuralSearchError};
+
+const DIFF_CONTEXT_RADIUS: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/structural_search/")]
+pub struct ReplacePreview {
+    pub replacement_count: usize,
+    pub old_text_content_string: String,
+    pub new_text_content_string: String,
+    pub unified_diff: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TemplateSegment {
+    Literal(String),
+    Metavariable(String),
+}
+
+// Replacement text in which `$name`, `$$$` and `$$$name` are substituted with what the
+// corresponding metavariable captured in the search pattern.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RewriteTemplate {
+    segments: Vec<TemplateSegment>,
+}
+
+impl RewriteTemplate {
+    pub fn parse(template: &str) -> Self {
+        let mut segments = vec![];
+        let mut literal = String::new();
+
+        let chars: Vec<char> = template.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i] != '$' {
+                literal.push(chars[i]);
+                i += 1;
+                continue;
+            }
+
+            let is_ellipsis = chars[i..].starts_with(&['$', '$', '$']);
+            let name_start = if is_ellipsis { i + 3 } else { i + 1 };
+            let mut name_end = name_start;
+            while name_end < chars.len()
+                && (chars[name_end].is_ascii_alphanumeric() || chars[name_end] == '_')
+            {
+                name_end += 1;
+            }
+            let name: String = chars[name_start..name_end].iter().collect();
+            let is_valid_name = name
+                .chars()
+                .next()
+                .map_or(false, |c| c.is_ascii_alphabetic() || c == '_');
+
+            let (metavariable, next_index) = match (is_ellipsis, is_valid_name) {
+                (true, true) => (name, name_end),
+                (true, false) => ("$$$".to_string(), name_start),
+                (false, true) => (name, name_end),
+                (false, false) => {
+                    // E.g. closure shorthand arguments like `$0`.
+                    literal.push(chars[i]);
+                    i += 1;
+                    continue;
+                }
+            };
+
+            if !literal.is_empty() {
+                segments.push(TemplateSegment::Literal(literal.clone()));
+                literal.clear();
+            }
+            segments.push(TemplateSegment::Metavariable(metavariable));
+            i = next_index;
+        }
+
+        if !literal.is_empty() {
+            segments.push(TemplateSegment::Literal(literal));
+        }
+
+        Self { segments }
+    }
+
+    pub fn render(
+        &self,
+        captures: &[MetavariableCapture],
+    ) -> Result<String, StructuralSearchError> {
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                TemplateSegment::Literal(literal) => rendered.push_str(literal),
+                TemplateSegment::Metavariable(name) => {
+                    let capture = captures
+                        .iter()
+                        .find(|capture| capture.name == *name)
+                        .ok_or_else(|| {
+                            let metavariable = match name.as_str() {
+                                "$$$" => name.clone(),
+                                _ => format!("${}", name),
+                            };
+                            StructuralSearchError::InvalidTemplate(format!(
+                                "`{}` is not captured by the search pattern",
+                                metavariable
+                            ))
+                        })?;
+                    rendered.push_str(&capture.text);
+                }
+            }
+        }
+
+        Ok(rendered)
+    }
+}
+
+// One edit per match in document order. Matches overlapping an earlier one, e.g. a nested
+// `if let` inside a matched `if let`, are skipped; the outer replacement already covers them.
+pub fn compute_replacement_edits(
+    search_matches: &[SearchMatch],
+    template: &RewriteTemplate,
+) -> Result<Vec<Edit>, StructuralSearchError> {
+    let mut ordered_matches: Vec<&SearchMatch> = search_matches.iter().collect();
+    ordered_matches.sort_by(|a, b| {
+        a.text_range
+            .index
+            .cmp(&b.text_range.index)
+            .then(b.text_range.length.cmp(&a.text_range.length))
+    });
+
+    let mut edits: Vec<Edit> = vec![];
+    for search_match in ordered_matches {
+        let start_index = search_match.text_range.index;
+        let end_index = start_index + search_match.text_range.length;
+        if let Some(previous) = edits.last() {
+            if start_index < previous.end_index {
+                continue;
+            }
+        }
+
+        edits.push(Edit {
+            text: XcodeText::from_str(&template.render(&search_match.captures)?),
+            start_index,
+            end_index,
+        });
+    }
+
+    Ok(edits)
+}
+
+// Either all edits are applied or none; the given text is left untouched on error.
+pub fn apply_edits(text: &XcodeText, edits: &[Edit]) -> Result<XcodeText, StructuralSearchError> {
+    let mut ordered_edits: Vec<&Edit> = edits.iter().collect();
+    ordered_edits.sort_by_key(|edit| edit.start_index);
+
+    let mut previous_end_index = 0;
+    for edit in &ordered_edits {
+        if edit.start_index < previous_end_index
+            || edit.start_index > edit.end_index
+            || edit.end_index > text.len()
+        {
+            return Err(StructuralSearchError::InvalidEdits);
+        }
+        previous_end_index = edit.end_index;
+    }
+
+    let mut edited_text = text.clone();
+    for edit in ordered_edits.into_iter().rev() {
+        edited_text.replace_range(edit.start_index..edit.end_index, edit.text.clone());
+    }
+
+    Ok(edited_text)
+}
+
+pub fn compute_replace_preview(
+    text: &XcodeText,
+    edits: &[Edit],
+) -> Result<ReplacePreview, StructuralSearchError> {
+    let old_text_content_string = text.as_string();
+    let new_text_content_string = apply_edits(text, edits)?.as_string();
+
+    let unified_diff = TextDiff::from_lines(&old_text_content_string, &new_text_content_string)
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT_RADIUS)
+        .to_string();
+
+    Ok(ReplacePreview {
+        replacement_count: edits.len(),
+        old_text_content_string,
+        new_text_content_string,
+        unified_diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    mod compute_replacement_edits {
+        use tauri::async_runtime::block_on;
+
+        use crate::core_engine::{
+            events::models::{SearchQueryKind, SearchQueryMessage},
+            features::structural_search::{
+                apply_edits, compute_replacement_edits, CompiledSearchQuery, RewriteTemplate,
+            },
+            syntax_tree::SwiftSyntaxTree,
+            XcodeText,
+        };
+
+        fn test_fn(pattern: &str, template: &str, text: &str) -> String {
+            let syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(text),
+                None,
+            ))
+            .unwrap();
+            let search_matches = CompiledSearchQuery::compile(&SearchQueryMessage {
+                query: pattern.to_string(),
+                kind: SearchQueryKind::Structural,
+                case_sensitive: true,
+            })
+            .unwrap()
+            .find_matches(&syntax_tree, 0, &None);
+
+            let edits =
+                compute_replacement_edits(&search_matches, &RewriteTemplate::parse(template))
+                    .unwrap();
+
+            apply_edits(syntax_tree.text_content(), &edits)
+                .unwrap()
+                .as_string()
+        }
+
+        #[test]
+        fn substitutes_metavariables() {
+            assert_eq!(
+                test_fn(
+                    "print($x)",
+                    "debugPrint($x, $0)",
+                    "print(a)\nlet b = 1\nprint(b + 1)\n"
+                ),
+                "debugPrint(a, $0)\nlet b = 1\ndebugPrint(b + 1, $0)\n"
+            );
+        }
+
+        #[test]
+        fn substitutes_ellipsis() {
+            assert_eq!(
+                test_fn(
+                    "if let $x = $y { $$$body }",
+                    "guard let $x = $y else { return }\n$$$body",
+                    "if let a = b { print(a) }\n"
+                ),
+                "guard let a = b else { return }\nprint(a)\n"
+            );
+        }
+
+        #[test]
+        fn unknown_metavariable_in_template() {
+            let syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str("print(a)"),
+                None,
+            ))
+            .unwrap();
+            let search_matches = CompiledSearchQuery::compile(&SearchQueryMessage {
+                query: "print($x)".to_string(),
+                kind: SearchQueryKind::Structural,
+                case_sensitive: true,
+            })
+            .unwrap()
+            .find_matches(&syntax_tree, 0, &None);
+
+            assert!(compute_replacement_edits(
+                &search_matches,
+                &RewriteTemplate::parse("dump($y)")
+            )
+            .is_err());
+        }
+    }
+
+    mod apply_edits {
+        use crate::core_engine::{
+            features::{complexity_refactoring::Edit, structural_search::apply_edits},
+            XcodeText,
+        };
+
+        fn edit(text: &str, start_index: usize, end_index: usize) -> Edit {
+            Edit {
+                text: XcodeText::from_str(text),
+                start_index,
+                end_index,
+            }
+        }
+
+        #[test]
+        fn applies_edits_in_any_order() {
+            let text = XcodeText::from_str("let a = b");
+
+            let edited_text = apply_edits(&text, &[edit("c", 8, 9), edit("var", 0, 3)]).unwrap();
+
+            assert_eq!(edited_text.as_string(), "var a = c");
+        }
+
+        #[test]
+        fn rejects_overlapping_edits() {
+            let text = XcodeText::from_str("let a = b");
+
+            assert!(apply_edits(&text, &[edit("x", 0, 5), edit("y", 4, 6)]).is_err());
+            assert!(apply_edits(&text, &[edit("x", 8, 12)]).is_err());
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::core_engine::{
+    events::models::{SearchQueryKind, SearchQueryMessage},
+    syntax_tree::SwiftSyntaxTree,
+    EditorWindowUid, TextPosition, TextRange, XcodeText,
+};
+
+use super::{MetavariableCapture, StructuralPattern, StructuralSearchError};
+
+const MAX_MATCHES_PER_DOCUMENT: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/structural_search/")]
+pub struct SearchMatch {
+    pub window_uid: EditorWindowUid,
+    pub file_path: Option<String>,
+    pub text_range: TextRange,
+    pub line: usize,   // One-based, like in Xcode
+    pub column: usize, // One-based and counted in graphemes, so an emoji is a single column
+    pub matched_text: String,
+    pub captures: Vec<MetavariableCapture>, // Only structural queries capture metavariables
+    pub score: u32,                         // Higher is more relevant
+}
+
+pub enum CompiledSearchQuery {
+    Text { regex: Regex, query: String },
+    Regex(Regex),
+    Structural(StructuralPattern),
+}
+
+impl CompiledSearchQuery {
+    pub fn compile(message: &SearchQueryMessage) -> Result<Self, StructuralSearchError> {
+        match message.kind {
+            SearchQueryKind::Text => Ok(CompiledSearchQuery::Text {
+                regex: build_regex(&regex::escape(&message.query), message.case_sensitive)?,
+                query: message.query.clone(),
+            }),
+            SearchQueryKind::Regex => Ok(CompiledSearchQuery::Regex(build_regex(
+                &message.query,
+                message.case_sensitive,
+            )?)),
+            SearchQueryKind::Structural => Ok(CompiledSearchQuery::Structural(
+                StructuralPattern::compile(&message.query)?,
+            )),
+        }
+    }
+
+    pub fn find_matches(
+        &self,
+        syntax_tree: &SwiftSyntaxTree,
+        window_uid: EditorWindowUid,
+        file_path: &Option<String>,
+    ) -> Vec<SearchMatch> {
+        let text = syntax_tree.text_content();
+        let mut matches: Vec<SearchMatch> = match self {
+            CompiledSearchQuery::Text { regex, query } => find_regex_matches(regex, text)
+                .into_iter()
+                .map(|(text_range, matched_text)| {
+                    let mut score = whole_word_score(&text_range, text);
+                    if matched_text == *query {
+                        score += 1;
+                    }
+                    (text_range, matched_text, vec![], score)
+                })
+                .collect::<Vec<_>>(),
+            CompiledSearchQuery::Regex(regex) => find_regex_matches(regex, text)
+                .into_iter()
+                .map(|(text_range, matched_text)| {
+                    let score = whole_word_score(&text_range, text);
+                    (text_range, matched_text, vec![], score)
+                })
+                .collect(),
+            CompiledSearchQuery::Structural(pattern) => pattern
+                .find_matches(syntax_tree)
+                .into_iter()
+                .map(|structural_match| {
+                    let matched_text = get_text(&structural_match.text_range, text);
+                    let score =
+                        literal_score(&structural_match.text_range, &structural_match.captures);
+                    (
+                        structural_match.text_range,
+                        matched_text,
+                        structural_match.captures,
+                        score,
+                    )
+                })
+                .collect(),
+        }
+        .into_iter()
+        .take(MAX_MATCHES_PER_DOCUMENT)
+        .filter_map(|(text_range, matched_text, captures, score)| {
+            let (line, column) = displayed_line_and_column(&text_range, text)?;
+            Some(SearchMatch {
+                window_uid,
+                file_path: file_path.clone(),
+                text_range,
+                line,
+                column,
+                matched_text,
+                captures,
+                score,
+            })
+        })
+        .collect();
+
+        rank_search_matches(&mut matches);
+        matches
+    }
+}
+
+// Most relevant first; ties keep the document order.
+pub fn rank_search_matches(matches: &mut [SearchMatch]) {
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.window_uid.cmp(&b.window_uid))
+            .then(a.text_range.index.cmp(&b.text_range.index))
+    });
+}
+
+fn build_regex(pattern: &str, case_sensitive: bool) -> Result<Regex, StructuralSearchError> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(!case_sensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|err| StructuralSearchError::InvalidPattern(err.to_string()))
+}
+
+// Regex offsets are UTF-8 bytes, text ranges count UTF-16 code units.
+fn find_regex_matches(regex: &Regex, text: &XcodeText) -> Vec<(TextRange, String)> {
+    let content = text.as_string();
+
+    let mut matches = vec![];
+    let mut utf8_offset = 0;
+    let mut utf16_offset = 0;
+    let mut to_utf16_index = |utf8_index: usize| {
+        utf16_offset += content[utf8_offset..utf8_index].encode_utf16().count();
+        utf8_offset = utf8_index;
+        utf16_offset
+    };
+
+    for regex_match in regex.find_iter(&content) {
+        if regex_match.as_str().is_empty() {
+            continue;
+        }
+        let start = to_utf16_index(regex_match.start());
+        let end = to_utf16_index(regex_match.end());
+        matches.push((
+            TextRange::from_StartEndIndex(start, end),
+            regex_match.as_str().to_string(),
+        ));
+    }
+
+    matches
+}
+
+fn displayed_line_and_column(text_range: &TextRange, text: &XcodeText) -> Option<(usize, usize)> {
+    let position = TextPosition::from_TextIndex(text, text_range.index)?;
+    // A text or regex match can start within a grapheme, e.g. at the skin tone of an emoji.
+    let grapheme_column = text.grapheme_column(&position).unwrap_or(position.column);
+    Some((position.row + 1, grapheme_column + 1))
+}
+
+fn whole_word_score(text_range: &TextRange, text: &XcodeText) -> u32 {
+    let is_word_char = |index: usize| {
+        text.get(index)
+            .and_then(|c| char::from_u32(*c as u32))
+            .map_or(false, |c| c.is_alphanumeric() || c == '_')
+    };
+
+    let starts_word = text_range.index == 0 || !is_word_char(text_range.index - 1);
+    let ends_word = !is_word_char(text_range.index + text_range.length);
+
+    if starts_word && ends_word {
+        2
+    } else {
+        0
+    }
+}
+
+// Share of the match in percent which is given by the pattern itself rather than by
+// metavariables; the closer a match is to the literal pattern, the higher it ranks.
+fn literal_score(text_range: &TextRange, captures: &[MetavariableCapture]) -> u32 {
+    if text_range.length == 0 {
+        return 0;
+    }
+
+    let captured_length: usize = captures
+        .iter()
+        .map(|capture| capture.text_range.length)
+        .sum();
+
+    (100 * text_range.length.saturating_sub(captured_length) / text_range.length) as u32
+}
+
+fn get_text(text_range: &TextRange, text: &XcodeText) -> String {
+    text.slice(text_range.index..text_range.index + text_range.length)
+        .as_string()
+}
+
+#[cfg(test)]
+mod tests {
+    mod find_matches {
+        use tauri::async_runtime::block_on;
+
+        use crate::core_engine::{
+            events::models::{SearchQueryKind, SearchQueryMessage},
+            features::structural_search::CompiledSearchQuery,
+            syntax_tree::SwiftSyntaxTree,
+            XcodeText,
+        };
+
+        fn test_fn(text: &str, query: &str, kind: SearchQueryKind) -> Vec<(String, u32)> {
+            let syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(text),
+                None,
+            ))
+            .unwrap();
+            let query = CompiledSearchQuery::compile(&SearchQueryMessage {
+                query: query.to_string(),
+                kind,
+                case_sensitive: false,
+            })
+            .unwrap();
+
+            query
+                .find_matches(&syntax_tree, 0, &None)
+                .into_iter()
+                .map(|search_match| (search_match.matched_text, search_match.score))
+                .collect()
+        }
+
+        #[test]
+        fn text_ranks_whole_words_and_exact_case_first() {
+            let matches = test_fn(
+                "let countValue = 1\nlet Count = 2\nlet count = 3",
+                "count",
+                SearchQueryKind::Text,
+            );
+
+            assert_eq!(
+                matches,
+                vec![
+                    ("count".to_string(), 3),
+                    ("Count".to_string(), 2),
+                    ("count".to_string(), 1),
+                ]
+            );
+        }
+
+        #[test]
+        fn regex_after_multi_byte_characters() {
+            let syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str("let a = \"😊\"; let b2 = 2"),
+                None,
+            ))
+            .unwrap();
+            let query = CompiledSearchQuery::compile(&SearchQueryMessage {
+                query: r"b\d".to_string(),
+                kind: SearchQueryKind::Regex,
+                case_sensitive: true,
+            })
+            .unwrap();
+
+            let matches = query.find_matches(&syntax_tree, 0, &None);
+
+            assert_eq!(matches.len(), 1);
+            // The emoji takes two UTF-16 code units, but is displayed as a single column.
+            assert_eq!(matches[0].text_range.index, 18);
+            assert_eq!(matches[0].text_range.length, 2);
+            assert_eq!((matches[0].line, matches[0].column), (1, 18));
+        }
+
+        #[test]
+        fn invalid_regex() {
+            assert!(CompiledSearchQuery::compile(&SearchQueryMessage {
+                query: "(unclosed".to_string(),
+                kind: SearchQueryKind::Regex,
+                case_sensitive: false,
+            })
+            .is_err());
+        }
+    }
+}
@@ -0,0 +1,578 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Query, QueryCursor};
+use ts_rs::TS;
+
+use crate::core_engine::{syntax_tree::SwiftSyntaxTree, TextRange, XcodeText};
+
+use super::StructuralSearchError;
+
+const PLACEHOLDER_PREFIX: &str = "__mv_";
+const ELLIPSIS_PLACEHOLDER_PREFIX: &str = "__mv_ellipsis_";
+const MATCH_CAPTURE: &str = "__match";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/structural_search/")]
+pub struct MetavariableCapture {
+    pub name: String, // Without the leading `$`; unnamed ellipses are called `$$$`
+    pub text: String,
+    pub text_range: TextRange,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructuralMatch {
+    pub text_range: TextRange,
+    pub captures: Vec<MetavariableCapture>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Placeholder {
+    Metavariable(String),
+    Ellipsis(Option<String>),
+}
+
+// An ellipsis stands for any number of named siblings between two other child patterns, so its
+// text is only known once the neighbours have matched.
+#[derive(Debug)]
+struct EllipsisCapture {
+    name: Option<String>,
+    parent: u32,
+    previous: Option<u32>,
+    next: Option<u32>,
+}
+
+#[derive(Default)]
+struct QueryBuilder {
+    source: String,
+    capture_count: usize,
+    metavariables: Vec<(String, String)>, // (metavariable name, capture name)
+    literals: Vec<(String, String)>,      // (capture name, expected text)
+    ellipses: Vec<(Option<String>, String, Option<String>, Option<String>)>,
+    include_anonymous_nodes: bool,
+}
+
+impl QueryBuilder {
+    fn next_capture_name(&mut self) -> String {
+        self.capture_count += 1;
+        format!("__n{}", self.capture_count)
+    }
+}
+
+// A Swift snippet with metavariables, compiled to a tree-sitter query. `$name` matches any single
+// node, `$$$` or `$$$name` any number of sibling nodes. A metavariable used more than once must
+// match the same text everywhere.
+pub struct StructuralPattern {
+    query: Query,
+    match_capture: u32,
+    metavariables: Vec<(String, u32)>,
+    literals: Vec<(u32, String)>,
+    ellipses: Vec<EllipsisCapture>,
+}
+
+impl StructuralPattern {
+    pub fn compile(pattern: &str) -> Result<Self, StructuralSearchError> {
+        let (source, placeholders) = replace_metavariables(pattern);
+
+        let mut parser = SwiftSyntaxTree::parser();
+        let tree = parser
+            .parse(&source, None)
+            .ok_or(StructuralSearchError::InvalidPattern(
+                "the pattern could not be parsed".to_string(),
+            ))?;
+        if tree.root_node().has_error() {
+            return Err(StructuralSearchError::InvalidPattern(
+                "the pattern is not valid Swift".to_string(),
+            ));
+        }
+
+        let pattern_root = find_pattern_root(tree.root_node(), &source)?;
+        let placeholder_nodes = find_placeholder_nodes(pattern_root, &source, &placeholders);
+        if placeholder_nodes.contains_key(&pattern_root.id()) {
+            return Err(StructuralSearchError::InvalidPattern(
+                "the pattern must contain code besides metavariables".to_string(),
+            ));
+        }
+
+        // Keywords and operators make the query more precise, but the grammar might not accept
+        // every token kind in a query; fall back to named nodes only.
+        Self::build(pattern_root, &source, &placeholder_nodes, true)
+            .or_else(|_| Self::build(pattern_root, &source, &placeholder_nodes, false))
+    }
+
+    fn build(
+        pattern_root: Node,
+        source: &str,
+        placeholder_nodes: &HashMap<usize, Placeholder>,
+        include_anonymous_nodes: bool,
+    ) -> Result<Self, StructuralSearchError> {
+        let mut builder = QueryBuilder {
+            include_anonymous_nodes,
+            ..Default::default()
+        };
+        emit_node_pattern(pattern_root, None, source, placeholder_nodes, &mut builder);
+        builder.source = format!("{} @{}", builder.source, MATCH_CAPTURE);
+
+        let query = Query::new(tree_sitter_swift::language(), &builder.source)
+            .map_err(|err| StructuralSearchError::InvalidPattern(err.message))?;
+
+        let capture_index = |name: &str| query.capture_index_for_name(name);
+        let match_capture = capture_index(MATCH_CAPTURE).ok_or(
+            StructuralSearchError::InvalidPattern("the pattern is empty".to_string()),
+        )?;
+        let metavariables = builder
+            .metavariables
+            .iter()
+            .filter_map(|(name, capture)| Some((name.clone(), capture_index(capture)?)))
+            .collect();
+        let literals = builder
+            .literals
+            .iter()
+            .filter_map(|(capture, text)| Some((capture_index(capture)?, text.clone())))
+            .collect();
+        let ellipses = builder
+            .ellipses
+            .iter()
+            .filter_map(|(name, parent, previous, next)| {
+                Some(EllipsisCapture {
+                    name: name.clone(),
+                    parent: capture_index(parent)?,
+                    previous: previous.as_deref().and_then(capture_index),
+                    next: next.as_deref().and_then(capture_index),
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            query,
+            match_capture,
+            metavariables,
+            literals,
+            ellipses,
+        })
+    }
+
+    pub fn find_matches(&self, syntax_tree: &SwiftSyntaxTree) -> Vec<StructuralMatch> {
+        let text = syntax_tree.text_content();
+        let mut cursor = QueryCursor::new();
+        // The query has no text predicates; literal and repeated metavariable texts are compared
+        // below, because the tree is parsed from UTF-16 while query strings are UTF-8.
+        let query_matches =
+            cursor.matches(&self.query, syntax_tree.tree().root_node(), "".as_bytes());
+
+        let mut matches: Vec<StructuralMatch> = vec![];
+        for query_match in query_matches {
+            let nodes: HashMap<u32, Node> = query_match
+                .captures
+                .iter()
+                .map(|capture| (capture.index, capture.node))
+                .collect();
+
+            if let Some(structural_match) = self.to_structural_match(&nodes, text) {
+                if !matches
+                    .iter()
+                    .any(|existing| existing.text_range == structural_match.text_range)
+                {
+                    matches.push(structural_match);
+                }
+            }
+        }
+
+        matches.sort_by_key(|structural_match| structural_match.text_range.index);
+        matches
+    }
+
+    fn to_structural_match(
+        &self,
+        nodes: &HashMap<u32, Node>,
+        text: &XcodeText,
+    ) -> Option<StructuralMatch> {
+        let matched_node = nodes.get(&self.match_capture)?;
+
+        for (capture, expected_text) in &self.literals {
+            if node_text(nodes.get(capture)?, text) != *expected_text {
+                return None;
+            }
+        }
+
+        let mut captures: Vec<MetavariableCapture> = vec![];
+        for (name, capture) in &self.metavariables {
+            let node = nodes.get(capture)?;
+            let captured_text = node_text(node, text);
+            match captures.iter().find(|existing| existing.name == *name) {
+                Some(existing) if existing.text != captured_text => return None,
+                Some(_) => {}
+                None => captures.push(MetavariableCapture {
+                    name: name.clone(),
+                    text: captured_text,
+                    text_range: node_text_range(node),
+                }),
+            }
+        }
+
+        for ellipsis in &self.ellipses {
+            let text_range = ellipsis_text_range(
+                nodes.get(&ellipsis.parent)?,
+                ellipsis.previous.and_then(|capture| nodes.get(&capture)),
+                ellipsis.next.and_then(|capture| nodes.get(&capture)),
+            );
+            let name = match &ellipsis.name {
+                Some(name) => name.clone(),
+                None => "$$$".to_string(),
+            };
+            if !captures.iter().any(|existing| existing.name == name) {
+                captures.push(MetavariableCapture {
+                    name,
+                    text: text_range_text(&text_range, text),
+                    text_range,
+                });
+            }
+        }
+
+        Some(StructuralMatch {
+            text_range: node_text_range(matched_node),
+            captures,
+        })
+    }
+}
+
+// Replaces `$name`, `$$$` and `$$$name` with identifiers, so the pattern parses as Swift. `$0` and
+// friends are closure shorthand arguments and stay untouched.
+fn replace_metavariables(pattern: &str) -> (String, HashMap<String, Placeholder>) {
+    let mut source = String::new();
+    let mut placeholders = HashMap::new();
+    let mut ellipsis_count = 0;
+
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let is_ellipsis = chars[i..].starts_with(&['$', '$', '$']);
+        let name_start = if is_ellipsis { i + 3 } else { i + 1 };
+
+        if chars[i] != '$' {
+            source.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let mut name_end = name_start;
+        while name_end < chars.len()
+            && (chars[name_end].is_ascii_alphanumeric() || chars[name_end] == '_')
+        {
+            name_end += 1;
+        }
+        let name: String = chars[name_start..name_end].iter().collect();
+        let is_valid_name = name
+            .chars()
+            .next()
+            .map_or(false, |c| c.is_ascii_alphabetic() || c == '_');
+
+        if is_ellipsis {
+            let placeholder = format!("{}{}", ELLIPSIS_PLACEHOLDER_PREFIX, ellipsis_count);
+            ellipsis_count += 1;
+            let name = if is_valid_name { Some(name) } else { None };
+            let consumed = if name.is_some() { name_end } else { name_start };
+            placeholders.insert(placeholder.clone(), Placeholder::Ellipsis(name));
+            source.push_str(&placeholder);
+            i = consumed;
+        } else if is_valid_name {
+            let placeholder = format!("{}{}", PLACEHOLDER_PREFIX, name);
+            placeholders.insert(placeholder.clone(), Placeholder::Metavariable(name));
+            source.push_str(&placeholder);
+            i = name_end;
+        } else {
+            source.push(chars[i]);
+            i += 1;
+        }
+    }
+
+    (source, placeholders)
+}
+
+// The deepest node spanning the whole pattern, e.g. the `if_statement` for `if let $x = $y {}`.
+fn find_pattern_root<'tree>(
+    root: Node<'tree>,
+    source: &str,
+) -> Result<Node<'tree>, StructuralSearchError> {
+    let start = source.len() - source.trim_start().len();
+    let end = source.trim_end().len();
+
+    let mut node = root;
+    loop {
+        let mut cursor = node.walk();
+        let child = node
+            .named_children(&mut cursor)
+            .find(|child| child.start_byte() == start && child.end_byte() == end);
+        match child {
+            Some(child) => node = child,
+            None => break,
+        }
+    }
+
+    if node.id() == root.id() {
+        return Err(StructuralSearchError::InvalidPattern(
+            "the pattern must be a single statement, declaration or expression".to_string(),
+        ));
+    }
+
+    Ok(node)
+}
+
+// Maps the outermost node spanning each placeholder to that placeholder.
+fn find_placeholder_nodes(
+    pattern_root: Node,
+    source: &str,
+    placeholders: &HashMap<String, Placeholder>,
+) -> HashMap<usize, Placeholder> {
+    let mut placeholder_nodes = HashMap::new();
+
+    for (placeholder_text, placeholder) in placeholders {
+        for (start, _) in source.match_indices(placeholder_text.as_str()) {
+            let end = start + placeholder_text.len();
+            // `__mv_x` must not be mistaken for a part of `__mv_xy`.
+            if source[end..]
+                .chars()
+                .next()
+                .map_or(false, |c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                continue;
+            }
+
+            let mut node = match pattern_root.descendant_for_byte_range(start, end) {
+                Some(node) if node.start_byte() == start && node.end_byte() == end => node,
+                _ => continue,
+            };
+            while let Some(parent) = node.parent() {
+                if parent.id() == pattern_root.id()
+                    || parent.start_byte() != start
+                    || parent.end_byte() != end
+                {
+                    break;
+                }
+                node = parent;
+            }
+
+            placeholder_nodes.insert(node.id(), placeholder.clone());
+        }
+    }
+
+    placeholder_nodes
+}
+
+fn emit_node_pattern(
+    node: Node,
+    field_name: Option<&str>,
+    source: &str,
+    placeholder_nodes: &HashMap<usize, Placeholder>,
+    builder: &mut QueryBuilder,
+) -> Option<String> {
+    if let Some(field_name) = field_name {
+        builder.source.push_str(&format!("{}: ", field_name));
+    }
+
+    if let Some(Placeholder::Metavariable(name)) = placeholder_nodes.get(&node.id()) {
+        let capture = builder.next_capture_name();
+        builder.source.push_str(&format!("(_) @{}", capture));
+        builder.metavariables.push((name.clone(), capture.clone()));
+        return Some(capture);
+    }
+
+    let capture = builder.next_capture_name();
+    if node.named_child_count() == 0 {
+        builder
+            .source
+            .push_str(&format!("({}) @{}", node.kind(), capture));
+        builder.literals.push((
+            capture.clone(),
+            node.utf8_text(source.as_bytes()).unwrap_or("").to_string(),
+        ));
+        return Some(capture);
+    }
+
+    builder.source.push_str(&format!("({}", node.kind()));
+
+    let mut previous_named_capture: Option<String> = None;
+    let mut pending_ellipses: Vec<Option<String>> = vec![];
+    let mut anchor_allowed = true; // No ellipsis or token between the last named pattern and here
+    let mut children: Vec<(Node, Option<&'static str>)> = vec![];
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            children.push((cursor.node(), cursor.field_name()));
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    for (child, child_field_name) in children {
+        if let Some(Placeholder::Ellipsis(name)) = placeholder_nodes.get(&child.id()) {
+            pending_ellipses.push(name.clone());
+            anchor_allowed = false;
+            continue;
+        }
+
+        if !child.is_named() {
+            if builder.include_anonymous_nodes && !child.is_extra() {
+                builder
+                    .source
+                    .push_str(&format!(" \"{}\"", escape_query_string(child.kind())));
+                anchor_allowed = false;
+            }
+            continue;
+        }
+
+        if anchor_allowed {
+            builder.source.push_str(" .");
+        }
+        builder.source.push(' ');
+        let child_capture =
+            emit_node_pattern(child, child_field_name, source, placeholder_nodes, builder);
+
+        for name in pending_ellipses.drain(..) {
+            builder.ellipses.push((
+                name,
+                capture.clone(),
+                previous_named_capture.clone(),
+                child_capture.clone(),
+            ));
+        }
+        previous_named_capture = child_capture;
+        anchor_allowed = true;
+    }
+
+    for name in pending_ellipses.drain(..) {
+        builder
+            .ellipses
+            .push((name, capture.clone(), previous_named_capture.clone(), None));
+    }
+    if anchor_allowed && previous_named_capture.is_some() {
+        builder.source.push_str(" .");
+    }
+
+    builder.source.push_str(&format!(") @{}", capture));
+    Some(capture)
+}
+
+fn escape_query_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn node_text_range(node: &Node) -> TextRange {
+    // The tree is parsed from UTF-16, so every character takes two bytes.
+    TextRange::from_StartEndIndex(node.start_byte() / 2, node.end_byte() / 2)
+}
+
+fn text_range_text(text_range: &TextRange, text: &XcodeText) -> String {
+    text.slice(text_range.index..text_range.index + text_range.length)
+        .as_string()
+}
+
+fn node_text(node: &Node, text: &XcodeText) -> String {
+    text_range_text(&node_text_range(node), text)
+}
+
+// Covers the named children of `parent` between the neighbouring matched siblings.
+fn ellipsis_text_range(parent: &Node, previous: Option<&Node>, next: Option<&Node>) -> TextRange {
+    let lower_bound = previous.map_or(0, |previous| previous.end_byte());
+    let upper_bound = next.map_or(usize::MAX, |next| next.start_byte());
+
+    let mut cursor = parent.walk();
+    let covered: Vec<Node> = parent
+        .named_children(&mut cursor)
+        .filter(|child| child.start_byte() >= lower_bound && child.end_byte() <= upper_bound)
+        .collect();
+
+    match (covered.first(), covered.last()) {
+        (Some(first), Some(last)) => {
+            TextRange::from_StartEndIndex(first.start_byte() / 2, last.end_byte() / 2)
+        }
+        _ => {
+            let index = previous.map_or(parent.start_byte(), |previous| previous.end_byte()) / 2;
+            TextRange::new(index, 0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod find_matches {
+        use tauri::async_runtime::block_on;
+
+        use crate::core_engine::{
+            features::structural_search::{StructuralMatch, StructuralPattern},
+            syntax_tree::SwiftSyntaxTree,
+            XcodeText,
+        };
+
+        fn test_fn(pattern: &str, text: &str) -> Vec<StructuralMatch> {
+            let syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(text),
+                None,
+            ))
+            .unwrap();
+
+            StructuralPattern::compile(pattern)
+                .unwrap()
+                .find_matches(&syntax_tree)
+        }
+
+        fn capture(structural_match: &StructuralMatch, name: &str) -> String {
+            structural_match
+                .captures
+                .iter()
+                .find(|capture| capture.name == name)
+                .unwrap()
+                .text
+                .clone()
+        }
+
+        #[test]
+        fn if_let_with_ellipsis_body() {
+            let matches = test_fn(
+                "if let $x = $y { $$$ }",
+                "if let a = b { print(a) }\nif c { print(c) }\nif let d = e.f { g()\nh() }\n",
+            );
+
+            assert_eq!(matches.len(), 2);
+            assert_eq!(capture(&matches[0], "x"), "a");
+            assert_eq!(capture(&matches[0], "y"), "b");
+            assert_eq!(capture(&matches[1], "x"), "d");
+            assert_eq!(capture(&matches[1], "y"), "e.f");
+            assert!(capture(&matches[1], "$$$").contains("g()"));
+            assert_eq!(matches[0].text_range.index, 0);
+        }
+
+        #[test]
+        fn repeated_metavariable_must_match_same_text() {
+            let matches = test_fn("$a == $a", "let r = x == x\nlet s = x == y\n");
+
+            assert_eq!(matches.len(), 1);
+            assert_eq!(capture(&matches[0], "a"), "x");
+            assert_eq!(matches[0].text_range.index, 8);
+            assert_eq!(matches[0].text_range.length, 6);
+        }
+
+        #[test]
+        fn literals_must_match() {
+            let matches = test_fn("print($x)", "print(1)\ndump(2)\n");
+
+            assert_eq!(matches.len(), 1);
+            assert_eq!(capture(&matches[0], "x"), "1");
+        }
+    }
+
+    mod compile {
+        use crate::core_engine::features::structural_search::StructuralPattern;
+
+        #[test]
+        fn invalid_swift() {
+            assert!(StructuralPattern::compile("if let $x = {").is_err());
+        }
+
+        #[test]
+        fn only_metavariable() {
+            assert!(StructuralPattern::compile("$x").is_err());
+        }
+    }
+}
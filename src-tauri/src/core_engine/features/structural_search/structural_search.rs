@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    app_handle,
+    core_engine::{
+        events::{
+            models::{
+                ReplaceQueryMessage, SearchQueryMessage, UpdateReplacePreviewMessage,
+                UpdateSearchResultsMessage,
+            },
+            StructuralSearchEvent,
+        },
+        features::{
+            complexity_refactoring::Edit,
+            feature_base::{CoreEngineTrigger, FeatureBase, FeatureError, UserCommand},
+            FeatureKind,
+        },
+        scheduler::CancellationToken,
+        CodeDocument, EditorWindowUid, XcodeText,
+    },
+    platform::macos::replace_text_content,
+    CORE_ENGINE_ACTIVE_AT_STARTUP,
+};
+
+use super::{
+    annotations::{remove_annotation_group, upsert_annotation_group_for_search_matches},
+    apply_edits, compute_replace_preview, compute_replacement_edits, rank_search_matches,
+    CompiledSearchQuery, RewriteTemplate, SearchMatch,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum StructuralSearchError {
+    #[error("Insufficient context for structural search")]
+    InsufficientContext,
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Invalid replacement template: {0}")]
+    InvalidTemplate(String),
+    #[error("Replacements overlap or exceed the text")]
+    InvalidEdits,
+    #[error("Something went wrong when executing the StructuralSearch feature.")]
+    GenericError(#[source] anyhow::Error),
+}
+
+enum SearchProcedure {
+    Search(SearchQueryMessage),
+    Refresh,
+    PreviewReplace(ReplaceQueryMessage),
+    PerformReplace(ReplaceQueryMessage),
+}
+
+pub struct StructuralSearch {
+    is_activated: bool,
+
+    active_query: Option<SearchQueryMessage>,
+    compiled_query: Option<CompiledSearchQuery>, // None if the active query failed to compile
+    results: HashMap<EditorWindowUid, Vec<SearchMatch>>,
+    annotation_groups: HashMap<EditorWindowUid, Uuid>,
+}
+
+impl FeatureBase for StructuralSearch {
+    fn compute(
+        &mut self,
+        code_document: CodeDocument,
+        trigger: CoreEngineTrigger,
+        _cancellation_token: CancellationToken,
+    ) -> Result<(), FeatureError> {
+        if !self.is_activated {
+            return Ok(());
+        }
+
+        match Self::determine_procedure(&trigger) {
+            Some(SearchProcedure::Search(query)) => {
+                self.procedure_search(&code_document, query)?;
+            }
+            Some(SearchProcedure::Refresh) => {
+                if self.compiled_query.is_some() {
+                    self.search_document(&code_document)?;
+                }
+            }
+            Some(SearchProcedure::PreviewReplace(query)) => {
+                self.procedure_preview_replace(&code_document, query);
+            }
+            Some(SearchProcedure::PerformReplace(query)) => {
+                self.procedure_perform_replace(&code_document, query)?;
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    fn activate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = true;
+
+        Ok(())
+    }
+
+    fn deactivate(&mut self) -> Result<(), FeatureError> {
+        self.is_activated = false;
+        self.clear_search();
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), FeatureError> {
+        self.clear_search();
+
+        Ok(())
+    }
+
+    fn kind(&self) -> FeatureKind {
+        FeatureKind::StructuralSearch
+    }
+
+    fn should_compute(_kind: &FeatureKind, trigger: &CoreEngineTrigger) -> bool {
+        Self::determine_procedure(trigger).is_some()
+    }
+
+    fn requires_ai(_kind: &FeatureKind, _trigger: &CoreEngineTrigger) -> bool {
+        false
+    }
+}
+
+impl StructuralSearch {
+    pub fn new() -> Self {
+        Self {
+            is_activated: CORE_ENGINE_ACTIVE_AT_STARTUP,
+            active_query: None,
+            compiled_query: None,
+            results: HashMap::new(),
+            annotation_groups: HashMap::new(),
+        }
+    }
+
+    fn determine_procedure(trigger: &CoreEngineTrigger) -> Option<SearchProcedure> {
+        match trigger {
+            CoreEngineTrigger::OnUserCommand(UserCommand::Search(msg)) => {
+                Some(SearchProcedure::Search(msg.clone()))
+            }
+            CoreEngineTrigger::OnUserCommand(UserCommand::PreviewReplace(msg)) => {
+                Some(SearchProcedure::PreviewReplace(msg.clone()))
+            }
+            CoreEngineTrigger::OnUserCommand(UserCommand::PerformReplace(msg)) => {
+                Some(SearchProcedure::PerformReplace(msg.clone()))
+            }
+            CoreEngineTrigger::OnTextContentChange => Some(SearchProcedure::Refresh),
+            _ => None,
+        }
+    }
+
+    // A search command is scheduled once per open document; only the first one of a new query
+    // replaces the previous results.
+    fn procedure_search(
+        &mut self,
+        code_document: &CodeDocument,
+        query: SearchQueryMessage,
+    ) -> Result<(), StructuralSearchError> {
+        if self.active_query.as_ref() != Some(&query) {
+            self.clear_search();
+
+            if query.query.trim().is_empty() {
+                return Ok(());
+            }
+
+            self.active_query = Some(query.clone());
+            match CompiledSearchQuery::compile(&query) {
+                Ok(compiled_query) => self.compiled_query = Some(compiled_query),
+                Err(err) => {
+                    self.publish_results(Some(err.to_string()));
+                    return Ok(());
+                }
+            }
+        }
+
+        if self.compiled_query.is_none() {
+            // The error has already been published for the first document.
+            return Ok(());
+        }
+
+        self.search_document(code_document)
+    }
+
+    fn search_document(
+        &mut self,
+        code_document: &CodeDocument,
+    ) -> Result<(), StructuralSearchError> {
+        let compiled_query = self
+            .compiled_query
+            .as_ref()
+            .ok_or(StructuralSearchError::InsufficientContext)?;
+        let window_uid = code_document.editor_window_props().window_uid;
+        let syntax_tree = code_document
+            .syntax_tree()
+            .ok_or(StructuralSearchError::InsufficientContext)?;
+
+        let search_matches =
+            compiled_query.find_matches(syntax_tree, window_uid, code_document.file_path());
+
+        if search_matches.is_empty() {
+            if let Some(group_id) = self.annotation_groups.remove(&window_uid) {
+                remove_annotation_group(group_id);
+            }
+        } else {
+            let group_id = *self
+                .annotation_groups
+                .entry(window_uid)
+                .or_insert_with(Uuid::new_v4);
+            upsert_annotation_group_for_search_matches(group_id, &search_matches, window_uid);
+        }
+
+        self.results.insert(window_uid, search_matches);
+        self.publish_results(None);
+
+        Ok(())
+    }
+
+    fn procedure_preview_replace(&self, code_document: &CodeDocument, query: ReplaceQueryMessage) {
+        let preview = Self::compute_replacement(code_document, &query)
+            .and_then(|(text, edits)| compute_replace_preview(&text, &edits));
+
+        let (preview, error) = match preview {
+            Ok(preview) => (Some(preview), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+
+        StructuralSearchEvent::UpdateReplacePreview(UpdateReplacePreviewMessage {
+            query,
+            preview,
+            error,
+        })
+        .publish_to_tauri(&app_handle());
+    }
+
+    // The replacements are recomputed against the current text instead of reusing the preview,
+    // so edits never apply to a text which changed in the meantime.
+    fn procedure_perform_replace(
+        &self,
+        code_document: &CodeDocument,
+        query: ReplaceQueryMessage,
+    ) -> Result<(), StructuralSearchError> {
+        let (text, edits) = Self::compute_replacement(code_document, &query)?;
+        if edits.is_empty() {
+            return Ok(());
+        }
+        let new_text = apply_edits(&text, &edits)?;
+
+        tauri::async_runtime::spawn({
+            let selected_text_range = code_document.selected_text_range().clone();
+            async move {
+                if let Err(err) = replace_text_content(&text, &new_text, &selected_text_range).await
+                {
+                    error!(?err, "Error replacing text content");
+                }
+            }
+        });
+
+        StructuralSearchEvent::UpdateReplacePreview(UpdateReplacePreviewMessage {
+            query,
+            preview: None,
+            error: None,
+        })
+        .publish_to_tauri(&app_handle());
+
+        Ok(())
+    }
+
+    fn compute_replacement(
+        code_document: &CodeDocument,
+        query: &ReplaceQueryMessage,
+    ) -> Result<(XcodeText, Vec<Edit>), StructuralSearchError> {
+        let syntax_tree = code_document
+            .syntax_tree()
+            .ok_or(StructuralSearchError::InsufficientContext)?;
+
+        let search_matches = CompiledSearchQuery::compile(&query.search)?.find_matches(
+            syntax_tree,
+            code_document.editor_window_props().window_uid,
+            code_document.file_path(),
+        );
+        let edits =
+            compute_replacement_edits(&search_matches, &RewriteTemplate::parse(&query.template))?;
+
+        Ok((syntax_tree.text_content().clone(), edits))
+    }
+
+    fn publish_results(&self, error: Option<String>) {
+        let query = match &self.active_query {
+            Some(query) => query.clone(),
+            None => return,
+        };
+
+        let mut matches: Vec<SearchMatch> = self.results.values().flatten().cloned().collect();
+        rank_search_matches(&mut matches);
+
+        StructuralSearchEvent::UpdateSearchResults(UpdateSearchResultsMessage {
+            query,
+            matches,
+            error,
+        })
+        .publish_to_tauri(&app_handle());
+    }
+
+    fn clear_search(&mut self) {
+        for (_, group_id) in self.annotation_groups.drain() {
+            remove_annotation_group(group_id);
+        }
+
+        if let Some(query) = self.active_query.take() {
+            StructuralSearchEvent::UpdateSearchResults(UpdateSearchResultsMessage {
+                query,
+                matches: vec![],
+                error: None,
+            })
+            .publish_to_tauri(&app_handle());
+        }
+
+        self.compiled_query = None;
+        self.results.clear();
+    }
+}
@@ -0,0 +1,33 @@
+use tauri::async_runtime::block_on;
+
+use crate::core_engine::{syntax_tree::SwiftSyntaxTree, XcodeText};
+
+use super::{
+    complexity_refactoring::ComplexityRefactoringError, structural_search::apply_edits, Edit,
+};
+
+pub fn parse(code: &str) -> SwiftSyntaxTree {
+    block_on(SwiftSyntaxTree::from_XcodeText(
+        XcodeText::from_str(code),
+        None,
+    ))
+    .unwrap()
+}
+
+pub fn applied(code: &str, edits: &[Edit]) -> String {
+    apply_edits(&XcodeText::from_str(code), edits)
+        .unwrap()
+        .as_string()
+}
+
+// The refactored code, or the reason the refactoring was rejected.
+pub fn refactored(
+    code: &str,
+    compute_edits: impl FnOnce(&SwiftSyntaxTree) -> Result<Vec<Edit>, ComplexityRefactoringError>,
+) -> Result<String, String> {
+    match compute_edits(&parse(code)) {
+        Ok(edits) => Ok(applied(code, &edits)),
+        Err(ComplexityRefactoringError::RefactoringRejected(reason)) => Err(reason),
+        Err(err) => panic!("Unexpected error: {:?}", err),
+    }
+}
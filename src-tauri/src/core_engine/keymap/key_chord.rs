@@ -0,0 +1,157 @@
+use std::fmt;
+
+use crate::platform::macos::models::editor::{EditorShortcutPressedMessage, ModifierKey};
+
+use super::KeymapError;
+
+// A modifier combination plus a key, written like `shift+cmd+k`. Keys are compared in upper
+// case, which is how Xcode reports the key equivalents of its menu items.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub modifier: ModifierKey,
+    pub key: String,
+}
+
+// Chords pressed one after another, written space separated like `cmd+k cmd+f`.
+pub type KeySequence = Vec<KeyChord>;
+
+impl KeyChord {
+    pub fn new(modifier: ModifierKey, key: &str) -> Self {
+        Self {
+            modifier,
+            key: key.to_uppercase(),
+        }
+    }
+
+    pub fn from_shortcut(msg: &EditorShortcutPressedMessage) -> Self {
+        Self::new(msg.modifier.clone(), &msg.key)
+    }
+
+    pub fn parse(chord: &str) -> Result<Self, KeymapError> {
+        let invalid = || KeymapError::InvalidShortcut(chord.to_string());
+
+        // The key itself may be `+`, as in `cmd++`.
+        let (modifiers, key) = match chord.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None => chord.rsplit_once('+').ok_or_else(invalid)?,
+        };
+        if key.is_empty() {
+            return Err(invalid());
+        }
+
+        let (mut cmd, mut shift, mut option, mut ctrl) = (false, false, false, false);
+        for modifier in modifiers.split('+') {
+            let flag = match modifier.to_lowercase().as_str() {
+                "cmd" | "command" => &mut cmd,
+                "shift" => &mut shift,
+                "option" | "alt" => &mut option,
+                "ctrl" | "control" => &mut ctrl,
+                _ => return Err(invalid()),
+            };
+            if *flag {
+                return Err(invalid());
+            }
+            *flag = true;
+        }
+
+        let modifier = match (cmd, shift, option, ctrl) {
+            (true, false, false, false) => ModifierKey::Cmd,
+            (true, true, false, false) => ModifierKey::ShiftCmd,
+            (true, false, true, false) => ModifierKey::OptionCmd,
+            (true, false, false, true) => ModifierKey::CtrlCmd,
+            (false, false, true, false) => ModifierKey::OptionAlt,
+            (false, false, false, true) => ModifierKey::Ctrl,
+            (false, false, true, true) => ModifierKey::CtrlOption,
+            (false, true, false, false) => ModifierKey::Shift,
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self::new(modifier, key))
+    }
+
+    pub fn parse_sequence(sequence: &str) -> Result<KeySequence, KeymapError> {
+        let chords = sequence
+            .split_whitespace()
+            .map(Self::parse)
+            .collect::<Result<KeySequence, KeymapError>>()?;
+
+        if chords.is_empty() {
+            return Err(KeymapError::InvalidShortcut(sequence.to_string()));
+        }
+
+        Ok(chords)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let modifier = match self.modifier {
+            ModifierKey::Cmd => "cmd",
+            ModifierKey::ShiftCmd => "shift+cmd",
+            ModifierKey::OptionCmd => "option+cmd",
+            ModifierKey::CtrlCmd => "ctrl+cmd",
+            ModifierKey::OptionAlt => "option",
+            ModifierKey::Ctrl => "ctrl",
+            ModifierKey::CtrlOption => "ctrl+option",
+            ModifierKey::Shift => "shift",
+            ModifierKey::Unknown => "unknown",
+        };
+        write!(f, "{}+{}", modifier, self.key.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse {
+        use crate::{core_engine::keymap::KeyChord, platform::macos::models::editor::ModifierKey};
+
+        #[test]
+        fn modifiers_in_any_order() {
+            assert_eq!(
+                KeyChord::parse("cmd+shift+k").unwrap(),
+                KeyChord::new(ModifierKey::ShiftCmd, "K")
+            );
+            assert_eq!(
+                KeyChord::parse("Option+Cmd+[").unwrap(),
+                KeyChord::new(ModifierKey::OptionCmd, "[")
+            );
+            assert_eq!(
+                KeyChord::parse("cmd++").unwrap(),
+                KeyChord::new(ModifierKey::Cmd, "+")
+            );
+        }
+
+        #[test]
+        fn invalid_chords() {
+            assert!(KeyChord::parse("s").is_err());
+            assert!(KeyChord::parse("cmd+").is_err());
+            assert!(KeyChord::parse("cmd+cmd+s").is_err());
+            assert!(KeyChord::parse("hyper+s").is_err());
+            assert!(KeyChord::parse("shift+option+cmd+s").is_err());
+        }
+
+        #[test]
+        fn display_round_trips() {
+            let chord = KeyChord::parse("ctrl+cmd+n").unwrap();
+
+            assert_eq!(chord.to_string(), "ctrl+cmd+n");
+            assert_eq!(KeyChord::parse(&chord.to_string()).unwrap(), chord);
+        }
+    }
+
+    mod parse_sequence {
+        use crate::{core_engine::keymap::KeyChord, platform::macos::models::editor::ModifierKey};
+
+        #[test]
+        fn chorded_sequence() {
+            assert_eq!(
+                KeyChord::parse_sequence(" cmd+k  cmd+f ").unwrap(),
+                vec![
+                    KeyChord::new(ModifierKey::Cmd, "K"),
+                    KeyChord::new(ModifierKey::Cmd, "F")
+                ]
+            );
+            assert!(KeyChord::parse_sequence("  ").is_err());
+        }
+    }
+}
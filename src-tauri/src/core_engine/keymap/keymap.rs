@@ -0,0 +1,356 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    core_engine::features::NavigationCommand, platform::macos::models::editor::ModifierKey,
+};
+
+use super::{KeyChord, KeySequence, KeymapAction};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum KeymapError {
+    #[error("Unknown shortcut action `{0}`")]
+    UnknownAction(String),
+    #[error("Invalid shortcut `{0}`")]
+    InvalidShortcut(String),
+    #[error("Shortcut `{shortcut}` of `{action}` conflicts with `{other_action}`")]
+    Conflict {
+        shortcut: String,
+        action: String,
+        other_action: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeymapResolution {
+    Action(KeymapAction),
+    Pending, // The chord starts a sequence; wait for the next one
+    Unbound,
+}
+
+// Shortcuts only reach the engine if Xcode has a menu item with that key equivalent, so the
+// defaults stick to those; other actions are unbound until the user binds them. Moving to the
+// enclosing block is unbound as well: Xcode's bracket chords all trigger edits or navigation of
+// their own (⌘[ shifts, ⌥⌘[ moves lines, ⌃⌘[ switches schemes).
+fn default_bindings() -> Vec<(KeymapAction, KeySequence)> {
+    let chord = |modifier: ModifierKey, key: &str| vec![KeyChord::new(modifier, key)];
+    let navigate = KeymapAction::Navigate;
+
+    vec![
+        (KeymapAction::FormatDocument, chord(ModifierKey::Cmd, "S")),
+        (
+            navigate(NavigationCommand::MatchingBracket),
+            chord(ModifierKey::OptionCmd, "B"),
+        ),
+        (
+            navigate(NavigationCommand::NextDeclaration),
+            chord(ModifierKey::CtrlCmd, "N"),
+        ),
+        (
+            navigate(NavigationCommand::PreviousDeclaration),
+            chord(ModifierKey::CtrlCmd, "P"),
+        ),
+        (
+            navigate(NavigationCommand::NextComplexityHotspot),
+            chord(ModifierKey::OptionCmd, "N"),
+        ),
+        (
+            navigate(NavigationCommand::PreviousComplexityHotspot),
+            chord(ModifierKey::OptionCmd, "P"),
+        ),
+    ]
+}
+
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: HashMap<KeySequence, KeymapAction>,
+    pending_chords: KeySequence,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_shortcuts(&HashMap::new()).0
+    }
+}
+
+impl Keymap {
+    // `shortcuts` maps action names to sequences and overrides the defaults; an empty sequence
+    // unbinds the action. Invalid and conflicting entries are skipped and returned as errors.
+    // A user binding silently replaces a default it conflicts with.
+    pub fn from_shortcuts(shortcuts: &HashMap<String, String>) -> (Self, Vec<KeymapError>) {
+        let mut errors = vec![];
+
+        let mut user_bindings: BTreeMap<String, (KeymapAction, KeySequence)> = BTreeMap::new();
+        let mut unbound_actions = vec![];
+        for (name, sequence) in shortcuts {
+            let action = match KeymapAction::parse(name) {
+                Ok(action) => action,
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                }
+            };
+
+            if sequence.trim().is_empty() {
+                unbound_actions.push(action);
+                continue;
+            }
+
+            match KeyChord::parse_sequence(sequence) {
+                Ok(sequence) => {
+                    user_bindings.insert(name.clone(), (action, sequence));
+                }
+                // The default binding, if any, is kept.
+                Err(err) => errors.push(err),
+            }
+        }
+
+        let mut accepted: Vec<(KeymapAction, KeySequence)> = vec![];
+        for (action, sequence) in user_bindings.into_values() {
+            match accepted
+                .iter()
+                .find(|(_, other_sequence)| sequences_conflict(&sequence, other_sequence))
+            {
+                Some((other_action, _)) => errors.push(KeymapError::Conflict {
+                    shortcut: format_sequence(&sequence),
+                    action: action.to_string(),
+                    other_action: other_action.to_string(),
+                }),
+                None => accepted.push((action, sequence)),
+            }
+        }
+
+        for (action, sequence) in default_bindings() {
+            let is_overridden = accepted.iter().any(|(other_action, other_sequence)| {
+                *other_action == action || sequences_conflict(&sequence, other_sequence)
+            });
+            if !is_overridden && !unbound_actions.contains(&action) {
+                accepted.push((action, sequence));
+            }
+        }
+
+        let keymap = Self {
+            bindings: accepted
+                .into_iter()
+                .map(|(action, sequence)| (sequence, action))
+                .collect(),
+            pending_chords: vec![],
+        };
+
+        (keymap, errors)
+    }
+
+    pub fn resolve(&mut self, chord: KeyChord) -> KeymapResolution {
+        self.pending_chords.push(chord);
+
+        if let Some(action) = self.bindings.get(&self.pending_chords) {
+            let action = action.clone();
+            self.pending_chords.clear();
+            return KeymapResolution::Action(action);
+        }
+
+        if self
+            .bindings
+            .keys()
+            .any(|sequence| sequence.starts_with(&self.pending_chords))
+        {
+            return KeymapResolution::Pending;
+        }
+
+        // The chord does not continue the pending sequence, but it may start a new one.
+        if self.pending_chords.len() > 1 {
+            let chord = self.pending_chords.pop().unwrap();
+            self.pending_chords.clear();
+            return self.resolve(chord);
+        }
+
+        self.pending_chords.clear();
+        KeymapResolution::Unbound
+    }
+}
+
+// Sequences conflict if they are equal or one is a prefix of the other, because the shorter
+// one would always fire first.
+fn sequences_conflict(a: &KeySequence, b: &KeySequence) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+fn format_sequence(sequence: &KeySequence) -> String {
+    sequence
+        .iter()
+        .map(|chord| chord.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    mod from_shortcuts {
+        use std::collections::HashMap;
+
+        use crate::core_engine::{
+            features::{FeatureKind, NavigationCommand},
+            keymap::{Keymap, KeymapAction, KeymapError},
+        };
+
+        use super::super::format_sequence;
+
+        fn shortcuts(entries: &[(&str, &str)]) -> HashMap<String, String> {
+            entries
+                .iter()
+                .map(|(name, sequence)| (name.to_string(), sequence.to_string()))
+                .collect()
+        }
+
+        fn shortcut_for(keymap: &Keymap, action: &KeymapAction) -> Option<String> {
+            keymap
+                .bindings
+                .iter()
+                .find(|(_, bound_action)| *bound_action == action)
+                .map(|(sequence, _)| format_sequence(sequence))
+        }
+
+        #[test]
+        fn defaults() {
+            let (keymap, errors) = Keymap::from_shortcuts(&HashMap::new());
+
+            assert!(errors.is_empty());
+            assert_eq!(
+                shortcut_for(&keymap, &KeymapAction::FormatDocument),
+                Some("cmd+s".to_string())
+            );
+            assert_eq!(
+                shortcut_for(
+                    &keymap,
+                    &KeymapAction::Navigate(NavigationCommand::MatchingBracket)
+                ),
+                Some("option+cmd+b".to_string())
+            );
+            assert_eq!(shortcut_for(&keymap, &KeymapAction::ExplainSelection), None);
+            // Would also move the line in Xcode
+            assert_eq!(
+                shortcut_for(
+                    &keymap,
+                    &KeymapAction::Navigate(NavigationCommand::EnclosingBlockStart)
+                ),
+                None
+            );
+        }
+
+        #[test]
+        fn user_bindings_override_and_unbind_defaults() {
+            let (keymap, errors) = Keymap::from_shortcuts(&shortcuts(&[
+                ("format_document", "shift+cmd+f"),
+                ("explain_selection", "cmd+s"),
+                ("navigate.MatchingBracket", ""),
+            ]));
+
+            assert!(errors.is_empty());
+            assert_eq!(
+                shortcut_for(&keymap, &KeymapAction::FormatDocument),
+                Some("shift+cmd+f".to_string())
+            );
+            assert_eq!(
+                shortcut_for(&keymap, &KeymapAction::ExplainSelection),
+                Some("cmd+s".to_string())
+            );
+            assert_eq!(
+                shortcut_for(
+                    &keymap,
+                    &KeymapAction::Navigate(NavigationCommand::MatchingBracket)
+                ),
+                None
+            );
+        }
+
+        #[test]
+        fn user_default_conflict_keeps_user_binding() {
+            let (keymap, errors) =
+                Keymap::from_shortcuts(&shortcuts(&[("toggle_feature.ScopeGuides", "ctrl+cmd+n")]));
+
+            assert!(errors.is_empty());
+            assert_eq!(
+                shortcut_for(
+                    &keymap,
+                    &KeymapAction::ToggleFeature(FeatureKind::ScopeGuides)
+                ),
+                Some("ctrl+cmd+n".to_string())
+            );
+            assert_eq!(
+                shortcut_for(
+                    &keymap,
+                    &KeymapAction::Navigate(NavigationCommand::NextDeclaration)
+                ),
+                None
+            );
+        }
+
+        #[test]
+        fn conflicting_user_bindings() {
+            let (keymap, errors) = Keymap::from_shortcuts(&shortcuts(&[
+                ("next_suggestion", "cmd+k"),
+                ("perform_suggestion", "cmd+k cmd+p"),
+            ]));
+
+            assert_eq!(
+                errors,
+                vec![KeymapError::Conflict {
+                    shortcut: "cmd+k cmd+p".to_string(),
+                    action: "perform_suggestion".to_string(),
+                    other_action: "next_suggestion".to_string(),
+                }]
+            );
+            assert_eq!(
+                shortcut_for(&keymap, &KeymapAction::NextSuggestion),
+                Some("cmd+k".to_string())
+            );
+        }
+
+        #[test]
+        fn invalid_entries() {
+            let (keymap, errors) = Keymap::from_shortcuts(&shortcuts(&[
+                ("launch_rockets", "cmd+r"),
+                ("format_document", "super+s"),
+            ]));
+
+            assert_eq!(errors.len(), 2);
+            assert_eq!(
+                shortcut_for(&keymap, &KeymapAction::FormatDocument),
+                Some("cmd+s".to_string())
+            );
+        }
+    }
+
+    mod resolve {
+        use std::collections::HashMap;
+
+        use crate::{
+            core_engine::keymap::{KeyChord, Keymap, KeymapAction, KeymapResolution},
+            platform::macos::models::editor::ModifierKey,
+        };
+
+        #[test]
+        fn chorded_sequence() {
+            let (mut keymap, _) = Keymap::from_shortcuts(&HashMap::from([(
+                "explain_selection".to_string(),
+                "cmd+k cmd+e".to_string(),
+            )]));
+            let cmd_k = KeyChord::new(ModifierKey::Cmd, "k");
+            let cmd_e = KeyChord::new(ModifierKey::Cmd, "e");
+            let cmd_s = KeyChord::new(ModifierKey::Cmd, "s");
+
+            assert_eq!(keymap.resolve(cmd_k.clone()), KeymapResolution::Pending);
+            assert_eq!(
+                keymap.resolve(cmd_e.clone()),
+                KeymapResolution::Action(KeymapAction::ExplainSelection)
+            );
+            assert_eq!(keymap.resolve(cmd_e), KeymapResolution::Unbound);
+
+            // An unrelated chord abandons the pending sequence and is resolved on its own.
+            assert_eq!(keymap.resolve(cmd_k), KeymapResolution::Pending);
+            assert_eq!(
+                keymap.resolve(cmd_s),
+                KeymapResolution::Action(KeymapAction::FormatDocument)
+            );
+        }
+    }
+}
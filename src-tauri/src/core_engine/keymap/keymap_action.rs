@@ -0,0 +1,66 @@
+use std::fmt;
+
+use strum::IntoEnumIterator;
+
+use crate::core_engine::features::{FeatureKind, NavigationCommand};
+
+use super::KeymapError;
+
+// Named engine actions a shortcut can be bound to. In the settings they are referred to by
+// name, e.g. `format_document`, `toggle_feature.ScopeGuides` or `navigate.MatchingBracket`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum KeymapAction {
+    FormatDocument,
+    ExplainSelection,
+    PerformSelectedSuggestion,
+    DismissSelectedSuggestion,
+    NextSuggestion,
+    ToggleFeature(FeatureKind),
+    Navigate(NavigationCommand),
+}
+
+const TOGGLE_FEATURE_PREFIX: &str = "toggle_feature.";
+const NAVIGATE_PREFIX: &str = "navigate.";
+
+impl KeymapAction {
+    pub fn parse(name: &str) -> Result<Self, KeymapError> {
+        let action = match name {
+            "format_document" => Some(KeymapAction::FormatDocument),
+            "explain_selection" => Some(KeymapAction::ExplainSelection),
+            "perform_suggestion" => Some(KeymapAction::PerformSelectedSuggestion),
+            "dismiss_suggestion" => Some(KeymapAction::DismissSelectedSuggestion),
+            "next_suggestion" => Some(KeymapAction::NextSuggestion),
+            _ => {
+                if let Some(feature_name) = name.strip_prefix(TOGGLE_FEATURE_PREFIX) {
+                    FeatureKind::iter()
+                        .find(|feature_kind| feature_kind.to_string() == feature_name)
+                        .map(KeymapAction::ToggleFeature)
+                } else if let Some(command_name) = name.strip_prefix(NAVIGATE_PREFIX) {
+                    NavigationCommand::iter()
+                        .find(|command| command.to_string() == command_name)
+                        .map(KeymapAction::Navigate)
+                } else {
+                    None
+                }
+            }
+        };
+
+        action.ok_or_else(|| KeymapError::UnknownAction(name.to_string()))
+    }
+}
+
+impl fmt::Display for KeymapAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeymapAction::FormatDocument => write!(f, "format_document"),
+            KeymapAction::ExplainSelection => write!(f, "explain_selection"),
+            KeymapAction::PerformSelectedSuggestion => write!(f, "perform_suggestion"),
+            KeymapAction::DismissSelectedSuggestion => write!(f, "dismiss_suggestion"),
+            KeymapAction::NextSuggestion => write!(f, "next_suggestion"),
+            KeymapAction::ToggleFeature(feature_kind) => {
+                write!(f, "{}{}", TOGGLE_FEATURE_PREFIX, feature_kind)
+            }
+            KeymapAction::Navigate(command) => write!(f, "{}{}", NAVIGATE_PREFIX, command),
+        }
+    }
+}
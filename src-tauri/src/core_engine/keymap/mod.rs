@@ -0,0 +1,7 @@
+pub use key_chord::{KeyChord, KeySequence};
+pub use keymap::{Keymap, KeymapError, KeymapResolution};
+pub use keymap_action::KeymapAction;
+
+mod key_chord;
+mod keymap;
+mod keymap_action;
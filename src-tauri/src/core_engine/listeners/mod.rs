@@ -1,2 +1,3 @@
+pub mod settings;
 pub mod user_interaction;
 pub mod xcode;
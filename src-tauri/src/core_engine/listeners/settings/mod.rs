@@ -0,0 +1,3 @@
+pub use settings_listener::settings_listener;
+
+mod settings_listener;
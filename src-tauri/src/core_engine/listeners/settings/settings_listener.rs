@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tauri::Manager;
+
+use crate::{
+    app_handle, app_state::SettingsEvent, core_engine::CoreEngine, utils::messaging::ChannelList,
+};
+
+pub fn settings_listener(core_engine: &Arc<Mutex<CoreEngine>>) {
+    app_handle().listen_global(ChannelList::SettingsEvent.to_string(), {
+        let core_engine = (core_engine).clone();
+        move |msg| {
+            let settings_event: SettingsEvent =
+                serde_json::from_str(&msg.payload().unwrap()).unwrap();
+            match settings_event {
+                SettingsEvent::SettingsChanged(settings) => {
+                    core_engine.lock().apply_settings(&settings);
+                }
+            }
+        }
+    });
+}
@@ -1,5 +0,0 @@
-pub use on_ai_features_activation_status_update::on_ai_features_activation_status_update;
-pub use on_swift_format_on_cmd_s_activation_status_update::on_swift_format_on_cmd_s_activation_status_update;
-
-mod on_ai_features_activation_status_update;
-mod on_swift_format_on_cmd_s_activation_status_update;
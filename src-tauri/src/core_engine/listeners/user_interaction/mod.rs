@@ -1,4 +1,3 @@
 pub use user_interaction_listener::user_interaction_listener;
 
-mod handlers;
 mod user_interaction_listener;
@@ -13,10 +13,6 @@ use parking_lot::Mutex;
 use tauri::Manager;
 use tracing::info;
 
-use super::handlers::{
-    on_ai_features_activation_status_update, on_swift_format_on_cmd_s_activation_status_update,
-};
-
 pub fn user_interaction_listener(core_engine: &Arc<Mutex<CoreEngine>>) {
     app_handle().listen_global(ChannelList::EventUserInteractions.to_string(), {
         let core_engine = (core_engine).clone();
@@ -24,12 +20,6 @@ pub fn user_interaction_listener(core_engine: &Arc<Mutex<CoreEngine>>) {
             let event_user_interaction: EventUserInteraction =
                 serde_json::from_str(&msg.payload().unwrap()).unwrap();
             match event_user_interaction {
-                EventUserInteraction::AiFeaturesStatus(msg) => {
-                    on_ai_features_activation_status_update(&core_engine, &msg);
-                }
-                EventUserInteraction::SwiftFormatOnCMDS(msg) => {
-                    on_swift_format_on_cmd_s_activation_status_update(&core_engine, &msg);
-                }
                 EventUserInteraction::NodeAnnotationClicked(msg) => {
                     info!(
                         ?msg,
@@ -75,6 +65,115 @@ pub fn user_interaction_listener(core_engine: &Arc<Mutex<CoreEngine>>) {
                         CoreEngineTrigger::OnUserCommand(UserCommand::SelectSuggestion(msg)),
                     );
                 }
+                EventUserInteraction::Navigate(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::StructuralNavigation.to_string(),
+                        "User request: Navigate"
+                    );
+                    _ = core_engine.lock().handle_trigger(
+                        msg.editor_window_uid,
+                        CoreEngineTrigger::OnUserCommand(UserCommand::Navigate(msg)),
+                    );
+                }
+                EventUserInteraction::Search(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::StructuralSearch.to_string(),
+                        "User request: Search"
+                    );
+                    _ = core_engine.lock().handle_trigger_for_all_documents(
+                        CoreEngineTrigger::OnUserCommand(UserCommand::Search(msg)),
+                    );
+                }
+                EventUserInteraction::PreviewReplace(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::StructuralSearch.to_string(),
+                        "User request: Preview replace"
+                    );
+                    _ = core_engine.lock().handle_trigger(
+                        msg.editor_window_uid,
+                        CoreEngineTrigger::OnUserCommand(UserCommand::PreviewReplace(msg)),
+                    );
+                }
+                EventUserInteraction::PerformReplace(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::StructuralSearch.to_string(),
+                        "User request: Perform replace"
+                    );
+                    _ = core_engine.lock().handle_trigger(
+                        msg.editor_window_uid,
+                        CoreEngineTrigger::OnUserCommand(UserCommand::PerformReplace(msg)),
+                    );
+                }
+                EventUserInteraction::PreviewRenameSymbol(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::ComplexityRefactoring.to_string(),
+                        "User request: Preview rename symbol"
+                    );
+                    _ = core_engine.lock().handle_trigger(
+                        msg.editor_window_uid,
+                        CoreEngineTrigger::OnUserCommand(UserCommand::PreviewRenameSymbol(msg)),
+                    );
+                }
+                EventUserInteraction::PerformRenameSymbol(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::ComplexityRefactoring.to_string(),
+                        "User request: Perform rename symbol"
+                    );
+                    _ = core_engine.lock().handle_trigger(
+                        msg.editor_window_uid,
+                        CoreEngineTrigger::OnUserCommand(UserCommand::PerformRenameSymbol(msg)),
+                    );
+                }
+                EventUserInteraction::PreviewLocalRefactoring(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::ComplexityRefactoring.to_string(),
+                        "User request: Preview local refactoring"
+                    );
+                    _ = core_engine.lock().handle_trigger(
+                        msg.editor_window_uid,
+                        CoreEngineTrigger::OnUserCommand(UserCommand::PreviewLocalRefactoring(msg)),
+                    );
+                }
+                EventUserInteraction::PerformLocalRefactoring(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::ComplexityRefactoring.to_string(),
+                        "User request: Perform local refactoring"
+                    );
+                    _ = core_engine.lock().handle_trigger(
+                        msg.editor_window_uid,
+                        CoreEngineTrigger::OnUserCommand(UserCommand::PerformLocalRefactoring(msg)),
+                    );
+                }
+                EventUserInteraction::ApplyQuickFix(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::DeadCode.to_string(),
+                        "User request: Apply quick fix"
+                    );
+                    _ = core_engine.lock().handle_trigger(
+                        msg.editor_window_uid,
+                        CoreEngineTrigger::OnUserCommand(UserCommand::ApplyQuickFix(msg)),
+                    );
+                }
+                EventUserInteraction::DetectClones(msg) => {
+                    info!(
+                        ?msg,
+                        feature = FeatureKind::CloneDetection.to_string(),
+                        "User request: Detect clones"
+                    );
+                    _ = core_engine.lock().handle_trigger(
+                        msg.editor_window_uid,
+                        CoreEngineTrigger::OnUserCommand(UserCommand::DetectClones(msg)),
+                    );
+                }
                 _ => {}
             }
         }
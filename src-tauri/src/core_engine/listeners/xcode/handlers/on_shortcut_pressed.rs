@@ -3,7 +3,7 @@ use std::sync::Arc;
 use parking_lot::Mutex;
 
 use crate::{
-    core_engine::{core_engine::CoreEngineError, features::CoreEngineTrigger, CoreEngine},
+    core_engine::{core_engine::CoreEngineError, CoreEngine},
     platform::macos::models::editor::EditorShortcutPressedMessage,
 };
 
@@ -11,10 +11,5 @@ pub fn on_editor_shortcut_pressed(
     core_engine_arc: &Arc<Mutex<CoreEngine>>,
     msg: &EditorShortcutPressedMessage,
 ) -> Result<(), CoreEngineError> {
-    let mut core_engine = core_engine_arc.lock();
-
-    core_engine.handle_trigger(
-        msg.window_uid,
-        CoreEngineTrigger::OnShortcutPressed(msg.clone()),
-    )
+    core_engine_arc.lock().handle_shortcut_pressed(msg)
 }
@@ -1,15 +1,21 @@
 pub use code_document::*;
 pub use core_engine::CoreEngine;
 pub use core_engine::EditorWindowUid;
+pub use diagnostics::{cmd_dump_diagnostics, cmd_get_diagnostics};
 pub use features::cmd_paste_docs;
+pub use features::FeatureKind;
+pub use keymap::Keymap;
 pub use utils::*;
 
 mod annotations_manager;
 mod code_document;
 mod core_engine;
+mod diagnostics;
 pub mod events;
 mod features;
+mod keymap;
 mod listeners;
 mod rules;
-mod syntax_tree;
+mod scheduler;
+pub mod syntax_tree;
 mod utils;
@@ -13,6 +13,7 @@ pub enum RuleName {
     BracketHighlight,
     SearchAndReplace,
     SwiftLinter,
+    DeadCode,
     None,
 }
 
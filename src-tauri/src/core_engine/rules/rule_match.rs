@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use crate::{
+    core_engine::features::Edit,
     platform::macos::{calc_rectangles_and_line_matches, GetVia},
     utils::{
         geometry::LogicalFrame,
@@ -19,6 +20,14 @@ pub struct RuleMatchProps {
     pub category: RuleMatchCategory,
 }
 
+// Edits which resolve the match, applied to the text the match was computed on.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/rules/")]
+pub struct RuleMatchQuickFix {
+    pub description: String,
+    pub edits: Vec<Edit>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "bindings/rules/")]
 pub struct RuleMatch {
@@ -28,6 +37,7 @@ pub struct RuleMatch {
     rectangles: Vec<LogicalFrame>,
     rule_name: RuleName,
     match_properties: RuleMatchProps,
+    quick_fix: Option<RuleMatchQuickFix>,
 }
 
 impl RuleMatch {
@@ -43,9 +53,27 @@ impl RuleMatch {
             id: uuid::Uuid::new_v4(),
             rule_name,
             match_properties,
+            quick_fix: None,
         }
     }
 
+    pub fn with_quick_fix(mut self, quick_fix: RuleMatchQuickFix) -> Self {
+        self.quick_fix = Some(quick_fix);
+        self
+    }
+
+    pub fn id(&self) -> uuid::Uuid {
+        self.id
+    }
+
+    pub fn match_properties(&self) -> &RuleMatchProps {
+        &self.match_properties
+    }
+
+    pub fn quick_fix(&self) -> Option<&RuleMatchQuickFix> {
+        self.quick_fix.as_ref()
+    }
+
     #[allow(unused)]
     pub fn match_range(&self) -> &MatchRange {
         &self.match_range
@@ -137,7 +137,7 @@ mod tests_TextConversions {
 
 pub fn get_index_of_next_row(index: usize, text: &XcodeText) -> Option<usize> {
     let mut i = 0;
-    for c in text[index..].to_vec() {
+    for c in text.slice(index..).to_vec() {
         if c == '\n' as XcodeChar {
             return Some(index + i + 1);
         }
@@ -146,6 +146,38 @@ pub fn get_index_of_next_row(index: usize, text: &XcodeText) -> Option<usize> {
     None
 }
 
+#[cfg(test)]
+mod tests_TextConversionProperties {
+    use proptest::prelude::*;
+
+    use crate::core_engine::{
+        rules::utils::text_types::{
+            StartEndIndex_from_StartEndTSPoint, StartEndTSPoint_from_StartEndIndex,
+        },
+        utils::{swift_text_strategies::swift_text, XcodeText},
+    };
+
+    proptest! {
+        #[test]
+        fn StartEndIndex_and_StartEndTSPoint_round_trip(
+            code in swift_text(),
+            a in any::<usize>(),
+            b in any::<usize>(),
+        ) {
+            let text = XcodeText::from_str(&code);
+            let (a, b) = (a % (text.len() + 1), b % (text.len() + 1));
+            let (start_index, end_index) = (a.min(b), a.max(b));
+
+            let (start_point, end_point) =
+                StartEndTSPoint_from_StartEndIndex(&text, start_index, end_index).unwrap();
+            prop_assert_eq!(
+                StartEndIndex_from_StartEndTSPoint(&text, &start_point, &end_point),
+                Some((start_index, end_index))
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests_Text {
     use crate::core_engine::{rules::get_index_of_next_row, XcodeText};
@@ -0,0 +1,68 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use tokio::sync::Notify;
+
+// Handed to a feature with every procedure. The scheduler cancels it as soon as a newer procedure
+// of the same feature is scheduled for the same window, or the feature gets disabled.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<CancellationState>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        if !self.state.cancelled.swap(true, Ordering::SeqCst) {
+            self.state.notify.notify_waiters();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub async fn cancelled(&self) {
+        loop {
+            // Register before checking the flag, otherwise a cancel in between would be missed.
+            let notified = self.state.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod cancel {
+        use tauri::async_runtime::block_on;
+
+        use crate::core_engine::scheduler::CancellationToken;
+
+        #[test]
+        fn cancels_all_clones() {
+            let token = CancellationToken::new();
+            let clone = token.clone();
+
+            assert!(!clone.is_cancelled());
+            token.cancel();
+            token.cancel();
+
+            assert!(clone.is_cancelled());
+            block_on(clone.cancelled());
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+use std::sync::Arc;
+
+#[cfg(test)]
+use parking_lot::Mutex;
+
+// Time source of the scheduler, measured from an arbitrary starting point.
+pub trait Clock: Send {
+    fn now(&self) -> Duration;
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+// Only moves when advanced, so tests observe debouncing without sleeping.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct VirtualClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+#[cfg(test)]
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock() += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for VirtualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock()
+    }
+}
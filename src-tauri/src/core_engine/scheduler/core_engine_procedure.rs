@@ -0,0 +1,82 @@
+use std::{
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
+use crate::core_engine::{
+    features::{CoreEngineTrigger, FeatureKind},
+    EditorWindowUid,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProcedurePriority {
+    Background,
+    Normal,
+    Interactive,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreEngineProcedure {
+    pub feature: FeatureKind,
+    pub trigger: CoreEngineTrigger,
+    pub window_uid: EditorWindowUid,
+}
+
+impl CoreEngineProcedure {
+    pub fn new(
+        feature: FeatureKind,
+        trigger: CoreEngineTrigger,
+        window_uid: EditorWindowUid,
+    ) -> Self {
+        Self {
+            feature,
+            trigger,
+            window_uid,
+        }
+    }
+
+    pub fn hash(&self) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.trigger.hash(&mut hasher);
+        self.feature.hash(&mut hasher);
+        self.window_uid.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Whatever the user explicitly asked for runs first, followed by what keeps the editor
+    // decorations in sync. Refactoring suggestions are computed last.
+    pub fn priority(&self) -> ProcedurePriority {
+        if matches!(
+            self.trigger,
+            CoreEngineTrigger::OnShortcutPressed(_) | CoreEngineTrigger::OnUserCommand(_)
+        ) {
+            return ProcedurePriority::Interactive;
+        }
+
+        match self.feature {
+            FeatureKind::BracketHighlight
+            | FeatureKind::ScopeGuides
+            | FeatureKind::StickyScopeHeader
+            | FeatureKind::StructuralNavigation => ProcedurePriority::Interactive,
+            FeatureKind::DocsGeneration
+            | FeatureKind::Formatter
+            | FeatureKind::DocumentOutline
+            | FeatureKind::StructuralSearch
+            | FeatureKind::DeadCode => ProcedurePriority::Normal,
+            FeatureKind::ComplexityRefactoring | FeatureKind::CloneDetection => {
+                ProcedurePriority::Background
+            }
+        }
+    }
+
+    // How long the procedure waits for newer triggers replacing it before it runs.
+    pub fn debounce(&self) -> Duration {
+        match (&self.feature, &self.trigger) {
+            (
+                FeatureKind::ComplexityRefactoring | FeatureKind::CloneDetection,
+                CoreEngineTrigger::OnTextContentChange,
+            ) => Duration::from_millis(50),
+            _ => Duration::ZERO,
+        }
+    }
+}
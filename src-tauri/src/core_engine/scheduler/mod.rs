@@ -0,0 +1,9 @@
+pub use cancellation_token::CancellationToken;
+pub use clock::{Clock, SystemClock};
+pub use core_engine_procedure::{CoreEngineProcedure, ProcedurePriority};
+pub use procedure_scheduler::{ProcedureScheduler, ScheduledProcedure};
+
+mod cancellation_token;
+mod clock;
+mod core_engine_procedure;
+mod procedure_scheduler;
@@ -0,0 +1,496 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use crate::core_engine::{features::FeatureKind, EditorWindowUid};
+
+use super::{CancellationToken, Clock, CoreEngineProcedure, ProcedurePriority, SystemClock};
+
+pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+// Typing triggers both a text content and a selection change; waiting this long before updating
+// the code document lets the second one replace the first. It is three because we are three
+// cofounders.
+pub const CODE_DOC_UPDATE_DEBOUNCE: Duration = Duration::from_millis(3);
+
+pub type ProcedureId = u64;
+
+#[derive(Debug, Clone)]
+pub struct ScheduledProcedure {
+    pub id: ProcedureId,
+    pub procedure: CoreEngineProcedure,
+    pub cancellation_token: CancellationToken,
+}
+
+struct PendingProcedure {
+    procedure: CoreEngineProcedure,
+    priority: ProcedurePriority,
+    ready_at: Duration,
+    sequence: u64,
+}
+
+// Decides which procedures run when; spawning them is left to the caller. Nothing in here
+// sleeps or reads the wall clock directly, so the order of execution is fully determined by
+// the scheduled procedures and the clock.
+pub struct ProcedureScheduler<C: Clock = SystemClock> {
+    clock: C,
+    max_concurrency: usize,
+
+    /// Procedures waiting for their debounce window to pass. A newly scheduled procedure replaces an
+    /// identical pending one and restarts its debounce window.
+    pending: HashMap<u64, PendingProcedure>,
+    running: HashSet<ProcedureId>,
+
+    /// The token of the latest procedure per feature and window.
+    cancellation_tokens: HashMap<(FeatureKind, EditorWindowUid), CancellationToken>,
+
+    /// Windows whose code document is going to be updated from the editor, and when. Procedures of
+    /// a window only start once its code document is up to date.
+    pending_code_doc_updates: HashMap<EditorWindowUid, Duration>,
+    running_code_doc_updates: HashMap<EditorWindowUid, CancellationToken>,
+
+    /// The deadline a timer is already waiting for, to avoid spawning one per scheduled procedure.
+    timer_deadline: Option<Duration>,
+    next_sequence: u64,
+}
+
+impl ProcedureScheduler<SystemClock> {
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock::new(), DEFAULT_MAX_CONCURRENCY)
+    }
+}
+
+impl<C: Clock> ProcedureScheduler<C> {
+    pub fn with_clock(clock: C, max_concurrency: usize) -> Self {
+        Self {
+            clock,
+            max_concurrency: max_concurrency.max(1),
+            pending: HashMap::new(),
+            running: HashSet::new(),
+            cancellation_tokens: HashMap::new(),
+            pending_code_doc_updates: HashMap::new(),
+            running_code_doc_updates: HashMap::new(),
+            timer_deadline: None,
+            next_sequence: 0,
+        }
+    }
+
+    pub fn schedule(&mut self, procedure: CoreEngineProcedure) {
+        if let Some(token) = self
+            .cancellation_tokens
+            .remove(&(procedure.feature.clone(), procedure.window_uid))
+        {
+            token.cancel();
+        }
+
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+
+        self.pending.insert(
+            procedure.hash(),
+            PendingProcedure {
+                priority: procedure.priority(),
+                ready_at: self.clock.now() + procedure.debounce(),
+                procedure,
+                sequence,
+            },
+        );
+    }
+
+    // Restarts the debounce window of a pending update and cancels a running one, which would
+    // read an outdated text.
+    pub fn schedule_code_doc_update(&mut self, window_uid: EditorWindowUid) {
+        if let Some(token) = self.running_code_doc_updates.remove(&window_uid) {
+            token.cancel();
+        }
+
+        self.pending_code_doc_updates
+            .insert(window_uid, self.clock.now() + CODE_DOC_UPDATE_DEBOUNCE);
+    }
+
+    // Returns the windows whose code document should be updated now.
+    pub fn take_ready_code_doc_updates(&mut self) -> Vec<(EditorWindowUid, CancellationToken)> {
+        let now = self.clock.now();
+
+        let ready: Vec<EditorWindowUid> = self
+            .pending_code_doc_updates
+            .iter()
+            .filter(|(_, ready_at)| **ready_at <= now)
+            .map(|(window_uid, _)| *window_uid)
+            .collect();
+
+        ready
+            .into_iter()
+            .map(|window_uid| {
+                self.pending_code_doc_updates.remove(&window_uid);
+                let cancellation_token = CancellationToken::new();
+                self.running_code_doc_updates
+                    .insert(window_uid, cancellation_token.clone());
+                (window_uid, cancellation_token)
+            })
+            .collect()
+    }
+
+    pub fn complete_code_doc_update(
+        &mut self,
+        window_uid: EditorWindowUid,
+        cancellation_token: &CancellationToken,
+    ) {
+        // A cancelled update has already been replaced by a newer one.
+        if !cancellation_token.is_cancelled() {
+            self.running_code_doc_updates.remove(&window_uid);
+        }
+    }
+
+    fn is_code_doc_updating(&self, window_uid: EditorWindowUid) -> bool {
+        self.pending_code_doc_updates.contains_key(&window_uid)
+            || self.running_code_doc_updates.contains_key(&window_uid)
+    }
+
+    // Returns the procedures to start now, highest priority first. Procedures of equal priority
+    // start in the order their debounce windows ended, then in the order they were scheduled.
+    pub fn take_ready(&mut self) -> Vec<ScheduledProcedure> {
+        let now = self.clock.now();
+
+        let mut ready: Vec<(u64, Reverse<ProcedurePriority>, Duration, u64)> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| {
+                pending.ready_at <= now && !self.is_code_doc_updating(pending.procedure.window_uid)
+            })
+            .map(|(key, pending)| {
+                (
+                    *key,
+                    Reverse(pending.priority),
+                    pending.ready_at,
+                    pending.sequence,
+                )
+            })
+            .collect();
+        ready.sort_by_key(|(_, priority, ready_at, sequence)| (*priority, *ready_at, *sequence));
+
+        let free_slots = self.max_concurrency.saturating_sub(self.running.len());
+
+        ready
+            .into_iter()
+            .take(free_slots)
+            .filter_map(|(key, ..)| self.pending.remove(&key))
+            .map(|pending| {
+                let cancellation_token = CancellationToken::new();
+                self.cancellation_tokens.insert(
+                    (
+                        pending.procedure.feature.clone(),
+                        pending.procedure.window_uid,
+                    ),
+                    cancellation_token.clone(),
+                );
+                self.running.insert(pending.sequence);
+
+                ScheduledProcedure {
+                    id: pending.sequence,
+                    procedure: pending.procedure,
+                    cancellation_token,
+                }
+            })
+            .collect()
+    }
+
+    pub fn complete(&mut self, id: ProcedureId) {
+        self.running.remove(&id);
+    }
+
+    // Returns how long to wait before procedures or code document updates still in their debounce
+    // window become ready, unless a timer is already set for that moment or earlier.
+    pub fn arm_timer(&mut self) -> Option<Duration> {
+        let now = self.clock.now();
+        let deadline = self
+            .pending
+            .values()
+            .map(|pending| pending.ready_at)
+            .chain(self.pending_code_doc_updates.values().cloned())
+            .filter(|ready_at| *ready_at > now)
+            .min()?;
+
+        if matches!(self.timer_deadline, Some(armed) if armed > now && armed <= deadline) {
+            return None;
+        }
+
+        self.timer_deadline = Some(deadline);
+        Some(deadline - now)
+    }
+
+    pub fn cancel_feature(&mut self, feature: &FeatureKind) {
+        self.pending
+            .retain(|_, pending| pending.procedure.feature != *feature);
+        self.cancellation_tokens
+            .retain(|(token_feature, _), token| {
+                if token_feature == feature {
+                    token.cancel();
+                }
+                token_feature != feature
+            });
+    }
+
+    pub fn remove_window(&mut self, window_uid: EditorWindowUid) {
+        self.pending
+            .retain(|_, pending| pending.procedure.window_uid != window_uid);
+        self.pending_code_doc_updates.remove(&window_uid);
+        if let Some(token) = self.running_code_doc_updates.remove(&window_uid) {
+            token.cancel();
+        }
+        self.cancellation_tokens
+            .retain(|(_, token_window_uid), token| {
+                if *token_window_uid == window_uid {
+                    token.cancel();
+                }
+                *token_window_uid != window_uid
+            });
+    }
+
+    #[cfg(test)]
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::core_engine::{
+        features::{CoreEngineTrigger, FeatureKind},
+        scheduler::{clock::VirtualClock, CoreEngineProcedure, ProcedureScheduler},
+    };
+
+    fn scheduler(max_concurrency: usize) -> ProcedureScheduler<VirtualClock> {
+        ProcedureScheduler::with_clock(VirtualClock::new(), max_concurrency)
+    }
+
+    fn procedure(feature: FeatureKind, trigger: CoreEngineTrigger) -> CoreEngineProcedure {
+        CoreEngineProcedure::new(feature, trigger, 1)
+    }
+
+    fn features(scheduled: &[super::ScheduledProcedure]) -> Vec<FeatureKind> {
+        scheduled
+            .iter()
+            .map(|scheduled| scheduled.procedure.feature.clone())
+            .collect()
+    }
+
+    mod take_ready {
+        use super::*;
+
+        #[test]
+        fn orders_by_priority() {
+            let mut scheduler = scheduler(4);
+            scheduler.schedule(procedure(
+                FeatureKind::DocumentOutline,
+                CoreEngineTrigger::OnTextSelectionChange,
+            ));
+            scheduler.schedule(procedure(
+                FeatureKind::BracketHighlight,
+                CoreEngineTrigger::OnTextSelectionChange,
+            ));
+            scheduler.schedule(procedure(
+                FeatureKind::ScopeGuides,
+                CoreEngineTrigger::OnTextSelectionChange,
+            ));
+
+            assert_eq!(
+                features(&scheduler.take_ready()),
+                vec![
+                    FeatureKind::BracketHighlight,
+                    FeatureKind::ScopeGuides,
+                    FeatureKind::DocumentOutline
+                ]
+            );
+            assert!(scheduler.take_ready().is_empty());
+        }
+
+        #[test]
+        fn waits_for_debounce_window() {
+            let mut scheduler = scheduler(4);
+            scheduler.schedule(procedure(
+                FeatureKind::ComplexityRefactoring,
+                CoreEngineTrigger::OnTextContentChange,
+            ));
+            scheduler.clock().advance(Duration::from_millis(30));
+            scheduler.schedule(procedure(
+                FeatureKind::ComplexityRefactoring,
+                CoreEngineTrigger::OnTextContentChange,
+            ));
+
+            // The second trigger restarted the debounce window.
+            scheduler.clock().advance(Duration::from_millis(30));
+            assert!(scheduler.take_ready().is_empty());
+
+            scheduler.clock().advance(Duration::from_millis(20));
+            assert_eq!(
+                features(&scheduler.take_ready()),
+                vec![FeatureKind::ComplexityRefactoring]
+            );
+        }
+
+        #[test]
+        fn limits_concurrency() {
+            let mut scheduler = scheduler(1);
+            scheduler.schedule(procedure(
+                FeatureKind::DocumentOutline,
+                CoreEngineTrigger::OnTextContentChange,
+            ));
+            scheduler.schedule(procedure(
+                FeatureKind::BracketHighlight,
+                CoreEngineTrigger::OnTextContentChange,
+            ));
+
+            let first = scheduler.take_ready();
+            assert_eq!(features(&first), vec![FeatureKind::BracketHighlight]);
+            assert!(scheduler.take_ready().is_empty());
+
+            scheduler.complete(first[0].id);
+            assert_eq!(
+                features(&scheduler.take_ready()),
+                vec![FeatureKind::DocumentOutline]
+            );
+        }
+    }
+
+    mod schedule {
+        use super::*;
+
+        #[test]
+        fn cancels_superseded_procedure() {
+            let mut scheduler = scheduler(4);
+            scheduler.schedule(procedure(
+                FeatureKind::ComplexityRefactoring,
+                CoreEngineTrigger::OnTextContentChange,
+            ));
+            scheduler.clock().advance(Duration::from_millis(50));
+            let running = scheduler.take_ready();
+
+            scheduler.schedule(procedure(
+                FeatureKind::ComplexityRefactoring,
+                CoreEngineTrigger::OnTextContentChange,
+            ));
+
+            assert!(running[0].cancellation_token.is_cancelled());
+        }
+    }
+
+    mod code_doc_updates {
+        use super::*;
+
+        #[test]
+        fn waits_for_debounce_window() {
+            let mut scheduler = scheduler(4);
+            scheduler.schedule_code_doc_update(1);
+            scheduler.clock().advance(Duration::from_millis(2));
+            scheduler.schedule_code_doc_update(1);
+
+            // The second trigger restarted the debounce window.
+            scheduler.clock().advance(Duration::from_millis(2));
+            assert!(scheduler.take_ready_code_doc_updates().is_empty());
+
+            scheduler.clock().advance(Duration::from_millis(1));
+            let ready = scheduler.take_ready_code_doc_updates();
+            assert_eq!(ready.len(), 1);
+            assert_eq!(ready[0].0, 1);
+            assert!(scheduler.take_ready_code_doc_updates().is_empty());
+        }
+
+        #[test]
+        fn procedures_wait_for_code_doc_update_of_their_window() {
+            let mut scheduler = scheduler(4);
+            scheduler.schedule(procedure(
+                FeatureKind::BracketHighlight,
+                CoreEngineTrigger::OnTextSelectionChange,
+            ));
+            scheduler.schedule(CoreEngineProcedure::new(
+                FeatureKind::ScopeGuides,
+                CoreEngineTrigger::OnTextSelectionChange,
+                2,
+            ));
+            scheduler.schedule_code_doc_update(1);
+
+            assert_eq!(
+                features(&scheduler.take_ready()),
+                vec![FeatureKind::ScopeGuides]
+            );
+
+            scheduler.clock().advance(Duration::from_millis(3));
+            let updates = scheduler.take_ready_code_doc_updates();
+            assert!(scheduler.take_ready().is_empty());
+
+            scheduler.complete_code_doc_update(1, &updates[0].1);
+            assert_eq!(
+                features(&scheduler.take_ready()),
+                vec![FeatureKind::BracketHighlight]
+            );
+        }
+
+        #[test]
+        fn cancels_superseded_code_doc_update() {
+            let mut scheduler = scheduler(4);
+            scheduler.schedule(procedure(
+                FeatureKind::BracketHighlight,
+                CoreEngineTrigger::OnTextSelectionChange,
+            ));
+            scheduler.schedule_code_doc_update(1);
+            scheduler.clock().advance(Duration::from_millis(3));
+            let first = scheduler.take_ready_code_doc_updates();
+
+            scheduler.schedule_code_doc_update(1);
+            assert!(first[0].1.is_cancelled());
+
+            // The cancelled update finishing does not release the procedures.
+            scheduler.complete_code_doc_update(1, &first[0].1);
+            assert!(scheduler.take_ready().is_empty());
+
+            scheduler.clock().advance(Duration::from_millis(3));
+            let second = scheduler.take_ready_code_doc_updates();
+            scheduler.complete_code_doc_update(1, &second[0].1);
+            assert_eq!(
+                features(&scheduler.take_ready()),
+                vec![FeatureKind::BracketHighlight]
+            );
+        }
+    }
+
+    mod arm_timer {
+        use super::*;
+
+        #[test]
+        fn arms_for_code_doc_updates() {
+            let mut scheduler = scheduler(4);
+            scheduler.schedule_code_doc_update(1);
+
+            assert_eq!(scheduler.arm_timer(), Some(Duration::from_millis(3)));
+        }
+
+        #[test]
+        fn arms_once_per_deadline() {
+            let mut scheduler = scheduler(4);
+            assert_eq!(scheduler.arm_timer(), None);
+
+            scheduler.schedule(procedure(
+                FeatureKind::ComplexityRefactoring,
+                CoreEngineTrigger::OnTextContentChange,
+            ));
+            assert_eq!(scheduler.arm_timer(), Some(Duration::from_millis(50)));
+
+            scheduler.clock().advance(Duration::from_millis(10));
+            assert_eq!(scheduler.arm_timer(), None);
+
+            // Once the timer fired, later deadlines need a new one.
+            scheduler.clock().advance(Duration::from_millis(40));
+            scheduler.take_ready();
+            scheduler.schedule(procedure(
+                FeatureKind::ComplexityRefactoring,
+                CoreEngineTrigger::OnTextContentChange,
+            ));
+            assert_eq!(scheduler.arm_timer(), Some(Duration::from_millis(50)));
+        }
+    }
+}
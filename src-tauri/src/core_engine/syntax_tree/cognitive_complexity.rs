@@ -1,10 +1,13 @@
-use std::{collections::HashMap, ops};
+use std::ops;
 
-use crate::core_engine::XcodeText;
+use crate::{core_engine::XcodeText, utils::calculate_hash};
 use anyhow::anyhow;
 use tree_sitter::Node;
 
-use super::{get_node_text, swift_syntax_tree::NodeMetadata, SwiftCodeBlockError};
+use super::{
+    get_node_text, swift_syntax_tree::NodeMetadata, tree_metadata::TreeMetaData,
+    SwiftCodeBlockError,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Complexities {
@@ -54,7 +57,7 @@ impl ops::Sub for Complexities {
 pub fn calculate_cognitive_complexities(
     node: &Node,
     text_content: &XcodeText,
-    output_node_metadata: &mut HashMap<usize, NodeMetadata>,
+    output_node_metadata: &mut TreeMetaData,
     starting_depth: Option<isize>,
 ) -> Result<Complexities, SwiftCodeBlockError> {
     calculate_cognitive_complexities_intl(
@@ -66,22 +69,42 @@ pub fn calculate_cognitive_complexities(
     )
 }
 
-// Iterate through subtrees of a node and save an entry for the accumulated complexity of each node and its children, hashed by id in node_complexities
+// Iterate through subtrees of a node and save an entry for the accumulated complexity of each node and its children in output_node_metadata.
+// Subtrees whose metadata is still present from a previous parse are skipped, if they are in the same context.
 fn calculate_cognitive_complexities_intl(
     node: &Node,
     text_content: &XcodeText,
-    output_node_metadata: &mut HashMap<usize, NodeMetadata>,
+    output_node_metadata: &mut TreeMetaData,
     mut nesting_depth: isize,
     mut parent_function_names: Vec<XcodeText>,
 ) -> Result<Complexities, SwiftCodeBlockError> {
+    let context = calculate_hash(&(
+        nesting_depth,
+        &parent_function_names,
+        node.parent().map(|parent| parent.kind_id()),
+    ));
+    if let Some(metadata) = output_node_metadata.get_reusable(node, context) {
+        return Ok(metadata.complexities.clone());
+    }
+
     let mut complexity = Complexities::new();
     match node.kind() {
-        "function_declaration" | "lambda_literal" => {
+        "function_declaration"
+        | "lambda_literal"
+        | "init_declaration"
+        | "deinit_declaration"
+        | "subscript_declaration" => {
             nesting_depth += 1;
             if let Some(name) = get_function_name(node, text_content).ok() {
                 parent_function_names.push(name);
             }
         }
+        "computed_property" | "willset_clause" | "didset_clause" => {
+            // The body of a subscript is a computed property as well; it is already nested
+            if node.parent().map(|parent| parent.kind()) != Some("subscript_declaration") {
+                nesting_depth += 1;
+            }
+        }
         "ternary_expression" => {
             complexity.nesting_complexity += (nesting_depth - 1).max(0);
             complexity.fundamental_complexity += 1;
@@ -158,10 +181,11 @@ fn calculate_cognitive_complexities_intl(
         )?
     }
     output_node_metadata.insert(
-        node.id(),
+        node,
         NodeMetadata {
             complexities: complexity.clone(),
         },
+        context,
     );
     Ok(complexity)
 }
@@ -196,13 +220,12 @@ fn control_transfer_statement_is_penalizable(control_transfer_statement: &Node)
 #[cfg(test)]
 mod tests {
     mod calculate_cognitive_complexities {
-        use std::collections::HashMap;
-
         use tree_sitter::Parser;
 
         use crate::core_engine::{
             syntax_tree::{
-                calculate_cognitive_complexities, swift_syntax_tree::NodeMetadata, Complexities,
+                calculate_cognitive_complexities, tree_metadata::TreeMetaData, Complexities,
+                SwiftSyntaxTree,
             },
             XcodeText,
         };
@@ -238,12 +261,12 @@ mod tests {
             parser
                 .set_language(tree_sitter_swift::language())
                 .expect("Swift Language not found");
-            let tree = parser.parse_utf16(text_content.clone(), None).unwrap();
+            let tree = SwiftSyntaxTree::parse(&mut parser, &text_content, None).unwrap();
             let expected_complexity: Complexities = Complexities {
                 nesting_complexity: 5,
                 fundamental_complexity: 6,
             };
-            let mut node_metadata = HashMap::<usize, NodeMetadata>::new();
+            let mut node_metadata = TreeMetaData::default();
             let calculated_complexity = calculate_cognitive_complexities(
                 &tree.root_node(),
                 &text_content,
@@ -253,10 +276,7 @@ mod tests {
             assert_eq!(expected_complexity, calculated_complexity.unwrap());
             assert_eq!(
                 expected_complexity,
-                node_metadata
-                    .get(&tree.root_node().id())
-                    .unwrap()
-                    .complexities
+                node_metadata.get(&tree.root_node()).unwrap().complexities
             );
         }
 
@@ -283,12 +303,12 @@ mod tests {
             parser
                 .set_language(tree_sitter_swift::language())
                 .expect("Swift Language not found");
-            let tree = parser.parse_utf16(text_content.clone(), None).unwrap();
+            let tree = SwiftSyntaxTree::parse(&mut parser, &text_content, None).unwrap();
             let expected_complexity: Complexities = Complexities {
                 nesting_complexity: 0,
                 fundamental_complexity: 5,
             };
-            let mut node_metadata = HashMap::<usize, NodeMetadata>::new();
+            let mut node_metadata = TreeMetaData::default();
             let calculated_complexity = calculate_cognitive_complexities(
                 &tree.root_node(),
                 &text_content,
@@ -318,12 +338,12 @@ mod tests {
             parser
                 .set_language(tree_sitter_swift::language())
                 .expect("Swift Language not found");
-            let tree = parser.parse_utf16(text_content.clone(), None).unwrap();
+            let tree = SwiftSyntaxTree::parse(&mut parser, &text_content, None).unwrap();
             let expected_complexity: Complexities = Complexities {
                 nesting_complexity: 0,
                 fundamental_complexity: 3,
             };
-            let mut node_metadata = HashMap::<usize, NodeMetadata>::new();
+            let mut node_metadata = TreeMetaData::default();
             let calculated_complexity = calculate_cognitive_complexities(
                 &tree.root_node(),
                 &text_content,
@@ -352,12 +372,12 @@ mod tests {
             parser
                 .set_language(tree_sitter_swift::language())
                 .expect("Swift Language not found");
-            let tree = parser.parse_utf16(text_content.clone(), None).unwrap();
+            let tree = SwiftSyntaxTree::parse(&mut parser, &text_content, None).unwrap();
             let expected_complexity: Complexities = Complexities {
                 nesting_complexity: 0,
                 fundamental_complexity: 2,
             };
-            let mut node_metadata = HashMap::<usize, NodeMetadata>::new();
+            let mut node_metadata = TreeMetaData::default();
             let calculated_complexity = calculate_cognitive_complexities(
                 &tree.root_node(),
                 &text_content,
@@ -385,12 +405,12 @@ mod tests {
             parser
                 .set_language(tree_sitter_swift::language())
                 .expect("Swift Language not found");
-            let tree = parser.parse_utf16(text_content.clone(), None).unwrap();
+            let tree = SwiftSyntaxTree::parse(&mut parser, &text_content, None).unwrap();
             let expected_complexity: Complexities = Complexities {
                 nesting_complexity: 1,
                 fundamental_complexity: 1,
             };
-            let mut node_metadata = HashMap::<usize, NodeMetadata>::new();
+            let mut node_metadata = TreeMetaData::default();
             let calculated_complexity = calculate_cognitive_complexities(
                 &tree.root_node(),
                 &text_content,
@@ -414,12 +434,12 @@ mod tests {
             parser
                 .set_language(tree_sitter_swift::language())
                 .expect("Swift Language not found");
-            let tree = parser.parse_utf16(text_content.clone(), None).unwrap();
+            let tree = SwiftSyntaxTree::parse(&mut parser, &text_content, None).unwrap();
             let expected_complexity: Complexities = Complexities {
                 nesting_complexity: 0,
                 fundamental_complexity: 2,
             };
-            let mut node_metadata = HashMap::<usize, NodeMetadata>::new();
+            let mut node_metadata = TreeMetaData::default();
             let calculated_complexity = calculate_cognitive_complexities(
                 &tree.root_node(),
                 &text_content,
@@ -465,12 +485,12 @@ mod tests {
             parser
                 .set_language(tree_sitter_swift::language())
                 .expect("Swift Language not found");
-            let tree = parser.parse_utf16(text_content.clone(), None).unwrap();
+            let tree = SwiftSyntaxTree::parse(&mut parser, &text_content, None).unwrap();
             let expected_complexity: Complexities = Complexities {
                 nesting_complexity: 2,
                 fundamental_complexity: 5,
             };
-            let mut node_metadata = HashMap::<usize, NodeMetadata>::new();
+            let mut node_metadata = TreeMetaData::default();
             let calculated_complexity = calculate_cognitive_complexities(
                 &tree.root_node(),
                 &text_content,
@@ -514,12 +534,12 @@ mod tests {
             parser
                 .set_language(tree_sitter_swift::language())
                 .expect("Swift Language not found");
-            let tree = parser.parse_utf16(text_content.clone(), None).unwrap();
+            let tree = SwiftSyntaxTree::parse(&mut parser, &text_content, None).unwrap();
             let expected_complexity: Complexities = Complexities {
                 nesting_complexity: 2,
                 fundamental_complexity: 5,
             };
-            let mut node_metadata = HashMap::<usize, NodeMetadata>::new();
+            let mut node_metadata = TreeMetaData::default();
             let calculated_complexity = calculate_cognitive_complexities(
                 &tree.root_node(),
                 &text_content,
@@ -528,5 +548,163 @@ mod tests {
             );
             assert_eq!(expected_complexity, calculated_complexity.unwrap());
         }
+
+        fn nested_if_complexities(code: &str) -> Complexities {
+            let text_content = XcodeText::from_str(code);
+
+            let mut parser = Parser::new();
+            parser
+                .set_language(tree_sitter_swift::language())
+                .expect("Swift Language not found");
+            let tree = SwiftSyntaxTree::parse(&mut parser, &text_content, None).unwrap();
+            let mut node_metadata = TreeMetaData::default();
+            calculate_cognitive_complexities(
+                &tree.root_node(),
+                &text_content,
+                &mut node_metadata,
+                None,
+            )
+            .unwrap()
+        }
+
+        // An if (+1) with a nested if (+1, +1 for nesting)
+        const NESTED_IF_COMPLEXITIES: Complexities = Complexities {
+            nesting_complexity: 1,
+            fundamental_complexity: 2,
+        };
+
+        #[test]
+        fn nesting_in_initializers_and_deinitializers() {
+            assert_eq!(
+                nested_if_complexities(
+                    r#"
+                    class A {
+                        init(a: Bool) {
+                            if a { if b { c() } }
+                        }
+                    }
+                "#
+                ),
+                NESTED_IF_COMPLEXITIES
+            );
+            assert_eq!(
+                nested_if_complexities(
+                    r#"
+                    class A {
+                        deinit {
+                            if a { if b { c() } }
+                        }
+                    }
+                "#
+                ),
+                NESTED_IF_COMPLEXITIES
+            );
+        }
+
+        #[test]
+        fn nesting_in_subscripts() {
+            assert_eq!(
+                nested_if_complexities(
+                    r#"
+                    struct A {
+                        subscript(index: Int) -> Int {
+                            if a { if b { return 1 } }
+                            return 0
+                        }
+                    }
+                "#
+                ),
+                NESTED_IF_COMPLEXITIES
+            );
+        }
+
+        #[test]
+        fn nesting_in_computed_property_accessors() {
+            assert_eq!(
+                nested_if_complexities(
+                    r#"
+                    struct A {
+                        var a: Int {
+                            if a { if b { return 1 } }
+                            return 0
+                        }
+                    }
+                "#
+                ),
+                NESTED_IF_COMPLEXITIES
+            );
+            assert_eq!(
+                nested_if_complexities(
+                    r#"
+                    struct A {
+                        var a: Int {
+                            get { return 0 }
+                            set { if a { if b { c() } } }
+                        }
+                    }
+                "#
+                ),
+                NESTED_IF_COMPLEXITIES
+            );
+            assert_eq!(
+                nested_if_complexities(
+                    r#"
+                    struct A {
+                        var a = 0 {
+                            didSet { if a { if b { c() } } }
+                        }
+                    }
+                "#
+                ),
+                NESTED_IF_COMPLEXITIES
+            );
+        }
+    }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use crate::core_engine::{
+            syntax_tree::{
+                calculate_cognitive_complexities, tree_metadata::TreeMetaData, Complexities,
+                SwiftSyntaxTree,
+            },
+            utils::swift_text_strategies::{swift_code, swift_text},
+            XcodeText,
+        };
+
+        proptest! {
+            // Every node adds a non-negative complexity of its own to the sum of its children, and
+            // the returned total is the one stored for the root.
+            #[test]
+            fn totals_are_sums_of_node_contributions(
+                code in prop_oneof![swift_code(), swift_text()],
+                starting_depth in 0..3isize,
+            ) {
+                let text = XcodeText::from_str(&code);
+                let tree = SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &text, None).unwrap();
+                let mut metadata = TreeMetaData::default();
+                let total = calculate_cognitive_complexities(
+                    &tree.root_node(),
+                    &text,
+                    &mut metadata,
+                    Some(starting_depth),
+                )
+                .unwrap();
+                prop_assert_eq!(&total, &metadata.get(&tree.root_node()).unwrap().complexities);
+
+                let mut nodes = vec![tree.root_node()];
+                while let Some(node) = nodes.pop() {
+                    let mut children_sum = Complexities::new();
+                    for child in node.named_children(&mut node.walk()) {
+                        children_sum += metadata.get(&child).unwrap().complexities.clone();
+                        nodes.push(child);
+                    }
+                    let own = metadata.get(&node).unwrap().complexities.clone() - children_sum;
+                    prop_assert!(own.nesting_complexity >= 0, "{:?} of {}", own, node.kind());
+                    prop_assert!(own.fundamental_complexity >= 0, "{:?} of {}", own, node.kind());
+                }
+            }
+        }
     }
 }
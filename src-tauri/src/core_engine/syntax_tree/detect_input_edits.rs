@@ -1,15 +1,14 @@
-#![allow(dead_code)]
-
 use std::time::Instant;
 
-use crate::core_engine::utils::{TextPosition, TextRange, XcodeText};
+use crate::core_engine::utils::{TextRange, XcodeText};
 use similar::{capture_diff_slices_deadline, Algorithm, DiffOp};
-use tree_sitter::InputEdit;
+use tree_sitter::{InputEdit, Point};
+
 #[derive(Clone, Debug)]
-pub struct Diff {
-    pub added_char_count: usize,
-    pub removed_char_count: usize,
-    pub start_index: usize,
+struct Diff {
+    added_char_count: usize,
+    removed_char_count: usize,
+    start_index: usize,
 }
 
 pub fn detect_input_edits(
@@ -23,10 +22,11 @@ pub fn detect_input_edits(
     // "Due to the recursive, divide and conquer nature of Myer’s diff you will still get a pretty decent diff in many cases if the deadline is reached"
     let deadline = Instant::now() + std::time::Duration::from_millis(deadline_ms);
 
+    // The diff needs contiguous slices. Copying the texts is linear, like the diff itself.
     let ops = capture_diff_slices_deadline(
         Algorithm::Myers,
-        &old_string.text,
-        &new_string.text,
+        &old_string.to_vec(),
+        &new_string.to_vec(),
         Some(deadline),
     );
 
@@ -77,28 +77,46 @@ pub fn detect_input_edits(
     construct_InputEdits_from_detected_edits(old_string, new_string, &edits)
 }
 
+// Tree-sitter applies the edits one after another, so each edit is expressed in the text with the
+// previous edits applied: the new text up to the edit, followed by the old text.
 fn construct_InputEdits_from_detected_edits(
     old_string: &XcodeText,
     new_string: &XcodeText,
     detected_edits: &Vec<Diff>,
 ) -> Vec<InputEdit> {
     let mut input_edits: Vec<InputEdit> = Vec::new();
+    let mut offset: isize = 0;
     for edit in detected_edits.iter() {
+        let start_index = (edit.start_index as isize + offset) as usize;
+        offset += edit.added_char_count as isize - edit.removed_char_count as isize;
+
         let edit_range_before = TextRange::new(edit.start_index, edit.removed_char_count);
-        let edit_range_after = TextRange::new(edit.start_index, edit.added_char_count);
+        let removed_range = TextRange::new(start_index, edit.removed_char_count);
+        let edit_range_after = TextRange::new(start_index, edit.added_char_count);
 
-        if let (Some(old_pts), Some(new_pts), Some(start_position)) = (
+        if let (Some((old_start, old_end)), Some((new_start, new_end))) = (
             edit_range_before.as_StartEndTSPoint(&old_string),
             edit_range_after.as_StartEndTSPoint(&new_string),
-            TextPosition::from_TextIndex(old_string, edit.start_index),
         ) {
+            // The removed text is moved to where the edit starts in the new text
+            let old_end_position = if old_end.row == old_start.row {
+                Point::new(
+                    new_start.row,
+                    new_start.column + old_end.column - old_start.column,
+                )
+            } else {
+                Point::new(new_start.row + old_end.row - old_start.row, old_end.column)
+            };
+
+            let (start_byte, old_end_byte) = removed_range.as_StartEndTSByte();
+            let (_, new_end_byte) = edit_range_after.as_StartEndTSByte();
             input_edits.push(InputEdit {
-                start_byte: edit.start_index * 2, // UTF-16
-                old_end_byte: (edit.start_index + edit.removed_char_count) * 2,
-                new_end_byte: (edit.start_index + edit.added_char_count) * 2,
-                start_position: start_position.as_TSPoint(),
-                old_end_position: old_pts.1,
-                new_end_position: new_pts.1,
+                start_byte,
+                old_end_byte,
+                new_end_byte,
+                start_position: new_start,
+                old_end_position,
+                new_end_position: new_end,
             });
         }
     }
@@ -109,6 +127,7 @@ fn construct_InputEdits_from_detected_edits(
 mod tests {
 
     use super::*;
+    use crate::core_engine::utils::TextPosition;
     use pretty_assertions::assert_eq;
 
     fn test_fn(
@@ -255,4 +274,91 @@ mod tests {
             TextPosition { row: 1, column: 19 },
         );
     }
+
+    #[test]
+    fn later_edits_are_relative_to_earlier_ones() {
+        let input_edits = detect_input_edits(
+            &XcodeText::from_str("abcXdef\nghYi"),
+            &XcodeText::from_str("abcdef\nghi"),
+            1000,
+        );
+
+        assert_eq!(input_edits.len(), 2);
+        assert_eq!(input_edits[0].start_byte, 3 * 2);
+        assert_eq!(input_edits[1].start_byte, 9 * 2);
+        assert_eq!(input_edits[1].old_end_byte, 10 * 2);
+        assert_eq!(
+            TextPosition::from_TSPoint(&input_edits[1].start_position),
+            TextPosition { row: 1, column: 2 }
+        );
+        assert_eq!(
+            TextPosition::from_TSPoint(&input_edits[1].old_end_position),
+            TextPosition { row: 1, column: 3 }
+        );
+    }
+
+    mod properties {
+        use proptest::{collection::vec, prelude::*};
+        use tree_sitter::{Point, Tree};
+
+        use crate::core_engine::{
+            syntax_tree::{detect_input_edits, SwiftSyntaxTree},
+            utils::swift_text_strategies::{swift_code, text_edit},
+            XcodeText,
+        };
+
+        fn nodes_of(tree: &Tree) -> Vec<(&'static str, usize, usize, Point, Point)> {
+            let mut nodes = vec![];
+            let mut stack = vec![tree.root_node()];
+            while let Some(node) = stack.pop() {
+                nodes.push((
+                    node.kind(),
+                    node.start_byte(),
+                    node.end_byte(),
+                    node.start_position(),
+                    node.end_position(),
+                ));
+                stack.extend(node.children(&mut node.walk()));
+            }
+            nodes
+        }
+
+        proptest! {
+            // Trees with errors are not compared, as error recovery may legitimately differ
+            // between incremental and fresh parses. The edits are undone at the end, so each case
+            // compares at least the trees of the original code.
+            #[test]
+            fn edited_trees_equal_fresh_parses(
+                code in swift_code(),
+                edits in vec(text_edit(), 1..4),
+            ) {
+                let mut texts: Vec<String> = edits
+                    .iter()
+                    .scan(code.clone(), |text, edit| {
+                        *text = edit.apply(text);
+                        Some(text.clone())
+                    })
+                    .collect();
+                texts.push(code);
+
+                let mut previous_text = XcodeText::from_str(&texts[texts.len() - 1]);
+                let mut previous_tree = SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &previous_text, None).unwrap();
+                for text in texts {
+                    let text = XcodeText::from_str(&text);
+                    for edit in detect_input_edits(&previous_text, &text, 1000) {
+                        previous_tree.edit(&edit);
+                    }
+                    let tree = SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &text, Some(&previous_tree))
+                        .unwrap();
+                    let fresh_tree = SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &text, None).unwrap();
+
+                    if !fresh_tree.root_node().has_error() {
+                        prop_assert_eq!(nodes_of(&tree), nodes_of(&fresh_tree));
+                    }
+                    previous_text = text;
+                    previous_tree = tree;
+                }
+            }
+        }
+    }
 }
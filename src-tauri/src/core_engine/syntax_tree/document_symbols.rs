@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+use ts_rs::TS;
+
+use crate::core_engine::{TextRange, XcodeText};
+
+use super::{get_node_text, SwiftSyntaxTree};
+
+// Signatures are rendered in a single row of the outline; everything beyond is cut off.
+const MAX_SIGNATURE_LENGTH: usize = 120;
+
+const BODY_KINDS: [&str; 5] = [
+    "class_body",
+    "enum_class_body",
+    "protocol_body",
+    "function_body",
+    "computed_property",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/document_outline/")]
+pub enum DocumentSymbolKind {
+    Class,
+    Struct,
+    Enum,
+    Protocol,
+    Extension,
+    Actor,
+    Function,
+    Initializer,
+    Deinitializer,
+    Subscript,
+    Property,
+    EnumCase,
+}
+
+impl DocumentSymbolKind {
+    fn from_node(node: &Node) -> Option<Self> {
+        match node.kind() {
+            "class_declaration" => match node.child_by_field_name("declaration_kind")?.kind() {
+                "class" => Some(DocumentSymbolKind::Class),
+                "struct" => Some(DocumentSymbolKind::Struct),
+                "enum" => Some(DocumentSymbolKind::Enum),
+                "extension" => Some(DocumentSymbolKind::Extension),
+                "actor" => Some(DocumentSymbolKind::Actor),
+                _ => None,
+            },
+            "protocol_declaration" => Some(DocumentSymbolKind::Protocol),
+            "function_declaration" | "protocol_function_declaration" => {
+                Some(DocumentSymbolKind::Function)
+            }
+            "init_declaration" => Some(DocumentSymbolKind::Initializer),
+            "deinit_declaration" => Some(DocumentSymbolKind::Deinitializer),
+            "subscript_declaration" => Some(DocumentSymbolKind::Subscript),
+            "property_declaration" | "protocol_property_declaration" => {
+                Some(DocumentSymbolKind::Property)
+            }
+            "enum_entry" => Some(DocumentSymbolKind::EnumCase),
+            _ => None,
+        }
+    }
+
+    fn is_type(&self) -> bool {
+        match self {
+            DocumentSymbolKind::Class
+            | DocumentSymbolKind::Struct
+            | DocumentSymbolKind::Enum
+            | DocumentSymbolKind::Protocol
+            | DocumentSymbolKind::Extension
+            | DocumentSymbolKind::Actor => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/features/document_outline/")]
+pub struct DocumentSymbol {
+    pub kind: DocumentSymbolKind,
+    pub name: String,
+    pub signature: String,
+    pub text_range: TextRange,
+    pub complexity: isize, // Aggregated over the symbol, including all of its children
+    pub children: Vec<DocumentSymbol>,
+}
+
+impl SwiftSyntaxTree {
+    // Computes the hierarchical outline of the document. Members are nested below the types
+    // declaring them; symbols declared inside function bodies are not part of the outline.
+    pub fn get_document_symbols(&self) -> Vec<DocumentSymbol> {
+        let mut symbols = vec![];
+        collect_document_symbols(self.tree().root_node(), self, &mut symbols);
+
+        symbols
+    }
+}
+
+// Sorts the symbols of every level of the outline, most complex first.
+pub fn sort_document_symbols_by_complexity(symbols: &mut Vec<DocumentSymbol>) {
+    symbols.sort_by(|a, b| b.complexity.cmp(&a.complexity));
+
+    for symbol in symbols.iter_mut() {
+        sort_document_symbols_by_complexity(&mut symbol.children);
+    }
+}
+
+fn collect_document_symbols(
+    node: Node,
+    syntax_tree: &SwiftSyntaxTree,
+    symbols: &mut Vec<DocumentSymbol>,
+) {
+    for child in node.named_children(&mut node.walk()) {
+        if let Some(kind) = DocumentSymbolKind::from_node(&child) {
+            symbols.append(&mut create_document_symbols(child, kind, syntax_tree));
+        } else if !BODY_KINDS.contains(&child.kind()) {
+            // Code blocks of top level statements may still contain declarations.
+            collect_document_symbols(child, syntax_tree, symbols);
+        }
+    }
+}
+
+fn create_document_symbols(
+    node: Node,
+    kind: DocumentSymbolKind,
+    syntax_tree: &SwiftSyntaxTree,
+) -> Vec<DocumentSymbol> {
+    let text_content = syntax_tree.text_content();
+
+    let text_range = match get_text_range_of_node(&node, text_content) {
+        Some(text_range) => text_range,
+        None => return vec![],
+    };
+
+    let complexity = syntax_tree
+        .get_metadata_of_node(&node)
+        .map(|metadata| metadata.complexities.get_total_complexity())
+        .unwrap_or(0);
+
+    let signature = get_signature(&node, text_content).unwrap_or_default();
+
+    let mut children = vec![];
+    if kind.is_type() {
+        if let Some(body) = node.child_by_field_name("body") {
+            collect_document_symbols(body, syntax_tree, &mut children);
+        }
+    }
+
+    // A single enum entry can declare multiple cases, e.g. `case north, south`.
+    get_names(&node, kind, text_content)
+        .into_iter()
+        .map(|name| DocumentSymbol {
+            kind,
+            name,
+            signature: signature.clone(),
+            text_range,
+            complexity,
+            children: children.clone(),
+        })
+        .collect()
+}
+
+fn get_names(node: &Node, kind: DocumentSymbolKind, text_content: &XcodeText) -> Vec<String> {
+    match kind {
+        DocumentSymbolKind::Initializer => return vec!["init".to_string()],
+        DocumentSymbolKind::Deinitializer => return vec!["deinit".to_string()],
+        DocumentSymbolKind::Subscript => return vec!["subscript".to_string()],
+        _ => {}
+    }
+
+    let names: Vec<String> = node
+        .children_by_field_name("name", &mut node.walk())
+        .filter_map(|name_node| get_node_text(&name_node, text_content).ok())
+        .map(|name| name.as_string())
+        .collect();
+
+    if names.is_empty() {
+        vec!["<anonymous>".to_string()]
+    } else if kind == DocumentSymbolKind::EnumCase {
+        names
+    } else {
+        vec![names.join(", ")]
+    }
+}
+
+// The signature is the declaration up to its body, with all whitespace collapsed.
+fn get_signature(node: &Node, text_content: &XcodeText) -> Option<String> {
+    let start_index = node.start_byte() / 2;
+    let end_index = node
+        .named_children(&mut node.walk())
+        .find(|child| BODY_KINDS.contains(&child.kind()))
+        .map(|body| body.start_byte() / 2)
+        .unwrap_or(node.end_byte() / 2);
+
+    if start_index > end_index || end_index > text_content.len() {
+        return None;
+    }
+    let text = text_content.slice(start_index..end_index).as_string();
+    let signature = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if signature.chars().count() > MAX_SIGNATURE_LENGTH {
+        Some(
+            signature
+                .chars()
+                .take(MAX_SIGNATURE_LENGTH)
+                .collect::<String>()
+                + "…",
+        )
+    } else {
+        Some(signature)
+    }
+}
+
+fn get_text_range_of_node(node: &Node, text_content: &XcodeText) -> Option<TextRange> {
+    TextRange::from_StartEndTSPoint(text_content, &node.start_position(), &node.end_position())
+}
+
+#[cfg(test)]
+mod tests {
+    mod get_document_symbols {
+        use crate::core_engine::{
+            syntax_tree::{DocumentSymbol, DocumentSymbolKind, SwiftSyntaxTree},
+            XcodeText,
+        };
+
+        fn test_fn(text: &str) -> Vec<DocumentSymbol> {
+            let syntax_tree = tauri::async_runtime::block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(text),
+                None,
+            ))
+            .unwrap();
+
+            syntax_tree.get_document_symbols()
+        }
+
+        fn kinds_and_names(symbols: &Vec<DocumentSymbol>) -> Vec<(DocumentSymbolKind, String)> {
+            symbols
+                .iter()
+                .map(|symbol| (symbol.kind, symbol.name.clone()))
+                .collect()
+        }
+
+        #[test]
+        fn nested_types_and_members() {
+            let symbols = test_fn(
+                "class A {
+    var b: Int = 0
+
+    init() {}
+
+    func c(d: Int) -> Int {
+        let e = d
+        return e
+    }
+
+    struct F {}
+}
+
+enum G {
+    case h, i
+}",
+            );
+
+            assert_eq!(
+                kinds_and_names(&symbols),
+                vec![
+                    (DocumentSymbolKind::Class, "A".to_string()),
+                    (DocumentSymbolKind::Enum, "G".to_string()),
+                ]
+            );
+            assert_eq!(
+                kinds_and_names(&symbols[0].children),
+                vec![
+                    (DocumentSymbolKind::Property, "b".to_string()),
+                    (DocumentSymbolKind::Initializer, "init".to_string()),
+                    (DocumentSymbolKind::Function, "c".to_string()),
+                    (DocumentSymbolKind::Struct, "F".to_string()),
+                ]
+            );
+            assert_eq!(
+                kinds_and_names(&symbols[1].children),
+                vec![
+                    (DocumentSymbolKind::EnumCase, "h".to_string()),
+                    (DocumentSymbolKind::EnumCase, "i".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn signature_excludes_body() {
+            let symbols = test_fn(
+                "func a(b: Int,
+       c: String) -> Bool {
+    return true
+}",
+            );
+
+            assert_eq!(symbols.len(), 1);
+            assert_eq!(symbols[0].signature, "func a(b: Int, c: String) -> Bool");
+            assert_eq!(symbols[0].text_range.index, 0);
+        }
+
+        #[test]
+        fn complexity_is_aggregated() {
+            let symbols = test_fn(
+                "class A {
+    func b() {
+        if c && d { e() }
+    }
+
+    func f() {
+        for x in xs { g(x) }
+    }
+}",
+            );
+
+            assert_eq!(symbols[0].children[0].complexity, 2);
+            assert_eq!(symbols[0].children[1].complexity, 1);
+            assert_eq!(symbols[0].complexity, 3);
+        }
+    }
+
+    mod sort_document_symbols_by_complexity {
+        use crate::core_engine::{
+            syntax_tree::{sort_document_symbols_by_complexity, SwiftSyntaxTree},
+            XcodeText,
+        };
+
+        #[test]
+        fn sorts_every_level() {
+            let syntax_tree = tauri::async_runtime::block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(
+                    "func a() {}
+
+class B {
+    func c() {}
+
+    func d() {
+        if e { f() }
+    }
+}",
+                ),
+                None,
+            ))
+            .unwrap();
+
+            let mut symbols = syntax_tree.get_document_symbols();
+            sort_document_symbols_by_complexity(&mut symbols);
+
+            assert_eq!(symbols[0].name, "B");
+            assert_eq!(symbols[0].children[0].name, "d");
+        }
+    }
+}
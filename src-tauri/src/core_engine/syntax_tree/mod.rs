@@ -4,6 +4,11 @@ pub use cognitive_complexity::*;
 mod detect_input_edits;
 pub use detect_input_edits::*;
 
+mod document_symbols;
+pub use document_symbols::sort_document_symbols_by_complexity;
+pub use document_symbols::DocumentSymbol;
+pub use document_symbols::DocumentSymbolKind;
+
 mod swift;
 pub use swift::*;
 
@@ -11,3 +16,18 @@ mod swift_syntax_tree;
 pub use swift_syntax_tree::NodeMetadata;
 pub use swift_syntax_tree::SwiftSyntaxTree;
 pub use swift_syntax_tree::SwiftSyntaxTreeError;
+
+mod symbol_table;
+pub use symbol_table::Scope;
+pub use symbol_table::ScopeId;
+pub use symbol_table::ScopeKind;
+pub use symbol_table::Symbol;
+pub use symbol_table::SymbolId;
+pub use symbol_table::SymbolKind;
+pub use symbol_table::SymbolTable;
+
+mod tree_metadata;
+pub use tree_metadata::TreeMetaData;
+
+mod utils;
+pub use utils::*;
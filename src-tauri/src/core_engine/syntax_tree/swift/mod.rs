@@ -4,6 +4,12 @@ pub use swift_function::*;
 mod swift_class;
 pub use swift_class::*;
 
+mod swift_closure;
+pub use swift_closure::*;
+
+mod swift_computed_property;
+pub use swift_computed_property::*;
+
 mod swift_generic_codeblock;
 pub use swift_generic_codeblock::*;
 
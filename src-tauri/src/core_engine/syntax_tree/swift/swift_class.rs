@@ -8,19 +8,26 @@ use crate::core_engine::{
 };
 
 use super::swift_code_block::{
-    get_first_char_position, get_last_char_position, get_node_text, get_parent_code_block,
-    SwiftCodeBlockBase, SwiftCodeBlockKind, SwiftCodeBlockProps,
+    get_first_char_position, get_generic_constraints, get_last_char_position, get_node_text,
+    get_parent_code_block, SwiftCodeBlockBase, SwiftCodeBlockKind, SwiftCodeBlockProps,
 };
 
+// Covers all type declarations; the grammar parses structs, enums, extensions and actors as
+// `class_declaration` and tells them apart by their `declaration_kind`.
 pub struct SwiftClass<'a> {
     props: SwiftCodeBlockProps<'a>,
+    kind: SwiftCodeBlockKind,
 }
 impl SwiftClass<'_> {
     pub fn get_name(&self) -> Option<String> {
         let x = self.props.node.child_by_field_name("name")?;
         get_node_text(&x, &self.props.text_content)
             .ok()
-            .map(|text| String::from_utf16_lossy(&text))
+            .map(|text| text.as_string())
+    }
+
+    pub fn get_generic_constraints(&self) -> Vec<String> {
+        get_generic_constraints(&self.props.node, &self.props.text_content)
     }
 }
 
@@ -31,6 +38,21 @@ impl SwiftCodeBlockBase<'_> for SwiftClass<'_> {
         node_metadata: &'a NodeMetadata,
         text_content: &'a XcodeText,
     ) -> Result<SwiftCodeBlock<'a>, SwiftCodeBlockError> {
+        let kind = if node.kind() == "protocol_declaration" {
+            SwiftCodeBlockKind::Protocol
+        } else {
+            match node
+                .child_by_field_name("declaration_kind")
+                .map(|declaration_kind| declaration_kind.kind())
+            {
+                Some("struct") => SwiftCodeBlockKind::Struct,
+                Some("enum") => SwiftCodeBlockKind::Enum,
+                Some("extension") => SwiftCodeBlockKind::Extension,
+                Some("actor") => SwiftCodeBlockKind::Actor,
+                _ => SwiftCodeBlockKind::Class,
+            }
+        };
+
         Ok(SwiftCodeBlock::Class(SwiftClass {
             props: SwiftCodeBlockProps {
                 tree,
@@ -38,11 +60,12 @@ impl SwiftCodeBlockBase<'_> for SwiftClass<'_> {
                 node_metadata,
                 node,
             },
+            kind,
         }))
     }
 
     fn get_kind(&self) -> SwiftCodeBlockKind {
-        SwiftCodeBlockKind::Class
+        self.kind
     }
 
     // Boilerplate
@@ -0,0 +1,167 @@
+use tree_sitter::Node;
+
+use crate::core_engine::{
+    syntax_tree::{
+        swift_syntax_tree::NodeMetadata, SwiftCodeBlock, SwiftCodeBlockError, SwiftSyntaxTree,
+    },
+    TextPosition, XcodeText,
+};
+
+use super::{
+    swift_code_block::{
+        get_first_char_position, get_last_char_position, get_node_text, get_parent_code_block,
+        SwiftCodeBlockBase, SwiftCodeBlockKind, SwiftCodeBlockProps,
+    },
+    FunctionParameter,
+};
+
+pub struct SwiftClosure<'a> {
+    props: SwiftCodeBlockProps<'a>,
+}
+
+impl SwiftClosure<'_> {
+    pub fn get_complexity(&self) -> isize {
+        self.props.node_metadata.complexities.get_total_complexity()
+    }
+
+    // Closure parameters are often untyped, e.g. `{ a, b in ... }`; their type is left empty then.
+    pub fn get_parameters(&self) -> Vec<FunctionParameter> {
+        let function_type = match self
+            .props
+            .node
+            .named_children(&mut self.props.node.walk())
+            .find(|node| node.kind() == "lambda_function_type")
+        {
+            Some(function_type) => function_type,
+            None => return vec![],
+        };
+
+        let mut parameter_nodes = vec![];
+        collect_lambda_parameters(function_type, &mut parameter_nodes);
+
+        parameter_nodes
+            .iter()
+            .filter_map(|node| {
+                let name =
+                    get_node_text(&node.child_by_field_name("name")?, &self.props.text_content)
+                        .ok()
+                        .map(|text| text.as_string())?;
+
+                let param_type = node
+                    .child_by_field_name("type")
+                    .and_then(|type_node| get_node_text(&type_node, &self.props.text_content).ok())
+                    .map(|text| text.as_string())
+                    .unwrap_or_default();
+
+                Some(FunctionParameter { name, param_type })
+            })
+            .filter(|parameter| parameter.name != "_")
+            .collect()
+    }
+}
+
+fn collect_lambda_parameters<'a>(node: Node<'a>, parameters: &mut Vec<Node<'a>>) {
+    for child in node.named_children(&mut node.walk()) {
+        if child.kind() == "lambda_parameter" {
+            parameters.push(child);
+        } else if child.kind() == "lambda_function_type_parameters" {
+            collect_lambda_parameters(child, parameters);
+        }
+    }
+}
+
+impl SwiftCodeBlockBase<'_> for SwiftClosure<'_> {
+    fn new<'a>(
+        tree: &'a SwiftSyntaxTree,
+        node: Node<'a>,
+        node_metadata: &'a NodeMetadata,
+        text_content: &'a XcodeText,
+    ) -> Result<SwiftCodeBlock<'a>, SwiftCodeBlockError> {
+        Ok(SwiftCodeBlock::Closure(SwiftClosure {
+            props: SwiftCodeBlockProps {
+                tree,
+                text_content,
+                node_metadata,
+                node,
+            },
+        }))
+    }
+
+    fn get_kind(&self) -> SwiftCodeBlockKind {
+        SwiftCodeBlockKind::Closure
+    }
+
+    // Boilerplate
+    fn as_text(&self) -> Result<XcodeText, SwiftCodeBlockError> {
+        get_node_text(&self.props.node, &self.props.text_content)
+    }
+    fn get_first_char_position(&self) -> TextPosition {
+        get_first_char_position(&self.props)
+    }
+    fn get_last_char_position(&self) -> TextPosition {
+        get_last_char_position(&self.props)
+    }
+    fn get_parent_code_block(&self) -> Result<SwiftCodeBlock, SwiftCodeBlockError> {
+        get_parent_code_block(&self.props)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod get_parameters {
+        use tauri::async_runtime::block_on;
+
+        use crate::core_engine::{
+            syntax_tree::{FunctionParameter, SwiftCodeBlock, SwiftSyntaxTree},
+            TextRange, XcodeText,
+        };
+
+        fn test_fn(text: &str, index: usize) -> Vec<FunctionParameter> {
+            let swift_syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(text),
+                None,
+            ))
+            .unwrap();
+
+            let text_range = TextRange::new(index, 0);
+            match SwiftCodeBlock::from_text_range(
+                &swift_syntax_tree,
+                &text_range,
+                swift_syntax_tree.text_content(),
+            )
+            .unwrap()
+            {
+                SwiftCodeBlock::Closure(closure) => closure.get_parameters(),
+                _ => panic!("Wrong codeblock type"),
+            }
+        }
+
+        #[test]
+        fn typed_parameters() {
+            assert_eq!(
+                test_fn("let a = { (b: Int, c: String) in\n    print(b)\n}", 37),
+                vec![
+                    FunctionParameter {
+                        name: "b".to_string(),
+                        param_type: "Int".to_string()
+                    },
+                    FunctionParameter {
+                        name: "c".to_string(),
+                        param_type: "String".to_string()
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn untyped_parameters() {
+            assert_eq!(
+                test_fn("a.map { b in\n    b + 1\n}", 17),
+                vec![FunctionParameter {
+                    name: "b".to_string(),
+                    param_type: "".to_string()
+                }]
+            );
+        }
+    }
+}
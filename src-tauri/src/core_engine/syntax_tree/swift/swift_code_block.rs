@@ -10,7 +10,9 @@ use crate::core_engine::{
     utils::{TextPosition, TextRange, XcodeText},
 };
 
-use super::{SwiftClass, SwiftFunction, SwiftGenericCodeBlock};
+use super::{
+    SwiftClass, SwiftClosure, SwiftComputedProperty, SwiftFunction, SwiftGenericCodeBlock,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum SwiftCodeBlockError {
@@ -27,6 +29,8 @@ pub enum SwiftCodeBlockError {
 pub enum SwiftCodeBlock<'a> {
     Function(SwiftFunction<'a>),
     Class(SwiftClass<'a>),
+    Closure(SwiftClosure<'a>),
+    ComputedProperty(SwiftComputedProperty<'a>),
     Other(SwiftGenericCodeBlock<'a>),
 }
 
@@ -76,6 +80,8 @@ impl<'a> SwiftCodeBlockBase<'a> for SwiftCodeBlock<'a> {
         match self {
             SwiftCodeBlock::Function(f) => f.as_text(),
             SwiftCodeBlock::Class(c) => c.as_text(),
+            SwiftCodeBlock::Closure(c) => c.as_text(),
+            SwiftCodeBlock::ComputedProperty(p) => p.as_text(),
             SwiftCodeBlock::Other(o) => o.as_text(),
         }
     }
@@ -88,8 +94,19 @@ impl<'a> SwiftCodeBlockBase<'a> for SwiftCodeBlock<'a> {
     ) -> Result<Self, SwiftCodeBlockError> {
         let kind = node.kind();
         match kind {
-            "function_declaration" => SwiftFunction::new(tree, node, node_metadata, text_content),
-            "class_declaration" => SwiftClass::new(tree, node, node_metadata, text_content),
+            "function_declaration"
+            | "init_declaration"
+            | "deinit_declaration"
+            | "subscript_declaration" => {
+                SwiftFunction::new(tree, node, node_metadata, text_content)
+            }
+            "class_declaration" | "protocol_declaration" => {
+                SwiftClass::new(tree, node, node_metadata, text_content)
+            }
+            "lambda_literal" => SwiftClosure::new(tree, node, node_metadata, text_content),
+            "property_declaration" => {
+                SwiftComputedProperty::new(tree, node, node_metadata, text_content)
+            }
             "for_statement" | "if_statement" | "else_statement" | "switch_statement"
             | "while_statement" | "do_statement" | "guard_statement" => {
                 SwiftGenericCodeBlock::new(tree, node, node_metadata, text_content)
@@ -102,6 +119,8 @@ impl<'a> SwiftCodeBlockBase<'a> for SwiftCodeBlock<'a> {
         match self {
             SwiftCodeBlock::Function(f) => f.get_first_char_position(),
             SwiftCodeBlock::Class(c) => c.get_first_char_position(),
+            SwiftCodeBlock::Closure(c) => c.get_first_char_position(),
+            SwiftCodeBlock::ComputedProperty(p) => p.get_first_char_position(),
             SwiftCodeBlock::Other(o) => o.get_first_char_position(),
         }
     }
@@ -109,6 +128,8 @@ impl<'a> SwiftCodeBlockBase<'a> for SwiftCodeBlock<'a> {
         match self {
             SwiftCodeBlock::Function(f) => f.get_last_char_position(),
             SwiftCodeBlock::Class(c) => c.get_last_char_position(),
+            SwiftCodeBlock::Closure(c) => c.get_last_char_position(),
+            SwiftCodeBlock::ComputedProperty(p) => p.get_last_char_position(),
             SwiftCodeBlock::Other(o) => o.get_last_char_position(),
         }
     }
@@ -116,6 +137,8 @@ impl<'a> SwiftCodeBlockBase<'a> for SwiftCodeBlock<'a> {
         match self {
             SwiftCodeBlock::Function(f) => f.get_kind(),
             SwiftCodeBlock::Class(c) => c.get_kind(),
+            SwiftCodeBlock::Closure(c) => c.get_kind(),
+            SwiftCodeBlock::ComputedProperty(p) => p.get_kind(),
             SwiftCodeBlock::Other(o) => o.get_kind(),
         }
     }
@@ -124,6 +147,8 @@ impl<'a> SwiftCodeBlockBase<'a> for SwiftCodeBlock<'a> {
         match self {
             SwiftCodeBlock::Function(f) => f.get_parent_code_block(),
             SwiftCodeBlock::Class(c) => c.get_parent_code_block(),
+            SwiftCodeBlock::Closure(c) => c.get_parent_code_block(),
+            SwiftCodeBlock::ComputedProperty(p) => p.get_parent_code_block(),
             SwiftCodeBlock::Other(o) => o.get_parent_code_block(),
         }
     }
@@ -158,9 +183,8 @@ pub fn get_node_text(
     if let Some(code_block_range) =
         TextRange::from_StartEndTSPoint(&text_content, &node.start_position(), &node.end_position())
     {
-        Ok(XcodeText::from_array(
-            &text_content[code_block_range.index..code_block_range.index + code_block_range.length],
-        ))
+        Ok(text_content
+            .slice(code_block_range.index..code_block_range.index + code_block_range.length))
     } else {
         Err(SwiftCodeBlockError::GenericError(anyhow!(
             "get_codeblock_text: TextRange::from_StartEndTSPoint failed for: {:?}",
@@ -169,6 +193,30 @@ pub fn get_node_text(
     }
 }
 
+// Generic parameters with their inline constraints, e.g. `T: Equatable`, followed by the
+// requirements of the `where` clause.
+pub fn get_generic_constraints(node: &Node, text_content: &XcodeText) -> Vec<String> {
+    let mut constraints = vec![];
+    for child in node.named_children(&mut node.walk()) {
+        let constraint_kind = match child.kind() {
+            "type_parameters" => "type_parameter",
+            "type_constraints" => "type_constraint",
+            _ => continue,
+        };
+
+        for constraint in child
+            .named_children(&mut child.walk())
+            .filter(|constraint| constraint.kind() == constraint_kind)
+        {
+            if let Ok(text) = get_node_text(&constraint, text_content) {
+                constraints.push(text.as_string());
+            }
+        }
+    }
+
+    constraints
+}
+
 fn get_code_block_of_node<'a>(
     tree: &'a SwiftSyntaxTree,
     node: Node<'a>,
@@ -179,7 +227,7 @@ fn get_code_block_of_node<'a>(
         if let Ok(codeblock) = SwiftCodeBlock::new(
             tree,
             current_node,
-            tree.get_metadata_of_node(&node).map_err(|err| {
+            tree.get_metadata_of_node(&current_node).map_err(|err| {
                 SwiftCodeBlockError::GenericError(anyhow!(
                     "get_code_block_of_node: get_node_metadata() failed for: {:?}, err: {:?}",
                     current_node,
@@ -211,6 +259,16 @@ pub enum SwiftCodeBlockKind {
     While,
     Do,
     Guard,
+    Struct,
+    Enum,
+    Protocol,
+    Extension,
+    Actor,
+    Closure,
+    Initializer,
+    Deinitializer,
+    ComputedProperty,
+    Subscript,
 }
 
 impl FromStr for SwiftCodeBlockKind {
@@ -226,6 +284,11 @@ impl FromStr for SwiftCodeBlockKind {
             "while_statement" => Ok(SwiftCodeBlockKind::While),
             "do_statement" => Ok(SwiftCodeBlockKind::Do),
             "guard_statement" => Ok(SwiftCodeBlockKind::Guard),
+            "protocol_declaration" => Ok(SwiftCodeBlockKind::Protocol),
+            "lambda_literal" => Ok(SwiftCodeBlockKind::Closure),
+            "init_declaration" => Ok(SwiftCodeBlockKind::Initializer),
+            "deinit_declaration" => Ok(SwiftCodeBlockKind::Deinitializer),
+            "subscript_declaration" => Ok(SwiftCodeBlockKind::Subscript),
             "function_statement" => Ok(SwiftCodeBlockKind::Guard),
             _ => Err(SwiftCodeBlockError::UnsupportedCodeblock),
         }
@@ -269,3 +332,86 @@ pub fn is_expression(kind: &str) -> bool {
 pub fn is_l_expression(kind: &str) -> bool {
     kind == "directly_assignable_expression"
 }
+
+#[cfg(test)]
+mod tests {
+    mod from_text_range {
+        use tauri::async_runtime::block_on;
+
+        use crate::core_engine::{
+            syntax_tree::{
+                SwiftCodeBlock, SwiftCodeBlockBase, SwiftCodeBlockKind, SwiftSyntaxTree,
+            },
+            TextRange, XcodeText,
+        };
+
+        fn test_fn(text: &str, index: usize) -> Option<SwiftCodeBlockKind> {
+            let swift_syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(text),
+                None,
+            ))
+            .unwrap();
+
+            let text_range = TextRange::new(index, 0);
+            SwiftCodeBlock::from_text_range(
+                &swift_syntax_tree,
+                &text_range,
+                swift_syntax_tree.text_content(),
+            )
+            .ok()
+            .map(|code_block| code_block.get_kind())
+        }
+
+        #[test]
+        fn type_declarations() {
+            assert_eq!(test_fn("class A {}", 9), Some(SwiftCodeBlockKind::Class));
+            assert_eq!(test_fn("struct A {}", 10), Some(SwiftCodeBlockKind::Struct));
+            assert_eq!(test_fn("enum A {}", 8), Some(SwiftCodeBlockKind::Enum));
+            assert_eq!(
+                test_fn("extension A {}", 13),
+                Some(SwiftCodeBlockKind::Extension)
+            );
+            assert_eq!(test_fn("actor A {}", 9), Some(SwiftCodeBlockKind::Actor));
+            assert_eq!(
+                test_fn("protocol A {}", 12),
+                Some(SwiftCodeBlockKind::Protocol)
+            );
+        }
+
+        #[test]
+        fn function_like_declarations() {
+            assert_eq!(
+                test_fn("class A {\n    init() {}\n}", 16),
+                Some(SwiftCodeBlockKind::Initializer)
+            );
+            assert_eq!(
+                test_fn("class A {\n    deinit {}\n}", 16),
+                Some(SwiftCodeBlockKind::Deinitializer)
+            );
+            assert_eq!(
+                test_fn(
+                    "class A {\n    subscript(b: Int) -> Int { return b }\n}",
+                    42
+                ),
+                Some(SwiftCodeBlockKind::Subscript)
+            );
+        }
+
+        #[test]
+        fn closures_and_computed_properties() {
+            assert_eq!(
+                test_fn("let a = { print(1) }", 12),
+                Some(SwiftCodeBlockKind::Closure)
+            );
+            assert_eq!(
+                test_fn("class A {\n    var b: Int { return 1 }\n}", 30),
+                Some(SwiftCodeBlockKind::ComputedProperty)
+            );
+        }
+
+        #[test]
+        fn stored_property_is_not_a_code_block() {
+            assert_eq!(test_fn("let a = 1", 8), None);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use tree_sitter::Node;
+
+use crate::core_engine::{
+    syntax_tree::{
+        swift_syntax_tree::NodeMetadata, SwiftCodeBlock, SwiftCodeBlockError, SwiftSyntaxTree,
+    },
+    TextPosition, XcodeText,
+};
+
+use super::swift_code_block::{
+    get_first_char_position, get_last_char_position, get_node_text, get_parent_code_block,
+    SwiftCodeBlockBase, SwiftCodeBlockKind, SwiftCodeBlockProps,
+};
+
+pub struct SwiftComputedProperty<'a> {
+    props: SwiftCodeBlockProps<'a>,
+}
+
+impl SwiftComputedProperty<'_> {
+    pub fn get_complexity(&self) -> isize {
+        self.props.node_metadata.complexities.get_total_complexity()
+    }
+
+    pub fn get_name(&self) -> Option<String> {
+        let x = self.props.node.child_by_field_name("name")?;
+        get_node_text(&x, &self.props.text_content)
+            .ok()
+            .map(|text| text.as_string())
+    }
+}
+
+impl SwiftCodeBlockBase<'_> for SwiftComputedProperty<'_> {
+    fn new<'a>(
+        tree: &'a SwiftSyntaxTree,
+        node: Node<'a>,
+        node_metadata: &'a NodeMetadata,
+        text_content: &'a XcodeText,
+    ) -> Result<SwiftCodeBlock<'a>, SwiftCodeBlockError> {
+        // Stored properties are not code blocks.
+        if !node
+            .named_children(&mut node.walk())
+            .any(|child| child.kind() == "computed_property")
+        {
+            return Err(SwiftCodeBlockError::UnsupportedCodeblock);
+        }
+
+        Ok(SwiftCodeBlock::ComputedProperty(SwiftComputedProperty {
+            props: SwiftCodeBlockProps {
+                tree,
+                text_content,
+                node_metadata,
+                node,
+            },
+        }))
+    }
+
+    fn get_kind(&self) -> SwiftCodeBlockKind {
+        SwiftCodeBlockKind::ComputedProperty
+    }
+
+    // Boilerplate
+    fn as_text(&self) -> Result<XcodeText, SwiftCodeBlockError> {
+        get_node_text(&self.props.node, &self.props.text_content)
+    }
+    fn get_first_char_position(&self) -> TextPosition {
+        get_first_char_position(&self.props)
+    }
+    fn get_last_char_position(&self) -> TextPosition {
+        get_last_char_position(&self.props)
+    }
+    fn get_parent_code_block(&self) -> Result<SwiftCodeBlock, SwiftCodeBlockError> {
+        get_parent_code_block(&self.props)
+    }
+}
@@ -9,10 +9,17 @@ use crate::core_engine::{
 };
 
 use super::swift_code_block::{
-    get_first_char_position, get_last_char_position, get_node_text, get_parent_code_block,
-    SwiftCodeBlockBase, SwiftCodeBlockKind, SwiftCodeBlockProps,
+    get_first_char_position, get_generic_constraints, get_last_char_position, get_node_text,
+    get_parent_code_block, SwiftCodeBlockBase, SwiftCodeBlockKind, SwiftCodeBlockProps,
 };
 
+// Subscripts are function-like as well, but their body consists of accessors instead of statements.
+const TOP_LEVEL_FUNCTION_KINDS: [&str; 3] = [
+    "function_declaration",
+    "init_declaration",
+    "deinit_declaration",
+];
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FunctionParameter {
     pub name: String,
@@ -21,6 +28,7 @@ pub struct FunctionParameter {
 
 pub struct SwiftFunction<'a> {
     pub props: SwiftCodeBlockProps<'a>,
+    kind: SwiftCodeBlockKind,
 }
 
 impl SwiftFunction<'_> {
@@ -42,11 +50,11 @@ impl SwiftFunction<'_> {
 
             let param_type = get_type_for_parameter(&node, &self.props.text_content)?;
 
-            if let Some(internal_name) = internal_name.map(|name| String::from_utf16_lossy(&name)) {
+            if let Some(internal_name) = internal_name.map(|name| name.as_string()) {
                 if internal_name != "_" {
                     result.push(FunctionParameter {
                         name: internal_name,
-                        param_type: String::from_utf16_lossy(&param_type),
+                        param_type: param_type.as_string(),
                     });
                 }
             }
@@ -54,10 +62,21 @@ impl SwiftFunction<'_> {
         Ok(result)
     }
     pub fn get_name(&self) -> Option<String> {
+        match self.kind {
+            SwiftCodeBlockKind::Initializer => return Some("init".to_string()),
+            SwiftCodeBlockKind::Deinitializer => return Some("deinit".to_string()),
+            SwiftCodeBlockKind::Subscript => return Some("subscript".to_string()),
+            _ => {}
+        }
+
         let x = self.props.node.child_by_field_name("name")?;
         get_node_text(&x, &self.props.text_content)
             .ok()
-            .map(|text| String::from_utf16_lossy(&text))
+            .map(|text| text.as_string())
+    }
+
+    pub fn get_generic_constraints(&self) -> Vec<String> {
+        get_generic_constraints(&self.props.node, &self.props.text_content)
     }
 
     pub fn get_top_level_functions<'a>(
@@ -74,7 +93,7 @@ impl SwiftFunction<'_> {
         text_content: &'a XcodeText,
     ) -> Result<Vec<SwiftFunction<'a>>, SwiftCodeBlockError> {
         let mut results: Vec<SwiftFunction<'a>> = vec![];
-        if node.kind() == "function_declaration" {
+        if TOP_LEVEL_FUNCTION_KINDS.contains(&node.kind()) {
             let node_metadata = syntax_tree
                 .get_metadata_of_node(&node)
                 .map_err(|err| SwiftCodeBlockError::GenericError(err.into()))?;
@@ -153,6 +172,14 @@ impl SwiftCodeBlockBase<'_> for SwiftFunction<'_> {
         node_metadata: &'a NodeMetadata,
         text_content: &'a XcodeText,
     ) -> Result<SwiftCodeBlock<'a>, SwiftCodeBlockError> {
+        let kind = match node.kind() {
+            "function_declaration" => SwiftCodeBlockKind::Function,
+            "init_declaration" => SwiftCodeBlockKind::Initializer,
+            "deinit_declaration" => SwiftCodeBlockKind::Deinitializer,
+            "subscript_declaration" => SwiftCodeBlockKind::Subscript,
+            _ => return Err(SwiftCodeBlockError::WrongCodeBlockType),
+        };
+
         Ok(SwiftCodeBlock::Function(SwiftFunction {
             props: SwiftCodeBlockProps {
                 tree,
@@ -160,11 +187,12 @@ impl SwiftCodeBlockBase<'_> for SwiftFunction<'_> {
                 node_metadata,
                 node,
             },
+            kind,
         }))
     }
 
     fn get_kind(&self) -> SwiftCodeBlockKind {
-        SwiftCodeBlockKind::Function
+        self.kind
     }
 
     // Boilerplate
@@ -222,5 +250,74 @@ mod tests {
                 2
             )
         }
+
+        #[test]
+        fn includes_initializers_and_deinitializers() {
+            let swift_syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(
+                    r#"
+                class A {
+                    init(b: Int) {}
+                    deinit {}
+                    subscript(c: Int) -> Int { return c }
+                    func d() {}
+                }
+            "#,
+                ),
+                None,
+            ))
+            .unwrap();
+
+            let names: Vec<Option<String>> = SwiftFunction::get_top_level_functions(
+                &swift_syntax_tree,
+                &swift_syntax_tree.text_content(),
+            )
+            .unwrap()
+            .iter()
+            .map(|function| function.get_name())
+            .collect();
+
+            assert_eq!(
+                names,
+                vec![
+                    Some("init".to_string()),
+                    Some("deinit".to_string()),
+                    Some("d".to_string())
+                ]
+            );
+        }
+    }
+
+    mod get_generic_constraints {
+        use tauri::async_runtime::block_on;
+
+        use crate::core_engine::{
+            syntax_tree::{SwiftFunction, SwiftSyntaxTree},
+            XcodeText,
+        };
+
+        #[test]
+        fn inline_and_where_clause_constraints() {
+            let swift_syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str("func a<T: Equatable, U>(b: T, c: U) where U: Hashable {}"),
+                None,
+            ))
+            .unwrap();
+
+            let functions = SwiftFunction::get_top_level_functions(
+                &swift_syntax_tree,
+                &swift_syntax_tree.text_content(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                functions[0].get_generic_constraints(),
+                vec![
+                    "T: Equatable".to_string(),
+                    "U".to_string(),
+                    "U: Hashable".to_string()
+                ]
+            );
+        }
     }
 }
@@ -1,15 +1,17 @@
 use anyhow::anyhow;
-use std::collections::HashMap;
 use tokio::sync::oneshot;
 use tracing::error;
-use tree_sitter::{Node, Parser, Tree};
+use tree_sitter::{InputEdit, Node, Parser, Range, Tree};
 
 use crate::core_engine::{
+    diagnostics::{DiagnosticsScope, DiagnosticsSpan},
     syntax_tree::detect_input_edits,
     utils::{TextPosition, TextRange, XcodeText},
 };
 
-use super::{calculate_cognitive_complexities, Complexities, SwiftCodeBlockError};
+use super::{
+    calculate_cognitive_complexities, Complexities, SwiftCodeBlockError, SymbolTable, TreeMetaData,
+};
 
 #[derive(Debug, Clone)]
 pub struct NodeMetadata {
@@ -34,24 +36,29 @@ impl From<SwiftCodeBlockError> for SwiftSyntaxTreeError {
     }
 }
 
-type TreeMetaData = HashMap<usize, NodeMetadata>;
-
 #[derive(Clone)]
 pub struct SwiftSyntaxTree {
     tree: Tree,
     content: XcodeText,
     node_metadata: TreeMetaData,
+    symbol_table: SymbolTable,
 }
 
 unsafe impl Send for SwiftSyntaxTree {}
 unsafe impl Sync for SwiftSyntaxTree {}
 
 impl SwiftSyntaxTree {
-    pub fn new(tree: Tree, node_metadata: TreeMetaData, content: XcodeText) -> Self {
+    pub fn new(
+        tree: Tree,
+        node_metadata: TreeMetaData,
+        symbol_table: SymbolTable,
+        content: XcodeText,
+    ) -> Self {
         Self {
             tree,
             content,
             node_metadata,
+            symbol_table,
         }
     }
 
@@ -59,11 +66,6 @@ impl SwiftSyntaxTree {
         content: XcodeText,
         previous_tree: Option<SwiftSyntaxTree>,
     ) -> Result<Self, SwiftSyntaxTreeError> {
-        // We wait for a very short time in order to allow quickly subsequently scheduled calls to cancel this one
-        // There are occasions where we receive multiple calls to this function in a very short time frame (e.g. on typing always triggers TextContentChange and SelectedTextChange)
-        // It is three because we are three cofounders.
-        tokio::time::sleep(std::time::Duration::from_millis(3)).await;
-
         let (send, recv) = oneshot::channel();
 
         rayon::spawn(move || {
@@ -90,9 +92,13 @@ impl SwiftSyntaxTree {
         &self.content
     }
 
+    pub fn symbol_table(&self) -> &SymbolTable {
+        &self.symbol_table
+    }
+
     pub fn get_metadata_of_node(&self, node: &Node) -> Result<&NodeMetadata, SwiftSyntaxTreeError> {
         self.node_metadata
-            .get(&node.id())
+            .get(node)
             .ok_or(SwiftSyntaxTreeError::NoMetadataFoundForNode)
     }
 
@@ -127,44 +133,74 @@ impl SwiftSyntaxTree {
     fn apply_edits_from_diff_to_tree(
         previous_tree: &mut SwiftSyntaxTree,
         new_content: &XcodeText,
-    ) -> Result<(), SwiftSyntaxTreeError> {
+    ) -> Result<Vec<InputEdit>, SwiftSyntaxTreeError> {
         const DIFF_DEADLINE_MS: u64 = 20;
         let edits = detect_input_edits(previous_tree.text_content(), new_content, DIFF_DEADLINE_MS);
 
-        for edit in edits {
-            previous_tree.tree_mut().edit(&edit);
+        for edit in &edits {
+            previous_tree.tree_mut().edit(edit);
         }
-        return Ok(());
+        return Ok(edits);
     }
 
     fn parse_content(
         code_text: XcodeText,
         mut previous_ast: Option<SwiftSyntaxTree>,
     ) -> Result<SwiftSyntaxTree, SwiftSyntaxTreeError> {
+        let mut span = DiagnosticsSpan::enter(DiagnosticsScope::Engine, "parse_content");
         let mut parser = SwiftSyntaxTree::parser();
 
+        // The metadata of the previous tree is moved along with its nodes, so only the subtrees
+        // affected by the edits are computed again.
         let mut previous_TSTree: Option<&Tree> = None;
+        let mut node_metadata = TreeMetaData::default();
         if let Some(previous_ast) = &mut previous_ast {
-            previous_TSTree = match Self::apply_edits_from_diff_to_tree(previous_ast, &code_text) {
-                Ok(_) => Some(previous_ast.tree()),
-                Err(_) => None,
-            };
+            if let Ok(edits) = Self::apply_edits_from_diff_to_tree(previous_ast, &code_text) {
+                node_metadata = std::mem::take(&mut previous_ast.node_metadata);
+                node_metadata.apply_edits(&edits);
+                previous_TSTree = Some(previous_ast.tree());
+            }
         }
 
-        let mut node_metadata: TreeMetaData = HashMap::new();
-        match parser.parse_utf16(&code_text, previous_TSTree) {
+        let result = match Self::parse(&mut parser, &code_text, previous_TSTree) {
             Some(tree) => {
-                calculate_cognitive_complexities(
-                    &tree.root_node(),
-                    &code_text,
-                    &mut node_metadata,
-                    None,
-                )?;
-
-                Ok(SwiftSyntaxTree::new(tree, node_metadata, code_text))
+                if let Some(previous_TSTree) = previous_TSTree {
+                    let changed_ranges: Vec<Range> =
+                        previous_TSTree.changed_ranges(&tree).collect();
+                    node_metadata.remove_changed_ranges(&changed_ranges);
+                }
+
+                let complexities = DiagnosticsSpan::measure(
+                    DiagnosticsScope::Engine,
+                    "calculate_cognitive_complexities",
+                    || {
+                        calculate_cognitive_complexities(
+                            &tree.root_node(),
+                            &code_text,
+                            &mut node_metadata,
+                            None,
+                        )
+                    },
+                );
+
+                // Unlike the metadata, the symbol table is built again for the whole file on every
+                // parse: an edited declaration changes which symbol the references after it resolve
+                // to, also outside of the changed ranges, and every symbol id after it shifts.
+                // Building it is two linear walks over the tree, measured by the span below.
+                let symbols_span =
+                    DiagnosticsSpan::enter(DiagnosticsScope::Engine, "build_symbol_table");
+                let symbol_table = SymbolTable::new(&tree, &code_text);
+                drop(symbols_span);
+
+                complexities
+                    .map(|_| SwiftSyntaxTree::new(tree, node_metadata, symbol_table, code_text))
+                    .map_err(SwiftSyntaxTreeError::from)
             }
             None => Err(SwiftSyntaxTreeError::CouldNotParseTree),
-        }
+        };
+
+        span.record_result(&result);
+        result
     }
 
     pub fn parser() -> Parser {
@@ -175,6 +211,11 @@ impl SwiftSyntaxTree {
 
         parser
     }
+
+    // Tree-sitter reads the text chunk by chunk, so it is not copied out of the rope.
+    pub fn parse(parser: &mut Parser, text: &XcodeText, old_tree: Option<&Tree>) -> Option<Tree> {
+        parser.parse_utf16_with(&mut |index, _| text.chunk_at(index), old_tree)
+    }
 }
 
 #[cfg(test)]
@@ -236,8 +277,7 @@ mod tests_SwiftSyntaxTree {
     #[test]
     fn test_start_end_point_with_UTF16_chars() {
         let mut text = XcodeText::from_str("// 😊\n");
-        let mut utf8_str = XcodeText::from_str("let x = 1; cansole.lug(x);");
-        text.append(&mut utf8_str);
+        text += XcodeText::from_str("let x = 1; cansole.lug(x);");
 
         let swift_syntax_tree = block_on(SwiftSyntaxTree::from_XcodeText(text, None)).unwrap();
 
@@ -257,4 +297,126 @@ mod tests_SwiftSyntaxTree {
             TextPosition { row: 1, column: 26 }
         );
     }
+
+    fn assert_incremental_metadata_matches_fresh_parse(code: &str, edited_code: &str) {
+        let previous_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+            XcodeText::from_str(code),
+            None,
+        ))
+        .unwrap();
+        let incremental_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+            XcodeText::from_str(edited_code),
+            Some(previous_tree),
+        ))
+        .unwrap();
+        let fresh_tree = block_on(SwiftSyntaxTree::from_XcodeText(
+            XcodeText::from_str(edited_code),
+            None,
+        ))
+        .unwrap();
+
+        let mut nodes = vec![fresh_tree.tree().root_node()];
+        while let Some(node) = nodes.pop() {
+            assert_eq!(
+                incremental_tree
+                    .get_metadata_of_node(&node)
+                    .map(|metadata| metadata.complexities.clone())
+                    .ok(),
+                fresh_tree
+                    .get_metadata_of_node(&node)
+                    .map(|metadata| metadata.complexities.clone())
+                    .ok(),
+                "{} at {}",
+                node.kind(),
+                node.start_byte()
+            );
+            nodes.extend(node.named_children(&mut node.walk()));
+        }
+    }
+
+    #[test]
+    fn test_incremental_parse_reuses_metadata_of_unchanged_functions() {
+        let code =
+            "func a() {\n    if x { return }\n}\n\nfunc b() {\n    for y in z { print(y) }\n}\n";
+
+        assert_incremental_metadata_matches_fresh_parse(
+            code,
+            &code.replace("print(y)", "if y { print(y) }"),
+        );
+        assert_incremental_metadata_matches_fresh_parse(
+            code,
+            &code.replace("func a() {", "func z() {\n    z()"),
+        );
+    }
+
+    #[test]
+    fn test_incremental_parse_updates_nesting_of_wrapped_code() {
+        let code = "func a() {\n    for y in z {\n        if y { print(y) }\n    }\n}\n";
+
+        // The inner `if` keeps its text but is nested one level deeper
+        assert_incremental_metadata_matches_fresh_parse(
+            code,
+            &code
+                .replace("    for y in z {\n", "    while true {\n    for y in z {\n")
+                .replace("}\n}\n", "}\n}\n}\n"),
+        );
+    }
+
+    mod properties {
+        use proptest::{collection::vec, prelude::*};
+        use tauri::async_runtime::block_on;
+
+        use crate::core_engine::{
+            syntax_tree::SwiftSyntaxTree,
+            utils::swift_text_strategies::{swift_code, text_edit},
+            XcodeText,
+        };
+
+        proptest! {
+            #[test]
+            fn incremental_metadata_equals_fresh_metadata(
+                code in swift_code(),
+                edits in vec(text_edit(), 1..4),
+            ) {
+                let mut texts = vec![];
+                let mut text = code.clone();
+                for edit in &edits {
+                    text = edit.apply(&text);
+                    texts.push(text.clone());
+                }
+                texts.push(code.clone());
+
+                let mut tree =
+                    block_on(SwiftSyntaxTree::from_XcodeText(XcodeText::from_str(&code), None))
+                        .unwrap();
+                for text in texts {
+                    tree = block_on(SwiftSyntaxTree::from_XcodeText(
+                        XcodeText::from_str(&text),
+                        Some(tree),
+                    ))
+                    .unwrap();
+                    let fresh_tree =
+                        block_on(SwiftSyntaxTree::from_XcodeText(XcodeText::from_str(&text), None))
+                            .unwrap();
+                    if fresh_tree.tree().root_node().has_error() {
+                        continue;
+                    }
+
+                    let mut nodes = vec![fresh_tree.tree().root_node()];
+                    while let Some(node) = nodes.pop() {
+                        prop_assert_eq!(
+                            tree.get_metadata_of_node(&node)
+                                .map(|metadata| metadata.complexities.clone())
+                                .ok(),
+                            fresh_tree
+                                .get_metadata_of_node(&node)
+                                .map(|metadata| metadata.complexities.clone())
+                                .ok()
+                        );
+                        nodes.extend(node.named_children(&mut node.walk()));
+                    }
+                }
+            }
+        }
+    }
 }
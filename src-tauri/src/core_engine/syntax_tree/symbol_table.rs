@@ -0,0 +1,603 @@
+use std::collections::{HashMap, HashSet};
+
+use tree_sitter::{Node, Tree};
+
+use crate::core_engine::utils::{TextRange, XcodeText};
+
+use super::get_node_text;
+
+pub type ScopeId = usize;
+pub type SymbolId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    File,
+    Type,
+    Function,
+    Closure,
+    Block,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Variable,
+    Property,
+    Binding, // if let, guard let, for, switch and catch patterns
+    Parameter,
+    ClosureParameter,
+    Function,
+    Method,
+    Type,
+    EnumCase,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    pub id: ScopeId,
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    pub range: TextRange,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub id: SymbolId,
+    pub name: XcodeText,
+    pub kind: SymbolKind,
+    pub scope: ScopeId,
+    pub declaration: TextRange,
+    pub visibility: TextRange, // Part of the scope in which references resolve to this symbol
+    pub references: Vec<TextRange>,
+}
+
+// Declarations and resolved references of a single file. Names are resolved lexically: the
+// innermost scope wins and within a scope the latest visible declaration shadows earlier ones.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    scopes: Vec<Scope>,
+    symbols: Vec<Symbol>,
+    names_by_scope: Vec<HashMap<XcodeText, Vec<SymbolId>>>,
+}
+
+impl SymbolTable {
+    pub fn new(tree: &Tree, text: &XcodeText) -> Self {
+        let mut builder = SymbolTableBuilder {
+            table: SymbolTable::default(),
+            text,
+            scope_by_node: HashMap::new(),
+            declaration_nodes: HashSet::new(),
+        };
+
+        let root = tree.root_node();
+        builder.collect_declarations(root, None);
+        if let Some(root_scope) = builder.scope_by_node.get(&root.id()).cloned() {
+            builder.resolve_references(root, root_scope);
+        }
+
+        builder.table
+    }
+
+    pub fn scopes(&self) -> &Vec<Scope> {
+        &self.scopes
+    }
+
+    pub fn symbols(&self) -> &Vec<Symbol> {
+        &self.symbols
+    }
+
+    pub fn symbol(&self, id: SymbolId) -> Option<&Symbol> {
+        self.symbols.get(id)
+    }
+
+    // The innermost scope containing the index.
+    pub fn scope_at(&self, index: usize) -> Option<&Scope> {
+        self.scopes
+            .iter()
+            .filter(|scope| contains_index(&scope.range, index))
+            .min_by_key(|scope| scope.range.length)
+    }
+
+    // The symbol declared or referenced at the index.
+    pub fn symbol_at(&self, index: usize) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| {
+            symbol.declaration.includes_index(index)
+                || symbol
+                    .references
+                    .iter()
+                    .any(|reference| reference.includes_index(index))
+        })
+    }
+
+    // Reference to definition: the symbol a reference at the index resolves to.
+    pub fn definition_of(&self, reference_index: usize) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| {
+            symbol
+                .references
+                .iter()
+                .any(|reference| reference.includes_index(reference_index))
+        })
+    }
+
+    // The symbol whose declaring name is at the index.
+    pub fn declared_at(&self, declaration_index: usize) -> Option<&Symbol> {
+        self.symbols
+            .iter()
+            .find(|symbol| symbol.declaration.includes_index(declaration_index))
+    }
+
+    // Definition to references: all resolved references of the symbol declared at the index.
+    pub fn references_of(&self, declaration_index: usize) -> Option<&Vec<TextRange>> {
+        self.declared_at(declaration_index)
+            .map(|symbol| &symbol.references)
+    }
+
+    // The symbol the name would resolve to if it was written at the index.
+    pub fn lookup(&self, name: &XcodeText, index: usize) -> Option<&Symbol> {
+        let scope = self.scope_at(index)?;
+        self.resolve(name, index, scope.id, |_| true)
+            .and_then(|id| self.symbols.get(id))
+    }
+
+    fn resolve(
+        &self,
+        name: &XcodeText,
+        index: usize,
+        innermost_scope: ScopeId,
+        accepts: impl Fn(SymbolKind) -> bool,
+    ) -> Option<SymbolId> {
+        let mut scope_id = Some(innermost_scope);
+        while let Some(id) = scope_id {
+            let candidates = self
+                .names_by_scope
+                .get(id)
+                .and_then(|names| names.get(name));
+            let visible = candidates.into_iter().flatten().filter(|symbol_id| {
+                let symbol = &self.symbols[**symbol_id];
+                accepts(symbol.kind) && contains_index(&symbol.visibility, index)
+            });
+            if let Some(symbol_id) =
+                visible.max_by_key(|symbol_id| self.symbols[**symbol_id].visibility.index)
+            {
+                return Some(*symbol_id);
+            }
+            scope_id = self.scopes[id].parent;
+        }
+        None
+    }
+}
+
+struct SymbolTableBuilder<'a> {
+    table: SymbolTable,
+    text: &'a XcodeText,
+    scope_by_node: HashMap<usize, ScopeId>,
+    declaration_nodes: HashSet<usize>,
+}
+
+impl<'a> SymbolTableBuilder<'a> {
+    fn collect_declarations(&mut self, node: Node, enclosing_scope: Option<ScopeId>) {
+        let scope = match scope_kind(&node) {
+            Some(kind) => Some(self.add_scope(&node, kind, enclosing_scope)),
+            None => enclosing_scope,
+        };
+
+        if let Some(enclosing_scope) = enclosing_scope {
+            self.collect_named_declaration(&node, enclosing_scope);
+        }
+
+        let mut cursor = node.walk();
+        if !cursor.goto_first_child() {
+            return;
+        }
+        loop {
+            let child = cursor.node();
+            if let Some(scope) = scope {
+                if cursor.field_name() == Some("bound_identifier") {
+                    self.collect_binding(child, scope);
+                }
+            }
+            self.collect_declarations(child, scope);
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    // Declarations which live in the scope around the declaring node, e.g. a function's name.
+    fn collect_named_declaration(&mut self, node: &Node, enclosing_scope: ScopeId) {
+        let in_type = self.table.scopes[enclosing_scope].kind == ScopeKind::Type;
+        let whole_scope = self.table.scopes[enclosing_scope].range;
+
+        match node.kind() {
+            "function_declaration" | "protocol_function_declaration" => {
+                let kind = if in_type {
+                    SymbolKind::Method
+                } else {
+                    SymbolKind::Function
+                };
+                if let Some(name) = last_child_by_field_name(node, "name", "simple_identifier") {
+                    self.add_symbol(&name, kind, enclosing_scope, whole_scope);
+                }
+            }
+            "class_declaration" | "protocol_declaration" | "typealias_declaration" => {
+                // Extensions name an existing type, which makes the name a reference instead.
+                if let Some(name) = last_child_by_field_name(node, "name", "type_identifier") {
+                    self.add_symbol(&name, SymbolKind::Type, enclosing_scope, whole_scope);
+                }
+            }
+            "enum_entry" => {
+                for name in node.children_by_field_name("name", &mut node.walk()) {
+                    if name.kind() == "simple_identifier" {
+                        self.add_symbol(&name, SymbolKind::EnumCase, enclosing_scope, whole_scope);
+                    }
+                }
+            }
+            "parameter" => {
+                // The last name is the internal one; a preceding one is the argument label.
+                if let Some(name) = last_child_by_field_name(node, "name", "simple_identifier") {
+                    self.add_symbol(&name, SymbolKind::Parameter, enclosing_scope, whole_scope);
+                }
+            }
+            "lambda_parameter" => {
+                if let Some(name) = last_child_by_field_name(node, "name", "simple_identifier") {
+                    self.add_symbol(
+                        &name,
+                        SymbolKind::ClosureParameter,
+                        enclosing_scope,
+                        whole_scope,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // `let`/`var` declarations and pattern bindings. `scope` is the innermost scope around them.
+    fn collect_binding(&mut self, identifier: Node, scope: ScopeId) {
+        let scope_range = self.table.scopes[scope].range;
+        let scope_end = scope_range.index + scope_range.length;
+        let scope_kind = self.table.scopes[scope].kind;
+
+        let mut child = identifier;
+        while let Some(ancestor) = child.parent() {
+            match ancestor.kind() {
+                "property_declaration" | "protocol_property_declaration" => {
+                    if scope_kind == ScopeKind::File || scope_kind == ScopeKind::Type {
+                        self.add_symbol(&identifier, SymbolKind::Property, scope, scope_range);
+                    } else {
+                        // Visible after the declaration, so `let x = x + 1` refers to an outer `x`.
+                        let visibility =
+                            TextRange::from_StartEndIndex(node_end(&ancestor), scope_end);
+                        self.add_symbol(&identifier, SymbolKind::Variable, scope, visibility);
+                    }
+                    return;
+                }
+                "guard_statement" => {
+                    let visibility = TextRange::from_StartEndIndex(node_end(&ancestor), scope_end);
+                    self.add_symbol(&identifier, SymbolKind::Binding, scope, visibility);
+                    return;
+                }
+                _ if scope_kind(&ancestor).is_some() => {
+                    let visibility = binding_visibility(&ancestor, &child, scope_end);
+                    self.add_symbol(&identifier, SymbolKind::Binding, scope, visibility);
+                    return;
+                }
+                _ => child = ancestor,
+            }
+        }
+    }
+
+    fn resolve_references(&mut self, node: Node, enclosing_scope: ScopeId) {
+        let scope = self
+            .scope_by_node
+            .get(&node.id())
+            .cloned()
+            .unwrap_or(enclosing_scope);
+
+        if !self.declaration_nodes.contains(&node.id()) {
+            match node.kind() {
+                "simple_identifier" => self.resolve_identifier(&node, scope),
+                "type_identifier" => {
+                    self.add_reference(&node, scope, |kind| kind == SymbolKind::Type)
+                }
+                _ => {}
+            }
+        }
+
+        for child in node.children(&mut node.walk()) {
+            self.resolve_references(child, scope);
+        }
+    }
+
+    fn resolve_identifier(&mut self, node: &Node, scope: ScopeId) {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return,
+        };
+
+        match parent.kind() {
+            // Labels and names which only have a meaning in the context of another symbol.
+            "value_argument_label"
+            | "parameter"
+            | "lambda_parameter"
+            | "identifier"
+            | "enum_entry"
+            | "function_declaration"
+            | "protocol_function_declaration" => {}
+            "navigation_suffix" => {
+                // Only `self.member` can be resolved without type information.
+                let target = parent
+                    .parent()
+                    .and_then(|navigation| navigation.child_by_field_name("target"));
+                if target.map(|target| target.kind()) == Some("self_expression") {
+                    if let Some(type_scope) = self.enclosing_type_scope(scope) {
+                        self.add_reference(node, type_scope, is_member);
+                    }
+                }
+            }
+            "prefix_expression" if parent.child(0).map(|op| op.kind()) == Some(".") => {
+                // Implicit member expressions like `.success` need the type of the context.
+            }
+            _ => self.add_reference(node, scope, |_| true),
+        }
+    }
+
+    fn enclosing_type_scope(&self, scope: ScopeId) -> Option<ScopeId> {
+        let mut scope_id = Some(scope);
+        while let Some(id) = scope_id {
+            if self.table.scopes[id].kind == ScopeKind::Type {
+                return Some(id);
+            }
+            scope_id = self.table.scopes[id].parent;
+        }
+        None
+    }
+
+    fn add_scope(&mut self, node: &Node, kind: ScopeKind, parent: Option<ScopeId>) -> ScopeId {
+        let id = self.table.scopes.len();
+        self.table.scopes.push(Scope {
+            id,
+            kind,
+            parent,
+            range: node_range(node),
+        });
+        self.table.names_by_scope.push(HashMap::new());
+        self.scope_by_node.insert(node.id(), id);
+        id
+    }
+
+    fn add_symbol(
+        &mut self,
+        name_node: &Node,
+        kind: SymbolKind,
+        scope: ScopeId,
+        visibility: TextRange,
+    ) {
+        let name = match get_node_text(name_node, self.text) {
+            Ok(name) => name,
+            Err(_) => return,
+        };
+
+        let id = self.table.symbols.len();
+        self.table.names_by_scope[scope]
+            .entry(name.clone())
+            .or_default()
+            .push(id);
+        self.table.symbols.push(Symbol {
+            id,
+            name,
+            kind,
+            scope,
+            declaration: node_range(name_node),
+            visibility,
+            references: Vec::new(),
+        });
+        self.declaration_nodes.insert(name_node.id());
+    }
+
+    fn add_reference(&mut self, node: &Node, scope: ScopeId, accepts: impl Fn(SymbolKind) -> bool) {
+        let name = match get_node_text(node, self.text) {
+            Ok(name) => name,
+            Err(_) => return,
+        };
+
+        let range = node_range(node);
+        if let Some(symbol_id) = self.table.resolve(&name, range.index, scope, accepts) {
+            self.table.symbols[symbol_id].references.push(range);
+        }
+    }
+}
+
+fn scope_kind(node: &Node) -> Option<ScopeKind> {
+    match node.kind() {
+        "source_file" => Some(ScopeKind::File),
+        "class_body" | "enum_class_body" | "protocol_body" => Some(ScopeKind::Type),
+        "function_declaration"
+        | "protocol_function_declaration"
+        | "init_declaration"
+        | "deinit_declaration"
+        | "subscript_declaration" => Some(ScopeKind::Function),
+        "lambda_literal" => Some(ScopeKind::Closure),
+        "statements" | "if_statement" | "for_statement" | "while_statement" | "switch_entry"
+        | "catch_block" => Some(ScopeKind::Block),
+        _ => None,
+    }
+}
+
+// Bindings in conditions and patterns become visible where their clause ends, so in
+// `if let x = x {` the right hand side still refers to the outer `x`. Bindings of an `if` are
+// not visible in its `else` branch.
+fn binding_visibility(scope_node: &Node, binding_child: &Node, scope_end: usize) -> TextRange {
+    const CLAUSE_TERMINATORS: [&str; 6] = [",", "{", ":", "else", "where", "where_keyword"];
+
+    let mut visible_from = node_end(binding_child);
+    let mut sibling = binding_child.next_sibling();
+    while let Some(node) = sibling {
+        if CLAUSE_TERMINATORS.contains(&node.kind()) {
+            visible_from = node.start_byte() / 2;
+            break;
+        }
+        sibling = node.next_sibling();
+    }
+
+    let mut visible_until = scope_end;
+    if scope_node.kind() == "if_statement" {
+        let mut sibling = binding_child.next_sibling();
+        while let Some(node) = sibling {
+            if node.kind() == "}" {
+                visible_until = node_end(&node);
+                break;
+            }
+            sibling = node.next_sibling();
+        }
+    }
+
+    TextRange::from_StartEndIndex(visible_from, visible_until.max(visible_from))
+}
+
+fn last_child_by_field_name<'a>(node: &Node<'a>, field_name: &str, kind: &str) -> Option<Node<'a>> {
+    node.children_by_field_name(field_name, &mut node.walk())
+        .filter(|child| child.kind() == kind)
+        .last()
+}
+
+fn is_member(kind: SymbolKind) -> bool {
+    kind == SymbolKind::Property || kind == SymbolKind::Method || kind == SymbolKind::EnumCase
+}
+
+// Unlike `TextRange::includes_index`, the end of a range counts as inside, matching a cursor
+// placed right after the last character.
+fn contains_index(range: &TextRange, index: usize) -> bool {
+    index >= range.index && index <= range.index + range.length
+}
+
+fn node_range(node: &Node) -> TextRange {
+    TextRange::from_StartEndIndex(node.start_byte() / 2, node_end(node))
+}
+
+fn node_end(node: &Node) -> usize {
+    node.end_byte() / 2
+}
+
+#[cfg(test)]
+mod tests {
+    mod symbol_table {
+        use tauri::async_runtime::block_on;
+
+        use crate::core_engine::{
+            syntax_tree::{SwiftSyntaxTree, SymbolKind},
+            XcodeText,
+        };
+
+        fn occurrences(code: &str, name: &str) -> Vec<usize> {
+            code.match_indices(name).map(|(index, _)| index).collect()
+        }
+
+        fn parse(code: &str) -> SwiftSyntaxTree {
+            block_on(SwiftSyntaxTree::from_XcodeText(
+                XcodeText::from_str(code),
+                None,
+            ))
+            .unwrap()
+        }
+
+        #[test]
+        fn resolves_shadowed_locals() {
+            let code = r#"
+func compute(value: Int) -> Int {
+    let value = value + 1
+    if let value = Optional(value) {
+        return value
+    }
+    return value
+}
+"#;
+            let tree = parse(code);
+            let symbols = tree.symbol_table();
+            let value = occurrences(code, "value");
+
+            let parameter = symbols.definition_of(value[2]).unwrap();
+            assert_eq!(parameter.kind, SymbolKind::Parameter);
+            assert_eq!(parameter.declaration.index, value[0]);
+
+            let local = symbols.definition_of(value[4]).unwrap();
+            assert_eq!(local.kind, SymbolKind::Variable);
+            assert_eq!(local.declaration.index, value[1]);
+            assert_eq!(symbols.definition_of(value[6]).unwrap().id, local.id);
+
+            let binding = symbols.definition_of(value[5]).unwrap();
+            assert_eq!(binding.kind, SymbolKind::Binding);
+            assert_eq!(binding.declaration.index, value[3]);
+        }
+
+        #[test]
+        fn finds_references_of_guard_bindings_and_closure_parameters() {
+            let code = r#"
+func load(items: [Int]) {
+    guard let first = items.first else { return }
+    let doubled = items.map { item in item * first }
+    print(doubled)
+}
+"#;
+            let tree = parse(code);
+            let symbols = tree.symbol_table();
+            let first = occurrences(code, "first");
+            let item = occurrences(code, "item ");
+
+            let references = symbols.references_of(first[0]).unwrap();
+            assert_eq!(references.len(), 1);
+            assert_eq!(references[0].index, first[2]);
+
+            let closure_parameter = symbols.definition_of(item[1]).unwrap();
+            assert_eq!(closure_parameter.kind, SymbolKind::ClosureParameter);
+            assert_eq!(closure_parameter.declaration.index, item[0]);
+        }
+
+        #[test]
+        fn resolves_members_and_types() {
+            let code = r#"
+class Counter {
+    func increment() {
+        count += 1
+        self.count += 1
+    }
+    var count = 0
+}
+let counter = Counter()
+"#;
+            let tree = parse(code);
+            let symbols = tree.symbol_table();
+            let count = occurrences(code, "count ");
+            let counter_type = occurrences(code, "Counter");
+
+            let property = symbols.symbol_at(count[2]).unwrap();
+            assert_eq!(property.kind, SymbolKind::Property);
+            assert_eq!(property.references.len(), 2);
+
+            let counter_type_symbol = symbols.definition_of(counter_type[1]).unwrap();
+            assert_eq!(counter_type_symbol.kind, SymbolKind::Type);
+            assert_eq!(counter_type_symbol.declaration.index, counter_type[0]);
+        }
+
+        #[test]
+        fn looks_up_names_at_index() {
+            let code = r#"
+func outer() {
+    let a = 1
+    func inner() {
+        print(a)
+    }
+}
+"#;
+            let tree = parse(code);
+            let symbols = tree.symbol_table();
+            let print = occurrences(code, "print")[0];
+
+            let a = symbols.lookup(&XcodeText::from_str("a"), print).unwrap();
+            assert_eq!(a.kind, SymbolKind::Variable);
+            assert!(symbols
+                .lookup(&XcodeText::from_str("missing"), print)
+                .is_none());
+        }
+    }
+}
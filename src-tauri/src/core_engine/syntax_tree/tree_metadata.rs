@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use tree_sitter::{InputEdit, Node, Range};
+
+use super::NodeMetadata;
+
+// Identifies a node by its kind and position. Unlike `Node::id`, the key of a node which
+// tree-sitter reuses when parsing incrementally can be derived from the key it had before.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeKey {
+    kind_id: u16,
+    start_byte: usize,
+    end_byte: usize,
+}
+
+impl NodeKey {
+    pub fn new(node: &Node) -> Self {
+        Self {
+            kind_id: node.kind_id(),
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+        }
+    }
+
+    // None if the edit touches the node, because its subtree may have changed.
+    fn edited(&self, edit: &InputEdit) -> Option<Self> {
+        if self.end_byte < edit.start_byte {
+            Some(*self)
+        } else if self.start_byte > edit.old_end_byte {
+            Some(Self {
+                kind_id: self.kind_id,
+                start_byte: self.start_byte - edit.old_end_byte + edit.new_end_byte,
+                end_byte: self.end_byte - edit.old_end_byte + edit.new_end_byte,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn intersects(&self, range: &Range) -> bool {
+        self.start_byte < range.end_byte && range.start_byte < self.end_byte
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CachedMetadata {
+    metadata: NodeMetadata,
+    context: u64, // Hash of what the metadata depends on outside of the node's subtree
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TreeMetaData {
+    entries: HashMap<NodeKey, CachedMetadata>,
+}
+
+impl TreeMetaData {
+    pub fn get(&self, node: &Node) -> Option<&NodeMetadata> {
+        self.entries
+            .get(&NodeKey::new(node))
+            .map(|cached| &cached.metadata)
+    }
+
+    // The metadata of the node if it was computed in the same context, in which case the metadata
+    // of its descendants is still valid as well.
+    pub fn get_reusable(&self, node: &Node, context: u64) -> Option<&NodeMetadata> {
+        self.entries
+            .get(&NodeKey::new(node))
+            .filter(|cached| cached.context == context)
+            .map(|cached| &cached.metadata)
+    }
+
+    pub fn insert(&mut self, node: &Node, metadata: NodeMetadata, context: u64) {
+        self.entries
+            .insert(NodeKey::new(node), CachedMetadata { metadata, context });
+    }
+
+    // Moves the metadata to the positions of the edited text, in the same way `Tree::edit` moves
+    // the nodes. Metadata of the nodes containing an edit is dropped.
+    pub fn apply_edits(&mut self, edits: &[InputEdit]) {
+        if edits.is_empty() {
+            return;
+        }
+
+        self.entries = std::mem::take(&mut self.entries)
+            .into_iter()
+            .filter_map(|(key, cached)| {
+                let key = edits.iter().try_fold(key, |key, edit| key.edited(edit))?;
+                Some((key, cached))
+            })
+            .collect();
+    }
+
+    // Drops the metadata of the nodes whose structure changed, as reported by
+    // `Tree::changed_ranges` after parsing the edited text.
+    pub fn remove_changed_ranges(&mut self, changed_ranges: &[Range]) {
+        if changed_ranges.is_empty() {
+            return;
+        }
+
+        self.entries
+            .retain(|key, _| !changed_ranges.iter().any(|range| key.intersects(range)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod apply_edits {
+        use tree_sitter::{InputEdit, Node, Point};
+
+        use crate::core_engine::{
+            syntax_tree::{
+                calculate_cognitive_complexities, get_node_text, tree_metadata::TreeMetaData,
+                SwiftSyntaxTree,
+            },
+            XcodeText,
+        };
+
+        fn function_named<'a>(root: &Node<'a>, text: &XcodeText, name: &str) -> Node<'a> {
+            root.named_children(&mut root.walk())
+                .find(|node| {
+                    node.child_by_field_name("name")
+                        .and_then(|name_node| get_node_text(&name_node, text).ok())
+                        .map_or(false, |node_name| node_name == XcodeText::from_str(name))
+                })
+                .unwrap()
+        }
+
+        #[test]
+        fn moves_metadata_of_untouched_nodes() {
+            let code = "func a() {\n    if x { return }\n}\nfunc b() {\n    if y { return }\n}\nfunc c() {\n    if z { return }\n}\n";
+            let text = XcodeText::from_str(code);
+            let tree = SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &text, None).unwrap();
+            let mut metadata = TreeMetaData::default();
+            calculate_cognitive_complexities(&tree.root_node(), &text, &mut metadata, None)
+                .unwrap();
+
+            // Negates `y`, inserting a single UTF-16 character
+            let new_text = XcodeText::from_str(&code.replace("if y", "if !y"));
+            let y_byte = code.find('y').unwrap() * 2;
+            let edit = InputEdit {
+                start_byte: y_byte,
+                old_end_byte: y_byte,
+                new_end_byte: y_byte + 2,
+                start_position: Point::new(4, 14),
+                old_end_position: Point::new(4, 14),
+                new_end_position: Point::new(4, 16),
+            };
+            metadata.apply_edits(&[edit]);
+            let new_tree =
+                SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &new_text, None).unwrap();
+            let root = new_tree.root_node();
+
+            assert!(metadata
+                .get(&function_named(&root, &new_text, "a"))
+                .is_some());
+            assert!(metadata
+                .get(&function_named(&root, &new_text, "b"))
+                .is_none());
+            assert!(metadata
+                .get(&function_named(&root, &new_text, "c"))
+                .is_some());
+            assert!(metadata.get(&root).is_none());
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use tree_sitter::Node;
+
+use crate::core_engine::utils::XcodeText;
+
+use super::SwiftSyntaxTree;
+
+pub fn contains_kind(node: &Node, kind: &str) -> bool {
+    node.kind() == kind
+        || node
+            .children(&mut node.walk())
+            .any(|child| contains_kind(&child, kind))
+}
+
+pub fn identifiers_in<'a>(node: &Node<'a>) -> Vec<Node<'a>> {
+    let mut identifiers = vec![];
+    let mut nodes = vec![*node];
+    while let Some(node) = nodes.pop() {
+        if node.kind() == "simple_identifier" {
+            identifiers.push(node);
+        }
+        nodes.extend(node.children(&mut node.walk()));
+    }
+    identifiers
+}
+
+// True if the reference at the range is assigned to or passed as `inout`.
+pub fn is_mutated_at(syntax_tree: &SwiftSyntaxTree, index: usize, length: usize) -> bool {
+    let reference = syntax_tree
+        .tree()
+        .root_node()
+        .descendant_for_byte_range(index * 2, (index + length) * 2);
+
+    match reference.and_then(|reference| reference.parent()) {
+        Some(parent) => {
+            parent.kind() == "directly_assignable_expression"
+                || (parent.kind() == "prefix_expression"
+                    && parent.child(0).map(|operation| operation.kind()) == Some("&"))
+        }
+        None => false,
+    }
+}
+
+// The whitespace at the start of the line containing the index, up to the index.
+pub fn indentation_at(text: &XcodeText, index: usize) -> XcodeText {
+    let line_start = text
+        .slice(..index)
+        .iter()
+        .rposition(|c| *c == '\n' as u16)
+        .map_or(0, |newline| newline + 1);
+    let indentation_end = text
+        .slice(line_start..index)
+        .iter()
+        .position(|c| !XcodeText::char_is_whitespace(c))
+        .map_or(index, |offset| line_start + offset);
+
+    text.slice(line_start..indentation_end)
+}
@@ -1,9 +1,19 @@
 use std::env;
 
+use crate::app_state::{settings, ExplanationProvider};
+
+// The environment variable takes precedence over the configured explanation provider.
 pub fn get_cloud_function_url() -> String {
-    env::var("PRETZL_CLOUD_BACKEND_URL").unwrap_or(
-        "https://europe-west1-analyze-text-dev.cloudfunctions.net/analyze-code".to_string(),
-    )
+    if let Ok(url) = env::var("PRETZL_CLOUD_BACKEND_URL") {
+        return url;
+    }
+
+    match settings().explanation_provider {
+        ExplanationProvider::Custom { url } => url,
+        ExplanationProvider::PretzlCloud => {
+            "https://europe-west1-analyze-text-dev.cloudfunctions.net/analyze-code".to_string()
+        }
+    }
 }
 
 pub fn get_cloud_function_apikey() -> String {
@@ -2,6 +2,7 @@ pub use env::*;
 pub use lsp::{log_list_of_module_names, Lsp, SwiftLsp, SwiftLspError};
 pub use misc::*;
 pub use swift_format::*;
+pub use text_coordinates::DEFAULT_TAB_WIDTH;
 pub use text_position::*;
 pub use text_range::*;
 pub use xcode_text::*;
@@ -10,6 +11,10 @@ mod env;
 mod lsp;
 mod misc;
 mod swift_format;
+#[cfg(test)]
+pub mod swift_text_strategies;
+mod text_coordinates;
 mod text_position;
 mod text_range;
 mod xcode_text;
+mod xcode_text_rope;
@@ -6,6 +6,8 @@ use tauri::{
 };
 use tracing::warn;
 
+use crate::app_state::settings_for_file;
+
 use super::XcodeText;
 
 #[derive(thiserror::Error, Debug)]
@@ -66,6 +68,10 @@ pub async fn format_code(
 }
 
 fn get_swiftformat_args(file_path: &Option<String>) -> Vec<String> {
+    let formatter_settings = settings_for_file(file_path).formatter;
+    let max_width = formatter_settings.max_width.to_string();
+    let indent = formatter_settings.indent.to_string();
+
     let mut args = vec!["--quiet"];
 
     let swift_version;
@@ -87,7 +93,7 @@ fn get_swiftformat_args(file_path: &Option<String>) -> Vec<String> {
     } else {
         let mut default_config = vec![
           "--maxwidth",
-          "100",
+          max_width.as_str(),
           "--wraparguments",
           "before-first",
           "--wrapparameters",
@@ -95,7 +101,7 @@ fn get_swiftformat_args(file_path: &Option<String>) -> Vec<String> {
           "--wrapcollections",
           "before-first",
           "--indent",
-          "4",
+          indent.as_str(),
           "--semicolons",
           "never",
           "--markcategories",
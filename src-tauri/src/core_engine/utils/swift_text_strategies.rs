@@ -0,0 +1,118 @@
+// Generators of Swift-like text and edits for property tests. The fragments mix keywords,
+// nesting, multi-line constructs and characters outside of the Basic Multilingual Plane, which
+// take two UTF-16 code units, as well as tabs and both kinds of line breaks.
+//
+// The properties run with proptest's default number of cases. The targets in `fuzz/` check the
+// text coordinates and incremental parsing on arbitrary text for as long as they are run.
+
+use proptest::{collection::vec, prelude::*, sample::select};
+
+const FRAGMENTS: &[&str] = &[
+    "func f() {",
+    "func g(a: Int) -> Int {",
+    "}",
+    "if x {",
+    "} else {",
+    "} else if y {",
+    "for i in xs {",
+    "while a && b || c {",
+    "guard let v = v else { return }",
+    "switch s { case 1: break default: break }",
+    "let a = 1",
+    "var b = \"é😊\"",
+    "f()",
+    "g(a: b ? 1 : 2)",
+    "xs.map { $0 + 1 }",
+    "return",
+    "// 😊 comment",
+    "/* block */",
+    "😊",
+    "\n",
+    "\n\n",
+    "\r\n",
+    "    ",
+    "\t",
+];
+
+const STATEMENTS: &[&str] = &[
+    "let a = 1",
+    "var b = \"é😊\"",
+    "f()",
+    "g(a: b ? 1 : 2)",
+    "xs.map { $0 + 1 }",
+    "guard let v = v else { return }",
+    "// 😊 comment",
+    "return",
+];
+
+const BLOCK_HEADERS: &[&str] = &[
+    "if x",
+    "if a && b || c",
+    "for i in xs",
+    "while y",
+    "func f()",
+    "func g(a: Int) -> Int",
+];
+
+// Any sequence of fragments, mostly not valid Swift.
+pub fn swift_text() -> impl Strategy<Value = String> {
+    vec(select(FRAGMENTS), 0..40).prop_map(|fragments| {
+        fragments
+            .into_iter()
+            .fold(String::new(), |text, fragment| text + fragment + " ")
+    })
+}
+
+// Nested statements which parse without errors.
+pub fn swift_code() -> impl Strategy<Value = String> {
+    let statement = select(STATEMENTS).prop_map(|statement| statement.to_string());
+    let nested_statement =
+        statement.prop_recursive(3, 32, 4, |inner| {
+            prop_oneof![
+                (select(BLOCK_HEADERS), vec(inner.clone(), 0..4))
+                    .prop_map(|(header, body)| format!("{} {{\n{}\n}}", header, body.join("\n"))),
+                (vec(inner.clone(), 0..3), vec(inner, 0..3)).prop_map(|(then_body, else_body)| {
+                    format!(
+                        "if x {{\n{}\n}} else {{\n{}\n}}",
+                        then_body.join("\n"),
+                        else_body.join("\n")
+                    )
+                }),
+            ]
+        });
+
+    vec(nested_statement, 0..6).prop_map(|statements| statements.join("\n"))
+}
+
+#[derive(Clone, Debug)]
+pub struct TextEdit {
+    start: usize,
+    removed_char_count: usize,
+    inserted: String,
+}
+
+impl TextEdit {
+    // Edits on char boundaries, so the text stays valid UTF-16. Positions beyond the end of the
+    // text are wrapped around.
+    pub fn apply(&self, text: &str) -> String {
+        let mut chars: Vec<char> = text.chars().collect();
+        let start = self.start % (chars.len() + 1);
+        let end = (start + self.removed_char_count).min(chars.len());
+
+        chars.splice(start..end, self.inserted.chars());
+        chars.into_iter().collect()
+    }
+}
+
+pub fn text_edit() -> impl Strategy<Value = TextEdit> {
+    (
+        any::<usize>(),
+        0..20usize,
+        vec(select(FRAGMENTS), 0..3).prop_map(|fragments| fragments.concat()),
+    )
+        .prop_map(|(start, removed_char_count, inserted)| TextEdit {
+            start,
+            removed_char_count,
+            inserted,
+        })
+}
@@ -0,0 +1,319 @@
+// Conversions between the coordinates used for text: UTF-16 indexes and columns as stored in
+// `XcodeText` and used by the AX API and tree-sitter, UTF-8 byte offsets and columns as used by
+// SourceKit, and grapheme and visual columns as seen by the user in the editor.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{TextPosition, TextRange, XcodeChar, XcodeText};
+
+pub const DEFAULT_TAB_WIDTH: usize = 4; // Xcode's default
+
+impl XcodeText {
+    // Index of the `\n` ending the row, or the end of the text for the last row.
+    pub fn row_end(&self, row: usize) -> Option<usize> {
+        self.line_start(row)?;
+        Some(
+            self.line_start(row + 1)
+                .map_or(self.len(), |next_row_start| next_row_start - 1),
+        )
+    }
+
+    // The characters of the row, without its `\n` or `\r\n` line break.
+    pub fn row_content_range(&self, row: usize) -> Option<TextRange> {
+        let start = self.line_start(row)?;
+        let mut end = self.row_end(row)?;
+        if end < self.len() && end > start && self[end - 1] == '\r' as XcodeChar {
+            end -= 1;
+        }
+        Some(TextRange::from_StartEndIndex(start, end))
+    }
+
+    // False between the two code units of a surrogate pair.
+    pub fn is_char_boundary(&self, index: usize) -> bool {
+        if index == 0 || index >= self.len() {
+            return index <= self.len();
+        }
+        !(is_low_surrogate(self[index]) && is_high_surrogate(self[index - 1]))
+    }
+
+    pub fn utf8_offset_of_index(&self, index: usize) -> Option<usize> {
+        if !self.is_char_boundary(index) {
+            return None;
+        }
+        Some(utf8_len(&self.slice(..index)))
+    }
+
+    pub fn index_of_utf8_offset(&self, utf8_offset: usize) -> Option<usize> {
+        index_of_utf8_len(self, utf8_offset)
+    }
+
+    pub fn utf8_column(&self, position: &TextPosition) -> Option<usize> {
+        let index = position.as_TextIndex(self)?;
+        if !self.is_char_boundary(index) {
+            return None;
+        }
+        Some(utf8_len(&self.slice(self.line_start(position.row)?..index)))
+    }
+
+    // None if the column is beyond the row's content or inside a character.
+    pub fn position_of_utf8_column(&self, row: usize, utf8_column: usize) -> Option<TextPosition> {
+        let content_range = self.row_content_range(row)?;
+        let content = self.slice(content_range.index..content_range.index + content_range.length);
+        let column = index_of_utf8_len(&content, utf8_column)?;
+        Some(TextPosition::new(row, column))
+    }
+
+    // None if the position is beyond the row's content or inside a grapheme, e.g. between an
+    // emoji and its skin tone modifier.
+    pub fn grapheme_column(&self, position: &TextPosition) -> Option<usize> {
+        self.grapheme_boundaries(position.row, |_, column| column + 1)?
+            .into_iter()
+            .find(|(column, _)| *column == position.column)
+            .map(|(_, grapheme_column)| grapheme_column)
+    }
+
+    pub fn position_of_grapheme_column(
+        &self,
+        row: usize,
+        grapheme_column: usize,
+    ) -> Option<TextPosition> {
+        self.grapheme_boundaries(row, |_, column| column + 1)?
+            .into_iter()
+            .find(|(_, other_column)| *other_column == grapheme_column)
+            .map(|(column, _)| TextPosition::new(row, column))
+    }
+
+    // The column the position is displayed at, with tabs advancing to the next tab stop and
+    // every other grapheme taking up one column.
+    pub fn visual_column(&self, position: &TextPosition, tab_width: usize) -> Option<usize> {
+        self.grapheme_boundaries(position.row, advance_visual_column(tab_width))?
+            .into_iter()
+            .find(|(column, _)| *column == position.column)
+            .map(|(_, visual_column)| visual_column)
+    }
+
+    // None if the column is beyond the row's content or within a tab.
+    pub fn position_of_visual_column(
+        &self,
+        row: usize,
+        visual_column: usize,
+        tab_width: usize,
+    ) -> Option<TextPosition> {
+        self.grapheme_boundaries(row, advance_visual_column(tab_width))?
+            .into_iter()
+            .find(|(_, other_column)| *other_column == visual_column)
+            .map(|(column, _)| TextPosition::new(row, column))
+    }
+
+    // The UTF-16 column of each grapheme boundary of the row's content, paired with the column
+    // counted by `advance`, which gets the grapheme and the column before it.
+    fn grapheme_boundaries(
+        &self,
+        row: usize,
+        advance: impl Fn(&str, usize) -> usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let content_range = self.row_content_range(row)?;
+        // Unpaired surrogates become U+FFFD, which is a single code unit as well.
+        let content = self
+            .slice(content_range.index..content_range.index + content_range.length)
+            .as_string();
+
+        let mut boundaries = vec![(0, 0)];
+        for grapheme in content.graphemes(true) {
+            let (column, other_column) = boundaries[boundaries.len() - 1];
+            boundaries.push((
+                column + grapheme.encode_utf16().count(),
+                advance(grapheme, other_column),
+            ));
+        }
+        Some(boundaries)
+    }
+}
+
+fn advance_visual_column(tab_width: usize) -> impl Fn(&str, usize) -> usize {
+    move |grapheme, column| {
+        if grapheme == "\t" {
+            (column / tab_width + 1) * tab_width
+        } else {
+            column + 1
+        }
+    }
+}
+
+fn is_high_surrogate(c: XcodeChar) -> bool {
+    (0xD800..0xDC00).contains(&c)
+}
+
+fn is_low_surrogate(c: XcodeChar) -> bool {
+    (0xDC00..0xE000).contains(&c)
+}
+
+// Code units and bytes of each character; unpaired surrogates are counted like U+FFFD.
+fn char_lengths(text: &XcodeText) -> impl Iterator<Item = (usize, usize)> + '_ {
+    char::decode_utf16(text.iter().copied()).map(|c| {
+        let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
+        (c.len_utf16(), c.len_utf8())
+    })
+}
+
+fn utf8_len(text: &XcodeText) -> usize {
+    char_lengths(text).map(|(_, utf8_len)| utf8_len).sum()
+}
+
+// The number of code units of the prefix with the given UTF-8 length, if it ends on a character.
+fn index_of_utf8_len(text: &XcodeText, utf8_len: usize) -> Option<usize> {
+    let mut index = 0;
+    let mut prefix_utf8_len = 0;
+    for (char_utf16_len, char_utf8_len) in char_lengths(text) {
+        if prefix_utf8_len >= utf8_len {
+            break;
+        }
+        index += char_utf16_len;
+        prefix_utf8_len += char_utf8_len;
+    }
+
+    if prefix_utf8_len == utf8_len {
+        Some(index)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod rows {
+        use crate::core_engine::utils::{TextRange, XcodeText};
+
+        #[test]
+        fn crlf_belongs_to_the_row_it_ends() {
+            let text = XcodeText::from_str("a\r\nbc\nd");
+
+            assert_eq!(text.row_count(), 3);
+            assert_eq!(text.line_start(1), Some(3));
+            assert_eq!(text.line_start(2), Some(6));
+            assert_eq!(text.line_start(3), None);
+            assert_eq!(text.row_of_index(1), Some(0));
+            assert_eq!(text.row_of_index(2), Some(0));
+            assert_eq!(text.row_of_index(3), Some(1));
+            assert_eq!(text.row_of_index(7), Some(2));
+            assert_eq!(text.row_of_index(8), None);
+            assert_eq!(text.row_content_range(0), Some(TextRange::new(0, 1)));
+            assert_eq!(text.row_content_range(1), Some(TextRange::new(3, 2)));
+            assert_eq!(text.row_content_range(2), Some(TextRange::new(6, 1)));
+            assert_eq!(text.row_content_range(3), None);
+        }
+
+        #[test]
+        fn carriage_return_at_the_end_of_the_text() {
+            let text = XcodeText::from_str("a\r");
+
+            assert_eq!(text.row_content_range(0), Some(TextRange::new(0, 2)));
+        }
+    }
+
+    mod columns {
+        use crate::core_engine::utils::{TextPosition, XcodeText};
+
+        #[test]
+        fn utf8_columns() {
+            // é takes two bytes, 😊 two code units and four bytes
+            let text = XcodeText::from_str("x\né😊y");
+
+            assert_eq!(text.utf8_column(&TextPosition::new(1, 1)), Some(2));
+            assert_eq!(text.utf8_column(&TextPosition::new(1, 2)), None);
+            assert_eq!(text.utf8_column(&TextPosition::new(1, 3)), Some(6));
+            assert_eq!(
+                text.position_of_utf8_column(1, 6),
+                Some(TextPosition::new(1, 3))
+            );
+            assert_eq!(text.position_of_utf8_column(1, 4), None);
+            assert_eq!(text.utf8_offset_of_index(5), Some(8));
+            assert_eq!(text.index_of_utf8_offset(8), Some(5));
+        }
+
+        #[test]
+        fn grapheme_columns() {
+            // ✌🏻 is a single grapheme of three code units
+            let text = XcodeText::from_str("a✌🏻b");
+
+            assert_eq!(text.grapheme_column(&TextPosition::new(0, 4)), Some(2));
+            assert_eq!(text.grapheme_column(&TextPosition::new(0, 2)), None);
+            assert_eq!(
+                text.position_of_grapheme_column(0, 3),
+                Some(TextPosition::new(0, 5))
+            );
+            assert_eq!(text.position_of_grapheme_column(0, 4), None);
+        }
+
+        #[test]
+        fn visual_columns_with_tabs() {
+            let text = XcodeText::from_str("\tx\ty\r\n");
+
+            assert_eq!(text.visual_column(&TextPosition::new(0, 1), 4), Some(4));
+            assert_eq!(text.visual_column(&TextPosition::new(0, 3), 4), Some(8));
+            assert_eq!(
+                text.position_of_visual_column(0, 8, 4),
+                Some(TextPosition::new(0, 3))
+            );
+            assert_eq!(text.position_of_visual_column(0, 6, 4), None);
+            // The line break is not displayed
+            assert_eq!(text.visual_column(&TextPosition::new(0, 5), 4), None);
+        }
+    }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use crate::core_engine::utils::{
+            swift_text_strategies::swift_text, TextPosition, XcodeText,
+        };
+
+        proptest! {
+            #[test]
+            fn utf8_offsets_match_the_utf8_encoding(code in swift_text()) {
+                let text = XcodeText::from_str(&code);
+
+                let mut index = 0;
+                for (utf8_offset, c) in code.char_indices() {
+                    prop_assert_eq!(text.utf8_offset_of_index(index), Some(utf8_offset));
+                    prop_assert_eq!(text.index_of_utf8_offset(utf8_offset), Some(index));
+                    if c.len_utf16() == 2 {
+                        prop_assert_eq!(text.utf8_offset_of_index(index + 1), None);
+                    }
+                    index += c.len_utf16();
+                }
+                prop_assert_eq!(text.utf8_offset_of_index(text.len()), Some(code.len()));
+            }
+
+            #[test]
+            fn columns_round_trip(code in swift_text()) {
+                let text = XcodeText::from_str(&code);
+
+                for row in 0..text.row_count() {
+                    let content_range = text.row_content_range(row).unwrap();
+                    for column in 0..=content_range.length {
+                        let position = TextPosition::new(row, column);
+                        if let Some(utf8_column) = text.utf8_column(&position) {
+                            prop_assert_eq!(
+                                text.position_of_utf8_column(row, utf8_column),
+                                Some(position)
+                            );
+                        }
+                        if let Some(grapheme_column) = text.grapheme_column(&position) {
+                            prop_assert_eq!(
+                                text.position_of_grapheme_column(row, grapheme_column),
+                                Some(position)
+                            );
+                        }
+                        if let Some(visual_column) = text.visual_column(&position, 4) {
+                            prop_assert_eq!(
+                                text.position_of_visual_column(row, visual_column, 4),
+                                Some(position)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
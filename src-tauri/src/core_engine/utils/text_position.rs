@@ -23,8 +23,8 @@ impl TextPosition {
 
     /// > Given a string and an index, return the row number and column number of the character at that
     /// index. Different from TextPosition, index does include the newline character.
-    /// In case the index references a new line character in the text, we return the position of the
-    /// next valid character.
+    /// An index referencing a line break is at the end of its row. Rows are only broken by `\n`, so
+    /// the `\r` of a `\r\n` takes up a column, like it does for tree-sitter.
     ///
     /// Arguments:
     ///
@@ -35,23 +35,11 @@ impl TextPosition {
     ///
     /// A TextPosition struct
     pub fn from_TextIndex(text: &XcodeText, index: usize) -> Option<TextPosition> {
-        if index > text.len() {
-            return None;
-        }
-
-        let mut i = 0;
-        for (row_i, row) in text.rows_iter().enumerate() {
-            for col_i in 0..=row.len() {
-                if i == index {
-                    return Some(TextPosition {
-                        row: row_i,
-                        column: col_i,
-                    });
-                }
-                i += 1;
-            }
-        }
-        None
+        let row = text.row_of_index(index)?;
+        Some(TextPosition {
+            row,
+            column: index - text.line_start(row)?,
+        })
     }
 
     pub fn as_TSPoint(&self) -> tree_sitter::Point {
@@ -62,21 +50,18 @@ impl TextPosition {
         self.as_TextIndex_stay_on_line(text, false)
     }
 
+    /// With `stay_on_line`, a column beyond the end of the row is moved to its end, before the
+    /// line break. Otherwise, columns up to and including the line break are valid.
     pub fn as_TextIndex_stay_on_line(&self, text: &XcodeText, stay_on_line: bool) -> Option<usize> {
-        let mut i = 0;
-        for (row_i, row) in text.rows_iter().enumerate() {
-            for col_i in 0..=row.len() {
-                if self.row == row_i && self.column == col_i {
-                    return Some(i);
-                }
-                i += 1;
-            }
-            if stay_on_line && self.row == row_i {
-                return Some(i - 1);
-            }
+        let row_start = text.line_start(self.row)?;
+        if row_start + self.column <= text.row_end(self.row)? {
+            Some(row_start + self.column)
+        } else if stay_on_line {
+            text.row_content_range(self.row)
+                .map(|content_range| content_range.index + content_range.length)
+        } else {
+            None
         }
-
-        None
     }
 }
 
@@ -265,4 +250,78 @@ mod tests {
 
         assert!(index_option.is_none());
     }
+
+    #[test]
+    fn TextPosition_from_TextIndex_crlf() {
+        let text = XcodeText::from_str("Hello,\r\nWorld!");
+
+        assert_eq!(
+            TextPosition::from_TextIndex(&text, 6),
+            Some(TextPosition::new(0, 6))
+        );
+        assert_eq!(
+            TextPosition::from_TextIndex(&text, 7),
+            Some(TextPosition::new(0, 7))
+        );
+        assert_eq!(
+            TextPosition::from_TextIndex(&text, 10),
+            Some(TextPosition::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn convert_TextPosition_as_TextIndex_crlf_stay_on_line() {
+        let text = XcodeText::from_str("Hello,\r\nWorld!");
+
+        assert_eq!(TextPosition::new(1, 2).as_TextIndex(&text), Some(10));
+        assert_eq!(TextPosition::new(0, 8).as_TextIndex(&text), None);
+        assert_eq!(
+            TextPosition::new(0, 8).as_TextIndex_stay_on_line(&text, true),
+            Some(6)
+        );
+    }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use crate::core_engine::{
+            syntax_tree::SwiftSyntaxTree,
+            utils::{swift_text_strategies::swift_text, TextPosition, XcodeText},
+        };
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(64))]
+
+            #[test]
+            fn index_position_and_point_round_trip(code in swift_text()) {
+                let text = XcodeText::from_str(&code);
+
+                for index in 0..=text.len() {
+                    let position = TextPosition::from_TextIndex(&text, index).unwrap();
+                    prop_assert_eq!(position.as_TextIndex(&text), Some(index));
+                    prop_assert_eq!(TextPosition::from_TSPoint(&position.as_TSPoint()), position);
+                }
+                prop_assert_eq!(TextPosition::from_TextIndex(&text, text.len() + 1), None);
+            }
+
+            #[test]
+            fn positions_of_nodes_match_their_byte_offsets(code in swift_text()) {
+                let text = XcodeText::from_str(&code);
+                let tree = SwiftSyntaxTree::parse(&mut SwiftSyntaxTree::parser(), &text, None).unwrap();
+
+                let mut nodes = vec![tree.root_node()];
+                while let Some(node) = nodes.pop() {
+                    let start_position = TextPosition::from_TSPoint(&node.start_position());
+                    let end_position = TextPosition::from_TSPoint(&node.end_position());
+                    prop_assert_eq!(
+                        start_position.as_TextIndex(&text),
+                        Some(node.start_byte() / 2)
+                    );
+                    prop_assert_eq!(end_position.as_TextIndex(&text), Some(node.end_byte() / 2));
+
+                    nodes.extend(node.children(&mut node.walk()));
+                }
+            }
+        }
+    }
 }
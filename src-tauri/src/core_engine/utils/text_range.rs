@@ -6,7 +6,7 @@ use crate::core_engine::utils::{TextPosition, XcodeText};
 
 /// A range in a multi-line text document, in terms of index and length.
 /// Index is zero-based.
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "bindings/rules/utils/")]
 pub struct TextRange {
     pub index: usize,
@@ -87,6 +87,22 @@ impl TextRange {
         }
     }
 
+    // Tree-sitter parses the text as UTF-16, so its byte offsets are twice the indexes.
+    pub fn as_StartEndTSByte(&self) -> (usize, usize) {
+        let (start_index, end_index) = self.as_StartEndIndex();
+        (start_index * 2, end_index * 2)
+    }
+
+    // The range's UTF-8 byte offsets, as used by SourceKit. None if it starts or ends inside a
+    // character.
+    pub fn as_StartEndUtf8Offset(&self, text: &XcodeText) -> Option<(usize, usize)> {
+        let (start_index, end_index) = self.as_StartEndIndex();
+        Some((
+            text.utf8_offset_of_index(start_index)?,
+            text.utf8_offset_of_index(end_index)?,
+        ))
+    }
+
     pub fn as_CFRange(&self) -> CFRange {
         CFRange {
             location: self.index as isize,
@@ -275,6 +291,18 @@ mod tests {
         assert_eq!(end_pos.column, 3);
     }
 
+    #[test]
+    fn TextRange_as_StartEndUtf8Offset() {
+        // é takes two bytes, 😊 two code units and four bytes
+        let text = XcodeText::from_str("é😊x");
+
+        assert_eq!(
+            TextRange::new(1, 3).as_StartEndUtf8Offset(&text),
+            Some((2, 7))
+        );
+        assert_eq!(TextRange::new(2, 1).as_StartEndUtf8Offset(&text), None);
+    }
+
     #[test]
     fn TextRange_includes_index() {
         let range = TextRange::new(3, 5);
@@ -336,4 +364,35 @@ mod tests {
         assert_eq!(first_char_range.unwrap().index, 3);
         assert_eq!(first_char_range.unwrap().length, 1);
     }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use crate::core_engine::utils::{swift_text_strategies::swift_text, TextRange, XcodeText};
+
+        proptest! {
+            #[test]
+            fn range_positions_and_points_round_trip(
+                code in swift_text(),
+                a in any::<usize>(),
+                b in any::<usize>(),
+            ) {
+                let text = XcodeText::from_str(&code);
+                let (a, b) = (a % (text.len() + 1), b % (text.len() + 1));
+                let range = TextRange::from_StartEndIndex(a.min(b), a.max(b));
+
+                let (start_position, end_position) = range.as_StartEndTextPosition(&text).unwrap();
+                prop_assert_eq!(
+                    TextRange::from_StartEndTextPosition(&text, &start_position, &end_position),
+                    Some(range)
+                );
+
+                let (start_point, end_point) = range.as_StartEndTSPoint(&text).unwrap();
+                prop_assert_eq!(
+                    TextRange::from_StartEndTSPoint(&text, &start_point, &end_point),
+                    Some(range)
+                );
+            }
+        }
+    }
 }
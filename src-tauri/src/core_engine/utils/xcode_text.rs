@@ -1,48 +1,104 @@
 use serde::{Deserialize, Serialize};
-use ts_rs::TS;
+use ts_rs::{Dependency, TS};
 
 use std::{
     fmt,
-    ops::{self, Deref, DerefMut, RangeBounds},
-    slice,
+    hash::{Hash, Hasher},
+    ops::{self, Bound, Index, RangeBounds},
 };
 
-use super::TextPosition;
+use super::{
+    xcode_text_rope::{Iter, Rope},
+    TextPosition,
+};
 
 pub type XcodeChar = u16;
 pub type XcodeTextRows = Vec<Vec<XcodeChar>>;
 
-#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, TS, Hash)]
+// Backed by a persistent rope, so clones share the text and edits and slices only create the
+// nodes along the paths to their ends. Reads go through the rope as well: single characters,
+// iterators and chunks instead of one contiguous slice.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(from = "SerializedXcodeText", into = "SerializedXcodeText")]
 pub struct XcodeText {
-    pub text: Vec<XcodeChar>,
-    pub rows: Vec<Vec<XcodeChar>>,
+    rope: Rope,
+}
+
+#[derive(Serialize, Deserialize, TS)]
+#[ts(rename = "XcodeText")]
+struct SerializedXcodeText {
+    text: Vec<XcodeChar>,
+    rows: XcodeTextRows,
+}
+
+impl From<SerializedXcodeText> for XcodeText {
+    fn from(serialized: SerializedXcodeText) -> Self {
+        Self::from_vec(&serialized.text)
+    }
+}
+
+impl From<XcodeText> for SerializedXcodeText {
+    fn from(text: XcodeText) -> Self {
+        let text = text.to_vec();
+        Self {
+            rows: XcodeText::create_rows(&text),
+            text,
+        }
+    }
+}
+
+impl TS for XcodeText {
+    fn name() -> String {
+        SerializedXcodeText::name()
+    }
+
+    fn decl() -> String {
+        SerializedXcodeText::decl()
+    }
+
+    fn inline() -> String {
+        SerializedXcodeText::inline()
+    }
+
+    fn dependencies() -> Vec<Dependency> {
+        SerializedXcodeText::dependencies()
+    }
+
+    fn transparent() -> bool {
+        false
+    }
+}
+
+impl PartialEq for XcodeText {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for XcodeText {}
+
+// Chunk boundaries differ between equal texts, so the characters are hashed one by one.
+impl Hash for XcodeText {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for c in self.iter() {
+            c.hash(state);
+        }
+    }
 }
 
 impl fmt::Debug for XcodeText {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", String::from_utf16_lossy(&self))
+        write!(f, "{}", self.as_string())
     }
 }
 
 impl ops::Add<XcodeText> for XcodeText {
     type Output = XcodeText;
     fn add(self, rhs: XcodeText) -> XcodeText {
-        let mut result = self;
-
-        result.text.append(rhs.text.clone().as_mut());
-
-        // Append rows
-        let mut rhs_start_row = 0;
-        if let Some(last) = result.rows.last_mut() {
-            if let Some(first) = rhs.rows.first() {
-                last.append(first.clone().as_mut());
-                rhs_start_row = 1;
-            }
+        Self {
+            rope: Rope::concat(self.rope, rhs.rope),
         }
-        let rhs_slice = &&rhs.rows[rhs_start_row..];
-
-        result.rows.extend_from_slice(rhs_slice);
-        result
     }
 }
 
@@ -67,17 +123,11 @@ impl ops::AddAssign<&str> for XcodeText {
 
 impl<'a> XcodeText {
     pub fn new_empty() -> Self {
-        Self {
-            text: vec![],
-            rows: vec![],
-        }
+        Self::default()
     }
 
     pub fn from_vec(vec: &Vec<XcodeChar>) -> Self {
-        Self {
-            text: vec.to_vec(),
-            rows: XcodeText::create_rows(vec),
-        }
+        Self::from_array(vec)
     }
 
     pub fn from_str(str: &str) -> Self {
@@ -85,42 +135,125 @@ impl<'a> XcodeText {
     }
 
     pub fn from_array(array: &[XcodeChar]) -> Self {
-        Self::from_vec(&array.to_vec())
+        Self {
+            rope: Rope::from_slice(array),
+        }
     }
 
     pub fn as_string(&self) -> String {
-        String::from_utf16_lossy(&self.text)
+        String::from_utf16_lossy(&self.to_vec())
+    }
+
+    pub fn len(&self) -> usize {
+        self.rope.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rope.len() == 0
     }
 
     pub fn utf16_bytes_count(&self) -> usize {
-        self.text.len() * 2
+        self.len() * 2
+    }
+
+    pub fn get(&self, index: usize) -> Option<&XcodeChar> {
+        self.rope.get(index)
     }
 
-    pub fn rows_iter(&self) -> slice::Iter<'_, Vec<u16>> {
-        self.rows.iter()
+    pub fn iter(&self) -> Iter<'_> {
+        self.rope.iter()
     }
 
-    pub fn clone(&self) -> Self {
+    // Shares the characters with the text. Panics if the range is out of bounds, like slicing.
+    pub fn slice<R>(&self, range: R) -> XcodeText
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = self.bounds_of(range);
         Self {
-            text: self.text.clone(),
-            rows: self.rows.clone(),
+            rope: self.rope.slice(start..end),
+        }
+    }
+
+    // The characters from the index to the end of the chunk containing it, empty at the end of
+    // the text. Lets tree-sitter read the text without copying it.
+    pub fn chunk_at(&self, index: usize) -> &[XcodeChar] {
+        self.rope.chunk_at(index)
+    }
+
+    pub fn to_vec(&self) -> Vec<XcodeChar> {
+        self.rope.to_vec()
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rope.newline_count() + 1
+    }
+
+    pub fn line_start(&self, row: usize) -> Option<usize> {
+        if row == 0 {
+            Some(0)
+        } else {
+            self.rope.index_after_newline(row)
+        }
+    }
+
+    // The `\n` ending a row belongs to it.
+    pub fn row_of_index(&self, index: usize) -> Option<usize> {
+        if index > self.len() {
+            return None;
         }
+        Some(self.rope.newlines_before(index))
+    }
+
+    // The characters of the row, without its `\n` or `\r\n` line break.
+    pub fn row(&self, row: usize) -> Option<XcodeText> {
+        let content_range = self.row_content_range(row)?;
+        Some(self.slice(content_range.index..content_range.index + content_range.length))
     }
+
+    pub fn rows_iter(&self) -> impl Iterator<Item = XcodeText> + '_ {
+        (0..self.row_count()).filter_map(|row| self.row(row))
+    }
+
+    pub fn push(&mut self, c: XcodeChar) {
+        let end = self.len();
+        self.replace_range(end..end, Self::from_array(&[c]));
+    }
+
     pub fn replace_range<R>(&mut self, range: R, replace_with: XcodeText)
     where
         R: RangeBounds<usize>,
     {
         // TODO: Check for char boundary (see String::replace_range implementation)
+        let (start, end) = self.bounds_of(range);
+        self.rope.replace_range(start..end, replace_with.rope);
+    }
 
-        // We assume the bounds reported by `range` remain the same, but
-        // an adversarial implementation could change between calls
-        self.text
-            .splice((range.start_bound(), range.end_bound()), replace_with.text);
-
-        self.rows = Self::create_rows(&self.text) // TODO: Inefficient implementation
+    fn bounds_of<R>(&self, range: R) -> (usize, usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end + 1,
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(
+            start <= end && end <= self.len(),
+            "range {}..{} out of bounds of text of length {}",
+            start,
+            end,
+            self.len()
+        );
+        (start, end)
     }
 
-    pub fn create_rows(text: &Vec<u16>) -> XcodeTextRows {
+    pub fn create_rows(text: &[XcodeChar]) -> XcodeTextRows {
         if text.is_empty() {
             return vec![vec![]];
         }
@@ -179,30 +312,36 @@ impl<'a> XcodeText {
     }
 }
 
-impl<'a> IntoIterator for XcodeText {
+impl Index<usize> for XcodeText {
+    type Output = XcodeChar;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(c) => c,
+            None => panic!(
+                "index {} out of bounds of text of length {}",
+                index,
+                self.len()
+            ),
+        }
+    }
+}
+
+impl IntoIterator for XcodeText {
     type Item = XcodeChar;
     type IntoIter = <Vec<u16> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.text.into_iter()
+        self.to_vec().into_iter()
     }
 }
 
 impl<'a> IntoIterator for &'a XcodeText {
     type Item = &'a XcodeChar;
-    type IntoIter = slice::Iter<'a, XcodeChar>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.text.iter()
-    }
-}
-
-impl<'a> IntoIterator for &'a mut XcodeText {
-    type Item = &'a mut XcodeChar;
-    type IntoIter = slice::IterMut<'a, XcodeChar>;
+    type IntoIter = Iter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.text.iter_mut()
+        self.iter()
     }
 }
 
@@ -216,26 +355,6 @@ impl FromIterator<u16> for XcodeText {
     }
 }
 
-impl<'a> AsRef<[u16]> for XcodeText {
-    fn as_ref(&self) -> &[u16] {
-        &self.text
-    }
-}
-
-impl<'a> Deref for XcodeText {
-    type Target = Vec<XcodeChar>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.text
-    }
-}
-
-impl<'a> DerefMut for XcodeText {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.text
-    }
-}
-
 #[cfg(test)]
 mod tests {
 
@@ -269,6 +388,55 @@ mod tests {
             a.replace_range(5..11, b);
             assert_eq!(a, XcodeText::from_str("Hello the\nre!"));
         }
+
+        #[test]
+        fn replace_range_updates_rows_and_line_starts() {
+            let mut a = XcodeText::from_str("Hello\r\nWorld!\nBye");
+
+            a.replace_range(3..9, XcodeText::from_str("p\nme\n"));
+
+            assert_eq!(a, XcodeText::from_str("Help\nme\nrld!\nBye"));
+            assert_eq!(
+                (0..a.row_count())
+                    .map(|row| a.line_start(row).unwrap())
+                    .collect::<Vec<usize>>(),
+                vec![0, 5, 8, 13]
+            );
+            assert_eq!(a.row(1), Some(XcodeText::from_str("me")));
+        }
+
+        #[test]
+        fn replace_range_joins_and_splits_crlf() {
+            let mut a = XcodeText::from_str("ab\r\ncd\ne");
+
+            a.replace_range(3..4, XcodeText::new_empty());
+            assert_eq!(a, XcodeText::from_str("ab\rcd\ne"));
+
+            a.replace_range(3..3, XcodeText::from_str("\n"));
+            assert_eq!(a, XcodeText::from_str("ab\r\ncd\ne"));
+
+            a.replace_range(6..6, XcodeText::from_str("\r"));
+            assert_eq!(a, XcodeText::from_str("ab\r\ncd\r\ne"));
+        }
+
+        #[test]
+        fn replace_range_does_not_change_clones() {
+            let mut a = XcodeText::from_str("Hello\nWorld!");
+            let clone = a.clone();
+
+            a.replace_range(5..6, XcodeText::from_str(" "));
+            assert_eq!(a, XcodeText::from_str("Hello World!"));
+            assert_eq!(clone, XcodeText::from_str("Hello\nWorld!"));
+            assert_eq!(clone.row_count(), 2);
+        }
+
+        #[test]
+        fn replace_range_in_empty_text() {
+            let mut a = XcodeText::new_empty();
+
+            a.replace_range(.., XcodeText::from_str("a\n"));
+            assert_eq!(a, XcodeText::from_str("a\n"));
+        }
     }
     #[cfg(test)]
     mod addition {
@@ -326,8 +494,13 @@ mod tests {
             for i in 0..expected_str.len() {
                 expected.push(expected_str[i].encode_utf16().collect::<Vec<u16>>());
             }
-            let rows = XcodeText::create_rows(&text.encode_utf16().collect::<XcodeText>());
+            let rows = XcodeText::create_rows(&text.encode_utf16().collect::<Vec<u16>>());
             assert_eq!(rows, expected);
+            let rows_of_text: XcodeTextRows = XcodeText::from_str(text)
+                .rows_iter()
+                .map(|row| row.to_vec())
+                .collect();
+            assert_eq!(rows_of_text, expected);
         }
 
         #[test]
@@ -0,0 +1,518 @@
+use std::{ops::Range, slice, sync::Arc, vec};
+
+use super::XcodeChar;
+
+const MAX_LEAF_LEN: usize = 1024;
+const NEWLINE: XcodeChar = '\n' as XcodeChar;
+
+// A persistent rope: a balanced tree of chunks of text, in which every node knows the length and
+// number of line breaks of its subtree. Clones share all nodes; edits only create new nodes along
+// the path to the edit, so clones are O(1) and edits are O(log n).
+#[derive(Clone, Default)]
+pub struct Rope {
+    root: Option<Arc<Node>>,
+}
+
+struct Node {
+    len: usize,
+    newlines: usize,
+    height: usize,
+    kind: NodeKind,
+}
+
+enum NodeKind {
+    Leaf(Vec<XcodeChar>),
+    Branch(Arc<Node>, Arc<Node>),
+}
+
+impl Rope {
+    pub fn from_slice(chars: &[XcodeChar]) -> Self {
+        let leaves: Vec<Arc<Node>> = chars
+            .chunks(MAX_LEAF_LEN)
+            .map(|chunk| Node::leaf(chunk.to_vec()))
+            .collect();
+        Self {
+            root: build_balanced(&leaves),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.len)
+    }
+
+    pub fn newline_count(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.newlines)
+    }
+
+    pub fn concat(left: Rope, right: Rope) -> Rope {
+        Rope {
+            root: join_optional(left.root, right.root),
+        }
+    }
+
+    pub fn split_at(&self, index: usize) -> (Rope, Rope) {
+        match &self.root {
+            Some(root) => {
+                let (left, right) = split(root, index);
+                (Rope { root: left }, Rope { root: right })
+            }
+            None => (Rope::default(), Rope::default()),
+        }
+    }
+
+    pub fn replace_range(&mut self, range: Range<usize>, replace_with: Rope) {
+        let (before, rest) = self.split_at(range.start);
+        let (_, after) = rest.split_at(range.end.saturating_sub(range.start));
+        *self = Rope::concat(Rope::concat(before, replace_with), after);
+    }
+
+    // Shares all nodes except the ones along the paths to the two ends of the range.
+    pub fn slice(&self, range: Range<usize>) -> Rope {
+        let (_, rest) = self.split_at(range.start);
+        let (middle, _) = rest.split_at(range.end.saturating_sub(range.start));
+        middle
+    }
+
+    pub fn get(&self, index: usize) -> Option<&XcodeChar> {
+        let (chars, offset) = self.leaf_at(index)?;
+        chars.get(offset)
+    }
+
+    // The characters from the index to the end of the chunk containing it; empty at the end of
+    // the text.
+    pub fn chunk_at(&self, index: usize) -> &[XcodeChar] {
+        match self.leaf_at(index) {
+            Some((chars, offset)) => &chars[offset..],
+            None => &[],
+        }
+    }
+
+    fn leaf_at(&self, mut index: usize) -> Option<(&[XcodeChar], usize)> {
+        let mut node = self.root.as_ref()?;
+        if index >= node.len {
+            return None;
+        }
+
+        loop {
+            match &node.kind {
+                NodeKind::Leaf(chars) => return Some((chars, index)),
+                NodeKind::Branch(left, right) => {
+                    if index < left.len {
+                        node = left;
+                    } else {
+                        index -= left.len;
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    // Number of `\n` before the index.
+    pub fn newlines_before(&self, mut index: usize) -> usize {
+        let mut node = match &self.root {
+            Some(root) => root,
+            None => return 0,
+        };
+
+        let mut count = 0;
+        loop {
+            match &node.kind {
+                NodeKind::Leaf(chars) => {
+                    return count
+                        + chars[..index.min(chars.len())]
+                            .iter()
+                            .filter(|c| **c == NEWLINE)
+                            .count();
+                }
+                NodeKind::Branch(left, right) => {
+                    if index <= left.len {
+                        node = left;
+                    } else {
+                        count += left.newlines;
+                        index -= left.len;
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    // Index following the `n`th `\n`, counting from one.
+    pub fn index_after_newline(&self, mut n: usize) -> Option<usize> {
+        let mut node = self.root.as_ref()?;
+        if n == 0 || n > node.newlines {
+            return None;
+        }
+
+        let mut offset = 0;
+        loop {
+            match &node.kind {
+                NodeKind::Leaf(chars) => {
+                    let (newline_index, _) = chars
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, c)| **c == NEWLINE)
+                        .nth(n - 1)?;
+                    return Some(offset + newline_index + 1);
+                }
+                NodeKind::Branch(left, right) => {
+                    if n <= left.newlines {
+                        node = left;
+                    } else {
+                        n -= left.newlines;
+                        offset += left.len;
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks {
+            stack: self.root.iter().map(|root| root.as_ref()).collect(),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            chunks: self.chunks().collect::<Vec<_>>().into_iter(),
+            front: [].iter(),
+            back: [].iter(),
+            remaining: self.len(),
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<XcodeChar> {
+        let mut chars = Vec::with_capacity(self.len());
+        for chunk in self.chunks() {
+            chars.extend_from_slice(chunk);
+        }
+        chars
+    }
+}
+
+// Like `slice::Iter`, iterates over references to the characters from both ends.
+pub struct Iter<'a> {
+    chunks: vec::IntoIter<&'a [XcodeChar]>,
+    front: slice::Iter<'a, XcodeChar>,
+    back: slice::Iter<'a, XcodeChar>,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a XcodeChar;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(c) = self.front.next() {
+                self.remaining -= 1;
+                return Some(c);
+            }
+            match self.chunks.next() {
+                Some(chunk) => self.front = chunk.iter(),
+                None => {
+                    let c = self.back.next()?;
+                    self.remaining -= 1;
+                    return Some(c);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(c) = self.back.next_back() {
+                self.remaining -= 1;
+                return Some(c);
+            }
+            match self.chunks.next_back() {
+                Some(chunk) => self.back = chunk.iter(),
+                None => {
+                    let c = self.front.next_back()?;
+                    self.remaining -= 1;
+                    return Some(c);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a [XcodeChar];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            match &node.kind {
+                NodeKind::Leaf(chars) => return Some(chars),
+                NodeKind::Branch(left, right) => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Node {
+    fn leaf(chars: Vec<XcodeChar>) -> Arc<Node> {
+        Arc::new(Node {
+            len: chars.len(),
+            newlines: chars.iter().filter(|c| **c == NEWLINE).count(),
+            height: 0,
+            kind: NodeKind::Leaf(chars),
+        })
+    }
+
+    fn branch(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        Arc::new(Node {
+            len: left.len + right.len,
+            newlines: left.newlines + right.newlines,
+            height: left.height.max(right.height) + 1,
+            kind: NodeKind::Branch(left, right),
+        })
+    }
+
+    fn children(&self) -> (&Arc<Node>, &Arc<Node>) {
+        match &self.kind {
+            NodeKind::Branch(left, right) => (left, right),
+            NodeKind::Leaf(_) => unreachable!("Only branches are rebalanced"),
+        }
+    }
+}
+
+fn build_balanced(nodes: &[Arc<Node>]) -> Option<Arc<Node>> {
+    match nodes.len() {
+        0 => None,
+        1 => Some(nodes[0].clone()),
+        len => {
+            let (left, right) = nodes.split_at(len / 2);
+            Some(Node::branch(build_balanced(left)?, build_balanced(right)?))
+        }
+    }
+}
+
+fn join_optional(left: Option<Arc<Node>>, right: Option<Arc<Node>>) -> Option<Arc<Node>> {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(join(left, right)),
+        (left, None) => left,
+        (None, right) => right,
+    }
+}
+
+// Concatenates two balanced trees by descending the taller one to the height of the other, as in
+// the join of AVL trees. Small leaves meeting at the seam are merged.
+fn join(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+    if let (NodeKind::Leaf(left_chars), NodeKind::Leaf(right_chars)) = (&left.kind, &right.kind) {
+        if left.len + right.len <= MAX_LEAF_LEN {
+            return Node::leaf([left_chars.as_slice(), right_chars.as_slice()].concat());
+        }
+    }
+
+    if left.height > right.height + 1 {
+        let (left_left, left_right) = left.children();
+        rebalance(left_left.clone(), join(left_right.clone(), right))
+    } else if right.height > left.height + 1 {
+        let (right_left, right_right) = right.children();
+        rebalance(join(left, right_left.clone()), right_right.clone())
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+// A branch of two balanced trees whose heights differ by at most two, rotated to be balanced.
+fn rebalance(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+    if left.height > right.height + 1 {
+        let (outer, inner) = left.children();
+        if outer.height >= inner.height {
+            Node::branch(outer.clone(), Node::branch(inner.clone(), right))
+        } else {
+            let (inner_left, inner_right) = inner.children();
+            Node::branch(
+                Node::branch(outer.clone(), inner_left.clone()),
+                Node::branch(inner_right.clone(), right),
+            )
+        }
+    } else if right.height > left.height + 1 {
+        let (inner, outer) = right.children();
+        if outer.height >= inner.height {
+            Node::branch(Node::branch(left, inner.clone()), outer.clone())
+        } else {
+            let (inner_left, inner_right) = inner.children();
+            Node::branch(
+                Node::branch(left, inner_left.clone()),
+                Node::branch(inner_right.clone(), outer.clone()),
+            )
+        }
+    } else {
+        Node::branch(left, right)
+    }
+}
+
+fn split(node: &Arc<Node>, index: usize) -> (Option<Arc<Node>>, Option<Arc<Node>>) {
+    if index == 0 {
+        return (None, Some(node.clone()));
+    }
+    if index >= node.len {
+        return (Some(node.clone()), None);
+    }
+
+    match &node.kind {
+        NodeKind::Leaf(chars) => (
+            Some(Node::leaf(chars[..index].to_vec())),
+            Some(Node::leaf(chars[index..].to_vec())),
+        ),
+        NodeKind::Branch(left, right) => {
+            if index <= left.len {
+                let (left_part, right_part) = split(left, index);
+                (left_part, join_optional(right_part, Some(right.clone())))
+            } else {
+                let (left_part, right_part) = split(right, index - left.len);
+                (join_optional(Some(left.clone()), left_part), right_part)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod replace_range {
+        use proptest::{collection::vec, prelude::*};
+
+        use crate::core_engine::utils::xcode_text_rope::{Node, NodeKind, Rope, MAX_LEAF_LEN};
+
+        // Returns the height, after checking that the subtree is balanced and its summary is
+        // correct.
+        fn check_node(node: &Node) -> usize {
+            match &node.kind {
+                NodeKind::Leaf(chars) => {
+                    assert!(!chars.is_empty() && chars.len() <= MAX_LEAF_LEN);
+                    assert_eq!(node.height, 0);
+                }
+                NodeKind::Branch(left, right) => {
+                    let (left_height, right_height) = (check_node(left), check_node(right));
+                    assert!(left_height.abs_diff(right_height) <= 1);
+                    assert_eq!(node.height, left_height.max(right_height) + 1);
+                    assert_eq!(node.len, left.len + right.len);
+                    assert_eq!(node.newlines, left.newlines + right.newlines);
+                }
+            }
+            node.height
+        }
+
+        fn chars(text: &str) -> Vec<u16> {
+            text.encode_utf16().collect()
+        }
+
+        #[test]
+        fn replaces_across_leaves() {
+            let text = "a\nb".repeat(MAX_LEAF_LEN);
+            let mut rope = Rope::from_slice(&chars(&text));
+            let start = MAX_LEAF_LEN - 2;
+            let end = MAX_LEAF_LEN * 2 + 5;
+
+            rope.replace_range(start..end, Rope::from_slice(&chars("x\ny")));
+
+            let mut expected = chars(&text);
+            expected.splice(start..end, chars("x\ny"));
+            assert_eq!(rope.to_vec(), expected);
+            assert_eq!(
+                rope.newline_count(),
+                expected.iter().filter(|c| **c == '\n' as u16).count()
+            );
+            check_node(rope.root.as_ref().unwrap());
+        }
+
+        #[test]
+        fn clones_are_not_affected_by_edits() {
+            let mut rope = Rope::from_slice(&chars("Hello World!"));
+            let clone = rope.clone();
+
+            rope.replace_range(6..11, Rope::from_slice(&chars("there")));
+
+            assert_eq!(rope.to_vec(), chars("Hello there!"));
+            assert_eq!(clone.to_vec(), chars("Hello World!"));
+        }
+
+        #[test]
+        fn iterates_from_both_ends() {
+            let text = "ab".repeat(MAX_LEAF_LEN);
+            let rope = Rope::from_slice(&chars(&text));
+
+            let mut iter = rope.iter();
+            assert_eq!(iter.len(), MAX_LEAF_LEN * 2);
+            assert_eq!(iter.next(), Some(&('a' as u16)));
+            assert_eq!(iter.next_back(), Some(&('b' as u16)));
+            assert_eq!(iter.len(), MAX_LEAF_LEN * 2 - 2);
+            assert_eq!(iter.rev().count(), MAX_LEAF_LEN * 2 - 2);
+        }
+
+        proptest! {
+            #[test]
+            fn edits_match_vec_splice(
+                initial_len in 0..5000usize,
+                edits in vec((any::<usize>(), 0..3000usize, 0..3000usize), 1..40),
+            ) {
+                let mut expected: Vec<u16> = (0..initial_len)
+                    .map(|i| if i % 7 == 0 { '\n' as u16 } else { i as u16 })
+                    .collect();
+                let mut rope = Rope::from_slice(&expected);
+
+                for (start, removed_len, inserted_len) in edits {
+                    let start = start % (expected.len() + 1);
+                    let end = (start + removed_len).min(expected.len());
+                    let inserted: Vec<u16> = (0..inserted_len)
+                        .map(|i| if i % 5 == 0 { '\n' as u16 } else { i as u16 })
+                        .collect();
+
+                    expected.splice(start..end, inserted.iter().copied());
+                    rope.replace_range(start..end, Rope::from_slice(&inserted));
+
+                    prop_assert_eq!(rope.len(), expected.len());
+                    if let Some(root) = &rope.root {
+                        check_node(root);
+                    }
+                }
+
+                prop_assert_eq!(rope.to_vec(), expected.clone());
+                prop_assert!(rope.iter().eq(expected.iter()));
+                prop_assert!(rope.iter().rev().eq(expected.iter().rev()));
+                for index in (0..=expected.len()).step_by(97) {
+                    prop_assert_eq!(rope.get(index), expected.get(index));
+                    prop_assert_eq!(rope.chunk_at(index).first(), expected.get(index));
+                    prop_assert_eq!(rope.slice(0..index).to_vec(), expected[..index].to_vec());
+                    prop_assert_eq!(
+                        rope.slice(index..expected.len()).to_vec(),
+                        expected[index..].to_vec()
+                    );
+                }
+                let newline_indexes: Vec<usize> = expected
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c == '\n' as u16)
+                    .map(|(index, _)| index)
+                    .collect();
+                for (n, newline_index) in newline_indexes.iter().enumerate() {
+                    prop_assert_eq!(rope.index_after_newline(n + 1), Some(newline_index + 1));
+                    prop_assert_eq!(rope.newlines_before(*newline_index), n);
+                    prop_assert_eq!(rope.newlines_before(newline_index + 1), n + 1);
+                }
+                prop_assert_eq!(rope.index_after_newline(newline_indexes.len() + 1), None);
+            }
+        }
+    }
+}
@@ -1,12 +1,9 @@
-use std::sync::Arc;
-
-use parking_lot::Mutex;
-use tauri::{CustomMenuItem, Manager, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem};
-use tracing::{debug, info};
+use tauri::{CustomMenuItem, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem};
+use tracing::{debug, error, info};
 
 use crate::{
     app_handle,
-    app_state::{CoreEngineState, CoreEngineStateCache},
+    app_state::{settings, update_settings},
     core_engine::events::{
         models::{AiFeaturesStatusMessage, SwiftFormatOnCMDSMessage},
         EventUserInteraction,
@@ -14,8 +11,6 @@ use crate::{
     platform,
 };
 
-use crate::app_state::AppHandleExtension;
-
 const TRAY_MENU_ACTIVATE_AI_FEATURES: &str = "activate_ai_features";
 const TRAY_MENU_DEACTIVATE_AI_FEATURES: &str = "deactivate_ai_features";
 const TRAY_MENU_ACTIVATE_SWIFT_FORMAT_ON_CMD_S: &str = "activate_swift_format_on_cmd_s";
@@ -87,19 +82,11 @@ fn swift_format_on_cmd_s_menu_item() -> CustomMenuItem {
 }
 
 fn check_ai_features() -> bool {
-    if let Some(cache) = app_handle().try_state::<CoreEngineStateCache>() {
-        cache.0.lock().ai_features_active
-    } else {
-        true
-    }
+    settings().ai_features_active
 }
 
 fn check_swift_format_on_cmd_s() -> bool {
-    if let Some(cache) = app_handle().try_state::<CoreEngineStateCache>() {
-        cache.0.lock().swift_format_on_cmd_s
-    } else {
-        true
-    }
+    settings().formatter.format_on_cmd_s
 }
 
 pub fn evaluate_system_tray_event(event: SystemTrayEvent) {
@@ -143,8 +130,6 @@ fn on_deactivate_ai_features() {
         })
         .publish_to_tauri();
     }
-
-    _ = app_handle().save_core_engine_state();
 }
 
 fn on_activate_ai_features() {
@@ -161,8 +146,6 @@ fn on_activate_ai_features() {
         })
         .publish_to_tauri();
     }
-
-    _ = app_handle().save_core_engine_state();
 }
 
 fn on_deactivate_swift_format_on_cmd_s() {
@@ -178,8 +161,6 @@ fn on_deactivate_swift_format_on_cmd_s() {
         })
         .publish_to_tauri();
     }
-
-    _ = app_handle().save_core_engine_state();
 }
 
 fn on_activate_swift_format_on_cmd_s() {
@@ -195,38 +176,18 @@ fn on_activate_swift_format_on_cmd_s() {
         })
         .publish_to_tauri();
     }
-
-    _ = app_handle().save_core_engine_state();
 }
 
 fn update_app_state_ai_features_active(ai_features_active: bool) {
-    if let Some(cache) = app_handle().try_state::<CoreEngineStateCache>() {
-        let current_core_engine_state = cache.0.lock().clone();
-        *cache.0.lock() = CoreEngineState {
-            ai_features_active,
-            ..current_core_engine_state
-        };
-    } else {
-        let cache = Arc::new(Mutex::new(CoreEngineState {
-            ai_features_active,
-            ..Default::default()
-        }));
-        app_handle().manage(CoreEngineStateCache(cache));
-    };
+    if let Err(err) = update_settings(|settings| settings.ai_features_active = ai_features_active) {
+        error!(?err, "Failed to update settings");
+    }
 }
 
 fn update_app_state_swift_format_on_cmd_s(swift_format_on_cmd_s: bool) {
-    if let Some(cache) = app_handle().try_state::<CoreEngineStateCache>() {
-        let current_core_engine_state = cache.0.lock().clone();
-        *cache.0.lock() = CoreEngineState {
-            swift_format_on_cmd_s,
-            ..current_core_engine_state
-        };
-    } else {
-        let cache = Arc::new(Mutex::new(CoreEngineState {
-            swift_format_on_cmd_s,
-            ..Default::default()
-        }));
-        app_handle().manage(CoreEngineStateCache(cache));
-    };
+    if let Err(err) = update_settings(|settings| {
+        settings.formatter.format_on_cmd_s = swift_format_on_cmd_s;
+    }) {
+        error!(?err, "Failed to update settings");
+    }
 }
@@ -1,13 +1,16 @@
-use std::{collections::HashMap, sync::Arc};
+use std::collections::HashMap;
 
-use parking_lot::Mutex;
+use async_trait::async_trait;
 use reqwest::header::AUTHORIZATION;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::async_runtime::block_on;
 use tracing::{debug, warn, Level};
 
-use crate::utils::{gcp::auth, tracing::Metadata};
+use crate::utils::{
+    gcp::auth,
+    telemetry::{anonymous_install_id, TelemetryEntry, TelemetryError, TelemetryExporter},
+    tracing::Metadata,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Severity {
@@ -38,6 +41,9 @@ pub struct MonitoredResource {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LogEntry {
+    // Cloud Logging drops entries sharing id and timestamp, e.g. spooled ones delivered twice.
+    #[serde(rename = "insertId")]
+    insert_id: String,
     jsonPayload: Value,
     labels: HashMap<String, String>,
     log_name: String,
@@ -47,13 +53,18 @@ pub struct LogEntry {
 }
 
 impl LogEntry {
-    pub fn new(log_name: String, jsonPayload: Value, metadata: Metadata) -> Self {
+    pub fn new(
+        log_name: String,
+        insert_id: String,
+        jsonPayload: Value,
+        metadata: Metadata,
+    ) -> Self {
         let mut labels = HashMap::new();
         if let Some(feature) = jsonPayload.get("feature") {
             labels.insert("feature".to_string(), feature.to_string());
         }
-        if let Ok(machine_id) = machine_uid::get() {
-            labels.insert("machine_id".to_string(), machine_id);
+        if let Some(install_id) = anonymous_install_id() {
+            labels.insert("install_id".to_string(), install_id);
         }
 
         let monitored_resource = MonitoredResource {
@@ -61,6 +72,7 @@ impl LogEntry {
         };
 
         Self {
+            insert_id,
             jsonPayload: jsonPayload,
             labels,
             log_name: log_name,
@@ -77,76 +89,58 @@ pub struct LoggingEntriesWriteRequest {
 }
 
 pub struct GcpLogging {
-    entries: Arc<Mutex<Vec<LogEntry>>>,
+    auth: auth::GcpAuth,
     log_name: String,
 }
 
 impl GcpLogging {
     pub fn new() -> Self {
         Self {
-            entries: Arc::new(Mutex::new(Vec::new())),
+            auth: auth::GcpAuth::new(),
             log_name: "projects/client-backend-x/logs/client".to_string(),
         }
     }
+}
 
-    pub fn start_remote(&self) {
-        tauri::async_runtime::spawn({
-            let entries = self.entries.clone();
-            async move {
-                let mut auth = auth::GcpAuth::new();
-                loop {
-                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-
-                    let token = auth.token_str().await;
-
-                    let current_entries;
-                    {
-                        let mut entries = entries.lock();
-                        current_entries = entries.clone();
-                        entries.clear();
-                    }
-
-                    if let Some(access_token) = token {
-                        publish_to_gcp(current_entries, access_token).await.ok();
-                    }
-                }
-            }
-        });
-    }
-
-    pub fn add_entry(&mut self, message: Value, metadata: Metadata) {
-        (*self.entries.lock()).push(LogEntry::new(self.log_name.clone(), message, metadata));
+#[async_trait]
+impl TelemetryExporter for GcpLogging {
+    fn is_remote(&self) -> bool {
+        true
     }
 
-    pub fn publish_entry_synchronously(&mut self, message: Value, metadata: Metadata) {
-        let entries = vec![LogEntry::new(self.log_name.clone(), message, metadata)];
-        // get token
-        let mut auth = auth::GcpAuth::new();
-        let token = block_on(auth.token_str());
-
-        // publish to gcp
-        if let Some(access_token) = token {
-            block_on(publish_to_gcp(entries, access_token)).ok();
+    async fn export(&mut self, entries: &[TelemetryEntry]) -> Result<(), TelemetryError> {
+        if entries.is_empty() {
+            return Ok(());
         }
-    }
 
-    #[allow(dead_code)] // Used in tests
-    pub fn entries(&self) -> Vec<LogEntry> {
-        (*self.entries.lock()).clone()
+        let access_token = self
+            .auth
+            .token_str()
+            .await
+            .ok_or(TelemetryError::Unauthenticated)?;
+
+        let log_entries = entries
+            .iter()
+            .map(|entry| {
+                LogEntry::new(
+                    self.log_name.clone(),
+                    entry.id.clone(),
+                    entry.message.clone(),
+                    entry.metadata.clone(),
+                )
+            })
+            .collect();
+
+        publish_to_gcp(log_entries, access_token).await
     }
 }
 
 async fn publish_to_gcp(
     entries: Vec<LogEntry>,
     access_token: String,
-) -> Result<(), reqwest::Error> {
-    if entries.is_empty() {
-        return Ok(());
-    }
-
-    let req_body = LoggingEntriesWriteRequest {
-        entries: entries.clone(),
-    };
+) -> Result<(), TelemetryError> {
+    let entries_count = entries.len();
+    let req_body = LoggingEntriesWriteRequest { entries };
     let response_result = reqwest::Client::new()
         .post("https://logging.googleapis.com/v2/entries:write")
         .header(AUTHORIZATION, format!("Bearer {}", access_token))
@@ -159,22 +153,22 @@ async fn publish_to_gcp(
             if response.status().is_success() {
                 debug!(
                     no_remote = true,
-                    "successfully published {} logs to GCP",
-                    entries.len()
+                    "successfully published {} logs to GCP", entries_count
                 );
+                Ok(())
             } else {
                 warn!(
                     no_remote = true,
                     response = ?response,
                     "failed to publish {} logs to GCP",
-                    entries.len()
+                    entries_count
                 );
+                Err(TelemetryError::Rejected(response.status().as_u16()))
             }
-            Ok(())
         }
         Err(e) => {
             warn!(no_remote = true, "failed to publish logs to GCP: {}", e);
-            Err(e)
+            Err(e.into())
         }
     }
 }
@@ -186,14 +180,20 @@ mod tests {
     use tauri::async_runtime::block_on;
     use tracing::Level;
 
-    use crate::utils::tracing::Metadata;
+    use crate::utils::{
+        telemetry::{TelemetryConfig, TelemetrySink},
+        tracing::Metadata,
+    };
 
     #[test]
     fn write_log() {
-        let mut gcp_logging = super::GcpLogging::new();
+        let telemetry_sink = TelemetrySink::new(
+            Box::new(super::GcpLogging::new()),
+            &TelemetryConfig::default(),
+        );
         let first_message =
             serde_json::from_str(r#"{"message": "TEST message 1", "log_test": "true"}"#).unwrap();
-        gcp_logging.add_entry(
+        telemetry_sink.add_entry(
             first_message,
             Metadata {
                 timestamp: Utc::now(),
@@ -202,11 +202,11 @@ mod tests {
             },
         );
 
-        gcp_logging.start_remote();
+        telemetry_sink.start();
         let second_message =
             serde_json::from_str(r#"{"message": "TEST message 2", "log_test": "true"}"#).unwrap();
 
-        gcp_logging.add_entry(
+        telemetry_sink.add_entry(
             second_message,
             Metadata {
                 timestamp: Utc::now(),
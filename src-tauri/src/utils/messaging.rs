@@ -20,6 +20,13 @@ pub enum ChannelList {
     EventViewport,
     NodeExplanationEvent,
     SuggestionEvent,
+    StickyScopeHeaderEvent,
+    DocumentOutlineEvent,
+    StructuralSearchEvent,
+    SettingsEvent,
+    FeatureStateEvent,
+    DeadCodeEvent,
+    CloneDetectionEvent,
 }
 impl fmt::Display for ChannelList {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -37,6 +44,13 @@ impl fmt::Display for ChannelList {
             ChannelList::EventViewport => write!(f, "EventViewport"),
             ChannelList::NodeExplanationEvent => write!(f, "NodeExplanationEvent"),
             ChannelList::SuggestionEvent => write!(f, "SuggestionEvent"),
+            ChannelList::StickyScopeHeaderEvent => write!(f, "StickyScopeHeaderEvent"),
+            ChannelList::DocumentOutlineEvent => write!(f, "DocumentOutlineEvent"),
+            ChannelList::StructuralSearchEvent => write!(f, "StructuralSearchEvent"),
+            ChannelList::SettingsEvent => write!(f, "SettingsEvent"),
+            ChannelList::FeatureStateEvent => write!(f, "FeatureStateEvent"),
+            ChannelList::DeadCodeEvent => write!(f, "DeadCodeEvent"),
+            ChannelList::CloneDetectionEvent => write!(f, "CloneDetectionEvent"),
         }
     }
 }
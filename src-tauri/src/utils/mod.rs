@@ -10,5 +10,6 @@ pub mod hash;
 pub mod messaging;
 pub mod rule_types;
 pub mod tauri_types;
+pub mod telemetry;
 pub mod tracing;
 pub mod updater;
@@ -30,7 +30,7 @@ impl MatchRange {
             return None;
         }
         Some(Self {
-            string: XcodeText::from_array(&text[(range.index)..(range.index + range.length)]),
+            string: text.slice((range.index)..(range.index + range.length)),
             range: *range,
         })
     }
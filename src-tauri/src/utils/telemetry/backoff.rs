@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+// Exponential backoff between failed export attempts, doubling up to `max`.
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    current: Option<Duration>,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max: max.max(initial),
+            current: None,
+        }
+    }
+
+    // `None` as long as the last attempt succeeded.
+    pub fn delay(&self) -> Option<Duration> {
+        self.current
+    }
+
+    pub fn on_failure(&mut self) -> Duration {
+        let next = match self.current {
+            Some(current) => (current * 2).min(self.max),
+            None => self.initial,
+        };
+        self.current = Some(next);
+        next
+    }
+
+    pub fn reset(&mut self) {
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod on_failure {
+        use std::time::Duration;
+
+        use crate::utils::telemetry::Backoff;
+
+        #[test]
+        fn doubles_up_to_max_and_resets() {
+            let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+
+            let delays: Vec<u64> = (0..5).map(|_| backoff.on_failure().as_secs()).collect();
+            assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+
+            backoff.reset();
+            assert_eq!(backoff.delay(), None);
+            assert_eq!(backoff.on_failure(), Duration::from_secs(1));
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::VecDeque;
+
+use tracing::Level;
+
+use super::TelemetryEntry;
+
+// Bounded queue of entries waiting to be exported. If the exporter cannot keep up, the queue
+// sheds the least severe entries first, so errors survive longer than debug output.
+pub struct BatchQueue {
+    entries: VecDeque<TelemetryEntry>,
+    capacity: usize,
+    dropped_count: usize,
+}
+
+impl BatchQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            dropped_count: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn push(&mut self, entry: TelemetryEntry) {
+        if self.entries.len() >= self.capacity && !self.make_room_for(&entry) {
+            self.dropped_count += 1;
+            return;
+        }
+
+        self.entries.push_back(entry);
+    }
+
+    pub fn drain_batch(&mut self, batch_size: usize) -> Vec<TelemetryEntry> {
+        let batch_size = batch_size.min(self.entries.len());
+        self.entries.drain(..batch_size).collect()
+    }
+
+    // Puts a batch which failed to export back in front of the queue, preserving the order.
+    pub fn requeue_front(&mut self, batch: Vec<TelemetryEntry>) {
+        for entry in batch.into_iter().rev() {
+            if self.entries.len() >= self.capacity && !self.make_room_for(&entry) {
+                self.dropped_count += 1;
+                continue;
+            }
+            self.entries.push_front(entry);
+        }
+    }
+
+    pub fn take_dropped_count(&mut self) -> usize {
+        std::mem::take(&mut self.dropped_count)
+    }
+
+    pub fn entries(&self) -> Vec<TelemetryEntry> {
+        self.entries.iter().cloned().collect()
+    }
+
+    // Evicts the oldest of the least severe entries, unless the new entry is even less severe.
+    fn make_room_for(&mut self, entry: &TelemetryEntry) -> bool {
+        let least_severe = self
+            .entries
+            .iter()
+            .enumerate()
+            .min_by_key(|(index, queued)| (severity(&queued.metadata.level), *index))
+            .map(|(index, queued)| (index, severity(&queued.metadata.level)));
+
+        match least_severe {
+            Some((index, queued_severity))
+                if queued_severity <= severity(&entry.metadata.level) =>
+            {
+                self.entries.remove(index);
+                self.dropped_count += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn severity(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 4,
+        Level::WARN => 3,
+        Level::INFO => 2,
+        Level::DEBUG => 1,
+        Level::TRACE => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod push {
+        use chrono::Utc;
+        use tracing::Level;
+
+        use crate::utils::{
+            telemetry::{BatchQueue, TelemetryEntry},
+            tracing::Metadata,
+        };
+
+        fn entry(name: &str, level: Level) -> TelemetryEntry {
+            TelemetryEntry::new(
+                serde_json::Value::Null,
+                Metadata {
+                    timestamp: Utc::now(),
+                    name: name.to_string(),
+                    level,
+                },
+            )
+        }
+
+        fn names(queue: &BatchQueue) -> Vec<String> {
+            queue
+                .entries()
+                .into_iter()
+                .map(|entry| entry.metadata.name)
+                .collect()
+        }
+
+        #[test]
+        fn evicts_least_severe_entry_when_full() {
+            let mut queue = BatchQueue::new(3);
+            queue.push(entry("a", Level::ERROR));
+            queue.push(entry("b", Level::DEBUG));
+            queue.push(entry("c", Level::INFO));
+            queue.push(entry("d", Level::WARN));
+
+            assert_eq!(names(&queue), vec!["a", "c", "d"]);
+            assert_eq!(queue.take_dropped_count(), 1);
+            assert_eq!(queue.take_dropped_count(), 0);
+        }
+
+        #[test]
+        fn drops_new_entry_if_queue_holds_more_severe_entries() {
+            let mut queue = BatchQueue::new(2);
+            queue.push(entry("a", Level::ERROR));
+            queue.push(entry("b", Level::WARN));
+            queue.push(entry("c", Level::DEBUG));
+
+            assert_eq!(names(&queue), vec!["a", "b"]);
+            assert_eq!(queue.take_dropped_count(), 1);
+        }
+
+        #[test]
+        fn requeue_preserves_order() {
+            let mut queue = BatchQueue::new(4);
+            queue.push(entry("a", Level::INFO));
+            queue.push(entry("b", Level::INFO));
+            queue.push(entry("c", Level::INFO));
+
+            let batch = queue.drain_batch(2);
+            queue.push(entry("d", Level::INFO));
+            queue.requeue_front(batch);
+
+            assert_eq!(names(&queue), vec!["a", "b", "c", "d"]);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::{collections::HashMap, fs, path::Path, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const TELEMETRY_CONFIG_FILENAME: &str = "telemetry.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum TelemetrySinkConfig {
+    Gcp,
+    LocalJsonl {
+        directory: PathBuf,
+        max_file_size_bytes: u64,
+        max_files: usize,
+    },
+    Otlp {
+        endpoint: String, // Base URL of the collector, e.g. `http://localhost:4318`
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+// How much is reported. Each level includes the ones before it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/telemetry/")]
+pub enum TelemetryLevel {
+    Off,
+    ErrorsOnly,
+    Usage, // Errors, warnings and info events
+    Full,  // Everything, including debug and trace events
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RedactionMode {
+    Keep,
+    Hash,
+    Strip,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub sink: TelemetrySinkConfig,
+    pub level: TelemetryLevel,
+    pub code_redaction: RedactionMode,
+    pub path_redaction: RedactionMode,
+    pub batch_size: usize,
+    pub flush_interval_ms: u64,
+    pub queue_capacity: usize,
+    pub spool_max_size_bytes: u64, // Undelivered entries kept on disk across restarts
+    pub retry_initial_backoff_ms: u64,
+    pub retry_max_backoff_ms: u64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            sink: TelemetrySinkConfig::Gcp,
+            level: TelemetryLevel::Usage,
+            code_redaction: RedactionMode::Strip,
+            path_redaction: RedactionMode::Hash,
+            batch_size: 100,
+            flush_interval_ms: 2000,
+            queue_capacity: 10_000,
+            spool_max_size_bytes: 5 * 1024 * 1024,
+            retry_initial_backoff_ms: 1000,
+            retry_max_backoff_ms: 5 * 60 * 1000,
+        }
+    }
+}
+
+impl TelemetryConfig {
+    // Tracing is configured before Tauri is set up, so the config is read straight from disk.
+    // A missing or malformed file falls back to the defaults.
+    pub fn load(app_dir: Option<&Path>) -> Self {
+        app_dir
+            .map(|app_dir| app_dir.join(TELEMETRY_CONFIG_FILENAME))
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod load {
+        use std::fs;
+
+        use crate::utils::telemetry::{
+            RedactionMode, TelemetryConfig, TelemetryLevel, TelemetrySinkConfig,
+            TELEMETRY_CONFIG_FILENAME,
+        };
+
+        fn test_fn(content: Option<&str>) -> TelemetryConfig {
+            let app_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            fs::create_dir_all(&app_dir).unwrap();
+            if let Some(content) = content {
+                fs::write(app_dir.join(TELEMETRY_CONFIG_FILENAME), content).unwrap();
+            }
+
+            let config = TelemetryConfig::load(Some(&app_dir));
+            _ = fs::remove_dir_all(app_dir);
+
+            config
+        }
+
+        #[test]
+        fn missing_file_falls_back_to_default() {
+            assert_eq!(test_fn(None), TelemetryConfig::default());
+        }
+
+        #[test]
+        fn malformed_file_falls_back_to_default() {
+            assert_eq!(test_fn(Some("{ not json")), TelemetryConfig::default());
+        }
+
+        #[test]
+        fn partial_config() {
+            let config = test_fn(Some(
+                r#"{ "sink": { "kind": "Otlp", "endpoint": "http://localhost:4318" }, "batch_size": 5 }"#,
+            ));
+
+            assert_eq!(
+                config.sink,
+                TelemetrySinkConfig::Otlp {
+                    endpoint: "http://localhost:4318".to_string(),
+                    headers: Default::default(),
+                }
+            );
+            assert_eq!(config.batch_size, 5);
+            assert_eq!(
+                config.queue_capacity,
+                TelemetryConfig::default().queue_capacity
+            );
+        }
+
+        #[test]
+        fn privacy_settings() {
+            let config = test_fn(Some(
+                r#"{ "level": "ErrorsOnly", "code_redaction": "Hash", "path_redaction": "Keep" }"#,
+            ));
+
+            assert_eq!(config.level, TelemetryLevel::ErrorsOnly);
+            assert_eq!(config.code_redaction, RedactionMode::Hash);
+            assert_eq!(config.path_redaction, RedactionMode::Keep);
+            assert_eq!(config.sink, TelemetrySinkConfig::Gcp);
+        }
+    }
+}
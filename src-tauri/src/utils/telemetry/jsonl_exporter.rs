@@ -0,0 +1,179 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{TelemetryEntry, TelemetryError, TelemetryExporter};
+
+const FILE_STEM: &str = "telemetry";
+
+// Appends entries as JSON lines to `telemetry.jsonl`. Once the file exceeds its size limit, it is
+// rotated to `telemetry.1.jsonl`, shifting older files up; files beyond `max_files` are deleted.
+pub struct JsonlExporter {
+    directory: PathBuf,
+    max_file_size_bytes: u64,
+    max_files: usize,
+}
+
+impl JsonlExporter {
+    pub fn new(directory: PathBuf, max_file_size_bytes: u64, max_files: usize) -> Self {
+        Self {
+            directory,
+            max_file_size_bytes,
+            max_files: max_files.max(1),
+        }
+    }
+
+    fn file_path(&self, generation: usize) -> PathBuf {
+        if generation == 0 {
+            self.directory.join(format!("{}.jsonl", FILE_STEM))
+        } else {
+            self.directory
+                .join(format!("{}.{}.jsonl", FILE_STEM, generation))
+        }
+    }
+
+    fn rotate(&self) -> Result<(), TelemetryError> {
+        let oldest = self.file_path(self.max_files - 1);
+        if oldest.exists() {
+            fs::remove_file(oldest)?;
+        }
+
+        for generation in (0..self.max_files - 1).rev() {
+            let path = self.file_path(generation);
+            if path.exists() {
+                fs::rename(path, self.file_path(generation + 1))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn current_file_size(&self) -> u64 {
+        fs::metadata(self.file_path(0))
+            .map(|metadata| metadata.len())
+            .unwrap_or(0)
+    }
+}
+
+#[async_trait]
+impl TelemetryExporter for JsonlExporter {
+    fn is_remote(&self) -> bool {
+        false
+    }
+
+    async fn export(&mut self, entries: &[TelemetryEntry]) -> Result<(), TelemetryError> {
+        fs::create_dir_all(&self.directory)?;
+
+        for entry in entries {
+            let mut line = serde_json::to_string(&json!({
+                "id": entry.id,
+                "timestamp": entry.metadata.timestamp.to_rfc3339(),
+                "level": entry.metadata.level.to_string(),
+                "name": entry.metadata.name,
+                "payload": entry.message,
+            }))?;
+            line.push('\n');
+
+            let file_size = self.current_file_size();
+            if file_size > 0 && file_size + line.len() as u64 > self.max_file_size_bytes {
+                self.rotate()?;
+            }
+
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.file_path(0))?
+                .write_all(line.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod export {
+        use std::fs;
+
+        use chrono::Utc;
+        use tauri::async_runtime::block_on;
+        use tracing::Level;
+
+        use crate::utils::{
+            telemetry::{JsonlExporter, TelemetryEntry, TelemetryExporter},
+            tracing::Metadata,
+        };
+
+        fn entries(count: usize) -> Vec<TelemetryEntry> {
+            (0..count)
+                .map(|i| {
+                    TelemetryEntry::new(
+                        serde_json::json!({ "i": i }),
+                        Metadata {
+                            timestamp: Utc::now(),
+                            name: "test".to_string(),
+                            level: Level::INFO,
+                        },
+                    )
+                })
+                .collect()
+        }
+
+        fn line_counts(directory: &std::path::Path) -> Vec<(String, usize)> {
+            let mut files: Vec<(String, usize)> = fs::read_dir(directory)
+                .unwrap()
+                .map(|entry| {
+                    let path = entry.unwrap().path();
+                    (
+                        path.file_name().unwrap().to_string_lossy().to_string(),
+                        fs::read_to_string(&path).unwrap().lines().count(),
+                    )
+                })
+                .collect();
+            files.sort();
+            files
+        }
+
+        #[test]
+        fn writes_json_lines() {
+            let directory = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            let mut exporter = JsonlExporter::new(directory.clone(), 1_000_000, 3);
+
+            block_on(exporter.export(&entries(3))).unwrap();
+
+            let content = fs::read_to_string(directory.join("telemetry.jsonl")).unwrap();
+            let first_line: serde_json::Value =
+                serde_json::from_str(content.lines().next().unwrap()).unwrap();
+            assert_eq!(first_line["payload"]["i"], 0);
+            assert_eq!(first_line["level"], "INFO");
+            assert_eq!(content.lines().count(), 3);
+
+            _ = fs::remove_dir_all(directory);
+        }
+
+        #[test]
+        fn rotates_and_caps_files() {
+            let directory = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            // Small enough that every file only fits a single entry.
+            let mut exporter = JsonlExporter::new(directory.clone(), 10, 3);
+
+            block_on(exporter.export(&entries(5))).unwrap();
+
+            assert_eq!(
+                line_counts(&directory),
+                vec![
+                    ("telemetry.1.jsonl".to_string(), 1),
+                    ("telemetry.2.jsonl".to_string(), 1),
+                    ("telemetry.jsonl".to_string(), 1),
+                ]
+            );
+
+            _ = fs::remove_dir_all(directory);
+        }
+    }
+}
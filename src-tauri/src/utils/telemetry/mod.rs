@@ -0,0 +1,22 @@
+pub use backoff::Backoff;
+pub use batch_queue::BatchQueue;
+pub use config::{
+    RedactionMode, TelemetryConfig, TelemetryLevel, TelemetrySinkConfig, TELEMETRY_CONFIG_FILENAME,
+};
+pub use jsonl_exporter::JsonlExporter;
+pub use otlp_exporter::OtlpExporter;
+pub use privacy::{anonymous_install_id, PrivacyFilter};
+pub use spool::{Spool, SPOOL_FILENAME};
+pub use telemetry_sink::{TelemetryEntry, TelemetryError, TelemetryExporter, TelemetrySink};
+
+mod backoff;
+mod batch_queue;
+mod config;
+mod jsonl_exporter;
+mod otlp_exporter;
+mod privacy;
+mod spool;
+mod telemetry_sink;
+
+#[cfg(test)]
+pub mod test_server;
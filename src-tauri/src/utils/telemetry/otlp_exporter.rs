@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tracing::Level;
+
+use super::{TelemetryEntry, TelemetryError, TelemetryExporter};
+
+const SERVICE_NAME: &str = "Pretzl";
+
+// Exports entries as OTLP log records, using the JSON encoding of the OTLP/HTTP protocol.
+pub struct OtlpExporter {
+    logs_url: String,
+    headers: HashMap<String, String>,
+    client: reqwest::Client,
+}
+
+impl OtlpExporter {
+    pub fn new(endpoint: String, headers: HashMap<String, String>) -> Self {
+        Self {
+            logs_url: format!("{}/v1/logs", endpoint.trim_end_matches('/')),
+            headers,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl TelemetryExporter for OtlpExporter {
+    fn is_remote(&self) -> bool {
+        true
+    }
+
+    async fn export(&mut self, entries: &[TelemetryEntry]) -> Result<(), TelemetryError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut request = self
+            .client
+            .post(&self.logs_url)
+            .json(&to_export_logs_request(entries));
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+
+        let response = request.send().await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(TelemetryError::Rejected(response.status().as_u16()))
+        }
+    }
+}
+
+fn to_export_logs_request(entries: &[TelemetryEntry]) -> Value {
+    json!({
+        "resourceLogs": [{
+            "resource": {
+                "attributes": [to_attribute("service.name", &json!(SERVICE_NAME))]
+            },
+            "scopeLogs": [{
+                "scope": { "name": SERVICE_NAME },
+                "logRecords": entries.iter().map(to_log_record).collect::<Vec<_>>()
+            }]
+        }]
+    })
+}
+
+fn to_log_record(entry: &TelemetryEntry) -> Value {
+    // Events serialized with `tracing_serde` carry their fields, including the message, in `fields`.
+    let fields = entry
+        .message
+        .get("fields")
+        .and_then(|fields| fields.as_object());
+
+    let body = fields
+        .and_then(|fields| fields.get("message"))
+        .and_then(|message| message.as_str())
+        .unwrap_or(&entry.metadata.name);
+
+    let mut attributes = vec![
+        to_attribute("event.name", &json!(entry.metadata.name)),
+        to_attribute("log.record.uid", &json!(entry.id)),
+    ];
+    if let Some(fields) = fields {
+        attributes.extend(
+            fields
+                .iter()
+                .filter(|(key, _)| key.as_str() != "message")
+                .map(|(key, value)| to_attribute(key, value)),
+        );
+    }
+
+    let time_unix_nano = entry.metadata.timestamp.timestamp_nanos();
+
+    json!({
+        "timeUnixNano": time_unix_nano.to_string(),
+        "severityNumber": severity_number(&entry.metadata.level),
+        "severityText": entry.metadata.level.to_string(),
+        "body": { "stringValue": body },
+        "attributes": attributes,
+    })
+}
+
+fn to_attribute(key: &str, value: &Value) -> Value {
+    let any_value = match value {
+        Value::String(string) => json!({ "stringValue": string }),
+        Value::Bool(boolean) => json!({ "boolValue": boolean }),
+        Value::Number(number) if number.is_i64() || number.is_u64() => {
+            json!({ "intValue": number.to_string() })
+        }
+        Value::Number(number) => json!({ "doubleValue": number.as_f64() }),
+        other => json!({ "stringValue": other.to_string() }),
+    };
+
+    json!({ "key": key, "value": any_value })
+}
+
+// https://opentelemetry.io/docs/specs/otel/logs/data-model/#field-severitynumber
+fn severity_number(level: &Level) -> u8 {
+    match *level {
+        Level::TRACE => 1,
+        Level::DEBUG => 5,
+        Level::INFO => 9,
+        Level::WARN => 13,
+        Level::ERROR => 17,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod export {
+        use std::collections::HashMap;
+
+        use chrono::Utc;
+        use tauri::async_runtime::block_on;
+        use tracing::Level;
+
+        use crate::utils::{
+            telemetry::{
+                test_server::LocalHttpServer, OtlpExporter, TelemetryEntry, TelemetryError,
+                TelemetryExporter,
+            },
+            tracing::Metadata,
+        };
+
+        fn entry() -> TelemetryEntry {
+            TelemetryEntry::new(
+                serde_json::json!({
+                    "fields": { "message": "Hello", "feature": "Formatter", "count": 3 }
+                }),
+                Metadata {
+                    timestamp: Utc::now(),
+                    name: "event src/main.rs:1".to_string(),
+                    level: Level::WARN,
+                },
+            )
+        }
+
+        #[test]
+        fn posts_log_records() {
+            let server = LocalHttpServer::start(200);
+            let mut exporter = OtlpExporter::new(
+                format!("{}/", server.url()),
+                HashMap::from([("x-api-key".to_string(), "secret".to_string())]),
+            );
+
+            block_on(exporter.export(&[entry()])).unwrap();
+
+            let request = server.requests().remove(0);
+            assert_eq!(request.method, "POST");
+            assert_eq!(request.path, "/v1/logs");
+            assert_eq!(
+                request.headers.get("x-api-key").map(String::as_str),
+                Some("secret")
+            );
+
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let record = &body["resourceLogs"][0]["scopeLogs"][0]["logRecords"][0];
+            assert_eq!(record["body"]["stringValue"], "Hello");
+            assert_eq!(record["severityNumber"], 13);
+            assert!(record["attributes"]
+                .as_array()
+                .unwrap()
+                .contains(&serde_json::json!({ "key": "count", "value": { "intValue": "3" } })));
+        }
+
+        #[test]
+        fn reports_rejected_requests() {
+            let server = LocalHttpServer::start(503);
+            let mut exporter = OtlpExporter::new(server.url(), HashMap::new());
+
+            assert!(matches!(
+                block_on(exporter.export(&[entry()])),
+                Err(TelemetryError::Rejected(503))
+            ));
+        }
+    }
+}
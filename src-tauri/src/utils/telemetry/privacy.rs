@@ -0,0 +1,329 @@
+use lazy_static::lazy_static;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tracing::Level;
+
+use super::{RedactionMode, TelemetryConfig, TelemetryLevel};
+
+const INSTALL_ID_SALT: &str = "pretzl-telemetry-install-id";
+const HASH_LENGTH: usize = 16;
+const STRIPPED_VALUE: &str = "<redacted>";
+
+// Field names are matched per snake_case segment, e.g. `suggestion_to_apply` contains `suggestion`.
+const CODE_FIELD_SEGMENTS: [&str; 8] = [
+    "code",
+    "text",
+    "content",
+    "source",
+    "snippet",
+    "suggestion",
+    "docstring",
+    "selection",
+];
+const PATH_FIELD_SEGMENTS: [&str; 5] = ["path", "file", "dir", "directory", "url"];
+// Feedback is free text, which often quotes code. It is only sent as written at the Full level and
+// is treated like code below it.
+const FEEDBACK_FIELD_SEGMENTS: [&str; 1] = ["feedback"];
+
+// Characters which end a path embedded in a message, e.g. in the `Debug` output of a struct.
+const PATH_DELIMITERS: [char; 13] = [
+    '"', '\'', '`', '(', ')', '[', ']', '{', '}', '<', '>', ',', ';',
+];
+
+lazy_static! {
+    static ref ANONYMOUS_INSTALL_ID: Option<String> = machine_uid::get()
+        .ok()
+        .map(|machine_id| sha256_hex(&format!("{}:{}", INSTALL_ID_SALT, machine_id)));
+}
+
+// Stable per installation, but the raw machine id cannot be recovered from it.
+pub fn anonymous_install_id() -> Option<String> {
+    ANONYMOUS_INSTALL_ID.clone()
+}
+
+// Applied to every entry before it is queued for export, so no exporter ever sees an entry the
+// user did not agree to share.
+#[derive(Clone, Debug)]
+pub struct PrivacyFilter {
+    level: TelemetryLevel,
+    code_redaction: RedactionMode,
+    path_redaction: RedactionMode,
+}
+
+impl PrivacyFilter {
+    pub fn new(config: &TelemetryConfig) -> Self {
+        Self {
+            level: config.level,
+            code_redaction: config.code_redaction,
+            path_redaction: config.path_redaction,
+        }
+    }
+
+    pub fn permits(&self, level: &Level) -> bool {
+        match self.level {
+            TelemetryLevel::Off => false,
+            TelemetryLevel::ErrorsOnly => *level == Level::ERROR,
+            TelemetryLevel::Usage => matches!(*level, Level::ERROR | Level::WARN | Level::INFO),
+            TelemetryLevel::Full => true,
+        }
+    }
+
+    // Returns `None` if the entry must not be reported at all. Only the event's `fields` are
+    // redacted; its metadata describes our own source locations, not the user's files.
+    pub fn apply(&self, mut message: Value, level: &Level) -> Option<Value> {
+        if !self.permits(level) {
+            return None;
+        }
+
+        if let Some(fields) = message.get_mut("fields").and_then(Value::as_object_mut) {
+            self.redact_fields(fields);
+        }
+
+        Some(message)
+    }
+
+    fn redact_fields(&self, fields: &mut Map<String, Value>) {
+        for (key, value) in fields.iter_mut() {
+            if is_field_of_kind(key, &FEEDBACK_FIELD_SEGMENTS) {
+                if self.level == TelemetryLevel::Full {
+                    self.scrub_paths_in_value(value);
+                } else {
+                    redact_value(value, self.code_redaction);
+                }
+            } else if is_field_of_kind(key, &CODE_FIELD_SEGMENTS) {
+                redact_value(value, self.code_redaction);
+            } else if is_field_of_kind(key, &PATH_FIELD_SEGMENTS) {
+                redact_value(value, self.path_redaction);
+            } else {
+                self.scrub_paths_in_value(value);
+            }
+        }
+    }
+
+    fn scrub_paths_in_value(&self, value: &mut Value) {
+        match value {
+            Value::String(string) => *string = scrub_paths(string, self.path_redaction),
+            Value::Array(values) => values
+                .iter_mut()
+                .for_each(|value| self.scrub_paths_in_value(value)),
+            Value::Object(fields) => self.redact_fields(fields),
+            _ => {}
+        }
+    }
+}
+
+fn is_field_of_kind(key: &str, segments: &[&str]) -> bool {
+    key.to_lowercase()
+        .split('_')
+        .any(|segment| segments.contains(&segment))
+}
+
+fn redact_value(value: &mut Value, mode: RedactionMode) {
+    match mode {
+        RedactionMode::Keep => {}
+        RedactionMode::Strip => *value = Value::String(STRIPPED_VALUE.to_string()),
+        RedactionMode::Hash => {
+            let text = match value {
+                Value::String(string) => string.clone(),
+                other => other.to_string(),
+            };
+            *value = Value::String(hash_placeholder(&text));
+        }
+    }
+}
+
+// Replaces absolute, home-relative and `file://` paths embedded in free text.
+fn scrub_paths(text: &str, mode: RedactionMode) -> String {
+    if mode == RedactionMode::Keep {
+        return text.to_string();
+    }
+
+    let mut scrubbed = String::with_capacity(text.len());
+    let mut token = String::new();
+    for c in text.chars() {
+        if c.is_whitespace() || PATH_DELIMITERS.contains(&c) {
+            scrubbed.push_str(&scrub_token(&token, mode));
+            scrubbed.push(c);
+            token.clear();
+        } else {
+            token.push(c);
+        }
+    }
+    scrubbed.push_str(&scrub_token(&token, mode));
+
+    scrubbed
+}
+
+fn scrub_token(token: &str, mode: RedactionMode) -> String {
+    // Punctuation ending a sentence is not part of the path.
+    let path = token.trim_end_matches(|c| c == ':' || c == '.');
+    let looks_like_path =
+        (path.starts_with('/') || path.starts_with("~/") || path.starts_with("file://"))
+            && path.matches('/').count() >= 2;
+
+    if !looks_like_path {
+        return token.to_string();
+    }
+
+    let replacement = match mode {
+        RedactionMode::Keep => path.to_string(),
+        RedactionMode::Strip => STRIPPED_VALUE.to_string(),
+        RedactionMode::Hash => hash_placeholder(path),
+    };
+    format!("{}{}", replacement, &token[path.len()..])
+}
+
+fn hash_placeholder(text: &str) -> String {
+    format!("<sha256:{}>", &sha256_hex(text)[..HASH_LENGTH])
+}
+
+fn sha256_hex(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    mod apply {
+        use serde_json::{json, Value};
+        use tracing::Level;
+
+        use crate::utils::telemetry::{
+            PrivacyFilter, RedactionMode, TelemetryConfig, TelemetryLevel,
+        };
+
+        fn filter(
+            level: TelemetryLevel,
+            code: RedactionMode,
+            path: RedactionMode,
+        ) -> PrivacyFilter {
+            PrivacyFilter::new(&TelemetryConfig {
+                level,
+                code_redaction: code,
+                path_redaction: path,
+                ..Default::default()
+            })
+        }
+
+        fn event(fields: Value) -> Value {
+            json!({ "metadata": { "file": "src/main.rs" }, "fields": fields })
+        }
+
+        #[test]
+        fn filters_by_level() {
+            let levels = [
+                Level::ERROR,
+                Level::WARN,
+                Level::INFO,
+                Level::DEBUG,
+                Level::TRACE,
+            ];
+            let permitted = |telemetry_level| {
+                let filter = filter(telemetry_level, RedactionMode::Keep, RedactionMode::Keep);
+                levels
+                    .iter()
+                    .filter(|level| filter.apply(event(json!({})), level).is_some())
+                    .count()
+            };
+
+            assert_eq!(permitted(TelemetryLevel::Off), 0);
+            assert_eq!(permitted(TelemetryLevel::ErrorsOnly), 1);
+            assert_eq!(permitted(TelemetryLevel::Usage), 3);
+            assert_eq!(permitted(TelemetryLevel::Full), 5);
+        }
+
+        #[test]
+        fn strips_code_fields() {
+            let filter = filter(
+                TelemetryLevel::Full,
+                RedactionMode::Strip,
+                RedactionMode::Keep,
+            );
+
+            let message = filter
+                .apply(
+                    event(json!({
+                        "message": "Perform suggestion",
+                        "suggestion_to_apply": "func foo() { return 42 }",
+                        "code_snippet": "let x = 1",
+                        "feature": "ComplexityRefactoring"
+                    })),
+                    &Level::INFO,
+                )
+                .unwrap();
+
+            assert_eq!(message["fields"]["suggestion_to_apply"], "<redacted>");
+            assert_eq!(message["fields"]["code_snippet"], "<redacted>");
+            assert_eq!(message["fields"]["message"], "Perform suggestion");
+            assert_eq!(message["fields"]["feature"], "ComplexityRefactoring");
+        }
+
+        #[test]
+        fn redacts_feedback_below_full_level() {
+            let feedback = |level| {
+                let filter = filter(level, RedactionMode::Strip, RedactionMode::Strip);
+                filter
+                    .apply(
+                        event(json!({
+                            "target": "NodeExplainer",
+                            "feedback": "\"Wrong for let x = compute() in /Users/jane/App.swift\""
+                        })),
+                        &Level::INFO,
+                    )
+                    .unwrap()["fields"]["feedback"]
+                    .clone()
+            };
+
+            assert_eq!(feedback(TelemetryLevel::Usage), "<redacted>");
+            assert_eq!(
+                feedback(TelemetryLevel::Full),
+                "\"Wrong for let x = compute() in <redacted>\""
+            );
+        }
+
+        #[test]
+        fn hashes_path_fields_deterministically() {
+            let filter = filter(
+                TelemetryLevel::Usage,
+                RedactionMode::Keep,
+                RedactionMode::Hash,
+            );
+            let fields = json!({ "file_path": "/Users/jane/Project/Sources/App.swift" });
+
+            let first = filter.apply(event(fields.clone()), &Level::WARN).unwrap();
+            let second = filter.apply(event(fields), &Level::WARN).unwrap();
+
+            let hashed = first["fields"]["file_path"].as_str().unwrap();
+            assert!(hashed.starts_with("<sha256:"));
+            assert!(!hashed.contains("jane"));
+            assert_eq!(first, second);
+        }
+
+        #[test]
+        fn scrubs_paths_embedded_in_messages() {
+            let filter = filter(
+                TelemetryLevel::Usage,
+                RedactionMode::Keep,
+                RedactionMode::Strip,
+            );
+
+            let message = filter
+                .apply(
+                    event(json!({
+                        "message": "Failed to open /Users/jane/Project/App.swift: not found",
+                        "error": "Io { path: \"~/Library/Pretzl/state\" }",
+                        "count": 3
+                    })),
+                    &Level::ERROR,
+                )
+                .unwrap();
+
+            assert_eq!(
+                message["fields"]["message"],
+                "Failed to open <redacted>: not found"
+            );
+            assert_eq!(message["fields"]["error"], "Io { path: \"<redacted>\" }");
+            assert_eq!(message["fields"]["count"], 3);
+            assert_eq!(message["metadata"]["file"], "src/main.rs");
+        }
+    }
+}
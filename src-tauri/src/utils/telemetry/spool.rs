@@ -0,0 +1,236 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::Level;
+
+use crate::utils::tracing::Metadata;
+
+use super::{TelemetryEntry, TelemetryError};
+
+pub const SPOOL_FILENAME: &str = ".telemetry-spool.jsonl";
+
+// On-disk form of a `TelemetryEntry`; timestamp and level are kept as strings.
+#[derive(Serialize, Deserialize)]
+struct SpooledEntry {
+    id: String,
+    timestamp: String,
+    level: String,
+    name: String,
+    message: Value,
+}
+
+impl SpooledEntry {
+    fn from_entry(entry: &TelemetryEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            timestamp: entry.metadata.timestamp.to_rfc3339(),
+            level: entry.metadata.level.to_string(),
+            name: entry.metadata.name.clone(),
+            message: entry.message.clone(),
+        }
+    }
+
+    fn into_entry(self) -> Option<TelemetryEntry> {
+        Some(TelemetryEntry {
+            id: self.id,
+            message: self.message,
+            metadata: Metadata {
+                timestamp: DateTime::parse_from_rfc3339(&self.timestamp)
+                    .ok()?
+                    .with_timezone(&Utc),
+                name: self.name,
+                level: Level::from_str(&self.level).ok()?,
+            },
+        })
+    }
+}
+
+// Persists entries which have not been exported yet, so they survive a failed export followed by
+// a restart. The file always mirrors the complete backlog and is replaced atomically.
+pub struct Spool {
+    path: PathBuf,
+    max_size_bytes: u64,
+}
+
+impl Spool {
+    pub fn new(directory: &Path, max_size_bytes: u64) -> Self {
+        Self {
+            path: directory.join(SPOOL_FILENAME),
+            max_size_bytes,
+        }
+    }
+
+    // Malformed lines are skipped; entries spooled more than once are only returned once.
+    pub fn load(&self) -> Vec<TelemetryEntry> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(_) => return vec![],
+        };
+
+        let mut seen_ids = HashSet::new();
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<SpooledEntry>(line).ok())
+            .filter_map(SpooledEntry::into_entry)
+            .filter(|entry| seen_ids.insert(entry.id.clone()))
+            .collect()
+    }
+
+    // Replaces the spooled backlog. If the entries exceed the size cap, the oldest ones are
+    // dropped; returns how many.
+    pub fn store(&self, entries: &[TelemetryEntry]) -> Result<usize, TelemetryError> {
+        if entries.is_empty() {
+            if self.path.exists() {
+                fs::remove_file(&self.path)?;
+            }
+            return Ok(0);
+        }
+
+        let mut lines = vec![];
+        let mut size = 0;
+        for entry in entries.iter().rev() {
+            let mut line = serde_json::to_string(&SpooledEntry::from_entry(entry))?;
+            line.push('\n');
+            if size + line.len() as u64 > self.max_size_bytes {
+                break;
+            }
+            size += line.len() as u64;
+            lines.push(line);
+        }
+        lines.reverse();
+
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let temp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&temp_path, lines.concat())?;
+        fs::rename(temp_path, &self.path)?;
+
+        Ok(entries.len() - lines.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod store {
+        use std::fs;
+
+        use chrono::Utc;
+        use tracing::Level;
+
+        use crate::utils::{
+            telemetry::{Spool, TelemetryEntry, SPOOL_FILENAME},
+            tracing::Metadata,
+        };
+
+        fn entry(i: usize) -> TelemetryEntry {
+            TelemetryEntry::new(
+                serde_json::json!({ "i": i }),
+                Metadata {
+                    timestamp: Utc::now(),
+                    name: "test".to_string(),
+                    level: Level::WARN,
+                },
+            )
+        }
+
+        fn indices(entries: &[TelemetryEntry]) -> Vec<u64> {
+            entries
+                .iter()
+                .map(|entry| entry.message["i"].as_u64().unwrap())
+                .collect()
+        }
+
+        #[test]
+        fn round_trips_entries() {
+            let directory = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            let spool = Spool::new(&directory, 1_000_000);
+            let entries = vec![entry(0), entry(1)];
+
+            assert_eq!(spool.store(&entries).unwrap(), 0);
+            let loaded = spool.load();
+
+            assert_eq!(indices(&loaded), vec![0, 1]);
+            assert_eq!(loaded[0].id, entries[0].id);
+            assert_eq!(loaded[0].metadata.level, Level::WARN);
+            assert_eq!(
+                loaded[0].metadata.timestamp.timestamp_millis(),
+                entries[0].metadata.timestamp.timestamp_millis()
+            );
+
+            _ = fs::remove_dir_all(directory);
+        }
+
+        #[test]
+        fn drops_oldest_entries_beyond_size_cap() {
+            let directory = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            let line_length = Spool::new(&directory, 1_000_000)
+                .store(&[entry(0)])
+                .map(|_| fs::metadata(directory.join(SPOOL_FILENAME)).unwrap().len())
+                .unwrap();
+            // Room for two entries, even if their timestamps render slightly longer.
+            let spool = Spool::new(&directory, line_length * 5 / 2);
+
+            assert_eq!(spool.store(&[entry(1), entry(2), entry(3)]).unwrap(), 1);
+            assert_eq!(indices(&spool.load()), vec![2, 3]);
+
+            _ = fs::remove_dir_all(directory);
+        }
+
+        #[test]
+        fn empty_backlog_removes_file() {
+            let directory = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            let spool = Spool::new(&directory, 1_000_000);
+
+            spool.store(&[entry(0)]).unwrap();
+            spool.store(&[]).unwrap();
+
+            assert!(!directory.join(SPOOL_FILENAME).exists());
+            assert!(spool.load().is_empty());
+
+            _ = fs::remove_dir_all(directory);
+        }
+    }
+
+    mod load {
+        use std::fs;
+
+        use chrono::Utc;
+        use tracing::Level;
+
+        use crate::utils::{
+            telemetry::{Spool, TelemetryEntry, SPOOL_FILENAME},
+            tracing::Metadata,
+        };
+
+        #[test]
+        fn skips_duplicates_and_malformed_lines() {
+            let directory = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            let spool = Spool::new(&directory, 1_000_000);
+            let entry = TelemetryEntry::new(
+                serde_json::json!({ "i": 0 }),
+                Metadata {
+                    timestamp: Utc::now(),
+                    name: "test".to_string(),
+                    level: Level::INFO,
+                },
+            );
+            spool.store(&[entry.clone(), entry]).unwrap();
+
+            let path = directory.join(SPOOL_FILENAME);
+            let content = fs::read_to_string(&path).unwrap();
+            fs::write(&path, format!("{}{{ not json\n", content)).unwrap();
+
+            assert_eq!(spool.load().len(), 1);
+
+            _ = fs::remove_dir_all(directory);
+        }
+    }
+}
@@ -0,0 +1,491 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::Value;
+use tauri::async_runtime::block_on;
+use tokio::sync::Notify;
+use tracing::warn;
+
+use crate::utils::{gcp::logging::GcpLogging, tracing::Metadata};
+
+use super::{
+    Backoff, BatchQueue, JsonlExporter, OtlpExporter, PrivacyFilter, Spool, TelemetryConfig,
+    TelemetrySinkConfig,
+};
+
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(30);
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(thiserror::Error, Debug)]
+pub enum TelemetryError {
+    #[error("No access token available for the telemetry endpoint.")]
+    Unauthenticated,
+    #[error("The telemetry endpoint rejected the request with status {0}.")]
+    Rejected(u16),
+    #[error("The telemetry export did not finish in time.")]
+    TimedOut,
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Serialization(#[from] serde_json::Error),
+}
+
+#[derive(Clone, Debug)]
+pub struct TelemetryEntry {
+    pub id: String, // Lets endpoints discard entries which are delivered twice, e.g. after a crash
+    pub message: Value,
+    pub metadata: Metadata,
+}
+
+impl TelemetryEntry {
+    pub fn new(message: Value, metadata: Metadata) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            message,
+            metadata,
+        }
+    }
+}
+
+#[async_trait]
+pub trait TelemetryExporter: Send {
+    // Remote exporters are skipped in debug builds and for events marked `no_remote`.
+    fn is_remote(&self) -> bool;
+    async fn export(&mut self, entries: &[TelemetryEntry]) -> Result<(), TelemetryError>;
+}
+
+// Buffers entries in a bounded queue and hands them to the exporter in batches, either
+// periodically or as soon as a full batch is available. Entries pass the privacy filter first.
+// Failed exports are retried with exponential backoff; with a spool, the backlog also survives
+// restarts. Clones share the same queue and exporter.
+#[derive(Clone)]
+pub struct TelemetrySink {
+    privacy_filter: PrivacyFilter,
+    queue: Arc<Mutex<BatchQueue>>,
+    exporter: Arc<tokio::sync::Mutex<Box<dyn TelemetryExporter>>>,
+    spool: Option<Arc<Spool>>,
+    flush_requested: Arc<Notify>,
+    batch_size: usize,
+    flush_interval: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    is_remote: bool,
+}
+
+impl TelemetrySink {
+    pub fn new(exporter: Box<dyn TelemetryExporter>, config: &TelemetryConfig) -> Self {
+        Self {
+            privacy_filter: PrivacyFilter::new(config),
+            queue: Arc::new(Mutex::new(BatchQueue::new(config.queue_capacity))),
+            is_remote: exporter.is_remote(),
+            exporter: Arc::new(tokio::sync::Mutex::new(exporter)),
+            spool: None,
+            flush_requested: Arc::new(Notify::new()),
+            batch_size: config.batch_size.max(1),
+            flush_interval: Duration::from_millis(config.flush_interval_ms),
+            initial_backoff: Duration::from_millis(config.retry_initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.retry_max_backoff_ms),
+        }
+    }
+
+    pub fn from_config(config: &TelemetryConfig) -> Self {
+        let exporter: Box<dyn TelemetryExporter> = match &config.sink {
+            TelemetrySinkConfig::Gcp => Box::new(GcpLogging::new()),
+            TelemetrySinkConfig::LocalJsonl {
+                directory,
+                max_file_size_bytes,
+                max_files,
+            } => Box::new(JsonlExporter::new(
+                directory.clone(),
+                *max_file_size_bytes,
+                *max_files,
+            )),
+            TelemetrySinkConfig::Otlp { endpoint, headers } => {
+                Box::new(OtlpExporter::new(endpoint.clone(), headers.clone()))
+            }
+        };
+
+        Self::new(exporter, config)
+    }
+
+    // Entries left over from a previous run are queued again when the sink starts.
+    pub fn with_spool(mut self, spool: Spool) -> Self {
+        self.spool = Some(Arc::new(spool));
+        self
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.is_remote
+    }
+
+    pub fn start(&self) {
+        if let Some(spool) = &self.spool {
+            self.queue.lock().requeue_front(spool.load());
+        }
+
+        tauri::async_runtime::spawn({
+            let sink = self.clone();
+            async move {
+                let mut backoff = Backoff::new(sink.initial_backoff, sink.max_backoff);
+                loop {
+                    match backoff.delay() {
+                        // Skip flush requests while the endpoint is failing.
+                        Some(delay) => tokio::time::sleep(delay).await,
+                        None => {
+                            _ = tokio::time::timeout(
+                                sink.flush_interval,
+                                sink.flush_requested.notified(),
+                            )
+                            .await;
+                        }
+                    }
+
+                    match sink.flush().await {
+                        Ok(()) => backoff.reset(),
+                        Err(_) => {
+                            backoff.on_failure();
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn add_entry(&self, message: Value, metadata: Metadata) {
+        let message = match self.privacy_filter.apply(message, &metadata.level) {
+            Some(message) => message,
+            None => return,
+        };
+
+        let queue_len = {
+            let mut queue = self.queue.lock();
+            queue.push(TelemetryEntry::new(message, metadata));
+            queue.len()
+        };
+
+        if queue_len >= self.batch_size {
+            self.flush_requested.notify_one();
+        }
+    }
+
+    // Bypasses the queue; used for events which must not be lost, e.g. panics. If the export
+    // fails, the entry is queued and spooled instead.
+    pub fn publish_entry_synchronously(&self, message: Value, metadata: Metadata) {
+        let message = match self.privacy_filter.apply(message, &metadata.level) {
+            Some(message) => message,
+            None => return,
+        };
+
+        let entries = vec![TelemetryEntry::new(message, metadata)];
+        let result = block_on(async {
+            let mut exporter = self.exporter.lock().await;
+            export_with_timeout(&mut **exporter, &entries, EXPORT_TIMEOUT).await
+        });
+
+        if result.is_err() {
+            self.queue.lock().requeue_front(entries);
+            self.persist_queue();
+        }
+    }
+
+    pub async fn flush(&self) -> Result<(), TelemetryError> {
+        let result = {
+            let mut exporter = self.exporter.lock().await;
+            flush_queue(
+                &self.queue,
+                &mut **exporter,
+                self.batch_size,
+                EXPORT_TIMEOUT,
+            )
+            .await
+        };
+        self.persist_queue();
+        result
+    }
+
+    // Called when the app quits: makes a last, time-boxed export attempt and spools whatever
+    // could not be delivered.
+    pub fn shutdown(&self) {
+        block_on(async {
+            if let Ok(mut exporter) =
+                tokio::time::timeout(SHUTDOWN_TIMEOUT, self.exporter.lock()).await
+            {
+                _ = flush_queue(
+                    &self.queue,
+                    &mut **exporter,
+                    self.batch_size,
+                    SHUTDOWN_TIMEOUT,
+                )
+                .await;
+            }
+        });
+        self.persist_queue();
+    }
+
+    #[allow(dead_code)] // Used in tests
+    pub fn queued_entries(&self) -> Vec<TelemetryEntry> {
+        self.queue.lock().entries()
+    }
+
+    fn persist_queue(&self) {
+        let spool = match &self.spool {
+            Some(spool) => spool,
+            None => return,
+        };
+
+        let entries = self.queue.lock().entries();
+        match spool.store(&entries) {
+            Ok(dropped_entries) if dropped_entries > 0 => warn!(
+                no_remote = true,
+                "telemetry spool is full, dropped {} entries", dropped_entries
+            ),
+            Ok(_) => {}
+            Err(err) => warn!(
+                no_remote = true,
+                "failed to spool telemetry entries: {}", err
+            ),
+        }
+    }
+}
+
+async fn export_with_timeout(
+    exporter: &mut dyn TelemetryExporter,
+    entries: &[TelemetryEntry],
+    timeout: Duration,
+) -> Result<(), TelemetryError> {
+    tokio::time::timeout(timeout, exporter.export(entries))
+        .await
+        .unwrap_or(Err(TelemetryError::TimedOut))
+}
+
+// Expects the exporter to be locked already, so no batch is in flight elsewhere while the queue
+// is drained.
+async fn flush_queue(
+    queue: &Arc<Mutex<BatchQueue>>,
+    exporter: &mut dyn TelemetryExporter,
+    batch_size: usize,
+    export_timeout: Duration,
+) -> Result<(), TelemetryError> {
+    loop {
+        let batch = queue.lock().drain_batch(batch_size);
+        if batch.is_empty() {
+            break;
+        }
+
+        if let Err(err) = export_with_timeout(exporter, &batch, export_timeout).await {
+            warn!(
+                no_remote = true,
+                "failed to export {} telemetry entries: {}",
+                batch.len(),
+                err
+            );
+            // Keep the batch for the next attempt; the queue's capacity still applies.
+            queue.lock().requeue_front(batch);
+            return Err(err);
+        }
+    }
+
+    let dropped_entries = queue.lock().take_dropped_count();
+    if dropped_entries > 0 {
+        warn!(
+            no_remote = true,
+            "telemetry queue was full, dropped {} entries", dropped_entries
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    mod flush {
+        use std::sync::Arc;
+
+        use async_trait::async_trait;
+        use chrono::Utc;
+        use parking_lot::Mutex;
+        use tauri::async_runtime::block_on;
+        use tracing::Level;
+
+        use crate::utils::{
+            telemetry::{
+                TelemetryConfig, TelemetryEntry, TelemetryError, TelemetryExporter, TelemetryLevel,
+                TelemetrySink,
+            },
+            tracing::Metadata,
+        };
+
+        struct MockExporter {
+            exported_batches: Arc<Mutex<Vec<usize>>>,
+            fail: bool,
+        }
+
+        #[async_trait]
+        impl TelemetryExporter for MockExporter {
+            fn is_remote(&self) -> bool {
+                false
+            }
+
+            async fn export(&mut self, entries: &[TelemetryEntry]) -> Result<(), TelemetryError> {
+                if self.fail {
+                    return Err(TelemetryError::Rejected(503));
+                }
+                self.exported_batches.lock().push(entries.len());
+                Ok(())
+            }
+        }
+
+        fn test_fn(entry_count: usize, fail: bool, level: TelemetryLevel) -> (Vec<usize>, usize) {
+            let exported_batches = Arc::new(Mutex::new(vec![]));
+            let sink = TelemetrySink::new(
+                Box::new(MockExporter {
+                    exported_batches: exported_batches.clone(),
+                    fail,
+                }),
+                &TelemetryConfig {
+                    batch_size: 2,
+                    level,
+                    ..Default::default()
+                },
+            );
+
+            for i in 0..entry_count {
+                sink.add_entry(
+                    serde_json::json!({ "i": i }),
+                    Metadata {
+                        timestamp: Utc::now(),
+                        name: "test".to_string(),
+                        level: Level::INFO,
+                    },
+                );
+            }
+            _ = block_on(sink.flush());
+
+            let batches = exported_batches.lock().clone();
+            (batches, sink.queued_entries().len())
+        }
+
+        #[test]
+        fn exports_in_batches() {
+            assert_eq!(test_fn(5, false, TelemetryLevel::Usage), (vec![2, 2, 1], 0));
+        }
+
+        #[test]
+        fn keeps_entries_if_export_fails() {
+            assert_eq!(test_fn(5, true, TelemetryLevel::Usage), (vec![], 5));
+        }
+
+        #[test]
+        fn drops_entries_not_permitted_by_level() {
+            assert_eq!(test_fn(5, false, TelemetryLevel::ErrorsOnly), (vec![], 0));
+        }
+    }
+
+    mod shutdown {
+        use std::collections::HashSet;
+
+        use chrono::Utc;
+        use tauri::async_runtime::block_on;
+        use tracing::Level;
+
+        use crate::utils::{
+            telemetry::{
+                test_server::LocalHttpServer, OtlpExporter, Spool, TelemetryConfig, TelemetrySink,
+                SPOOL_FILENAME,
+            },
+            tracing::Metadata,
+        };
+
+        fn sink(server: &LocalHttpServer, spool_directory: &std::path::Path) -> TelemetrySink {
+            let config = TelemetryConfig {
+                batch_size: 2,
+                // Long enough that only the test's own flushes reach the server.
+                flush_interval_ms: 60_000,
+                ..Default::default()
+            };
+            TelemetrySink::new(
+                Box::new(OtlpExporter::new(server.url(), Default::default())),
+                &config,
+            )
+            .with_spool(Spool::new(spool_directory, config.spool_max_size_bytes))
+        }
+
+        fn exported_messages(
+            requests: &[crate::utils::telemetry::test_server::RecordedRequest],
+        ) -> Vec<String> {
+            requests
+                .iter()
+                .flat_map(|request| {
+                    let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+                    body["resourceLogs"][0]["scopeLogs"][0]["logRecords"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|record| record["body"]["stringValue"].as_str().unwrap().to_string())
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        }
+
+        #[test]
+        fn delivers_spooled_entries_after_restart() {
+            let spool_directory = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+            let server = LocalHttpServer::start(503);
+
+            // First run: the endpoint is down, so nothing can be delivered before quitting.
+            let first_run = sink(&server, &spool_directory);
+            for i in 0..5 {
+                first_run.add_entry(
+                    serde_json::json!({ "fields": { "message": format!("entry {}", i) } }),
+                    Metadata {
+                        timestamp: Utc::now(),
+                        name: "test".to_string(),
+                        level: Level::WARN,
+                    },
+                );
+            }
+            assert!(block_on(first_run.flush()).is_err());
+            first_run.shutdown();
+            drop(first_run);
+            assert!(spool_directory.join(SPOOL_FILENAME).exists());
+
+            // Second run: the endpoint is back and receives the backlog exactly once.
+            server.set_status(200);
+            let failed_request_count = server.requests().len();
+            let second_run = sink(&server, &spool_directory);
+            second_run.start();
+            block_on(second_run.flush()).unwrap();
+            second_run.shutdown();
+
+            let requests = server.requests();
+            let messages = exported_messages(&requests[failed_request_count..]);
+            assert_eq!(
+                messages,
+                (0..5).map(|i| format!("entry {}", i)).collect::<Vec<_>>()
+            );
+            assert!(!spool_directory.join(SPOOL_FILENAME).exists());
+
+            // Redelivered entries keep their ids, so the endpoint can discard duplicates.
+            let record_ids: HashSet<String> = requests
+                .iter()
+                .flat_map(|request| {
+                    let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+                    body["resourceLogs"][0]["scopeLogs"][0]["logRecords"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .flat_map(|record| record["attributes"].as_array().unwrap().clone())
+                        .filter(|attribute| attribute["key"] == "log.record.uid")
+                        .map(|attribute| attribute["value"]["stringValue"].to_string())
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            assert_eq!(record_ids.len(), 5);
+
+            _ = std::fs::remove_dir_all(spool_directory);
+        }
+    }
+}
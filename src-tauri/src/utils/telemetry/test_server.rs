@@ -0,0 +1,114 @@
+// Minimal HTTP server standing in for remote telemetry endpoints in tests. It records every
+// request and answers with a configurable status code.
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: HashMap<String, String>, // Header names are lowercased
+    pub body: Vec<u8>,
+}
+
+pub struct LocalHttpServer {
+    port: u16,
+    status: Arc<Mutex<u16>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl LocalHttpServer {
+    pub fn start(status: u16) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind test server");
+        let port = listener.local_addr().unwrap().port();
+
+        let server = Self {
+            port,
+            status: Arc::new(Mutex::new(status)),
+            requests: Arc::new(Mutex::new(vec![])),
+        };
+
+        thread::spawn({
+            let status = server.status.clone();
+            let requests = server.requests.clone();
+            move || {
+                for stream in listener.incoming().flatten() {
+                    if let Some(request) = read_request(&stream) {
+                        requests.lock().push(request);
+                    }
+                    respond(stream, *status.lock());
+                }
+            }
+        });
+
+        server
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    pub fn set_status(&self, status: u16) {
+        *self.status.lock() = status;
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().clone()
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Option<RecordedRequest> {
+    _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let content_length = headers
+        .get("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+
+    Some(RecordedRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn respond(mut stream: TcpStream, status: u16) {
+    _ = write!(
+        stream,
+        "HTTP/1.1 {} Test\r\nContent-Length: 2\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n{{}}",
+        status
+    );
+    _ = stream.flush();
+}
@@ -1,21 +1,21 @@
-use std::{panic, sync::Arc};
+use std::{panic, path::Path};
 
 use backtrace::Backtrace;
 use chrono::{DateTime, Utc};
-use parking_lot::Mutex;
 use serde_json::json;
 use tracing_serde::AsSerde;
 
 use tracing::{error, info, metadata::LevelFilter, Level, Subscriber};
 use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, Layer};
 
-use super::gcp::logging::GcpLogging;
+use super::telemetry::{Spool, TelemetryConfig, TelemetrySink};
 
-struct GcpLayer {
-    gcp_logging: Arc<Mutex<GcpLogging>>,
+// Forwards events to the configured telemetry sink, e.g. GCP, local JSONL files or an OTLP collector.
+struct TelemetryLayer {
+    telemetry_sink: TelemetrySink,
 }
 
-impl GcpLayer {
+impl TelemetryLayer {
     #[allow(unreachable_code, unused_variables)]
     fn should_send_to_remote(event: &tracing::Event<'_>) -> bool {
         #[cfg(debug_assertions)] // only include this code on debug builds
@@ -23,12 +23,12 @@ impl GcpLayer {
             return false;
         }
 
-        for field in event.fields() {
-            if field.name() == "no_remote" {
-                return false;
-            }
-        }
-        true
+        !Self::is_marked_no_remote(event)
+    }
+
+    // Events about the telemetry itself are marked, so they do not feed back into the sink.
+    fn is_marked_no_remote(event: &tracing::Event<'_>) -> bool {
+        event.fields().any(|field| field.name() == "no_remote")
     }
 
     fn should_send_sync(event: &tracing::Event<'_>) -> bool {
@@ -41,19 +41,14 @@ impl GcpLayer {
     }
 }
 
-impl<S: Subscriber> Layer<S> for GcpLayer {
+impl<S: Subscriber> Layer<S> for TelemetryLayer {
     fn enabled(
         &self,
         metadata: &tracing::Metadata<'_>,
         _ctx: tracing_subscriber::layer::Context<'_, S>,
     ) -> bool {
-        if let Some(module) = metadata.module_path() {
-            if module.contains("Pretzl") {
-                // Filter all non-Pretzl events
-                return true;
-            }
-        }
-        false
+        // Filter all non-Pretzl events
+        metadata.module_path().map_or(false, is_crate_module)
     }
 
     fn on_event(
@@ -65,23 +60,30 @@ impl<S: Subscriber> Layer<S> for GcpLayer {
         let name = event.metadata().name().to_string();
         let level = event.metadata().level().to_owned();
 
-        if Self::should_send_to_remote(event) {
+        let should_send = if self.telemetry_sink.is_remote() {
+            Self::should_send_to_remote(event)
+        } else {
+            !Self::is_marked_no_remote(event)
+        };
+
+        if should_send {
             let message = json!(event.as_serde());
             let metadata = Metadata {
                 timestamp,
                 name,
                 level,
             };
-            let mut gcp_logging = self.gcp_logging.lock();
             if Self::should_send_sync(event) {
-                gcp_logging.publish_entry_synchronously(message, metadata);
+                self.telemetry_sink
+                    .publish_entry_synchronously(message, metadata);
             } else {
-                gcp_logging.add_entry(message, metadata);
+                self.telemetry_sink.add_entry(message, metadata);
             }
         }
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct Metadata {
     pub timestamp: DateTime<Utc>,
     pub name: String,
@@ -91,17 +93,22 @@ pub struct Metadata {
 pub struct TracingSubscriber {}
 
 impl TracingSubscriber {
-    pub fn new() {
-        let gcp_logging = GcpLogging::new();
-        gcp_logging.start_remote();
+    // Returns a handle to the telemetry sink, which needs to be shut down when the app quits.
+    pub fn new(telemetry_config: TelemetryConfig, app_dir: Option<&Path>) -> TelemetrySink {
+        let mut telemetry_sink = TelemetrySink::from_config(&telemetry_config);
+        if let Some(app_dir) = app_dir {
+            telemetry_sink = telemetry_sink
+                .with_spool(Spool::new(app_dir, telemetry_config.spool_max_size_bytes));
+        }
+        telemetry_sink.start();
 
-        let gcp_layer = GcpLayer {
-            gcp_logging: Arc::new(Mutex::new(gcp_logging)),
+        let telemetry_layer = TelemetryLayer {
+            telemetry_sink: telemetry_sink.clone(),
         };
         let subscriber = tracing_subscriber::fmt()
             .with_max_level(LevelFilter::DEBUG)
             .finish()
-            .with(gcp_layer);
+            .with(telemetry_layer);
 
         tracing::subscriber::set_global_default(subscriber)
             .expect("Failed to set global default subscriber");
@@ -119,9 +126,20 @@ impl TracingSubscriber {
         }));
 
         info!("Starting Tracing");
+
+        telemetry_sink
     }
 }
 
+// The app is built as the `pretzl` library, so its events have module paths like `pretzl::utils`.
+fn is_crate_module(module_path: &str) -> bool {
+    let crate_name = env!("CARGO_CRATE_NAME");
+    module_path == crate_name
+        || module_path
+            .strip_prefix(crate_name)
+            .map_or(false, |rest| rest.starts_with("::"))
+}
+
 pub fn assert_or_error_trace(condition: bool, message: &str) {
     if !condition {
         error!("Assertion failed: {}", message);
@@ -132,12 +150,23 @@ pub fn assert_or_error_trace(condition: bool, message: &str) {
 mod tests {
     use tracing::info;
 
-    use crate::utils::tracing::TracingSubscriber;
+    use crate::utils::{
+        telemetry::TelemetryConfig,
+        tracing::{is_crate_module, TracingSubscriber},
+    };
 
     #[test]
     fn log_info() {
-        TracingSubscriber::new();
+        TracingSubscriber::new(TelemetryConfig::default(), None);
         let foo = 22;
         info!(foo, no_remote = true, "Here is the message");
     }
+
+    #[test]
+    fn events_of_crate_modules_are_enabled() {
+        assert!(is_crate_module(module_path!()));
+        assert!(is_crate_module("pretzl"));
+        assert!(!is_crate_module("pretzl_fuzz::fuzz_targets"));
+        assert!(!is_crate_module("tauri::app"));
+    }
 }